@@ -0,0 +1,32 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+mod scenarios;
+
+fn build_queries(c: &mut Criterion) {
+    c.bench_function("select_joined", |b| {
+        b.iter(|| black_box(scenarios::select_joined()))
+    });
+    c.bench_function("insert_values_1000", |b| {
+        b.iter(|| black_box(scenarios::insert_values_1000()))
+    });
+    c.bench_function("static_query", |b| {
+        b.iter(|| black_box(scenarios::static_query()))
+    });
+    c.bench_function("select_in_list_borrowed", |b| {
+        b.iter_batched(
+            scenarios::in_list_fragment,
+            |fragment| black_box(scenarios::select_in_list_borrowed(fragment)),
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("select_in_list_owned", |b| {
+        b.iter_batched(
+            scenarios::in_list_fragment,
+            |fragment| black_box(scenarios::select_in_list_owned(fragment)),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, build_queries);
+criterion_main!(benches);
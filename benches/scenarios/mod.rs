@@ -0,0 +1,116 @@
+//! Query scenarios shared by the benchmarks and the allocation tests.
+
+use sqlstr::expr::{
+    columns_iter, continue_condition, filter_where, from_table_as, insert_columns_iter,
+    insert_into, join, join_on, lhs_binary_rhs, limit, select, values, values_rows, Cmp, JoinType,
+    LogicBi,
+};
+use sqlstr::{
+    sqlexpr, sqlvalue, static_columns, static_condition, static_from_tables, static_join,
+    static_limit, SqlCommand, SqlError, Void, WriteSql,
+};
+
+/// Generated `IN` list condition of about 10 KB.
+pub fn in_list_fragment() -> String {
+    let mut fragment = String::with_capacity(10 * 1024);
+    fragment.push_str("id IN (");
+    for id in 0..1400u32 {
+        if id > 0 {
+            fragment.push_str(", ");
+        }
+        fragment.push_str(&id.to_string());
+    }
+    fragment.push(')');
+    fragment
+}
+
+/// `SELECT` filtered by the generated `IN` list, copied from a borrowed
+/// fragment.
+pub fn select_in_list_borrowed(fragment: String) -> SqlCommand<Void> {
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+
+    sql.push_cmd("SELECT id FROM user WHERE ");
+    sql.push_cmd(&fragment);
+    sql.push_cmd(" AND active");
+
+    sql
+}
+
+/// `SELECT` filtered by the generated `IN` list, reusing the owned fragment.
+pub fn select_in_list_owned(fragment: String) -> SqlCommand<Void> {
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+
+    sql.push_cmd("SELECT id FROM user WHERE ");
+    sql.push_string(fragment);
+    sql.push_cmd(" AND active");
+
+    sql
+}
+
+/// 5 columns, 2 joins and 3 conditions `SELECT`.
+pub fn select_joined() -> SqlCommand<Void> {
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+    write_select_joined(&mut sql);
+    sql
+}
+
+/// Writes the [select_joined] command into any writer.
+pub fn write_select_joined<Sql>(sql: &mut Sql)
+where
+    Sql: WriteSql<Void>,
+{
+    select(sql);
+    columns_iter(sql, ["u.id", "u.name", "u.email", "o.total", "p.title"]);
+    from_table_as(sql, "user", "u");
+    join(sql, JoinType::Inner, "order", Some("o"));
+    join_on(sql);
+    sql.push_cmd(" o.user_id = u.id");
+    join(sql, JoinType::Left, "product", Some("p"));
+    join_on(sql);
+    sql.push_cmd(" p.id = o.product_id");
+    filter_where(sql);
+    let _ = lhs_binary_rhs(sql, sqlexpr::<bool>("u.active"), Cmp::Eq, sqlvalue(true));
+    continue_condition(sql, LogicBi::And);
+    let _ = lhs_binary_rhs(sql, sqlexpr::<u32>("o.total"), Cmp::Gt, sqlvalue(100));
+    continue_condition(sql, LogicBi::And);
+    let _ = lhs_binary_rhs(sql, sqlexpr::<&str>("p.kind"), Cmp::Neq, sqlvalue("gift"));
+    let _ = limit(sql, 20);
+}
+
+/// `INSERT` with a 1000 rows `VALUES` list of 3 values.
+pub fn insert_values_1000() -> Result<SqlCommand<Void>, SqlError<core::convert::Infallible>> {
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+    write_insert_values_1000(&mut sql)?;
+    Ok(sql)
+}
+
+/// Writes the [insert_values_1000] command into any writer.
+pub fn write_insert_values_1000<Sql>(
+    sql: &mut Sql,
+) -> Result<(), SqlError<core::convert::Infallible>>
+where
+    Sql: WriteSql<Void>,
+{
+    insert_into(sql, "measurement");
+    insert_columns_iter(sql, ["sensor_id", "value", "recorded_at"])?;
+    values(sql);
+    values_rows(sql, 3, (0..1000u32).map(|i| [i, i * 2, i * 3]))
+}
+
+/// Query built only from static macro fragments.
+pub fn static_query() -> SqlCommand<Void> {
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+
+    sql.push_cmd("SELECT ");
+    sql.push_cmd(static_columns!("u.id", "u.name" AS "name", "o.total"));
+    sql.push_cmd(" ");
+    sql.push_cmd(static_from_tables!(FROM "user" AS "u"));
+    sql.push_cmd(" ");
+    sql.push_cmd(static_join!(INNER "order" ON "o.user_id" = "u.id"));
+    sql.push_cmd(" WHERE ");
+    sql.push_cmd(static_condition!("o.total" > "100"));
+    sql.push_cmd(" ");
+    sql.push_cmd(static_limit!(LIMIT "20"));
+
+    sql
+}
@@ -0,0 +1,43 @@
+//! Inserts many rows, splitting them in commands of a fixed number of rows.
+
+use std::convert::Infallible;
+
+use sqlstr::expr::{insert_columns_iter, insert_into, values, values_rows};
+use sqlstr::{SqlCommand, SqlError, Void};
+
+const CHUNK_ROWS: usize = 2;
+
+fn insert_readings(readings: &[(u32, u32)]) -> Result<Vec<SqlCommand<Void>>, SqlError<Infallible>> {
+    readings
+        .chunks(CHUNK_ROWS)
+        .map(|chunk| {
+            let mut sql: SqlCommand<Void> = SqlCommand::default();
+            insert_into(&mut sql, "reading");
+            insert_columns_iter(&mut sql, ["sensor_id", "value"])?;
+            values(&mut sql);
+            values_rows(&mut sql, 2, chunk.iter().map(|&(id, value)| [id, value]))?;
+            Ok(sql)
+        })
+        .collect()
+}
+
+fn main() -> Result<(), SqlError<Infallible>> {
+    let commands = insert_readings(&[(1, 20), (1, 21), (2, 19), (2, 18), (3, 25)])?;
+    for sql in &commands {
+        println!("{}", sql.as_command());
+    }
+
+    let text: Vec<&str> = commands.iter().map(SqlCommand::as_command).collect();
+    assert_eq!(
+        text,
+        [
+            "INSERT INTO reading (sensor_id, value) VALUES ($1, $2), ($3, $4)",
+            "INSERT INTO reading (sensor_id, value) VALUES ($1, $2), ($3, $4)",
+            "INSERT INTO reading (sensor_id, value) VALUES ($1, $2)",
+        ]
+    );
+    let counts: Vec<u32> = commands.iter().map(|sql| sql.arguments.count()).collect();
+    assert_eq!(counts, [4, 4, 2]);
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Reports the total spent per customer, combining the recent orders, read
+//! from a CTE, with the archived ones.
+
+use std::convert::Infallible;
+
+use sqlstr::expr::{
+    columns_iter, filter_where, from_table, group_by, lhs_binary_rhs, select, Cmp, With,
+};
+use sqlstr::{sqlexpr, sqlvalue, SqlCommand, SqlError, Void};
+
+type Error = SqlError<Infallible>;
+
+fn spending_report(since: &str, archived_year: u32) -> Result<SqlCommand<Void>, Error> {
+    let mut current: SqlCommand<Void> = SqlCommand::default();
+
+    let mut with = With::open(&mut current);
+    with.cte("recent", |sql, _| {
+        select(sql);
+        columns_iter(sql, ["customer_id", "total"]);
+        from_table(sql, "order");
+        filter_where(sql);
+        lhs_binary_rhs(
+            sql,
+            sqlexpr::<&str>("created_at"),
+            Cmp::Gte,
+            sqlvalue(since),
+        )?;
+        Ok::<_, Error>(())
+    })?;
+    let ctes = with.close();
+
+    select(&mut current);
+    columns_iter(&mut current, ["customer_id", "sum(total)"]);
+    from_table(&mut current, ctes.cte_ref("recent")?);
+    group_by(&mut current, ["customer_id"]);
+
+    let mut archived: SqlCommand<Void> = SqlCommand::default();
+    select(&mut archived);
+    columns_iter(&mut archived, ["customer_id", "sum(total)"]);
+    from_table(&mut archived, "order_archive");
+    filter_where(&mut archived);
+    lhs_binary_rhs(
+        &mut archived,
+        sqlexpr::<u32>("year"),
+        Cmp::Eq,
+        sqlvalue(archived_year),
+    )?;
+    group_by(&mut archived, ["customer_id"]);
+
+    let sql = current.union_all(archived)?;
+    Ok(sql)
+}
+
+fn main() -> Result<(), Error> {
+    let sql = spending_report("2024-01-01", 2023)?;
+    println!("{}", sql.as_command());
+    assert_eq!(
+        sql.as_command(),
+        "WITH recent AS (SELECT customer_id, total FROM order WHERE created_at >= $1) \
+         SELECT customer_id, sum(total) FROM recent GROUP BY customer_id \
+         UNION ALL \
+         SELECT customer_id, sum(total) FROM order_archive WHERE year = $2 GROUP BY customer_id"
+    );
+    assert_eq!(sql.arguments.count(), 2);
+
+    Ok(())
+}
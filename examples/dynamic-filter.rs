@@ -0,0 +1,89 @@
+//! Builds a `WHERE` clause from the options of a search, writing one
+//! condition per option set.
+
+use std::convert::Infallible;
+
+use sqlstr::expr::comparison::is_null;
+use sqlstr::expr::{
+    column, columns_iter, continue_condition, filter_where, from_table, lhs_binary_rhs, limit,
+    select, Cmp, LogicBi,
+};
+use sqlstr::{sqlexpr, sqlvalue, SqlCommand, SqlError, Void};
+
+#[derive(Default)]
+struct ProductSearch<'a> {
+    category: Option<&'a str>,
+    max_price: Option<u32>,
+    include_archived: bool,
+    limit: Option<usize>,
+}
+
+fn search(options: &ProductSearch<'_>) -> Result<SqlCommand<Void>, SqlError<Infallible>> {
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+    select(&mut sql);
+    columns_iter(&mut sql, ["id", "name", "price"]);
+    from_table(&mut sql, "product");
+
+    let mut has_condition = false;
+    let mut next_condition = |sql: &mut SqlCommand<Void>| {
+        if has_condition {
+            continue_condition(sql, LogicBi::And);
+        } else {
+            filter_where(sql);
+            has_condition = true;
+        }
+    };
+
+    if let Some(category) = options.category {
+        next_condition(&mut sql);
+        lhs_binary_rhs(
+            &mut sql,
+            sqlexpr::<&str>("category"),
+            Cmp::Eq,
+            sqlvalue(category),
+        )?;
+    }
+    if let Some(max_price) = options.max_price {
+        next_condition(&mut sql);
+        lhs_binary_rhs(
+            &mut sql,
+            sqlexpr::<u32>("price"),
+            Cmp::Lte,
+            sqlvalue(max_price),
+        )?;
+    }
+    if !options.include_archived {
+        next_condition(&mut sql);
+        column(&mut sql, "archived_at");
+        is_null(&mut sql);
+    }
+    if let Some(count) = options.limit {
+        limit(&mut sql, count)?;
+    }
+
+    Ok(sql)
+}
+
+fn main() -> Result<(), SqlError<Infallible>> {
+    let sql = search(&ProductSearch {
+        category: Some("books"),
+        max_price: Some(50),
+        limit: Some(10),
+        ..ProductSearch::default()
+    })?;
+    println!("{}", sql.as_command());
+    assert_eq!(
+        sql.as_command(),
+        "SELECT id, name, price FROM product \
+         WHERE category = $1 AND price <= $2 AND archived_at IS NULL LIMIT $3"
+    );
+
+    let sql = search(&ProductSearch {
+        include_archived: true,
+        ..ProductSearch::default()
+    })?;
+    println!("{}", sql.as_command());
+    assert_eq!(sql.as_command(), "SELECT id, name, price FROM product");
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+//! Inserts a list of domain structs, describing the columns and cells of each
+//! struct with `BindRow`.
+
+use std::convert::Infallible;
+
+use sqlstr::expr::{insert_rows, returning_columns, BindRow, ValuesRow};
+use sqlstr::{SqlCommand, SqlError, Void, WriteSql};
+
+struct Product<'a> {
+    sku: &'a str,
+    name: &'a str,
+    price: u32,
+}
+
+impl<'a> BindRow<Void> for Product<'a> {
+    type Error = Infallible;
+    const COLUMNS: &'static [&'static str] = &["sku", "name", "price", "created_at"];
+
+    fn bind<Sql>(self, row: &mut ValuesRow<'_, Sql, Void>) -> Result<(), Self::Error>
+    where
+        Sql: WriteSql<Void>,
+    {
+        row.value(self.sku)?
+            .value(self.name)?
+            .value(self.price)?
+            .raw("now()");
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), SqlError<Infallible>> {
+    let products = vec![
+        Product {
+            sku: "bk-001",
+            name: "The Book",
+            price: 30,
+        },
+        Product {
+            sku: "pn-002",
+            name: "Pen",
+            price: 2,
+        },
+    ];
+
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+    insert_rows(&mut sql, "product", products)?;
+    returning_columns(&mut sql, ["id"]);
+
+    println!("{}", sql.as_command());
+    assert_eq!(
+        sql.as_command(),
+        "INSERT INTO product (sku, name, price, created_at) \
+         VALUES ($1, $2, $3, now()), ($4, $5, $6, now()) RETURNING id"
+    );
+    assert_eq!(sql.arguments.count(), 6);
+
+    Ok(())
+}
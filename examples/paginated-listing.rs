@@ -0,0 +1,47 @@
+//! Lists a page of published posts with their author, newest first.
+
+use std::convert::Infallible;
+
+use sqlstr::expr::{
+    columns_iter, filter_where, from_table_as, join, join_on, lhs_binary_rhs, limit, offset,
+    order_by, select, Cmp, JoinType, OrderByNulls, OrderByOrd,
+};
+use sqlstr::{sqlexpr, sqlvalue, SqlCommand, SqlError, Void};
+
+fn list_posts(page: usize, page_size: usize) -> Result<SqlCommand<Void>, SqlError<Infallible>> {
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+    select(&mut sql);
+    columns_iter(&mut sql, ["p.id", "p.title", "u.name AS author"]);
+    from_table_as(&mut sql, "post", "p");
+    join(&mut sql, JoinType::Inner, "user", Some("u"));
+    join_on(&mut sql);
+    sql.push_cmd(" u.id = p.author_id");
+    filter_where(&mut sql);
+    lhs_binary_rhs(
+        &mut sql,
+        sqlexpr::<bool>("p.published"),
+        Cmp::Eq,
+        sqlvalue(true),
+    )?;
+    order_by(
+        &mut sql,
+        [("p.created_at", OrderByOrd::Desc, OrderByNulls::Last)],
+    );
+    limit(&mut sql, page_size)?;
+    offset(&mut sql, page.saturating_mul(page_size))?;
+    Ok(sql)
+}
+
+fn main() -> Result<(), SqlError<Infallible>> {
+    let sql = list_posts(3, 20)?;
+    println!("{}", sql.as_command());
+    assert_eq!(
+        sql.as_command(),
+        "SELECT p.id, p.title, u.name AS author FROM post AS p \
+         INNER JOIN user AS u ON u.id = p.author_id \
+         WHERE p.published = $1 ORDER BY p.created_at DESC NULLS LAST LIMIT $2 OFFSET $3"
+    );
+    assert_eq!(sql.arguments.count(), 3);
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+//! Inserts a user, or updates its name when the email is already taken.
+
+use std::convert::Infallible;
+
+use sqlstr::expr::{
+    insert_columns_iter, insert_into, on_conflict, set_column, set_update, values, values_rows,
+    ConflictAction, ConflictTarget,
+};
+use sqlstr::{SqlCommand, SqlError, Void};
+
+fn upsert_user(email: &str, name: &str) -> Result<SqlCommand<Void>, SqlError<Infallible>> {
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+    insert_into(&mut sql, "user");
+    insert_columns_iter(&mut sql, ["email", "name"])?;
+    values(&mut sql);
+    values_rows(&mut sql, 2, [[email, name]])?;
+    on_conflict(
+        &mut sql,
+        Some(ConflictTarget::IndexColumn("(email)")),
+        ConflictAction::Update,
+    );
+    set_update(&mut sql);
+    set_column(&mut sql, "name");
+    sql.push_cmd(" EXCLUDED.name");
+    Ok(sql)
+}
+
+fn main() -> Result<(), SqlError<Infallible>> {
+    let sql = upsert_user("ana@example.com", "Ana")?;
+    println!("{}", sql.as_command());
+    assert_eq!(
+        sql.as_command(),
+        "INSERT INTO user (email, name) VALUES ($1, $2) \
+         ON CONFLICT (email) DO UPDATE SET name = EXCLUDED.name"
+    );
+    assert_eq!(sql.arguments.count(), 2);
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+//! Checks of the [ArgumentBuffer] contract.
+//!
+//! The placeholders of a command are numbered from the
+//! [count](ArgumentBuffer::count) of its argument buffer, which must increase
+//! by exactly one after every successful [push](ArgumentBuffer::push). A
+//! buffer returning a stale count writes wrong placeholders without any
+//! error, so implementors can check their buffer in tests with
+//! [verify_buffer_contract], or wrap it in [CheckedArguments] while
+//! building commands in debug builds.
+
+use crate::{AppendArguments, ArgumentBuffer, ArgumentError};
+
+/// Error of a [CheckedArguments] buffer.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CheckedError<E> {
+    /// Error returned by the wrapped buffer.
+    Buffer(E),
+    /// The count of the wrapped buffer is not the `expected` one.
+    CountMismatch { expected: u32, found: u32 },
+}
+
+#[cfg(feature = "fmt")]
+impl<E: core::fmt::Display> core::fmt::Display for CheckedError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Buffer(err) => write!(f, "argument buffer error: {err}"),
+            Self::CountMismatch { expected, found } => write!(
+                f,
+                "argument count of {found}, expected {expected} after the push"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E> std::error::Error for CheckedError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Buffer(err) => Some(err),
+            Self::CountMismatch { .. } => None,
+        }
+    }
+}
+
+/// Argument buffer checking that the [count](ArgumentBuffer::count) of the
+/// wrapped buffer increases by exactly one after every push.
+///
+/// A push not increasing the count by one returns a
+/// [CountMismatch](CheckedError::CountMismatch) error.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, sqlexpr, sqlvalue};
+/// # use sqlstr::arguments::{CheckedArguments, CheckedError};
+/// # use sqlstr::expr::{filter_where, lhs_binary_rhs, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), CheckedError<Infallible>> {
+/// let mut sql = SqlCommand::new(String::new(), CheckedArguments::new(Void::new()));
+/// filter_where(&mut sql);
+/// lhs_binary_rhs(&mut sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(7))?;
+///
+/// assert_eq!(sql.as_command(), "WHERE id = $1");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct CheckedArguments<A>(A);
+
+impl<A> CheckedArguments<A> {
+    /// Wraps the `buffer`.
+    pub const fn new(buffer: A) -> Self {
+        Self(buffer)
+    }
+
+    /// Returns a reference to the wrapped buffer.
+    pub const fn get_ref(&self) -> &A {
+        &self.0
+    }
+
+    /// Returns the wrapped buffer.
+    pub fn into_inner(self) -> A {
+        self.0
+    }
+}
+
+impl<A> ArgumentError for CheckedArguments<A>
+where
+    A: ArgumentError,
+{
+    type Error = CheckedError<A::Error>;
+}
+
+impl<A, T> ArgumentBuffer<T> for CheckedArguments<A>
+where
+    A: ArgumentBuffer<T>,
+{
+    type Error = CheckedError<A::Error>;
+
+    fn push(&mut self, value: T) -> Result<(), Self::Error> {
+        let before = self.0.count();
+        self.0.push(value).map_err(CheckedError::Buffer)?;
+
+        let found = self.0.count();
+        match before.checked_add(1) {
+            Some(expected) if expected == found => Ok(()),
+            expected => Err(CheckedError::CountMismatch {
+                expected: expected.unwrap_or(u32::MAX),
+                found,
+            }),
+        }
+    }
+
+    fn count(&self) -> u32 {
+        self.0.count()
+    }
+}
+
+impl<A> AppendArguments for CheckedArguments<A>
+where
+    A: AppendArguments,
+{
+    type Error = A::Error;
+
+    fn argument_count(&self) -> u32 {
+        self.0.argument_count()
+    }
+
+    fn append(&mut self, other: Self) -> Result<(), Self::Error> {
+        self.0.append(other.0)
+    }
+}
+
+/// Checks that `buffer` follows the [ArgumentBuffer] contract, pushing every
+/// value of `samples` and returning the buffer.
+///
+/// The count must be the same across calls without a push, and increase by
+/// exactly one after every push, otherwise a
+/// [CountMismatch](CheckedError::CountMismatch) error is returned.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{ArgumentBuffer, Void};
+/// # use sqlstr::arguments::verify_buffer_contract;
+/// let buffer = verify_buffer_contract(Void::new(), [1, 2, 3]).unwrap();
+///
+/// assert_eq!(ArgumentBuffer::<i32>::count(&buffer), 3);
+/// ```
+pub fn verify_buffer_contract<A, T, I>(buffer: A, samples: I) -> Result<A, CheckedError<A::Error>>
+where
+    A: ArgumentBuffer<T>,
+    I: IntoIterator<Item = T>,
+{
+    let mut checked = CheckedArguments::new(buffer);
+    for value in samples {
+        let expected = checked.count();
+        let found = checked.count();
+        if found != expected {
+            return Err(CheckedError::CountMismatch { expected, found });
+        }
+        checked.push(value)?;
+    }
+    Ok(checked.into_inner())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        expr::{filter_where, lhs_binary_rhs, Cmp},
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+        SqlCommand,
+    };
+
+    type Error = core::fmt::Error;
+
+    /// Buffer counting every second push only.
+    #[derive(Default)]
+    struct Stale {
+        pushed: u32,
+    }
+
+    impl ArgumentBuffer<u32> for Stale {
+        type Error = Error;
+
+        fn push(&mut self, _: u32) -> Result<(), Error> {
+            self.pushed += 1;
+            Ok(())
+        }
+
+        fn count(&self) -> u32 {
+            self.pushed / 2
+        }
+    }
+
+    #[test]
+    fn valid_buffer_contract() {
+        let buffer = verify_buffer_contract(TestArgs::new(), [1, 2, 3]).unwrap();
+        assert_eq!(buffer.as_str(), "1;2;3;");
+
+        let mut sql = SqlCommand::new(Default::default(), CheckedArguments::new(TestArgs::new()));
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("a"), Cmp::Eq, sqlvalue(1)).unwrap();
+        assert_eq!(sql.as_command(), "WHERE a = $1");
+        assert_eq!(sql.arguments.get_ref().as_str(), "1;");
+    }
+
+    #[test]
+    fn stale_count_detected() {
+        assert_eq!(
+            verify_buffer_contract(Stale::default(), [1, 2]).err(),
+            Some(CheckedError::CountMismatch {
+                expected: 1,
+                found: 0
+            })
+        );
+
+        let mut sql = SqlCommand::new(Default::default(), CheckedArguments::new(Stale::default()));
+        filter_where(&mut sql);
+        assert_eq!(
+            lhs_binary_rhs(&mut sql, sqlexpr::<u32>("a"), Cmp::Eq, sqlvalue(1)),
+            Err(CheckedError::CountMismatch {
+                expected: 1,
+                found: 0
+            })
+        );
+    }
+}
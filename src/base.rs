@@ -1,8 +1,60 @@
+use alloc::collections::TryReserveError;
 use alloc::string::String;
+use alloc::{borrow::ToOwned, vec::Vec};
 use core::convert::Infallible;
+use core::time::Duration;
 
-use crate::format_num::format_u32_base10;
+use crate::expr::{check_plain_ident, emit, tail_state, Tail, Token};
+use crate::format_num::{digits_u64, format_u32_base10};
+use crate::placeholder::placeholder_count;
+use crate::scan::{command_end, tokens, SpanKind};
+use crate::{renumber_placeholders, SqlError};
 
+/// Buffer of the arguments bound to the placeholders of a command.
+///
+/// # Nullable values
+///
+/// A `Option<T>` value is bound like any other value, consuming a
+/// placeholder for both `Some` and `None`, so the buffer needs an
+/// `ArgumentBuffer<Option<T>>` implementation recording an explicit `NULL`
+/// for `None`. A [VecArguments] of `Option<T>` binds both `T` and `Option<T>`,
+/// and a buffer of a value enum can forward `Some` to the value conversion:
+///
+/// ```
+/// # use sqlstr::{SqlCommand, VecArguments, VecArgumentsError};
+/// # use sqlstr::expr::{insert_into, insert_columns_iter, values, ValuesRow};
+/// #[derive(Debug, PartialEq)]
+/// enum Value {
+///     Null,
+///     Int(i64),
+/// }
+///
+/// impl From<i64> for Value {
+///     fn from(value: i64) -> Self {
+///         Self::Int(value)
+///     }
+/// }
+///
+/// impl<T: Into<Value>> From<Option<T>> for Value {
+///     fn from(value: Option<T>) -> Self {
+///         value.map_or(Self::Null, Into::into)
+///     }
+/// }
+///
+/// # fn main() -> Result<(), sqlstr::SqlError<VecArgumentsError>> {
+/// let mut sql = SqlCommand::new(String::new(), VecArguments::<Value>::new());
+/// insert_into(&mut sql, "score");
+/// insert_columns_iter(&mut sql, ["points", "bonus"])?;
+/// values(&mut sql);
+/// let mut row = ValuesRow::open(&mut sql, 2);
+/// row.value(Some(10_i64))?.value_null::<i64>()?;
+/// row.close()?;
+///
+/// assert_eq!(sql.as_command(), "INSERT INTO score (points, bonus) VALUES ($1, $2)");
+/// assert_eq!(sql.arguments.into_inner(), [Value::Int(10), Value::Null]);
+/// # Ok(())
+/// # }
+/// ```
 pub trait ArgumentBuffer<T> {
     type Error;
 
@@ -11,6 +63,115 @@ pub trait ArgumentBuffer<T> {
 
     /// Returns the number of arguments pushed into `Self`
     fn count(&self) -> u32;
+
+    /// Pushes every value of `values` in order, stopping at the first error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{ArgumentBuffer, VecArguments};
+    /// let mut buffer = VecArguments::<u32>::new();
+    /// buffer.push_all([1_u32, 2, 3]).unwrap();
+    ///
+    /// assert_eq!(buffer.as_slice(), [1, 2, 3]);
+    /// ```
+    fn push_all<I>(&mut self, values: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for value in values {
+            self.push(value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lends a buffer to another command, such as a subquery built apart, whose
+/// placeholders continue the numbering of the buffer.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut arguments = Void::new();
+///
+/// let mut subquery = SqlCommand::new(String::new(), &mut arguments);
+/// select(&mut subquery);
+/// column(&mut subquery, "user_id");
+/// from_table(&mut subquery, "member");
+/// filter_where(&mut subquery);
+/// lhs_binary_rhs(&mut subquery, sqlexpr::<u32>("team_id"), Cmp::Eq, sqlvalue(3))?;
+/// let subquery = subquery.into_command();
+///
+/// let mut sql = SqlCommand::new(String::new(), &mut arguments);
+/// select(&mut sql);
+/// column(&mut sql, "name");
+/// from_table(&mut sql, "user");
+/// filter_where(&mut sql);
+/// lhs_binary_rhs(&mut sql, sqlexpr::<u32>("age"), Cmp::Gt, sqlvalue(18))?;
+/// sql.push_cmd(" AND id IN (");
+/// sql.push_cmd(&subquery);
+/// sql.push_cmd(")");
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT name FROM user WHERE age > $2 AND id IN (SELECT user_id FROM member WHERE team_id = $1)"
+/// );
+/// assert_eq!(arguments.count(), 2);
+/// # Ok(())
+/// # }
+/// ```
+impl<T, A> ArgumentBuffer<T> for &mut A
+where
+    A: ArgumentBuffer<T> + ?Sized,
+{
+    type Error = A::Error;
+
+    fn push(&mut self, value: T) -> Result<(), Self::Error> {
+        (**self).push(value)
+    }
+
+    fn count(&self) -> u32 {
+        (**self).count()
+    }
+
+    fn push_all<I>(&mut self, values: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        (**self).push_all(values)
+    }
+}
+
+/// Argument buffer that can take all the arguments of another buffer, used to
+/// combine commands.
+pub trait AppendArguments: Sized {
+    type Error;
+
+    /// Returns the number of arguments in the buffer.
+    fn argument_count(&self) -> u32;
+
+    /// Append the arguments of `other` after the arguments of `self`.
+    fn append(&mut self, other: Self) -> Result<(), Self::Error>;
+}
+
+/// Error of an argument buffer, for the functions that write into a command
+/// without pushing a value of a given type (e.g. closing a `VALUES` row).
+///
+/// The [Error](ArgumentError::Error) must be the same as the error of every
+/// [ArgumentBuffer] implementation of the buffer.
+pub trait ArgumentError {
+    type Error;
+}
+
+impl<A> ArgumentError for &mut A
+where
+    A: ArgumentError + ?Sized,
+{
+    type Error = A::Error;
 }
 
 pub trait WriteSql<Arg> {
@@ -24,7 +185,60 @@ pub trait WriteSql<Arg> {
 
     fn push_cmd(&mut self, expr: &str);
 
+    /// Appends an owned sql expression, which writers can splice without
+    /// copying.
+    ///
+    /// Writers without a reusable buffer can rely on the default
+    /// implementation, which is the same as [push_cmd](Self::push_cmd).
+    fn push_string(&mut self, expr: String) {
+        self.push_cmd(&expr)
+    }
+
     fn as_command(&self) -> &str;
+
+    /// Records a fact about the command being written.
+    ///
+    /// Writers that do not track anything can rely on the default
+    /// implementation, which ignores the record.
+    fn record(&mut self, record: Record<'_>) {
+        let _ = record;
+    }
+
+    /// Reserves capacity for at least `additional` more bytes of command.
+    ///
+    /// Writers without a growable buffer can rely on the default
+    /// implementation, which does nothing.
+    fn reserve(&mut self, additional: usize) {
+        let _ = additional;
+    }
+
+    /// Argument buffer of the command being written.
+    ///
+    /// Writers without an accessible buffer can rely on the default
+    /// implementation, which returns `None`.
+    fn arguments(&self) -> Option<&Arg> {
+        None
+    }
+
+    /// Characters rejected in the text of the command being written.
+    ///
+    /// Writers that do not check their text can rely on the default
+    /// implementation, which returns the default [TextCheck].
+    fn text_check(&self) -> TextCheck {
+        TextCheck::default()
+    }
+}
+
+/// Fact about a sql command, recorded by the expression writers through
+/// [WriteSql::record].
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Record<'r> {
+    /// A table referenced in a `FROM` or `JOIN` clause.
+    Table(&'r str),
+    /// A column returned by a `RETURNING` clause, `*` for all columns.
+    Returning(&'r str),
 }
 
 /// SQL Fragment
@@ -45,16 +259,344 @@ pub const fn sqlvalue<T>(expr: T) -> SqlExpr<'static, T> {
     SqlExpr::Value(expr)
 }
 
+/// Bound `NULL` of type `T`, a `None` value consuming a placeholder, for
+/// drivers that need the type of every argument.
+pub const fn sqlnull<T>() -> SqlExpr<'static, Option<T>> {
+    SqlExpr::Value(None)
+}
+
+/// Execution metadata of a command, read by the executors to choose how the
+/// command is run.
+///
+/// The options are not written into the sql command text.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecOptions {
+    /// Number of rows fetched at a time when streaming the result.
+    pub fetch_size: Option<u32>,
+    /// Rows are processed one by one as they arrive, instead of collected.
+    pub row_mode: bool,
+    /// Maximum time the command is allowed to run.
+    pub statement_timeout: Option<Duration>,
+}
+
+/// Characters rejected in the text written into a command, see
+/// [SqlCommand::check_text].
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextCheck {
+    /// Rejects the NUL character, refused by the Postgres protocol.
+    #[default]
+    Nul,
+    /// Rejects every C0 control character, except tab, line feed and
+    /// carriage return.
+    Control,
+}
+
+impl TextCheck {
+    /// Every rejected character is a single ASCII byte, never part of a
+    /// multibyte UTF-8 sequence.
+    const fn rejects(self, byte: u8) -> bool {
+        match self {
+            Self::Nul => byte == b'\0',
+            Self::Control => byte < 0x20 && !matches!(byte, b'\t' | b'\n' | b'\r'),
+        }
+    }
+
+    /// Byte position of the first character of `text` rejected.
+    pub(crate) fn find(self, text: &str) -> Option<usize> {
+        text.bytes().position(|byte| self.rejects(byte))
+    }
+
+    /// Whether `ch` is rejected.
+    pub(crate) fn rejects_char(self, ch: char) -> bool {
+        u8::try_from(ch).is_ok_and(|byte| self.rejects(byte))
+    }
+
+    /// Appends `text` to `command`, checking each character as it is copied,
+    /// and returns the byte position in `command` of the first one rejected.
+    fn push_checked(self, command: &mut String, text: &str) -> Option<usize> {
+        let start = command.len();
+        let mut rejected = None;
+        command.extend(text.char_indices().map(|(index, ch)| {
+            if rejected.is_none() && self.rejects_char(ch) {
+                rejected = Some(start.saturating_add(index));
+            }
+            ch
+        }));
+        rejected
+    }
+}
+
+/// A sql command, its text and the arguments bound to its placeholders.
+///
+/// Besides the public `command` and `arguments`, the command keeps private
+/// state (execution options, text check, recorded columns and tables), so it
+/// can not be built with a struct literal. Use [new](Self::new) or
+/// [default](Self::default) instead of `SqlCommand { command, arguments }`.
 #[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
 #[derive(Clone, Default, PartialEq, Eq)]
 pub struct SqlCommand<Arg> {
     pub command: String,
     pub arguments: Arg,
+    options: ExecOptions,
+    text_check: TextCheck,
+    invalid_text: Option<usize>,
+    /// Length of the text covered by `invalid_text`, any other length means
+    /// the `command` field was written directly.
+    checked: usize,
+    returning: Returning,
+    #[cfg(feature = "tables")]
+    tables: Vec<String>,
+    #[cfg(feature = "metrics")]
+    pub(crate) metrics: crate::metrics::MetricsHook<Arg>,
+}
+
+/// Columns recorded from the `RETURNING` clause.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Default, PartialEq, Eq)]
+enum Returning {
+    #[default]
+    Unknown,
+    Columns(Vec<String>),
+    Wildcard,
 }
 
 impl<Arg> SqlCommand<Arg> {
-    pub const fn new(command: String, arguments: Arg) -> Self {
-        Self { command, arguments }
+    /// Creates a command from its text and arguments.
+    ///
+    /// The text of `command` is checked once, as the text pushed afterwards,
+    /// see [check_text](Self::check_text).
+    pub fn new(command: String, arguments: Arg) -> Self {
+        Self {
+            invalid_text: TextCheck::Nul.find(&command),
+            checked: command.len(),
+            command,
+            arguments,
+            options: ExecOptions {
+                fetch_size: None,
+                row_mode: false,
+                statement_timeout: None,
+            },
+            text_check: TextCheck::Nul,
+            returning: Returning::Unknown,
+            #[cfg(feature = "tables")]
+            tables: Vec::new(),
+            #[cfg(feature = "metrics")]
+            metrics: crate::metrics::MetricsHook::NONE,
+        }
+    }
+
+    /// Names of the columns returned by the `RETURNING` clause, the alias when
+    /// present.
+    ///
+    /// Only the columns written by the returning functions, such as
+    /// [returning_columns](crate::expr::returning_columns), are recorded.
+    /// Returns `None` without a recorded `RETURNING` clause or when it returns
+    /// all columns with `*`.
+    pub fn returning_columns(&self) -> Option<&[String]> {
+        match &self.returning {
+            Returning::Columns(columns) => Some(columns),
+            Returning::Unknown | Returning::Wildcard => None,
+        }
+    }
+
+    /// Execution options of the command.
+    pub const fn options(&self) -> &ExecOptions {
+        &self.options
+    }
+
+    /// Sets the number of rows fetched at a time when streaming the result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, Void};
+    /// let sql = SqlCommand::new("SELECT * FROM event".into(), Void::new()).with_fetch_size(500);
+    ///
+    /// assert_eq!(sql.options().fetch_size, Some(500));
+    /// assert_eq!(sql.as_command(), "SELECT * FROM event");
+    /// ```
+    pub fn with_fetch_size(mut self, fetch_size: u32) -> Self {
+        self.options.fetch_size = Some(fetch_size);
+        self
+    }
+
+    /// Sets whether the rows are processed one by one as they arrive.
+    pub fn with_row_mode(mut self, row_mode: bool) -> Self {
+        self.options.row_mode = row_mode;
+        self
+    }
+
+    /// Sets the maximum time the command is allowed to run.
+    pub fn with_statement_timeout(mut self, timeout: Duration) -> Self {
+        self.options.statement_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets all the execution options of the command.
+    pub fn with_options(mut self, options: ExecOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets the characters rejected in the text written afterwards, see
+    /// [check_text](Self::check_text).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, TextCheck, Void};
+    /// # use core::convert::Infallible;
+    /// let mut sql: SqlCommand<Void> = SqlCommand::default().with_text_check(TextCheck::Control);
+    /// sql.push_cmd("SELECT\n  1\x07");
+    ///
+    /// assert_eq!(
+    ///     sql.check_text(),
+    ///     Err(SqlError::InvalidText { position: 10 })
+    /// );
+    /// ```
+    pub fn with_text_check(mut self, check: TextCheck) -> Self {
+        self.text_check = check;
+        self
+    }
+
+    /// Checks the text written into the command, returning an
+    /// [InvalidText](SqlError::InvalidText) error at the byte position of the
+    /// first rejected character.
+    ///
+    /// By default only the NUL character is rejected, see
+    /// [with_text_check](Self::with_text_check). Every column, table, raw
+    /// fragment and literal is checked as it is copied into the command, so
+    /// this is only a lookup. Text written directly into the
+    /// [command](Self::command) field, which changes its length, is not
+    /// known to be checked, the whole command is scanned instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, Void};
+    /// # use sqlstr::expr::{select, column, from_table};
+    /// # use core::convert::Infallible;
+    /// let mut sql: SqlCommand<Void> = SqlCommand::default();
+    /// select(&mut sql);
+    /// column(&mut sql, "na\0me");
+    /// from_table(&mut sql, "user");
+    ///
+    /// assert_eq!(
+    ///     sql.check_text(),
+    ///     Err(SqlError::InvalidText { position: 9 })
+    /// );
+    /// ```
+    pub fn check_text(&self) -> Result<(), SqlError<Infallible>> {
+        let invalid_text = match self.invalid_text {
+            None if self.checked != self.command.len() => self.text_check.find(&self.command),
+            invalid_text => invalid_text,
+        };
+        match invalid_text {
+            Some(position) => Err(SqlError::InvalidText { position }),
+            None => Ok(()),
+        }
+    }
+
+    /// Scans the command when its `command` field was written directly, so
+    /// the position of the first rejected character is up to date.
+    fn resolve_text(&mut self) {
+        if self.checked != self.command.len() {
+            self.invalid_text = self
+                .invalid_text
+                .or_else(|| self.text_check.find(&self.command));
+            self.checked = self.command.len();
+        }
+    }
+
+    /// Marks the text written by a method of the command from the byte
+    /// `start` as checked, unless the `command` field was written directly
+    /// before.
+    fn checked_from(&mut self, start: usize) {
+        if self.checked == start {
+            self.checked = self.command.len();
+        }
+    }
+
+    /// Splits the command into its text and arguments, discarding the
+    /// execution options.
+    ///
+    /// A command with rejected text returns an
+    /// [InvalidText](SqlError::InvalidText) error, see
+    /// [check_text](Self::check_text), so the text never reaches the driver.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, Void};
+    /// let sql = SqlCommand::new("SELECT 1".into(), Void::new());
+    /// assert_eq!(sql.into_parts(), Ok(("SELECT 1".to_owned(), Void::new())));
+    ///
+    /// let mut sql = SqlCommand::new(String::new(), Void::new());
+    /// sql.push_cmd("SELECT '\0'");
+    /// assert_eq!(sql.into_parts(), Err(SqlError::InvalidText { position: 8 }));
+    /// ```
+    pub fn into_parts(mut self) -> Result<(String, Arg), SqlError<Infallible>> {
+        self.check_text()?;
+        self.report_metrics();
+        Ok((self.command, self.arguments))
+    }
+
+    /// Splits the command into its text, arguments and execution options.
+    ///
+    /// A command with rejected text returns an error, as in
+    /// [into_parts](Self::into_parts).
+    pub fn into_parts_with_options(
+        mut self,
+    ) -> Result<(String, Arg, ExecOptions), SqlError<Infallible>> {
+        self.check_text()?;
+        self.report_metrics();
+        Ok((self.command, self.arguments, self.options))
+    }
+
+    /// Returns the text of the command, discarding its arguments, as when
+    /// generating sql with a [CountingArguments] buffer.
+    pub fn into_command(mut self) -> String {
+        self.report_metrics();
+        self.command
+    }
+
+    /// Ends the command, returned by the terminal method of a builder.
+    pub(crate) fn finished(mut self) -> Self {
+        self.report_metrics();
+        self
+    }
+
+    /// Reports the metrics of the finished command to the sink registered
+    /// with `with_metrics`, once.
+    fn report_metrics(&mut self) {
+        #[cfg(feature = "metrics")]
+        self.metrics.take().report(self);
+    }
+
+    /// Tables referenced in the `FROM` and `JOIN` clauses written by the
+    /// expression functions, in the order they were written.
+    ///
+    /// Tables inside raw fragments written with [push_cmd](Self::push_cmd)
+    /// are not tracked.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, Void};
+    /// # use sqlstr::expr::{select, column, from_table_as, join, JoinType};
+    /// let mut sql: SqlCommand<Void> = SqlCommand::default();
+    /// select(&mut sql);
+    /// column(&mut sql, "*");
+    /// from_table_as(&mut sql, "public.user", "u");
+    /// join(&mut sql, JoinType::Left, "post", Some("p"));
+    ///
+    /// assert_eq!(sql.tables(), ["public.user", "post"]);
+    /// ```
+    #[cfg(feature = "tables")]
+    pub fn tables(&self) -> &[String] {
+        &self.tables
     }
 
     pub fn push_expr<T>(&mut self, operand: SqlExpr<'_, T>) -> Result<(), Arg::Error>
@@ -63,7 +605,7 @@ impl<Arg> SqlCommand<Arg> {
     {
         match operand {
             SqlExpr::Value(val) => self.push_value(val)?,
-            SqlExpr::Expr(expr) => self.command.push_str(expr),
+            SqlExpr::Expr(expr) => self.push_cmd(expr),
         }
         Ok(())
     }
@@ -73,20 +615,607 @@ impl<Arg> SqlCommand<Arg> {
         Arg: ArgumentBuffer<T>,
     {
         self.arguments.push(value)?;
+        let count = self.arguments.count();
+        reserve_command(
+            &mut self.command,
+            digits_u64(count as u64).saturating_add(1),
+        );
+        let start = self.command.len();
         let mut buf = [0; 10];
+        let placeholder = format_u32_base10(count, &mut buf);
         self.command.push('$');
-        self.command
-            .push_str(format_u32_base10(self.arguments.count(), &mut buf));
+        self.command.push_str(placeholder);
+        self.checked_from(start);
         Ok(())
     }
 
     pub fn push_cmd(&mut self, expr: &str) {
-        self.command.push_str(expr);
+        reserve_command(&mut self.command, expr.len());
+
+        let start = self.command.len();
+        if self.invalid_text.is_none() {
+            self.invalid_text = self.text_check.push_checked(&mut self.command, expr);
+        } else {
+            self.command.push_str(expr);
+        }
+        self.checked_from(start);
+    }
+
+    /// Inserts `expr` at the byte `index` of the command, keeping the position
+    /// of the first rejected character in sync, see
+    /// [check_text](Self::check_text).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not a char boundary of the command.
+    pub(crate) fn insert_cmd(&mut self, index: usize, expr: &str) {
+        self.resolve_text();
+        self.command.insert_str(index, expr);
+        self.checked = self.command.len();
+
+        let shifted = self.invalid_text.map(|position| {
+            if position >= index {
+                position.saturating_add(expr.len())
+            } else {
+                position
+            }
+        });
+        let inserted = self
+            .text_check
+            .find(expr)
+            .map(|position| position.saturating_add(index));
+        self.invalid_text = match (shifted, inserted) {
+            (Some(shifted), Some(inserted)) => Some(shifted.min(inserted)),
+            (shifted, inserted) => shifted.or(inserted),
+        };
+    }
+
+    /// Appends an owned sql expression to the command.
+    ///
+    /// When the command has no room left for `expr` but `expr` has room for
+    /// the command, the command is moved in front of `expr` and the
+    /// allocation of `expr` is kept, avoiding a reallocation of the command.
+    /// A command still empty takes the allocation of `expr` as is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, Void};
+    /// let ids: Vec<String> = (1..=3).map(|id| id.to_string()).collect();
+    /// let mut sql: SqlCommand<Void> = SqlCommand::default();
+    /// sql.push_string(format!("SELECT * FROM user WHERE id IN ({})", ids.join(", ")));
+    ///
+    /// assert_eq!(sql.as_command(), "SELECT * FROM user WHERE id IN (1, 2, 3)");
+    /// ```
+    pub fn push_string(&mut self, mut expr: String) {
+        let len = self.command.len();
+        let spare = self.command.capacity().saturating_sub(len);
+        let expr_spare = expr.capacity().saturating_sub(expr.len());
+        if spare < expr.len() && expr_spare >= len {
+            if self.invalid_text.is_none() {
+                self.invalid_text = self
+                    .text_check
+                    .find(&expr)
+                    .map(|position| position.saturating_add(len));
+            }
+            expr.insert_str(0, &self.command);
+            self.command = expr;
+            self.checked_from(len);
+        } else {
+            self.push_cmd(&expr);
+        }
     }
 
     pub fn as_command(&self) -> &str {
         self.command.as_str()
     }
+
+    /// Wraps the command as a subquery of a new
+    /// `SELECT * FROM (<command>) AS <alias>` command, moving its arguments
+    /// into the outer command, see [wrap_as_subquery](Self::wrap_as_subquery).
+    pub(crate) fn wrapped(mut self, alias: &str) -> Self {
+        const OPEN: &str = "SELECT * FROM (";
+        const CLOSE: &str = ") AS ";
+
+        let capacity = [OPEN.len(), self.command.len(), CLOSE.len(), alias.len()]
+            .into_iter()
+            .fold(0, usize::saturating_add);
+        self.resolve_text();
+        let mut command = String::with_capacity(capacity);
+        command.push_str(OPEN);
+        command.push_str(&self.command);
+        command.push_str(CLOSE);
+        command.push_str(alias);
+
+        let invalid_text = match self.invalid_text {
+            Some(position) => Some(position.saturating_add(OPEN.len())),
+            None => self
+                .text_check
+                .find(alias)
+                .map(|position| position.saturating_add(capacity.saturating_sub(alias.len()))),
+        };
+
+        Self {
+            checked: command.len(),
+            command,
+            arguments: self.arguments,
+            options: self.options,
+            text_check: self.text_check,
+            invalid_text,
+            returning: Returning::Unknown,
+            #[cfg(feature = "tables")]
+            tables: self.tables,
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics,
+        }
+    }
+
+    /// Continues a finished command, such as one parsed from a template or
+    /// built by another crate, with its tail clauses only: `ORDER BY`,
+    /// `LIMIT`, `OFFSET` and row locking.
+    ///
+    /// The placeholders of the tail continue the numbering of the command.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, Void};
+    /// # use sqlstr::expr::{OrderByNulls, OrderByOrd};
+    /// # use core::convert::Infallible;
+    /// # fn main() -> Result<(), Infallible> {
+    /// let mut sql = SqlCommand::new("SELECT id FROM event WHERE kind = $1".into(), Void::with_count(1));
+    /// sql.continue_building()
+    ///     .order_by([("id", OrderByOrd::Desc, OrderByNulls::Default)])
+    ///     .limit(20)?;
+    ///
+    /// assert_eq!(
+    ///     sql.as_command(),
+    ///     "SELECT id FROM event WHERE kind = $1 ORDER BY id DESC LIMIT $2"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// A `GROUP BY` can not be appended:
+    ///
+    /// ```compile_fail
+    /// # use sqlstr::{SqlCommand, Void};
+    /// let mut sql = SqlCommand::new("SELECT kind FROM event".into(), Void::new());
+    /// sql.continue_building().group_by(["kind"]);
+    /// ```
+    pub fn continue_building(&mut self) -> Tail<'_, Self, Arg, tail_state::Continued> {
+        Tail::continued(self)
+    }
+
+    /// Wraps the command in parentheses, `(<command>)`.
+    ///
+    /// Required for a side of a set operation with its own `ORDER BY` or
+    /// `LIMIT` clauses, which the set operations, such as
+    /// [union](Self::union), parenthesize themselves.
+    pub fn parenthesized(mut self) -> Self {
+        self.resolve_text();
+        self.command.reserve(2);
+        self.command.insert(0, '(');
+        self.command.push(')');
+        self.invalid_text = self.invalid_text.map(|position| position.saturating_add(1));
+        self.checked = self.command.len();
+        self
+    }
+
+    /// Turns the command into a `PREPARE <name> AS <command>` statement,
+    /// creating a server-side prepared statement.
+    ///
+    /// The `PREPARE` statement binds no parameter, so the arguments are taken
+    /// off it and returned, to be bound by the [execute_prepared] command
+    /// running the prepared statement. The metrics of the command, if
+    /// registered, are reported here.
+    ///
+    /// A `name` that is not a plain identifier returns an
+    /// [InvalidText](SqlError::InvalidText) error at its byte position, and a
+    /// name longer than
+    /// [POSTGRES_IDENT_MAX_LEN](crate::expr::POSTGRES_IDENT_MAX_LEN) an
+    /// [IdentifierTooLong](SqlError::IdentifierTooLong) error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, Void, execute_prepared, sqlexpr, sqlvalue};
+    /// # use sqlstr::expr::{select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+    /// # use core::convert::Infallible;
+    /// # fn main() -> Result<(), SqlError<Infallible>> {
+    /// let mut sql: SqlCommand<Void> = SqlCommand::default();
+    /// select(&mut sql);
+    /// column(&mut sql, "name");
+    /// from_table(&mut sql, "user");
+    /// filter_where(&mut sql);
+    /// lhs_binary_rhs(&mut sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(7))?;
+    ///
+    /// let (prepare, arguments) = sql.into_prepare("user_name")?;
+    /// assert_eq!(
+    ///     prepare.as_command(),
+    ///     "PREPARE user_name AS SELECT name FROM user WHERE id = $1"
+    /// );
+    ///
+    /// let execute = execute_prepared("user_name", arguments)?;
+    /// assert_eq!(execute.as_command(), "EXECUTE user_name ($1)");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_prepare(
+        mut self,
+        name: &str,
+    ) -> Result<(SqlCommand<Void>, Arg), SqlError<Infallible>> {
+        const PREPARE: &str = "PREPARE ";
+        const AS: &str = " AS ";
+
+        check_plain_ident(name, PREPARE.len())?;
+        self.report_metrics();
+        self.resolve_text();
+
+        let prefix = [PREPARE.len(), name.len(), AS.len()]
+            .into_iter()
+            .fold(0, usize::saturating_add);
+        let mut command = String::with_capacity(prefix.saturating_add(self.command.len()));
+        command.push_str(PREPARE);
+        command.push_str(name);
+        command.push_str(AS);
+        command.push_str(&self.command);
+
+        let prepare = SqlCommand {
+            checked: command.len(),
+            command,
+            arguments: Void::new(),
+            options: self.options,
+            text_check: self.text_check,
+            invalid_text: self
+                .invalid_text
+                .map(|position| position.saturating_add(prefix)),
+            returning: Returning::Unknown,
+            #[cfg(feature = "tables")]
+            tables: self.tables,
+            #[cfg(feature = "metrics")]
+            metrics: crate::metrics::MetricsHook::NONE,
+        };
+        Ok((prepare, self.arguments))
+    }
+
+    /// Writes the command followed by a `;` terminator into `out`, as
+    /// expected by scripts and migration files.
+    ///
+    /// A command already ending with a `;`, outside quotes and comments, is
+    /// written as is. A command ending inside a `--` line comment has its
+    /// terminator written on a new line. The terminator is not part of the
+    /// command sent to the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, Void};
+    /// let sql: SqlCommand<Void> = SqlCommand::new("VACUUM user".into(), Void::new());
+    /// let mut script = String::new();
+    /// sql.write_terminated(&mut script).unwrap();
+    ///
+    /// assert_eq!(script, "VACUUM user;");
+    /// ```
+    pub fn write_terminated<W>(&self, out: &mut W) -> core::fmt::Result
+    where
+        W: core::fmt::Write,
+    {
+        out.write_str(&self.command)?;
+        let end = command_end(&self.command);
+        if end.terminator.is_some() {
+            return Ok(());
+        }
+        if end.line_comment {
+            out.write_char('\n')?;
+        }
+        out.write_char(';')
+    }
+
+    /// Displays the command followed by a `;` terminator, see
+    /// [write_terminated](Self::write_terminated).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, Void};
+    /// let sql: SqlCommand<Void> = SqlCommand::new("VACUUM user".into(), Void::new());
+    ///
+    /// assert_eq!(sql.terminated().to_string(), "VACUUM user;");
+    /// ```
+    #[cfg(feature = "fmt")]
+    pub fn terminated(&self) -> impl core::fmt::Display + '_ {
+        struct Terminated<'cmd, Arg>(&'cmd SqlCommand<Arg>);
+
+        impl<'cmd, Arg> core::fmt::Display for Terminated<'cmd, Arg> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                self.0.write_terminated(f)
+            }
+        }
+
+        Terminated(self)
+    }
+}
+
+/// How [wrap_as_subquery](SqlCommand::wrap_as_subquery) carries the
+/// arguments of the wrapped command into the outer command.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArgumentsStrategy {
+    /// Moves the arguments into the outer command as they are.
+    #[default]
+    Move,
+    /// Checks that the placeholders of the wrapped command are numbered from
+    /// `$1` to its argument count before moving the arguments, as for a
+    /// command built elsewhere.
+    Checked,
+}
+
+impl<Arg> SqlCommand<Arg>
+where
+    Arg: AppendArguments,
+{
+    /// Combines two commands into `<self> UNION <other>`.
+    ///
+    /// The placeholders of `other` are shifted by the number of arguments of
+    /// `self`, and its arguments are appended after the ones of `self`. A side
+    /// with its own `ORDER BY`, `LIMIT`, `OFFSET`, `FETCH` or row locking
+    /// clause is [parenthesized](Self::parenthesized), as required by
+    /// Postgres.
+    ///
+    /// A `$0` placeholder or a placeholder that overflows `u32` in `other`
+    /// returns an [InvalidPlaceholder](SqlError::InvalidPlaceholder) error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, Void, sqlexpr, sqlvalue};
+    /// # use sqlstr::expr::{select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+    /// # use core::convert::Infallible;
+    /// # fn main() -> Result<(), SqlError<Infallible>> {
+    /// let mut active: SqlCommand<Void> = SqlCommand::default();
+    /// select(&mut active);
+    /// column(&mut active, "id");
+    /// from_table(&mut active, "user");
+    /// filter_where(&mut active);
+    /// lhs_binary_rhs(&mut active, sqlexpr::<bool>("active"), Cmp::Eq, sqlvalue(true))?;
+    ///
+    /// let mut admin: SqlCommand<Void> = SqlCommand::default();
+    /// select(&mut admin);
+    /// column(&mut admin, "user_id");
+    /// from_table(&mut admin, "admin");
+    /// filter_where(&mut admin);
+    /// lhs_binary_rhs(&mut admin, sqlexpr::<u8>("level"), Cmp::Gt, sqlvalue(2))?;
+    ///
+    /// let sql = active.union(admin)?;
+    ///
+    /// assert_eq!(
+    ///     sql.as_command(),
+    ///     "SELECT id FROM user WHERE active = $1 UNION SELECT user_id FROM admin WHERE level > $2"
+    /// );
+    /// assert_eq!(sql.arguments.count(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn union(self, other: Self) -> Result<Self, SqlError<Arg::Error>> {
+        self.set_operation(Token::Keyword("UNION"), other)
+    }
+
+    /// Combines two commands into `<self> UNION ALL <other>`, keeping the
+    /// duplicate rows.
+    ///
+    /// The arguments are combined as in [union](Self::union).
+    pub fn union_all(self, other: Self) -> Result<Self, SqlError<Arg::Error>> {
+        self.set_operation(Token::Keyword("UNION ALL"), other)
+    }
+
+    /// Combines two commands into `<self> INTERSECT <other>`.
+    ///
+    /// The arguments are combined as in [union](Self::union).
+    pub fn intersect(self, other: Self) -> Result<Self, SqlError<Arg::Error>> {
+        self.set_operation(Token::Keyword("INTERSECT"), other)
+    }
+
+    /// Combines two commands into `<self> INTERSECT ALL <other>`, keeping the
+    /// duplicate rows.
+    ///
+    /// The arguments are combined as in [union](Self::union).
+    pub fn intersect_all(self, other: Self) -> Result<Self, SqlError<Arg::Error>> {
+        self.set_operation(Token::Keyword("INTERSECT ALL"), other)
+    }
+
+    /// Combines two commands into `<self> EXCEPT <other>`.
+    ///
+    /// The arguments are combined as in [union](Self::union).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, Void};
+    /// # use sqlstr::expr::{select, column, from_table, order_by, limit, OrderByOrd, OrderByNulls};
+    /// # use core::convert::Infallible;
+    /// # fn main() -> Result<(), SqlError<Infallible>> {
+    /// let mut newest: SqlCommand<Void> = SqlCommand::default();
+    /// select(&mut newest);
+    /// column(&mut newest, "id");
+    /// from_table(&mut newest, "post");
+    /// order_by(&mut newest, [("created_at", OrderByOrd::Desc, OrderByNulls::Default)]);
+    /// limit(&mut newest, 1)?;
+    ///
+    /// let mut pinned: SqlCommand<Void> = SqlCommand::default();
+    /// select(&mut pinned);
+    /// column(&mut pinned, "post_id");
+    /// from_table(&mut pinned, "pin");
+    ///
+    /// let sql = newest.except(pinned)?;
+    ///
+    /// assert_eq!(
+    ///     sql.as_command(),
+    ///     "(SELECT id FROM post ORDER BY created_at DESC LIMIT $1) EXCEPT SELECT post_id FROM pin"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn except(self, other: Self) -> Result<Self, SqlError<Arg::Error>> {
+        self.set_operation(Token::Keyword("EXCEPT"), other)
+    }
+
+    /// Combines two commands into `<self> EXCEPT ALL <other>`, keeping the
+    /// duplicate rows.
+    ///
+    /// The arguments are combined as in [union](Self::union).
+    pub fn except_all(self, other: Self) -> Result<Self, SqlError<Arg::Error>> {
+        self.set_operation(Token::Keyword("EXCEPT ALL"), other)
+    }
+
+    /// Appends the text of `other` into the command, shifting its placeholders
+    /// by the number of arguments of `self` and appending its arguments after
+    /// the ones of `self`.
+    ///
+    /// Used to write a command built apart as part of this command, such as a
+    /// subquery. A `$0` placeholder or a placeholder that overflows `u32` in
+    /// `other` returns an [InvalidPlaceholder](SqlError::InvalidPlaceholder)
+    /// error, and an error appending the arguments is returned, both without
+    /// writing anything.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, Void, sqlexpr, sqlvalue};
+    /// # use sqlstr::expr::{select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+    /// # use core::convert::Infallible;
+    /// # fn main() -> Result<(), SqlError<Infallible>> {
+    /// let mut banned: SqlCommand<Void> = SqlCommand::default();
+    /// select(&mut banned);
+    /// column(&mut banned, "user_id");
+    /// from_table(&mut banned, "ban");
+    /// filter_where(&mut banned);
+    /// lhs_binary_rhs(&mut banned, sqlexpr::<u32>("reason"), Cmp::Eq, sqlvalue(3))?;
+    ///
+    /// let mut sql: SqlCommand<Void> = SqlCommand::default();
+    /// select(&mut sql);
+    /// column(&mut sql, "id");
+    /// from_table(&mut sql, "user");
+    /// filter_where(&mut sql);
+    /// lhs_binary_rhs(&mut sql, sqlexpr::<bool>("active"), Cmp::Eq, sqlvalue(true))?;
+    /// sql.push_cmd(" AND id NOT IN (");
+    /// sql.push_command(banned)?;
+    /// sql.push_cmd(")");
+    ///
+    /// assert_eq!(
+    ///     sql.as_command(),
+    ///     "SELECT id FROM user WHERE active = $1 AND id NOT IN (SELECT user_id FROM ban WHERE reason = $2)"
+    /// );
+    /// assert_eq!(sql.arguments.count(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn push_command(&mut self, mut other: Self) -> Result<(), SqlError<Arg::Error>> {
+        let offset = self.arguments.argument_count();
+        let len = self.command.len();
+        other.resolve_text();
+
+        reserve_command(&mut self.command, other.command.len());
+        renumber_placeholders(&other.command, offset, false, &mut self.command)
+            .map_err(SqlError::widen)?;
+        if let Err(err) = self.arguments.append(other.arguments) {
+            self.command.truncate(len);
+            return Err(SqlError::Argument(err));
+        }
+
+        if self.invalid_text.is_none() && other.invalid_text.is_some() {
+            let appended = self.command.get(len..).unwrap_or_default();
+            self.invalid_text = self
+                .text_check
+                .find(appended)
+                .map(|position| position.saturating_add(len));
+        }
+        self.checked_from(len);
+
+        #[cfg(feature = "tables")]
+        self.tables.extend(other.tables);
+        Ok(())
+    }
+
+    /// Wraps the command as a subquery of a new
+    /// `SELECT * FROM (<command>) AS <alias>` command.
+    ///
+    /// The arguments are moved into the outer command, so the placeholders
+    /// written after continue the numbering of the inner command. With the
+    /// [Checked](ArgumentsStrategy::Checked) strategy, a placeholder missing
+    /// from the sequence returns an
+    /// [InvalidPlaceholder](SqlError::InvalidPlaceholder) error, and a
+    /// greatest placeholder other than the argument count an
+    /// [ArgumentCountMismatch](SqlError::ArgumentCountMismatch) error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{ArgumentsStrategy, SqlCommand, SqlError, Void, sqlexpr, sqlvalue};
+    /// # use sqlstr::expr::{select, columns_iter, from_table, filter_where, lhs_binary_rhs, Cmp};
+    /// # use core::convert::Infallible;
+    /// # fn main() -> Result<(), SqlError<Infallible>> {
+    /// let mut sql: SqlCommand<Void> = SqlCommand::default();
+    /// select(&mut sql);
+    /// columns_iter(&mut sql, ["id", "total"]);
+    /// from_table(&mut sql, "order");
+    /// filter_where(&mut sql);
+    /// lhs_binary_rhs(&mut sql, sqlexpr::<u32>("customer_id"), Cmp::Eq, sqlvalue(7))?;
+    ///
+    /// let mut sql = sql.wrap_as_subquery("o", ArgumentsStrategy::Move)?;
+    /// filter_where(&mut sql);
+    /// lhs_binary_rhs(&mut sql, sqlexpr::<u32>("o.total"), Cmp::Gt, sqlvalue(100))?;
+    ///
+    /// assert_eq!(
+    ///     sql.as_command(),
+    ///     "SELECT * FROM (SELECT id, total FROM order WHERE customer_id = $1) AS o WHERE o.total > $2"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn wrap_as_subquery(
+        self,
+        alias: &str,
+        strategy: ArgumentsStrategy,
+    ) -> Result<Self, SqlError<Arg::Error>> {
+        if strategy == ArgumentsStrategy::Checked {
+            let count = self.arguments.argument_count();
+            let max = placeholder_count(&self.command).map_err(SqlError::widen)?;
+            if max != count {
+                return Err(SqlError::ArgumentCountMismatch {
+                    expected: max as usize,
+                    found: count as usize,
+                });
+            }
+        }
+        Ok(self.wrapped(alias))
+    }
+
+    /// Combines two commands with a set `operator`, parenthesizing a side
+    /// with a tail clause.
+    fn set_operation(self, operator: Token<'_>, other: Self) -> Result<Self, SqlError<Arg::Error>> {
+        let parenthesize = |sql: Self| {
+            if has_tail_clause(sql.as_command()) {
+                sql.parenthesized()
+            } else {
+                sql
+            }
+        };
+        parenthesize(self).combine(operator, parenthesize(other))
+    }
+
+    pub(crate) fn combine(
+        mut self,
+        operator: Token<'_>,
+        other: Self,
+    ) -> Result<Self, SqlError<Arg::Error>> {
+        emit(&mut self, operator);
+        emit(&mut self, Token::Space);
+        self.push_command(other)?;
+
+        self.returning = Returning::Unknown;
+        Ok(self)
+    }
 }
 
 impl<Arg> WriteSql<Arg> for SqlCommand<Arg> {
@@ -108,9 +1237,129 @@ impl<Arg> WriteSql<Arg> for SqlCommand<Arg> {
         SqlCommand::push_cmd(self, expr)
     }
 
+    fn push_string(&mut self, expr: String) {
+        SqlCommand::push_string(self, expr)
+    }
+
     fn as_command(&self) -> &str {
         self.command.as_str()
     }
+
+    fn arguments(&self) -> Option<&Arg> {
+        Some(&self.arguments)
+    }
+
+    fn text_check(&self) -> TextCheck {
+        self.text_check
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        reserve_command(&mut self.command, additional);
+    }
+
+    fn record(&mut self, record: Record<'_>) {
+        match (record, &mut self.returning) {
+            #[cfg(feature = "tables")]
+            (Record::Table(table), _) => self.tables.push(table.to_owned()),
+            #[cfg(not(feature = "tables"))]
+            (Record::Table(_), _) => {}
+            (Record::Returning(_), Returning::Wildcard) => {}
+            (Record::Returning("*"), returning) => *returning = Returning::Wildcard,
+            (Record::Returning(column), Returning::Columns(columns)) => {
+                columns.push(column.to_owned())
+            }
+            (Record::Returning(column), returning) => {
+                *returning = Returning::Columns(Vec::from([column.to_owned()]))
+            }
+        }
+    }
+}
+
+/// Returns `true` when `sql` has an `ORDER BY`, `LIMIT`, `OFFSET`, `FETCH` or
+/// row locking clause outside parentheses, required to be parenthesized as a
+/// side of a set operation.
+fn has_tail_clause(sql: &str) -> bool {
+    const TAIL: [&str; 5] = ["ORDER", "LIMIT", "OFFSET", "FETCH", "FOR"];
+
+    let mut depth: usize = 0;
+    for span in tokens(sql) {
+        let Ok(span) = span else {
+            return false;
+        };
+        if span.kind != SpanKind::Text {
+            continue;
+        }
+        for part in span.text.split_inclusive(['(', ')']) {
+            let words = part.trim_end_matches(['(', ')']);
+            let is_tail = |word: &str| TAIL.iter().any(|tail| word.eq_ignore_ascii_case(tail));
+            if depth == 0
+                && words
+                    .split(|ch: char| !(ch.is_alphanumeric() || ch == '_' || ch == '$'))
+                    .any(is_tail)
+            {
+                return true;
+            }
+            match part.chars().last() {
+                Some('(') => depth = depth.saturating_add(1),
+                Some(')') => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+    false
+}
+
+/// Reserves room for `additional` bytes in the command.
+///
+/// With the `reserve-exact` feature, the command only grows when it has no
+/// room left, by exactly `additional` bytes but never less than its current
+/// length, so the number of reallocations stays logarithmic.
+fn reserve_command(command: &mut String, additional: usize) {
+    #[cfg(feature = "reserve-exact")]
+    if command.capacity().saturating_sub(command.len()) < additional {
+        command.reserve_exact(additional.max(command.len()));
+    }
+    #[cfg(not(feature = "reserve-exact"))]
+    command.reserve(additional);
+}
+
+/// Builds a `EXECUTE <name> ($1, ...)` command running the prepared
+/// statement `name`, with a placeholder per argument of `arguments`, see
+/// [into_prepare](SqlCommand::into_prepare).
+///
+/// Without arguments, the command is `EXECUTE <name>`. The `name` is checked
+/// as in [into_prepare](SqlCommand::into_prepare).
+pub fn execute_prepared<Arg>(
+    name: &str,
+    arguments: Arg,
+) -> Result<SqlCommand<Arg>, SqlError<Infallible>>
+where
+    Arg: AppendArguments,
+{
+    check_plain_ident(name, "EXECUTE ".len())?;
+
+    let count = arguments.argument_count();
+    let mut sql = SqlCommand::new(String::new(), arguments);
+    emit(&mut sql, Token::Keyword("EXECUTE"));
+    emit(&mut sql, Token::Ident(name));
+
+    let mut buf = [0; 10];
+    for number in 1..=count {
+        emit(
+            &mut sql,
+            if number == 1 {
+                Token::Open
+            } else {
+                Token::Comma
+            },
+        );
+        sql.push_cmd("$");
+        sql.push_cmd(format_u32_base10(number, &mut buf));
+    }
+    if count > 0 {
+        emit(&mut sql, Token::Close);
+    }
+    Ok(sql)
 }
 
 /// Void argument buffer
@@ -134,11 +1383,28 @@ impl Void {
     }
 }
 
+impl AppendArguments for Void {
+    type Error = Infallible;
+
+    fn argument_count(&self) -> u32 {
+        self.0
+    }
+
+    fn append(&mut self, other: Self) -> Result<(), Self::Error> {
+        self.0 = self.0.saturating_add(other.0);
+        Ok(())
+    }
+}
+
+impl ArgumentError for Void {
+    type Error = Infallible;
+}
+
 impl<T> ArgumentBuffer<T> for Void {
     type Error = Infallible;
 
     fn push(&mut self, _: T) -> Result<(), Self::Error> {
-        self.0 += 1;
+        self.0 = self.0.saturating_add(1);
         Ok(())
     }
 
@@ -146,3 +1412,983 @@ impl<T> ArgumentBuffer<T> for Void {
         self.0
     }
 }
+
+/// Argument buffer discarding every value and only counting them, to generate
+/// the sql text of a command (e.g. for logging or migrations).
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{CountingArguments, SqlCommand, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<CountingArguments> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, "id");
+/// from_table(&mut sql, "user");
+/// filter_where(&mut sql);
+/// lhs_binary_rhs(&mut sql, sqlexpr::<u32>("age"), Cmp::Gt, sqlvalue(18))?;
+///
+/// assert_eq!(sql.arguments.count(), 1);
+/// assert_eq!(sql.into_command(), "SELECT id FROM user WHERE age > $1");
+/// # Ok(())
+/// # }
+/// ```
+pub type CountingArguments = Void;
+
+/// Argument buffer holding the values in a [Vec], converted into `T`.
+///
+/// Every push reserves its space with [Vec::try_reserve], returning a
+/// [Reserve](VecArgumentsError::Reserve) error instead of aborting when the
+/// allocation fails. A push or append past `u32::MAX` values, more than the
+/// placeholders can number, returns a
+/// [TooManyArguments](VecArgumentsError::TooManyArguments) error.
+///
+/// A `VecArguments<Option<T>>` binds both `T` and `Option<T>` values, storing
+/// `None` for a `NULL`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, VecArguments, VecArgumentsError, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{select, columns_iter, from_table, filter_where, lhs_binary_rhs, continue_condition, Cmp, LogicBi};
+/// #[derive(Debug, PartialEq)]
+/// enum Value {
+///     Int(i64),
+///     Text(String),
+/// }
+///
+/// impl From<i64> for Value {
+///     fn from(value: i64) -> Self {
+///         Self::Int(value)
+///     }
+/// }
+///
+/// impl From<&str> for Value {
+///     fn from(value: &str) -> Self {
+///         Self::Text(value.to_owned())
+///     }
+/// }
+///
+/// # fn main() -> Result<(), SqlError<VecArgumentsError>> {
+/// let mut sql = SqlCommand::new(String::new(), VecArguments::<Value>::new());
+/// select(&mut sql);
+/// columns_iter(&mut sql, ["id", "name"]);
+/// from_table(&mut sql, "user");
+/// filter_where(&mut sql);
+/// lhs_binary_rhs(&mut sql, sqlexpr::<i64>("age"), Cmp::Gt, sqlvalue(18))?;
+/// continue_condition(&mut sql, LogicBi::And);
+/// lhs_binary_rhs(&mut sql, sqlexpr::<&str>("name"), Cmp::Neq, sqlvalue("admin"))?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT id, name FROM user WHERE age > $1 AND name <> $2"
+/// );
+/// assert_eq!(
+///     sql.arguments.into_inner(),
+///     [Value::Int(18), Value::Text("admin".to_owned())]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+pub struct VecArguments<T>(Vec<T>);
+
+impl<T> VecArguments<T> {
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity(capacity))
+    }
+
+    /// Returns the values pushed.
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    /// Returns the [Vec] of the values pushed, to bind them with a driver.
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+
+    /// Number of values, which [push](ArgumentBuffer::push) and
+    /// [append](AppendArguments::append) keep within `u32`.
+    fn len_u32(&self) -> u32 {
+        u32::try_from(self.0.len()).unwrap_or(u32::MAX)
+    }
+
+    /// Checks that `additional` values can be added within `u32::MAX` values.
+    fn check_room(&self, additional: usize) -> Result<(), VecArgumentsError> {
+        u32::try_from(additional)
+            .ok()
+            .and_then(|additional| self.len_u32().checked_add(additional))
+            .ok_or(VecArgumentsError::TooManyArguments)?;
+        Ok(())
+    }
+}
+
+impl<T> Default for VecArguments<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ArgumentError for VecArguments<T> {
+    type Error = VecArgumentsError;
+}
+
+impl<T> AppendArguments for VecArguments<T> {
+    type Error = VecArgumentsError;
+
+    fn argument_count(&self) -> u32 {
+        self.len_u32()
+    }
+
+    fn append(&mut self, other: Self) -> Result<(), Self::Error> {
+        self.check_room(other.0.len())?;
+        self.0.try_reserve(other.0.len())?;
+        self.0.extend(other.0);
+        Ok(())
+    }
+}
+
+impl<T, U> ArgumentBuffer<U> for VecArguments<T>
+where
+    U: Into<T>,
+{
+    type Error = VecArgumentsError;
+
+    fn push(&mut self, value: U) -> Result<(), Self::Error> {
+        self.check_room(1)?;
+        self.0.try_reserve(1)?;
+        self.0.push(value.into());
+        Ok(())
+    }
+
+    fn count(&self) -> u32 {
+        self.len_u32()
+    }
+}
+
+/// Error of a [VecArguments] buffer.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+pub enum VecArgumentsError {
+    /// The allocation of the values failed.
+    Reserve(TryReserveError),
+    /// The buffer would hold more than `u32::MAX` values.
+    TooManyArguments,
+}
+
+impl From<TryReserveError> for VecArgumentsError {
+    fn from(err: TryReserveError) -> Self {
+        Self::Reserve(err)
+    }
+}
+
+#[cfg(feature = "fmt")]
+impl core::fmt::Display for VecArgumentsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Reserve(err) => write!(f, "argument allocation error: {err}"),
+            Self::TooManyArguments => write!(f, "more than {} arguments", u32::MAX),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VecArgumentsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Reserve(err) => Some(err),
+            Self::TooManyArguments => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::expr::{
+        columns_iter, continue_condition, filter_where, from_table, lhs_binary_rhs, limit, select,
+        string_literal, Cmp, LogicBi, OrderByNulls, OrderByOrd, RowLockStrength,
+    };
+    use crate::test::{FailingAppend, TestArgs};
+
+    type Error = core::fmt::Error;
+
+    #[test]
+    fn wrap_as_subquery_placeholders() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        columns_iter(&mut sql, ["id", "name"]);
+        from_table(&mut sql, "user");
+        filter_where(&mut sql);
+        lhs_binary_rhs(
+            &mut sql,
+            sqlexpr::<&str>("name"),
+            Cmp::Neq,
+            sqlvalue("admin"),
+        )
+        .unwrap();
+        limit(&mut sql, 10).unwrap();
+
+        let mut sql = sql.wrap_as_subquery("u", ArgumentsStrategy::Move).unwrap();
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("u.id"), Cmp::Gt, sqlvalue(20)).unwrap();
+        limit(&mut sql, 5).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM (SELECT id, name FROM user WHERE name <> $1 LIMIT $2) AS u WHERE u.id > $3 LIMIT $4"
+        );
+        assert_eq!(sql.arguments.as_str(), "admin;10;20;5;");
+    }
+
+    #[test]
+    fn wrap_as_subquery_checked() {
+        let command = |text: &str, count: u32| {
+            let mut arguments = TestArgs::new();
+            for value in 0..count {
+                ArgumentBuffer::push(&mut arguments, value).unwrap();
+            }
+            SqlCommand::new(text.to_owned(), arguments)
+        };
+
+        let mut sql = command("SELECT * FROM t WHERE a = $2 OR b = $1", 2)
+            .wrap_as_subquery("t", ArgumentsStrategy::Checked)
+            .unwrap();
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("t.c"), Cmp::Eq, sqlvalue(9)).unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM (SELECT * FROM t WHERE a = $2 OR b = $1) AS t WHERE t.c = $3"
+        );
+
+        assert_eq!(
+            command("SELECT * FROM t WHERE a = $2", 2)
+                .wrap_as_subquery("t", ArgumentsStrategy::Checked),
+            Err(SqlError::InvalidPlaceholder { position: 26 })
+        );
+        assert_eq!(
+            command("SELECT * FROM t WHERE a = $1", 2)
+                .wrap_as_subquery("t", ArgumentsStrategy::Checked),
+            Err(SqlError::ArgumentCountMismatch {
+                expected: 1,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn continue_building_tail() {
+        let mut arguments = TestArgs::new();
+        ArgumentBuffer::push(&mut arguments, "login").unwrap();
+        let mut sql = SqlCommand::new("SELECT id FROM event WHERE kind = $1".into(), arguments);
+
+        sql.continue_building()
+            .order_by([("id", OrderByOrd::Desc, OrderByNulls::Default)])
+            .limit(10)
+            .unwrap()
+            .offset(20)
+            .unwrap()
+            .row_lock(RowLockStrength::Share, [], None);
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT id FROM event WHERE kind = $1 ORDER BY id DESC LIMIT $2 OFFSET $3 FOR SHARE"
+        );
+        assert_eq!(sql.arguments.as_str(), "login;10;20;");
+    }
+
+    #[test]
+    fn union_arguments_both_sides() {
+        let mut left: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut left);
+        columns_iter(&mut left, ["id", "name"]);
+        from_table(&mut left, "user");
+        filter_where(&mut left);
+        lhs_binary_rhs(&mut left, sqlexpr::<u32>("age"), Cmp::Gt, sqlvalue(18)).unwrap();
+        limit(&mut left, 10).unwrap();
+
+        let mut right: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut right);
+        columns_iter(&mut right, ["id", "name"]);
+        from_table(&mut right, "admin");
+        filter_where(&mut right);
+        lhs_binary_rhs(
+            &mut right,
+            sqlexpr::<&str>("role"),
+            Cmp::Eq,
+            sqlvalue("owner"),
+        )
+        .unwrap();
+        right.push_cmd(" AND note <> '$1'");
+
+        let sql = left.clone().union(right.clone()).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "(SELECT id, name FROM user WHERE age > $1 LIMIT $2) UNION SELECT id, name FROM admin WHERE role = $3 AND note <> '$1'"
+        );
+        assert_eq!(sql.arguments.as_str(), "18;10;owner;");
+
+        let sql = right.union_all(left).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT id, name FROM admin WHERE role = $1 AND note <> '$1' UNION ALL (SELECT id, name FROM user WHERE age > $2 LIMIT $3)"
+        );
+        assert_eq!(sql.arguments.as_str(), "owner;18;10;");
+    }
+
+    #[test]
+    fn set_operation_tail_clauses() {
+        let command = |text: &str| SqlCommand::new(text.to_owned(), TestArgs::new());
+
+        let sql = command("SELECT id FROM a ORDER BY id")
+            .intersect(command("SELECT id FROM b for update"))
+            .unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "(SELECT id FROM a ORDER BY id) INTERSECT (SELECT id FROM b for update)"
+        );
+
+        let sql = command("SELECT order_id FROM a WHERE note = 'LIMIT'")
+            .union(command(
+                "SELECT id FROM b WHERE id IN (SELECT id FROM c LIMIT 1)",
+            ))
+            .unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "SELECT order_id FROM a WHERE note = 'LIMIT' \
+             UNION SELECT id FROM b WHERE id IN (SELECT id FROM c LIMIT 1)"
+        );
+
+        let sql = command("(SELECT id FROM a LIMIT 1)")
+            .except_all(command("SELECT id FROM b OFFSET 2"))
+            .unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "(SELECT id FROM a LIMIT 1) EXCEPT ALL (SELECT id FROM b OFFSET 2)"
+        );
+    }
+
+    #[test]
+    fn except_parenthesized_sides() {
+        let mut left: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut left);
+        columns_iter(&mut left, ["id"]);
+        from_table(&mut left, "user");
+        filter_where(&mut left);
+        lhs_binary_rhs(&mut left, sqlexpr::<u32>("age"), Cmp::Gt, sqlvalue(18)).unwrap();
+        left.push_cmd(" ORDER BY x");
+        limit(&mut left, 1).unwrap();
+
+        let mut right: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut right);
+        columns_iter(&mut right, ["user_id"]);
+        from_table(&mut right, "ban");
+        filter_where(&mut right);
+        lhs_binary_rhs(
+            &mut right,
+            sqlexpr::<&str>("kind"),
+            Cmp::Eq,
+            sqlvalue("spam"),
+        )
+        .unwrap();
+
+        let sql = left
+            .clone()
+            .parenthesized()
+            .except(right.clone().parenthesized())
+            .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "(SELECT id FROM user WHERE age > $1 ORDER BY x LIMIT $2) EXCEPT (SELECT user_id FROM ban WHERE kind = $3)"
+        );
+        assert_eq!(sql.arguments.as_str(), "18;1;spam;");
+
+        let sql = right.clone().intersect(right.clone()).unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "SELECT user_id FROM ban WHERE kind = $1 INTERSECT SELECT user_id FROM ban WHERE kind = $2"
+        );
+
+        let sql = right.clone().intersect_all(right.clone()).unwrap();
+        assert!(sql.as_command().contains(" INTERSECT ALL "));
+
+        let sql = right.except_all(left.parenthesized()).unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "SELECT user_id FROM ban WHERE kind = $1 EXCEPT ALL (SELECT id FROM user WHERE age > $2 ORDER BY x LIMIT $3)"
+        );
+        assert_eq!(sql.arguments.as_str(), "spam;18;1;");
+    }
+
+    #[test]
+    fn union_invalid_placeholder() {
+        let left = SqlCommand::new("SELECT 1".into(), TestArgs::new());
+        let right = SqlCommand::new("SELECT $0".into(), TestArgs::new());
+
+        assert_eq!(
+            left.union(right),
+            Err(SqlError::InvalidPlaceholder { position: 7 })
+        );
+    }
+
+    /// Executor choosing how to run a command from its options.
+    fn mock_execute(sql: SqlCommand<TestArgs>) -> (&'static str, Option<Duration>) {
+        let (_, _, options) = sql.into_parts_with_options().unwrap();
+        let mode = match options {
+            ExecOptions { row_mode: true, .. } => "fetch_many",
+            ExecOptions {
+                fetch_size: Some(_),
+                ..
+            } => "portal",
+            _ => "fetch_all",
+        };
+        (mode, options.statement_timeout)
+    }
+
+    #[test]
+    fn exec_options_survive_building() {
+        let mut sql: SqlCommand<TestArgs> =
+            SqlCommand::default().with_statement_timeout(Duration::from_secs(5));
+
+        select(&mut sql);
+        columns_iter(&mut sql, ["id"]);
+        from_table(&mut sql, "event");
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("id"), Cmp::Gt, sqlvalue(10)).unwrap();
+
+        let sql = sql
+            .with_fetch_size(100)
+            .wrap_as_subquery("e", ArgumentsStrategy::Move)
+            .unwrap();
+        assert_eq!(
+            sql.options(),
+            &ExecOptions {
+                fetch_size: Some(100),
+                row_mode: false,
+                statement_timeout: Some(Duration::from_secs(5)),
+            }
+        );
+        assert_eq!(
+            mock_execute(sql.clone()),
+            ("portal", Some(Duration::from_secs(5)))
+        );
+        assert_eq!(
+            mock_execute(sql.clone().with_row_mode(true)),
+            ("fetch_many", Some(Duration::from_secs(5)))
+        );
+
+        let (command, arguments) = sql.into_parts().unwrap();
+        assert_eq!(
+            command,
+            "SELECT * FROM (SELECT id FROM event WHERE id > $1) AS e"
+        );
+        assert_eq!(arguments.as_str(), "10;");
+        assert_eq!(
+            mock_execute(SqlCommand::new(command, arguments)),
+            ("fetch_all", None)
+        );
+    }
+
+    #[cfg(feature = "tables")]
+    #[test]
+    fn tables_joined_query() {
+        use crate::expr::{column, from_table_as, join, join_on, static_from, JoinType};
+        use crate::static_from_tables;
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "u.name, p.title, c.body");
+        from_table_as(&mut sql, "auth.user", "u");
+        join(&mut sql, JoinType::Inner, "blog.post", Some("p"));
+        join_on(&mut sql);
+        sql.push_cmd(" p.author_id = u.id");
+        join(&mut sql, JoinType::Left, "comment", None);
+        join_on(&mut sql);
+        sql.push_cmd(" comment.post_id = p.id");
+
+        assert_eq!(sql.tables(), ["auth.user", "blog.post", "comment"]);
+
+        let mut sql = sql.wrap_as_subquery("t", ArgumentsStrategy::Move).unwrap();
+        static_from(
+            &mut sql,
+            static_from_tables!(FROM "audit" AS "a", ONLY "event"),
+        );
+        assert_eq!(
+            sql.tables(),
+            ["auth.user", "blog.post", "comment", "audit", "event"]
+        );
+    }
+
+    #[cfg(feature = "tables")]
+    #[test]
+    fn tables_aliased_schema_qualified_joins() {
+        use crate::expr::{also_only, cross_join, from_as, from_table, join, JoinType};
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        sql.push_cmd(" *");
+        from_table(&mut sql, "auth.user u");
+        from_as(&mut sql, "ONLY sales.order", "o");
+        also_only(&mut sql, "archive.user AS au");
+        join(&mut sql, JoinType::Inner, "blog.post AS p", None);
+        cross_join(&mut sql, "audit.event e");
+
+        assert_eq!(
+            sql.tables(),
+            [
+                "auth.user",
+                "sales.order",
+                "archive.user",
+                "blog.post",
+                "audit.event"
+            ]
+        );
+    }
+
+    #[cfg(feature = "tables")]
+    #[test]
+    fn tables_skip_functions_and_aliases() {
+        use crate::expr::{also_table, from_function, from_table};
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        from_function(&mut sql, "generate_series", [1, 3]).unwrap();
+        also_table(&mut sql, "team t");
+
+        let mut other: SqlCommand<TestArgs> = SqlCommand::default();
+        from_table(&mut other, "user AS u");
+
+        assert_eq!(sql.tables(), ["team"]);
+        assert_eq!(other.tables(), ["user"]);
+    }
+
+    #[cfg(feature = "reserve-exact")]
+    #[test]
+    fn reserve_exact_command_capacity() {
+        let mut sql: SqlCommand<Void> = SqlCommand::default();
+
+        select(&mut sql);
+        columns_iter(&mut sql, ["id", "name", "email"]);
+        from_table(&mut sql, "user");
+        filter_where(&mut sql);
+        for id in 0..12 {
+            lhs_binary_rhs(&mut sql, sqlexpr::<u32>("id"), Cmp::Neq, sqlvalue(id)).unwrap();
+            sql.push_cmd(" AND ");
+        }
+        sql.push_cmd("TRUE");
+
+        assert!(sql.command.capacity() <= sql.command.len() * 2);
+        assert!(sql.command.capacity() >= sql.command.len());
+    }
+
+    #[cfg(feature = "reserve-exact")]
+    #[test]
+    fn reserve_exact_placeholder_digits() {
+        let mut sql = SqlCommand::new(String::new(), Void::new());
+        sql.push_value(0).unwrap();
+        assert_eq!(sql.as_command(), "$1");
+        assert_eq!(sql.command.capacity(), sql.command.len());
+
+        let mut sql = SqlCommand::new(String::new(), Void::with_count(u32::MAX - 1));
+        sql.push_value(0).unwrap();
+        assert_eq!(sql.as_command(), "$4294967295");
+        assert_eq!(sql.command.capacity(), sql.command.len());
+    }
+
+    #[test]
+    fn push_string_same_as_push_cmd() {
+        let fragment = |capacity: usize| {
+            let mut fragment = String::with_capacity(capacity);
+            fragment.push_str(" WHERE id IN (1, 2, 3)");
+            fragment
+        };
+
+        for capacity in [0, 64] {
+            for head in ["", "SELECT * FROM user"] {
+                let mut borrowed: SqlCommand<Void> = SqlCommand::default();
+                borrowed.push_cmd(head);
+                borrowed.push_cmd(&fragment(capacity));
+                borrowed.push_cmd(" LIMIT 1");
+
+                let mut owned: SqlCommand<Void> = SqlCommand::default();
+                owned.push_cmd(head);
+                owned.push_string(fragment(capacity));
+                owned.push_cmd(" LIMIT 1");
+
+                assert_eq!(owned.as_command(), borrowed.as_command());
+            }
+        }
+    }
+
+    #[test]
+    fn push_string_reuses_allocation() {
+        let mut fragment = String::with_capacity(64);
+        fragment.push_str("id IN (1, 2)");
+        let ptr = fragment.as_ptr();
+
+        let mut sql: SqlCommand<Void> = SqlCommand::default();
+        sql.push_string(fragment);
+        assert_eq!(sql.command.as_ptr(), ptr);
+
+        let mut sql = SqlCommand::new(String::from("SELECT "), Void::new());
+        sql.command.shrink_to_fit();
+        let mut fragment = String::with_capacity(64);
+        fragment.push_str("id IN (1, 2)");
+        let ptr = fragment.as_ptr();
+        sql.push_string(fragment);
+        assert_eq!(sql.as_command(), "SELECT id IN (1, 2)");
+        assert_eq!(sql.command.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn invalid_text_positions() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        columns_iter(&mut sql, ["id", "na\0me"]);
+        from_table(&mut sql, "user");
+        assert_eq!(
+            sql.check_text(),
+            Err(SqlError::InvalidText { position: 13 })
+        );
+        assert_eq!(sql.as_command(), "SELECT id, na\0me FROM user");
+
+        let sql = sql.wrap_as_subquery("u", ArgumentsStrategy::Move).unwrap();
+        assert_eq!(
+            sql.check_text(),
+            Err(SqlError::InvalidText { position: 28 })
+        );
+        let sql = sql.parenthesized();
+        assert_eq!(
+            sql.check_text(),
+            Err(SqlError::InvalidText { position: 29 })
+        );
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        sql.push_cmd(" 1 -- \0");
+        assert_eq!(
+            sql.check_text(),
+            Err(SqlError::InvalidText { position: 12 })
+        );
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        assert_eq!(
+            string_literal(&mut sql, "a\0b"),
+            Err(SqlError::InvalidText { position: 1 })
+        );
+        assert_eq!(sql.check_text(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_text_strictness() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        sql.push_cmd("SELECT\n\t1\x1b");
+        assert_eq!(sql.check_text(), Ok(()));
+
+        let mut sql: SqlCommand<TestArgs> =
+            SqlCommand::default().with_text_check(TextCheck::Control);
+        sql.push_cmd("SELECT\n\t1");
+        assert_eq!(sql.check_text(), Ok(()));
+        sql.push_cmd("\x1b");
+        assert_eq!(sql.check_text(), Err(SqlError::InvalidText { position: 9 }));
+    }
+
+    #[test]
+    fn invalid_text_combined() {
+        let mut inner: SqlCommand<TestArgs> = SqlCommand::default();
+        inner.push_cmd("SELECT $1");
+        inner.arguments = TestArgs::new();
+        ArgumentBuffer::push(&mut inner.arguments, 1).unwrap();
+        inner.push_cmd(" -- \0");
+
+        let mut outer: SqlCommand<TestArgs> = SqlCommand::default();
+        outer.push_value(2).unwrap();
+        outer.push_cmd(" IN (");
+        outer.push_command(inner).unwrap();
+        assert_eq!(outer.as_command(), "$1 IN (SELECT $2 -- \0");
+        assert_eq!(
+            outer.check_text(),
+            Err(SqlError::InvalidText { position: 20 })
+        );
+
+        let mut owned: SqlCommand<TestArgs> = SqlCommand::default();
+        owned.push_string(String::from("SELECT \0"));
+        assert_eq!(
+            owned.check_text(),
+            Err(SqlError::InvalidText { position: 7 })
+        );
+    }
+
+    #[test]
+    fn invalid_text_unchecked_writes() {
+        let sql = SqlCommand::new(String::from("SELECT '\0'"), TestArgs::new());
+        assert_eq!(sql.check_text(), Err(SqlError::InvalidText { position: 8 }));
+        assert_eq!(
+            sql.clone().into_parts(),
+            Err(SqlError::InvalidText { position: 8 })
+        );
+        let mut script = crate::ScriptWriter::new(String::new());
+        assert_eq!(
+            script.write(&sql),
+            Err(SqlError::InvalidText { position: 8 })
+        );
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        sql.command.push_str(" '\0'");
+        columns_iter(&mut sql, ["id"]);
+        assert_eq!(sql.check_text(), Err(SqlError::InvalidText { position: 8 }));
+
+        let mut inner: SqlCommand<TestArgs> = SqlCommand::default();
+        inner.command.push_str("SELECT \0");
+        let mut outer: SqlCommand<TestArgs> = SqlCommand::default();
+        outer.push_cmd("EXISTS (");
+        outer.push_command(inner).unwrap();
+        outer.push_cmd(")");
+        assert_eq!(
+            outer.check_text(),
+            Err(SqlError::InvalidText { position: 15 })
+        );
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        sql.command.push_str("SELECT \0");
+        let sql = sql.parenthesized();
+        assert_eq!(sql.check_text(), Err(SqlError::InvalidText { position: 8 }));
+    }
+
+    #[test]
+    fn push_command_append_error() {
+        let mut inner: SqlCommand<FailingAppend> = SqlCommand::default();
+        inner.push_cmd("SELECT ");
+        inner.push_value(1).unwrap();
+
+        let mut outer: SqlCommand<FailingAppend> = SqlCommand::default();
+        outer.push_value(2).unwrap();
+        outer.push_cmd(" IN (");
+        assert_eq!(
+            outer.push_command(inner),
+            Err(SqlError::Argument(core::fmt::Error))
+        );
+        assert_eq!(outer.as_command(), "$1 IN (");
+        assert_eq!(outer.arguments.argument_count(), 1);
+    }
+
+    #[test]
+    fn prepare_and_execute() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        columns_iter(&mut sql, ["id", "name"]);
+        from_table(&mut sql, "user");
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("team_id"), Cmp::Eq, sqlvalue(3)).unwrap();
+        limit(&mut sql, 10).unwrap();
+
+        let (prepare, arguments) = sql.into_prepare("team_users").unwrap();
+        assert_eq!(
+            prepare.as_command(),
+            "PREPARE team_users AS SELECT id, name FROM user WHERE team_id = $1 LIMIT $2"
+        );
+        let (_, prepare_arguments) = prepare.into_parts().unwrap();
+        assert_eq!(ArgumentBuffer::<u32>::count(&prepare_arguments), 0);
+
+        let execute = execute_prepared("team_users", arguments).unwrap();
+        assert_eq!(execute.as_command(), "EXECUTE team_users ($1, $2)");
+        let (_, arguments) = execute.into_parts().unwrap();
+        assert_eq!(arguments.as_str(), "3;10;");
+    }
+
+    #[test]
+    fn execute_prepared_without_arguments() {
+        let (sql, arguments) = SqlCommand::new(String::from("SELECT now()"), TestArgs::new())
+            .into_prepare("clock")
+            .unwrap();
+        assert_eq!(sql.as_command(), "PREPARE clock AS SELECT now()");
+
+        let execute = execute_prepared("clock", arguments).unwrap();
+        assert_eq!(execute.as_command(), "EXECUTE clock");
+        assert_eq!(execute.arguments.as_str(), "");
+    }
+
+    #[test]
+    fn prepared_statement_names_checked() {
+        let sql = || SqlCommand::new(String::from("SELECT 1"), TestArgs::new());
+
+        assert_eq!(
+            sql().into_prepare("a; DROP TABLE user"),
+            Err(SqlError::InvalidText { position: 9 })
+        );
+        assert_eq!(
+            sql().into_prepare(""),
+            Err(SqlError::InvalidText { position: 8 })
+        );
+        assert_eq!(
+            sql().into_prepare(&"s".repeat(64)),
+            Err(SqlError::IdentifierTooLong { len: 64, max: 63 })
+        );
+        assert_eq!(
+            execute_prepared("1st", TestArgs::new()),
+            Err(SqlError::InvalidText { position: 8 })
+        );
+        assert_eq!(
+            execute_prepared("a\0", TestArgs::new()),
+            Err(SqlError::InvalidText { position: 9 })
+        );
+    }
+
+    #[test]
+    fn vec_arguments_union() {
+        let mut first = SqlCommand::new(String::new(), VecArguments::<u64>::new());
+        select(&mut first);
+        columns_iter(&mut first, ["id"]);
+        from_table(&mut first, "user");
+        filter_where(&mut first);
+        lhs_binary_rhs(&mut first, sqlexpr::<u32>("age"), Cmp::Gt, sqlvalue(18_u32)).unwrap();
+        assert_eq!(ArgumentBuffer::<u32>::count(&first.arguments), 1);
+
+        let mut second = SqlCommand::new(String::new(), VecArguments::<u64>::with_capacity(1));
+        select(&mut second);
+        columns_iter(&mut second, ["id"]);
+        from_table(&mut second, "admin");
+        filter_where(&mut second);
+        lhs_binary_rhs(
+            &mut second,
+            sqlexpr::<u32>("level"),
+            Cmp::Eq,
+            sqlvalue(5_u32),
+        )
+        .unwrap();
+
+        let sql = first.union(second).unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "SELECT id FROM user WHERE age > $1 UNION SELECT id FROM admin WHERE level = $2"
+        );
+        assert_eq!(sql.arguments.as_slice(), [18, 5]);
+        assert_eq!(sql.arguments.into_inner(), [18, 5]);
+    }
+
+    #[test]
+    #[allow(clippy::uninit_vec)]
+    fn vec_arguments_too_many() {
+        let mut values: Vec<()> = Vec::new();
+        // SAFETY: `()` is zero sized, so every element is already initialized
+        unsafe { values.set_len(u32::MAX as usize) };
+        let mut arguments = VecArguments(values);
+        assert_eq!(ArgumentBuffer::<()>::count(&arguments), u32::MAX);
+
+        assert_eq!(
+            ArgumentBuffer::push(&mut arguments, ()),
+            Err(VecArgumentsError::TooManyArguments)
+        );
+        assert_eq!(
+            arguments.append(VecArguments(Vec::from([()]))),
+            Err(VecArgumentsError::TooManyArguments)
+        );
+        assert_eq!(ArgumentBuffer::<()>::count(&arguments), u32::MAX);
+    }
+
+    #[test]
+    fn counting_arguments_numbering() {
+        let mut sql: SqlCommand<CountingArguments> = SqlCommand::default();
+        select(&mut sql);
+        columns_iter(&mut sql, ["id"]);
+        from_table(&mut sql, "event");
+        filter_where(&mut sql);
+        let pushed: Result<(), Infallible> = lhs_binary_rhs(
+            &mut sql,
+            sqlexpr::<&str>("kind"),
+            Cmp::Eq,
+            sqlvalue("login"),
+        );
+        pushed.unwrap();
+        assert_eq!(sql.arguments.count(), 1);
+        assert!(sql.as_command().ends_with("$1"));
+
+        continue_condition(&mut sql, LogicBi::And);
+        lhs_binary_rhs(
+            &mut sql,
+            sqlexpr::<u64>("user_id"),
+            Cmp::Eq,
+            sqlvalue(7_u64),
+        )
+        .unwrap();
+        assert_eq!(sql.arguments.count(), 2);
+        assert!(sql.as_command().ends_with("$2"));
+
+        limit(&mut sql, 10).unwrap();
+        assert_eq!(sql.arguments.count(), 3);
+        assert_eq!(
+            crate::placeholder::placeholder_count(sql.as_command()),
+            Ok(sql.arguments.count())
+        );
+        assert_eq!(
+            sql.into_command(),
+            "SELECT id FROM event WHERE kind = $1 AND user_id = $2 LIMIT $3"
+        );
+
+        let mut sql = SqlCommand::new(String::new(), CountingArguments::with_count(41));
+        sql.push_value("late").unwrap();
+        assert_eq!(sql.arguments.count(), 42);
+        assert_eq!(sql.as_command(), "$42");
+    }
+
+    #[test]
+    fn buffer_lent_to_closure() {
+        let mut arguments = TestArgs::new();
+
+        let build_subquery = |arguments: &mut TestArgs| -> Result<String, Error> {
+            let mut sql = SqlCommand::new(String::new(), arguments);
+            select(&mut sql);
+            columns_iter(&mut sql, ["user_id"]);
+            from_table(&mut sql, "member");
+            filter_where(&mut sql);
+            lhs_binary_rhs(&mut sql, sqlexpr::<u32>("team_id"), Cmp::Eq, sqlvalue(3))?;
+            Ok(sql.into_command())
+        };
+        let subquery = build_subquery(&mut arguments).unwrap();
+
+        let mut sql = SqlCommand::new(String::new(), &mut arguments);
+        select(&mut sql);
+        columns_iter(&mut sql, ["id"]);
+        from_table(&mut sql, "user");
+        filter_where(&mut sql);
+        sql.push_cmd(" id IN (");
+        sql.push_cmd(&subquery);
+        sql.push_cmd(")");
+        continue_condition(&mut sql, LogicBi::And);
+        sql.push_cmd(" role IN (");
+        sql.push_value("admin").unwrap();
+        sql.push_cmd(", ");
+        sql.push_value("owner").unwrap();
+        sql.push_cmd(")");
+        let command = sql.into_command();
+
+        assert_eq!(
+            command,
+            "SELECT id FROM user WHERE id IN (SELECT user_id FROM member WHERE team_id = $1) \
+             AND role IN ($2, $3)"
+        );
+
+        let mut lent = &mut arguments;
+        ArgumentBuffer::<u32>::push_all(&mut lent, [4, 5]).unwrap();
+        assert_eq!(arguments.as_str(), "3;admin;owner;4;5;");
+        assert_eq!(ArgumentBuffer::<u32>::count(&arguments), 5);
+    }
+}
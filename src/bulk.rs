@@ -0,0 +1,168 @@
+//! Row limits of bulk commands.
+//!
+//! The Postgres protocol limits the number of bind parameters of a command to
+//! [MAX_BIND_PARAMETERS]. These helpers compute how many rows of a
+//! multi-row `VALUES` list fit in a single command, and split the rows into
+//! chunks that do.
+
+use core::convert::Infallible;
+
+use crate::SqlError;
+
+/// Maximum number of bind parameters of a command in the Postgres protocol.
+pub const MAX_BIND_PARAMETERS: u32 = u16::MAX as u32;
+
+/// Maximum number of rows, with `params_per_row` bound values each, that fit
+/// in a single command also binding `reserved_params` values outside the
+/// rows.
+///
+/// Rows without bound values are not limited, returning `u32::MAX`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::bulk::max_rows;
+/// assert_eq!(max_rows(5, 0), 13107);
+/// assert_eq!(max_rows(3, 1), 21844);
+/// assert_eq!(max_rows(0, 10), u32::MAX);
+/// ```
+pub const fn max_rows(params_per_row: u32, reserved_params: u32) -> u32 {
+    match MAX_BIND_PARAMETERS
+        .saturating_sub(reserved_params)
+        .checked_div(params_per_row)
+    {
+        Some(rows) => rows,
+        None => u32::MAX,
+    }
+}
+
+/// Splits `rows` into chunks of at most [max_rows] rows.
+///
+/// The number of bound values of each row, `params_per_row`, is the arity
+/// passed to [values_rows](crate::expr::values_rows). If not even a single
+/// row fits in a command, no chunk is yielded, see [split_rows_checked] to
+/// report it as an error.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::bulk::split_rows;
+/// # use sqlstr::expr::{insert_into, insert_columns_iter, values, values_rows};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let points = vec![[0, 1]; 40_000];
+///
+/// let mut commands = Vec::new();
+/// for chunk in split_rows(&points, 2, 0) {
+///     let mut sql: SqlCommand<Void> = SqlCommand::default();
+///     insert_into(&mut sql, "point");
+///     let arity = insert_columns_iter(&mut sql, ["x", "y"])?;
+///     values(&mut sql);
+///     values_rows(&mut sql, arity, chunk)?;
+///     commands.push(sql);
+/// }
+///
+/// let counts: Vec<u32> = commands.iter().map(|sql| sql.arguments.count()).collect();
+/// assert_eq!(counts, [65534, 14466]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn split_rows<T>(
+    rows: &[T],
+    params_per_row: u32,
+    reserved_params: u32,
+) -> impl Iterator<Item = &[T]> {
+    let size = usize::try_from(max_rows(params_per_row, reserved_params)).unwrap_or(usize::MAX);
+    let rows: &[T] = if size == 0 { &[] } else { rows };
+    rows.chunks(size.max(1))
+}
+
+/// Splits `rows` into chunks of at most [max_rows] rows, see [split_rows].
+///
+/// If not even a single row fits in a command, returns a
+/// [TooManyArguments](SqlError::TooManyArguments) error.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::SqlError;
+/// # use sqlstr::bulk::{split_rows_checked, MAX_BIND_PARAMETERS};
+/// let rows = [[0; 3]; 4];
+///
+/// assert_eq!(split_rows_checked(&rows, 3, 0).map(Iterator::count), Ok(1));
+/// assert_eq!(
+///     split_rows_checked(&rows, 3, MAX_BIND_PARAMETERS - 2).map(Iterator::count),
+///     Err(SqlError::TooManyArguments { max: MAX_BIND_PARAMETERS })
+/// );
+/// ```
+pub fn split_rows_checked<T>(
+    rows: &[T],
+    params_per_row: u32,
+    reserved_params: u32,
+) -> Result<impl Iterator<Item = &[T]>, SqlError<Infallible>> {
+    if !rows.is_empty() && max_rows(params_per_row, reserved_params) == 0 {
+        return Err(SqlError::TooManyArguments {
+            max: MAX_BIND_PARAMETERS,
+        });
+    }
+    Ok(split_rows(rows, params_per_row, reserved_params))
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn max_rows_at_limit() {
+        assert_eq!(max_rows(5, 0), 13107);
+        assert_eq!(max_rows(5, 0) * 5, MAX_BIND_PARAMETERS);
+        assert_eq!(max_rows(1, 0), MAX_BIND_PARAMETERS);
+        assert_eq!(max_rows(4, 3), 16383);
+        assert_eq!(max_rows(4, 3) * 4 + 3, MAX_BIND_PARAMETERS);
+        assert_eq!(max_rows(2, 0), 32767);
+    }
+
+    #[test]
+    fn max_rows_not_fitting() {
+        assert_eq!(max_rows(MAX_BIND_PARAMETERS + 1, 0), 0);
+        assert_eq!(max_rows(1, MAX_BIND_PARAMETERS), 0);
+        assert_eq!(max_rows(1, u32::MAX), 0);
+        assert_eq!(max_rows(0, 0), u32::MAX);
+    }
+
+    #[test]
+    fn split_rows_at_limit() {
+        let rows: Vec<u32> = (0..13107).collect();
+        let chunks: Vec<usize> = split_rows(&rows, 5, 0).map(<[u32]>::len).collect();
+        assert_eq!(chunks, [13107]);
+
+        let rows: Vec<u32> = (0..13108).collect();
+        let chunks: Vec<usize> = split_rows(&rows, 5, 0).map(<[u32]>::len).collect();
+        assert_eq!(chunks, [13107, 1]);
+
+        let chunks: Vec<usize> = split_rows(&rows, 5, 1).map(<[u32]>::len).collect();
+        assert_eq!(chunks, [13106, 2]);
+    }
+
+    #[test]
+    fn split_rows_not_fitting() {
+        let rows = [1, 2, 3];
+        assert_eq!(split_rows(&rows, 1, MAX_BIND_PARAMETERS).count(), 0);
+        assert_eq!(
+            split_rows_checked(&rows, 1, MAX_BIND_PARAMETERS).map(Iterator::count),
+            Err(SqlError::TooManyArguments {
+                max: MAX_BIND_PARAMETERS
+            })
+        );
+        assert_eq!(
+            split_rows_checked(&rows[..0], 1, MAX_BIND_PARAMETERS).map(Iterator::count),
+            Ok(0)
+        );
+
+        let chunks: Vec<&[u32]> = split_rows(&rows, 0, 0).collect();
+        assert_eq!(chunks, [[1, 2, 3]]);
+    }
+}
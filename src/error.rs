@@ -0,0 +1,243 @@
+use core::convert::Infallible;
+
+/// Error writing a sql command.
+///
+/// `E` is the error of the [ArgumentBuffer](crate::ArgumentBuffer) used by the
+/// command, which can be converted with `?` into this error.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+pub enum SqlError<E> {
+    /// Error pushing a value into the argument buffer.
+    Argument(E),
+    /// A row does not have the expected number of values.
+    RowLengthMismatch { expected: usize, found: usize },
+    /// A list that requires at least one item is empty.
+    EmptyList,
+    /// A text written into the command contains a character not allowed
+    /// (e.g. NUL) at the byte `position`.
+    InvalidText { position: usize },
+    /// A referenced common table expression was not defined in the `WITH`
+    /// clause.
+    UnknownCte,
+    /// A placeholder at the byte `position` of a command is not valid (e.g.
+    /// `$0`, out of range or out of sequence).
+    InvalidPlaceholder { position: usize },
+    /// An interval literal is not one of the supported intervals.
+    InvalidInterval,
+    /// An identifier of `len` bytes is longer than the `max` allowed.
+    IdentifierTooLong { len: usize, max: usize },
+    /// A command binds more than the `max` arguments allowed by the protocol.
+    TooManyArguments { max: u32 },
+    /// A `UPDATE` command has no column assigned.
+    NoAssignments,
+    /// A `CASE` expression has a `WHEN` arm without a `THEN` result.
+    MissingThen,
+    /// A `CASE` expression has a `THEN` result without a `WHEN` arm before
+    /// it, or a `WHEN` arm or `ELSE` result after its `ELSE` result.
+    MisplacedCaseClause,
+    /// A command joined to a batch is already terminated by the `;` at the
+    /// byte `position`.
+    TerminatedCommand { position: usize },
+    /// The placeholders of a command or fragment bind a number of arguments
+    /// other than the number of values given.
+    ArgumentCountMismatch { expected: usize, found: usize },
+}
+
+impl<E> SqlError<E> {
+    /// Stable identifier of the error kind, such as `"argument"` or
+    /// `"empty_list"`, suited for counting failures by kind.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::SqlError;
+    /// # use core::convert::Infallible;
+    /// let err: SqlError<Infallible> = SqlError::EmptyList;
+    ///
+    /// assert_eq!(err.code(), "empty_list");
+    /// ```
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::Argument(_) => "argument",
+            Self::RowLengthMismatch { .. } => "row_length_mismatch",
+            Self::EmptyList => "empty_list",
+            Self::InvalidText { .. } => "invalid_text",
+            Self::UnknownCte => "unknown_cte",
+            Self::InvalidPlaceholder { .. } => "invalid_placeholder",
+            Self::InvalidInterval => "invalid_interval",
+            Self::IdentifierTooLong { .. } => "identifier_too_long",
+            Self::TooManyArguments { .. } => "too_many_arguments",
+            Self::NoAssignments => "no_assignments",
+            Self::MissingThen => "missing_then",
+            Self::MisplacedCaseClause => "misplaced_case_clause",
+            Self::TerminatedCommand { .. } => "terminated_command",
+            Self::ArgumentCountMismatch { .. } => "argument_count_mismatch",
+        }
+    }
+
+    /// Returns `true` if the error was returned by the argument buffer.
+    pub const fn is_argument(&self) -> bool {
+        matches!(self, Self::Argument(_))
+    }
+}
+
+impl SqlError<Infallible> {
+    /// Converts an error returned without an argument buffer, such as by
+    /// [renumber_placeholders](crate::renumber_placeholders), into the error
+    /// of any buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::SqlError;
+    /// # use core::convert::Infallible;
+    /// let err: SqlError<Infallible> = SqlError::EmptyList;
+    /// let err: SqlError<core::fmt::Error> = err.widen();
+    ///
+    /// assert_eq!(err, SqlError::EmptyList);
+    /// ```
+    pub fn widen<E>(self) -> SqlError<E> {
+        match self {
+            Self::Argument(never) => match never {},
+            Self::RowLengthMismatch { expected, found } => {
+                SqlError::RowLengthMismatch { expected, found }
+            }
+            Self::EmptyList => SqlError::EmptyList,
+            Self::InvalidText { position } => SqlError::InvalidText { position },
+            Self::UnknownCte => SqlError::UnknownCte,
+            Self::InvalidPlaceholder { position } => SqlError::InvalidPlaceholder { position },
+            Self::InvalidInterval => SqlError::InvalidInterval,
+            Self::IdentifierTooLong { len, max } => SqlError::IdentifierTooLong { len, max },
+            Self::TooManyArguments { max } => SqlError::TooManyArguments { max },
+            Self::NoAssignments => SqlError::NoAssignments,
+            Self::MissingThen => SqlError::MissingThen,
+            Self::MisplacedCaseClause => SqlError::MisplacedCaseClause,
+            Self::TerminatedCommand { position } => SqlError::TerminatedCommand { position },
+            Self::ArgumentCountMismatch { expected, found } => {
+                SqlError::ArgumentCountMismatch { expected, found }
+            }
+        }
+    }
+}
+
+impl<E> From<E> for SqlError<E> {
+    fn from(value: E) -> Self {
+        Self::Argument(value)
+    }
+}
+
+#[cfg(feature = "fmt")]
+impl<E: core::fmt::Display> core::fmt::Display for SqlError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: ", self.code())?;
+        match self {
+            Self::Argument(err) => write!(f, "argument error: {err}"),
+            Self::RowLengthMismatch { expected, found } => {
+                write!(
+                    f,
+                    "row length mismatch: expected {expected} values, found {found}"
+                )
+            }
+            Self::EmptyList => f.write_str("empty list"),
+            Self::InvalidText { position } => {
+                write!(f, "invalid character in text at byte {position}")
+            }
+            Self::UnknownCte => f.write_str("unknown common table expression"),
+            Self::InvalidPlaceholder { position } => {
+                write!(f, "invalid placeholder at byte {position}")
+            }
+            Self::InvalidInterval => f.write_str("unsupported interval"),
+            Self::IdentifierTooLong { len, max } => {
+                write!(f, "identifier of {len} bytes is longer than {max} bytes")
+            }
+            Self::TooManyArguments { max } => write!(f, "more than {max} arguments"),
+            Self::NoAssignments => f.write_str("update without assignments"),
+            Self::MissingThen => f.write_str("case arm without result"),
+            Self::MisplacedCaseClause => f.write_str("case clause out of order"),
+            Self::TerminatedCommand { position } => {
+                write!(f, "command terminated by `;` at byte {position}")
+            }
+            Self::ArgumentCountMismatch { expected, found } => {
+                write!(
+                    f,
+                    "argument count mismatch: expected {expected} arguments, found {found}"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E> std::error::Error for SqlError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Argument(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SqlError;
+
+    type Error = SqlError<core::fmt::Error>;
+
+    #[test]
+    fn error_codes() {
+        let cases: [(Error, &str); 14] = [
+            (SqlError::Argument(core::fmt::Error), "argument"),
+            (
+                SqlError::RowLengthMismatch {
+                    expected: 2,
+                    found: 1,
+                },
+                "row_length_mismatch",
+            ),
+            (SqlError::EmptyList, "empty_list"),
+            (SqlError::InvalidText { position: 0 }, "invalid_text"),
+            (SqlError::UnknownCte, "unknown_cte"),
+            (
+                SqlError::InvalidPlaceholder { position: 0 },
+                "invalid_placeholder",
+            ),
+            (SqlError::InvalidInterval, "invalid_interval"),
+            (
+                SqlError::IdentifierTooLong { len: 64, max: 63 },
+                "identifier_too_long",
+            ),
+            (
+                SqlError::TooManyArguments { max: 65535 },
+                "too_many_arguments",
+            ),
+            (SqlError::NoAssignments, "no_assignments"),
+            (SqlError::MissingThen, "missing_then"),
+            (SqlError::MisplacedCaseClause, "misplaced_case_clause"),
+            (
+                SqlError::TerminatedCommand { position: 8 },
+                "terminated_command",
+            ),
+            (
+                SqlError::ArgumentCountMismatch {
+                    expected: 2,
+                    found: 1,
+                },
+                "argument_count_mismatch",
+            ),
+        ];
+
+        for (err, code) in cases {
+            assert_eq!(err.code(), code);
+            assert_eq!(err.is_argument(), code == "argument");
+
+            #[cfg(feature = "fmt")]
+            {
+                use alloc::string::ToString;
+                assert!(err.to_string().starts_with(code));
+            }
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use alloc::vec::Vec;
+
+use crate::SqlCommand;
+
+/// Executor of sql commands, implemented over a database driver.
+///
+/// Implementations take the text and arguments of the command with
+/// [into_parts](SqlCommand::into_parts), refusing a command with rejected
+/// text before it reaches the driver.
+///
+/// Code generic over the executor, such as a repository layer, can be tested
+/// without a database with the
+/// [RecordingExecutor](crate::testing::RecordingExecutor) of the `testing`
+/// feature.
+pub trait Execute<Arg> {
+    /// Row returned by a command.
+    type Row;
+    type Error;
+
+    /// Executes the command, returning the rows of its result.
+    fn execute(&mut self, command: SqlCommand<Arg>) -> Result<Vec<Self::Row>, Self::Error>;
+}
@@ -1,39 +1,65 @@
+mod aggregate;
+mod bind_row;
 mod cast;
 mod column;
 mod condition;
+mod conditional;
 mod delete;
 mod expression;
+mod fanout;
 mod filter;
 mod group;
 mod group_by;
+mod ident;
 mod insert;
 mod join;
 mod limit;
+mod literal;
 mod locking;
 mod order_by;
 mod returning;
 mod select;
 mod separator;
+mod sorted;
+mod subquery;
 mod table;
+mod tail;
+mod truncate;
 mod update;
 mod value;
+mod window;
+mod with;
 
+pub mod states;
+
+pub use aggregate::*;
+pub use bind_row::*;
 pub use cast::*;
 pub use column::*;
 pub use condition::*;
+pub use conditional::*;
 pub use delete::*;
 pub use expression::*;
+pub use fanout::*;
 pub use filter::*;
 pub use group::*;
 pub use group_by::*;
+pub use ident::*;
 pub use insert::*;
 pub use join::*;
 pub use limit::*;
+pub use literal::*;
 pub use locking::*;
 pub use order_by::*;
 pub use returning::*;
 pub use select::*;
 pub use separator::*;
+pub use sorted::*;
+pub use subquery::*;
 pub use table::*;
+pub use tail::*;
+pub use truncate::*;
 pub use update::*;
 pub use value::*;
+pub use window::*;
+pub use with::*;
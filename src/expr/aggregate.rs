@@ -0,0 +1,496 @@
+use super::{
+    emit, filter_where, order_by, separator_optional, subquery, Group, OrderByNulls, OrderByOrd,
+    Token,
+};
+use crate::WriteSql;
+
+fn aggregate_fn<Sql, Arg>(sql: &mut Sql, name: &str, distinct: bool, column: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    separator_optional(sql);
+    sql.push_cmd(name);
+    sql.push_cmd("(");
+    if distinct {
+        emit(sql, Token::Keyword("DISTINCT"));
+    }
+    emit(sql, Token::Ident(column));
+    sql.push_cmd(")");
+}
+
+/// Writes a `count(*)` aggregate.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, count_star, as_alias, from_table};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// count_star(&mut sql);
+/// as_alias(&mut sql, "total");
+/// from_table(&mut sql, "user");
+///
+/// assert_eq!(sql.as_command(), "SELECT count(*) AS total FROM user");
+/// ```
+pub fn count_star<Sql, Arg>(sql: &mut Sql)
+where
+    Sql: WriteSql<Arg>,
+{
+    aggregate_fn(sql, "count", false, "*");
+}
+
+/// Writes a `count(<column>)` aggregate.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, count};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// count(&mut sql, "email");
+///
+/// assert_eq!(sql.as_command(), "SELECT count(email)");
+/// ```
+pub fn count<Sql, Arg>(sql: &mut Sql, column: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    aggregate_fn(sql, "count", false, column);
+}
+
+/// Writes a `count(DISTINCT <column>)` aggregate.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, count_distinct};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// count_distinct(&mut sql, "country");
+///
+/// assert_eq!(sql.as_command(), "SELECT count(DISTINCT country)");
+/// ```
+pub fn count_distinct<Sql, Arg>(sql: &mut Sql, column: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    aggregate_fn(sql, "count", true, column);
+}
+
+/// Writes a `sum(<column>)` aggregate.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, sum};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// sum(&mut sql, "price");
+///
+/// assert_eq!(sql.as_command(), "SELECT sum(price)");
+/// ```
+pub fn sum<Sql, Arg>(sql: &mut Sql, column: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    aggregate_fn(sql, "sum", false, column);
+}
+
+/// Writes an `avg(<column>)` aggregate.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, avg};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// avg(&mut sql, "price");
+///
+/// assert_eq!(sql.as_command(), "SELECT avg(price)");
+/// ```
+pub fn avg<Sql, Arg>(sql: &mut Sql, column: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    aggregate_fn(sql, "avg", false, column);
+}
+
+/// Writes a `min(<column>)` aggregate.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, min};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// min(&mut sql, "created");
+///
+/// assert_eq!(sql.as_command(), "SELECT min(created)");
+/// ```
+pub fn min<Sql, Arg>(sql: &mut Sql, column: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    aggregate_fn(sql, "min", false, column);
+}
+
+/// Writes a `max(<column>)` aggregate.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, max};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// max(&mut sql, "created");
+///
+/// assert_eq!(sql.as_command(), "SELECT max(created)");
+/// ```
+pub fn max<Sql, Arg>(sql: &mut Sql, column: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    aggregate_fn(sql, "max", false, column);
+}
+
+/// Writes a `FILTER (WHERE <condition>)` clause for the previous aggregate.
+///
+/// The condition is written by `build` after the `WHERE` keyword, sharing the
+/// argument buffer with the outer command.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{select, count_star, aggregate_filter, as_alias, lhs_binary_rhs, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// count_star(&mut sql);
+/// aggregate_filter(&mut sql, |filter| {
+///     lhs_binary_rhs(filter, sqlexpr::<&str>("status"), Cmp::Eq, sqlvalue("paid"))
+/// })?;
+/// as_alias(&mut sql, "paid");
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT count(*) FILTER (WHERE status = $1) AS paid"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn aggregate_filter<Sql, Arg, F, E>(sql: &mut Sql, build: F) -> Result<(), E>
+where
+    Sql: WriteSql<Arg>,
+    F: FnOnce(&mut Group<'_, Sql, Arg>) -> Result<(), E>,
+{
+    emit(sql, Token::Keyword("FILTER"));
+    subquery(sql, |filter| {
+        filter_where(filter);
+        build(filter)
+    })
+}
+
+/// Writes a `<name>([DISTINCT] <argument>, ... ORDER BY <order>)` aggregate,
+/// ordering the input rows of aggregates like `string_agg` and `array_agg`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, aggregate_ordered, as_alias, OrderByOrd, OrderByNulls};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// aggregate_ordered(
+///     &mut sql,
+///     "string_agg",
+///     true,
+///     &["name", "', '"],
+///     [("name", OrderByOrd::Desc, OrderByNulls::Default)],
+/// );
+/// as_alias(&mut sql, "names");
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT string_agg(DISTINCT name, ', ' ORDER BY name DESC) AS names"
+/// );
+/// ```
+pub fn aggregate_ordered<'col, 'ord_expr, Sql, Arg, I>(
+    sql: &mut Sql,
+    name: &str,
+    distinct: bool,
+    arguments: &[&str],
+    order: I,
+) where
+    Sql: WriteSql<Arg>,
+    I: IntoIterator<Item = (&'col str, OrderByOrd<'ord_expr>, OrderByNulls)>,
+{
+    separator_optional(sql);
+    sql.push_cmd(name);
+    sql.push_cmd("(");
+    if distinct {
+        emit(sql, Token::Keyword("DISTINCT"));
+    }
+    for (index, argument) in arguments.iter().enumerate() {
+        if index > 0 {
+            emit(sql, Token::Comma);
+        }
+        emit(sql, Token::Ident(argument));
+    }
+    order_by(sql, order);
+    sql.push_cmd(")");
+}
+
+/// Static aggregate expression, the macro counterpart of the aggregate
+/// writers.
+///
+/// The aggregate is written as `<name>(<arguments>)`, where the arguments
+/// are `*` or a list of literals, optionally preceded by `DISTINCT` and
+/// followed by a `ORDER BY` in the syntax of
+/// [static_order_by](crate::static_order_by). A `FILTER (WHERE <condition>)`
+/// clause, with a literal or a [static_condition](crate::static_condition),
+/// and an alias can follow.
+///
+/// The expression can be used as a column of
+/// [static_columns](crate::static_columns).
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{static_aggregate, static_columns};
+/// assert_eq!(
+///     static_aggregate!(count(DISTINCT "user_id") FILTER (WHERE "active") AS "n"),
+///     "count(DISTINCT user_id) FILTER (WHERE active) AS n"
+/// );
+/// assert_eq!(
+///     static_columns!("team_id", static_aggregate!(string_agg("name", "', '" ORDER BY "name" ord(DESC)))),
+///     "team_id, string_agg(name, ', ' ORDER BY name DESC)"
+/// );
+/// ```
+#[macro_export]
+macro_rules! static_aggregate {
+    ($name:ident ($($arguments:tt)+) $(FILTER (WHERE $($filter:tt)+))? $(AS $alias:literal)?) => {
+        concat!(
+            stringify!($name),
+            "(",
+            $crate::static_aggregate_arguments!($($arguments)+),
+            ")"
+            $(, " FILTER (WHERE ", $crate::static_aggregate_filter!($($filter)+), ")")?
+            $(, " AS ", $alias)?
+        )
+    };
+}
+
+pub use static_aggregate;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! static_aggregate_arguments {
+    (*) => {
+        "*"
+    };
+    (DISTINCT $($arguments:tt)+) => {
+        concat!("DISTINCT ", $crate::static_aggregate_arguments!($($arguments)+))
+    };
+    ($first:literal $(, $argument:literal)* $(ORDER BY $($order:tt)+)?) => {
+        concat!(
+            $first
+            $(, ", ", $argument)*
+            $(, " ", $crate::static_order_by!($($order)+))?
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! static_aggregate_filter {
+    ($condition:literal) => {
+        $condition
+    };
+    ($($condition:tt)+) => {
+        $crate::static_condition!($($condition)+)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        expr::{
+            as_alias, column, continue_condition, from_table, group_by, item_separator,
+            lhs_binary_rhs, select, Cmp, LogicBi,
+        },
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+        SqlCommand,
+    };
+
+    #[test]
+    fn aggregates_and_columns() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "country");
+        item_separator(&mut sql);
+        count_star(&mut sql);
+        as_alias(&mut sql, "total");
+        item_separator(&mut sql);
+        count_distinct(&mut sql, "city");
+        item_separator(&mut sql);
+        sum(&mut sql, "revenue");
+        as_alias(&mut sql, "revenue");
+        item_separator(&mut sql);
+        avg(&mut sql, "age");
+        item_separator(&mut sql);
+        min(&mut sql, "created");
+        item_separator(&mut sql);
+        max(&mut sql, "created");
+        item_separator(&mut sql);
+        count(&mut sql, "email");
+        from_table(&mut sql, "customer");
+        group_by(&mut sql, ["country"]);
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT country, count(*) AS total, count(DISTINCT city), sum(revenue) AS revenue, avg(age), min(created), max(created), count(email) FROM customer GROUP BY country"
+        );
+        assert_eq!(sql.arguments.as_str(), "");
+    }
+
+    #[test]
+    fn aggregate_filter_conditions() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        sum(&mut sql, "total");
+        aggregate_filter(&mut sql, |filter| {
+            lhs_binary_rhs(filter, sqlexpr::<&str>("status"), Cmp::Eq, sqlvalue("paid"))?;
+            continue_condition(filter, LogicBi::And);
+            let mut group = Group::open(filter.sql());
+            lhs_binary_rhs(&mut group, sqlexpr::<u32>("region"), Cmp::Eq, sqlvalue(1))?;
+            continue_condition(&mut group, LogicBi::Or);
+            lhs_binary_rhs(&mut group, sqlexpr::<u32>("region"), Cmp::Eq, sqlvalue(2))
+        })
+        .unwrap();
+        as_alias(&mut sql, "paid");
+        item_separator(&mut sql);
+        count_star(&mut sql);
+        aggregate_filter(&mut sql, |filter| {
+            lhs_binary_rhs(filter, sqlexpr::<&str>("status"), Cmp::Eq, sqlvalue("open"))
+        })
+        .unwrap();
+        from_table(&mut sql, "invoice");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT sum(total) FILTER (WHERE status = $1 AND (region = $2 OR region = $3)) AS paid, count(*) FILTER (WHERE status = $4) FROM invoice"
+        );
+        assert_eq!(sql.arguments.as_str(), "paid;1;2;open;");
+
+        let cmd = sql.as_command();
+        assert_eq!(cmd.matches('(').count(), cmd.matches(')').count());
+    }
+
+    #[test]
+    fn static_aggregate_macro() {
+        assert_eq!(static_aggregate!(count(*)), "count(*)");
+        assert_eq!(static_aggregate!(sum("total")), "sum(total)");
+        assert_eq!(
+            static_aggregate!(sum("total") AS "revenue"),
+            "sum(total) AS revenue"
+        );
+        assert_eq!(
+            static_aggregate!(count(DISTINCT "city")),
+            "count(DISTINCT city)"
+        );
+        assert_eq!(
+            static_aggregate!(count(DISTINCT "city") AS "cities"),
+            "count(DISTINCT city) AS cities"
+        );
+        assert_eq!(
+            static_aggregate!(count(*) FILTER (WHERE "paid")),
+            "count(*) FILTER (WHERE paid)"
+        );
+        assert_eq!(
+            static_aggregate!(sum("total") FILTER (WHERE "status" = "'paid'") AS "paid"),
+            "sum(total) FILTER (WHERE status = 'paid') AS paid"
+        );
+        assert_eq!(
+            static_aggregate!(count(DISTINCT "user_id") FILTER (WHERE "active") AS "n"),
+            "count(DISTINCT user_id) FILTER (WHERE active) AS n"
+        );
+        assert_eq!(
+            static_aggregate!(array_agg("id" ORDER BY "created")),
+            "array_agg(id ORDER BY created)"
+        );
+        assert_eq!(
+            static_aggregate!(string_agg("name", "', '" ORDER BY "name" ord(DESC), "id")),
+            "string_agg(name, ', ' ORDER BY name DESC, id)"
+        );
+        assert_eq!(
+            static_aggregate!(array_agg(DISTINCT "tag" ORDER BY "tag" ord(ASC NULLS LAST)) AS "tags"),
+            "array_agg(DISTINCT tag ORDER BY tag ASC NULLS LAST) AS tags"
+        );
+        assert_eq!(
+            static_aggregate!(
+                array_agg(DISTINCT "tag" ORDER BY "tag") FILTER (WHERE "tag" != "''") AS "tags"
+            ),
+            "array_agg(DISTINCT tag ORDER BY tag) FILTER (WHERE tag <> '') AS tags"
+        );
+    }
+
+    #[test]
+    fn static_aggregate_columns() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        sql.push_cmd(" ");
+        sql.push_cmd(crate::static_columns!(
+            "country",
+            static_aggregate!(count(*) AS "total"),
+            static_aggregate!(count(DISTINCT "user_id") FILTER (WHERE "active") AS "n"),
+            "max(created)" AS "last",
+        ));
+        from_table(&mut sql, "customer");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT country, count(*) AS total, \
+             count(DISTINCT user_id) FILTER (WHERE active) AS n, max(created) AS last \
+             FROM customer"
+        );
+    }
+
+    #[test]
+    fn runtime_and_static_aggregate() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        aggregate_ordered(
+            &mut sql,
+            "array_agg",
+            true,
+            &["tag"],
+            [("tag", OrderByOrd::Asc, OrderByNulls::Last)],
+        );
+        aggregate_filter(&mut sql, |filter| {
+            filter.push_cmd(" tag <> ''");
+            Ok::<_, core::fmt::Error>(())
+        })
+        .unwrap();
+        as_alias(&mut sql, "tags");
+
+        assert_eq!(
+            sql.as_command(),
+            static_aggregate!(
+                array_agg(DISTINCT "tag" ORDER BY "tag" ord(ASC NULLS LAST))
+                    FILTER (WHERE "tag <> ''") AS "tags"
+            )
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use super::{emit, insert_column_list, insert_into, values, Token, ValuesRow};
+use crate::{bulk::MAX_BIND_PARAMETERS, SqlError, WriteSql};
+
+/// Type inserted as a row of a `VALUES` list, binding one cell per column.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void, WriteSql};
+/// # use sqlstr::expr::{insert_rows, BindRow, ValuesRow};
+/// # use core::convert::Infallible;
+/// struct User<'a> {
+///     name: &'a str,
+///     email: &'a str,
+/// }
+///
+/// impl<'a> BindRow<Void> for User<'a> {
+///     type Error = Infallible;
+///     const COLUMNS: &'static [&'static str] = &["name", "email", "created"];
+///
+///     fn bind<Sql>(self, row: &mut ValuesRow<'_, Sql, Void>) -> Result<(), Self::Error>
+///     where
+///         Sql: WriteSql<Void>,
+///     {
+///         row.value(self.name)?.value(self.email)?.raw("now()");
+///         Ok(())
+///     }
+/// }
+///
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let users = vec![
+///     User { name: "Rusty", email: "rusty@email.com" },
+///     User { name: "Ferris", email: "ferris@email.com" },
+/// ];
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// insert_rows(&mut sql, "user", users)?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "INSERT INTO user (name, email, created) VALUES ($1, $2, now()), ($3, $4, now())"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub trait BindRow<Arg> {
+    type Error;
+
+    /// Columns of the row, in the order of the cells written by
+    /// [bind](Self::bind).
+    const COLUMNS: &'static [&'static str];
+
+    /// Writes one cell per column into `row`.
+    fn bind<Sql>(self, row: &mut ValuesRow<'_, Sql, Arg>) -> Result<(), Self::Error>
+    where
+        Sql: WriteSql<Arg>;
+}
+
+/// Write a `INSERT INTO <table> (<column>, ...) VALUES (<cell>, ...), ...`
+/// command inserting every row of `rows`, see [BindRow].
+///
+/// Every row must write a cell per column, otherwise a
+/// [RowLengthMismatch](SqlError::RowLengthMismatch) error is returned. An
+/// empty list of rows or of columns returns an [EmptyList](SqlError::EmptyList) error,
+/// without writing anything. As in [values_rows](super::values_rows), the
+/// cells are counted as bound values and a list of more than
+/// [MAX_BIND_PARAMETERS] cells returns a
+/// [TooManyArguments](SqlError::TooManyArguments) error.
+pub fn insert_rows<Sql, Arg, R, I>(
+    sql: &mut Sql,
+    table: &str,
+    rows: I,
+) -> Result<(), SqlError<R::Error>>
+where
+    Sql: WriteSql<Arg>,
+    R: BindRow<Arg>,
+    I: IntoIterator<Item = R>,
+{
+    let arity = R::COLUMNS.len();
+    let max = MAX_BIND_PARAMETERS as usize;
+
+    let mut rows = rows.into_iter().peekable();
+    if rows.peek().is_none() || arity == 0 {
+        return Err(SqlError::EmptyList);
+    }
+    if rows.size_hint().0.saturating_mul(arity) > max {
+        return Err(SqlError::TooManyArguments {
+            max: MAX_BIND_PARAMETERS,
+        });
+    }
+
+    insert_into(sql, table);
+    insert_column_list(sql, R::COLUMNS.iter().copied());
+    values(sql);
+
+    let mut bound: usize = 0;
+    for (index, row) in rows.enumerate() {
+        bound = bound.saturating_add(arity);
+        if bound > max {
+            return Err(SqlError::TooManyArguments {
+                max: MAX_BIND_PARAMETERS,
+            });
+        }
+        if index > 0 {
+            emit(sql, Token::Comma);
+        }
+
+        let mut values_row = ValuesRow::open(sql, arity);
+        row.bind(&mut values_row).map_err(SqlError::Argument)?;
+        values_row.finish()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::{expr::returning_columns, test::TestArgs, SqlCommand};
+
+    type Error = core::fmt::Error;
+
+    struct Measurement {
+        sensor_id: u32,
+        value: u32,
+        note: Option<&'static str>,
+    }
+
+    impl BindRow<TestArgs> for Measurement {
+        type Error = Error;
+        const COLUMNS: &'static [&'static str] = &["sensor_id", "value", "note"];
+
+        fn bind<Sql>(self, row: &mut ValuesRow<'_, Sql, TestArgs>) -> Result<(), Error>
+        where
+            Sql: WriteSql<TestArgs>,
+        {
+            row.value(self.sensor_id)?.value(self.value)?;
+            match self.note {
+                Some(note) => row.value(note)?,
+                None => row.default(),
+            };
+            Ok(())
+        }
+    }
+
+    struct Short;
+
+    impl BindRow<TestArgs> for Short {
+        type Error = Error;
+        const COLUMNS: &'static [&'static str] = &["a", "b"];
+
+        fn bind<Sql>(self, row: &mut ValuesRow<'_, Sql, TestArgs>) -> Result<(), Error>
+        where
+            Sql: WriteSql<TestArgs>,
+        {
+            row.value(1)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn insert_struct_rows() {
+        let rows = Vec::from([
+            Measurement {
+                sensor_id: 1,
+                value: 20,
+                note: Some("calibrated"),
+            },
+            Measurement {
+                sensor_id: 2,
+                value: 21,
+                note: None,
+            },
+        ]);
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        insert_rows(&mut sql, "measurement", rows).unwrap();
+        returning_columns(&mut sql, ["id"]);
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO measurement (sensor_id, value, note) \
+             VALUES ($1, $2, $3), ($4, $5, DEFAULT) RETURNING id"
+        );
+        assert_eq!(sql.arguments.as_str(), "1;20;calibrated;2;21;");
+    }
+
+    #[test]
+    fn insert_rows_errors() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        assert_eq!(
+            insert_rows(&mut sql, "measurement", Vec::<Measurement>::new()),
+            Err(SqlError::EmptyList)
+        );
+        assert_eq!(sql.as_command(), "");
+
+        assert_eq!(
+            insert_rows(&mut sql, "pair", [Short]),
+            Err(SqlError::RowLengthMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use super::{emit, separator_optional, Token};
 use crate::{ArgumentBuffer, SqlExpr, WriteSql};
 
 /// **CAST** Command
@@ -28,9 +29,86 @@ where
 {
     sql.push_cmd("CAST (");
     sql.push_expr(expr)?;
-    sql.push_cmd(" AS ");
+    emit(sql, Token::Keyword("AS"));
+    emit(sql, Token::Ident(typ));
+    emit(sql, Token::Close);
+    Ok(())
+}
+
+/// Syntax of a type cast.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum CastStyle {
+    /// Standard sql `CAST (<expr> AS <type>)`
+    #[default]
+    Function,
+    /// Postgres shorthand `<expr>::<type>`
+    Shorthand,
+}
+
+/// Writes a column casted to a type as a select list item.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, column_cast, item_separator, CastStyle};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// column_cast(&mut sql, "total", "numeric", CastStyle::Function);
+/// item_separator(&mut sql);
+/// column_cast(&mut sql, "id", "text", CastStyle::Shorthand);
+///
+/// assert_eq!(sql.as_command(), "SELECT CAST (total AS numeric), id::text");
+/// ```
+pub fn column_cast<Sql, Arg>(sql: &mut Sql, column: &str, typ: &str, style: CastStyle)
+where
+    Sql: WriteSql<Arg>,
+{
+    separator_optional(sql);
+    match style {
+        CastStyle::Function => {
+            sql.push_cmd("CAST (");
+            sql.push_cmd(column);
+            emit(sql, Token::Keyword("AS"));
+            emit(sql, Token::Ident(typ));
+            emit(sql, Token::Close);
+        }
+        CastStyle::Shorthand => {
+            sql.push_cmd(column);
+            sql.push_cmd("::");
+            sql.push_cmd(typ);
+        }
+    }
+}
+
+/// Writes a bound value with a type annotation, `$n::<type>`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, sqlexpr};
+/// # use sqlstr::expr::{filter_where, value_cast};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// filter_where(&mut sql);
+/// sql.push_cmd(" id = ");
+/// value_cast(&mut sql, "8c1f5d2e-0f6b-4e8e-9a57-1d3b7c1e2f40", "uuid")?;
+///
+/// assert_eq!(sql.as_command(), "WHERE id = $1::uuid");
+/// # Ok(())
+/// # }
+/// ```
+pub fn value_cast<Sql, Arg, T>(sql: &mut Sql, value: T, typ: &str) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    separator_optional(sql);
+    sql.push_value(value)?;
+    sql.push_cmd("::");
     sql.push_cmd(typ);
-    sql.push_cmd(")");
     Ok(())
 }
 
@@ -45,6 +123,36 @@ pub use static_cast;
 
 #[cfg(test)]
 mod test {
+    use super::*;
+    use crate::{
+        expr::{as_alias, from_table, item_separator, select},
+        test::TestArgs,
+        SqlCommand,
+    };
+
+    #[test]
+    fn select_casted_columns_and_values() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column_cast(&mut sql, "total", "numeric", CastStyle::Function);
+        as_alias(&mut sql, "total");
+        item_separator(&mut sql);
+        column_cast(&mut sql, "created", "date", CastStyle::Shorthand);
+        item_separator(&mut sql);
+        value_cast(&mut sql, "0b0e7c44-3f2d-4d0a-8a41-2f7e4b1c9d11", "uuid").unwrap();
+        from_table(&mut sql, "order");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT CAST (total AS numeric) AS total, created::date, $1::uuid FROM order"
+        );
+        assert_eq!(
+            sql.arguments.as_str(),
+            "0b0e7c44-3f2d-4d0a-8a41-2f7e4b1c9d11;"
+        );
+    }
+
     #[test]
     fn cast() {
         assert_eq!(static_cast!("'-1'" AS "INTEGER"), "CAST ('-1' AS INTEGER)");
@@ -1,8 +1,8 @@
 use alloc::{borrow::Cow, string::String, vec::Vec};
-use core::{borrow::Borrow, ops::Deref};
+use core::{borrow::Borrow, convert::Infallible, ops::Deref};
 
-use super::separator_optional;
-use crate::WriteSql;
+use super::{check_ident_len, emit, separator_optional, subquery, Group, IdentLimit, Token};
+use crate::{ArgumentBuffer, SqlError, WriteSql};
 
 /// Write a list of columns from a iterator into the sql command buffer.
 ///
@@ -33,7 +33,7 @@ where
     }
 
     for col in cols {
-        sql.push_cmd(", ");
+        emit(sql, Token::Comma);
         sql.push_cmd(col);
     }
 }
@@ -73,13 +73,223 @@ where
 /// # }
 /// ```
 pub fn column<Sql, Arg>(sql: &mut Sql, column: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Ident(column));
+}
+
+/// Write a column expression owned by the caller, such as a formatted
+/// expression, reusing its allocation when possible.
+///
+/// Writes the same command as [column], see
+/// [push_string](crate::SqlCommand::push_string).
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, column_owned};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+///
+/// select(&mut sql);
+/// column_owned(&mut sql, format!("coalesce({}, 0)", "total"));
+///
+/// assert_eq!(sql.as_command(), "SELECT coalesce(total, 0)");
+/// ```
+pub fn column_owned<Sql, Arg>(sql: &mut Sql, column: String)
 where
     Sql: WriteSql<Arg>,
 {
     separator_optional(sql);
+    sql.push_string(column);
+}
+
+/// Write a qualified column, `<table>.<column>`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, column_qualified};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+///
+/// select(&mut sql);
+/// column_qualified(&mut sql, "u", "name");
+///
+/// assert_eq!(sql.as_command(), "SELECT u.name");
+/// ```
+pub fn column_qualified<Sql, Arg>(sql: &mut Sql, table: &str, column: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Ident(table));
+    sql.push_cmd(".");
     sql.push_cmd(column);
 }
 
+/// Write all columns of a table, `<table>.*`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, table_star};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+///
+/// select(&mut sql);
+/// table_star(&mut sql, "u");
+///
+/// assert_eq!(sql.as_command(), "SELECT u.*");
+/// ```
+pub fn table_star<Sql, Arg>(sql: &mut Sql, table: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    column_qualified(sql, table, "*");
+}
+
+/// Write all columns, `*`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, star, from_table};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+///
+/// select(&mut sql);
+/// star(&mut sql);
+/// from_table(&mut sql, "user");
+///
+/// assert_eq!(sql.as_command(), "SELECT * FROM user");
+/// ```
+pub fn star<Sql, Arg>(sql: &mut Sql)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Ident("*"));
+}
+
+/// Write a column expression with a bound value, `<before> $n <after>`.
+///
+/// The expression text around the placeholder is written as is.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, column, column_expr_value, as_alias, item_separator, from_table};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+///
+/// select(&mut sql);
+/// column_expr_value(&mut sql, "price *", 1.1, "")?;
+/// as_alias(&mut sql, "adjusted");
+/// item_separator(&mut sql);
+/// column(&mut sql, "name");
+/// from_table(&mut sql, "product");
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT price * $1 AS adjusted, name FROM product"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn column_expr_value<Sql, Arg, T>(
+    sql: &mut Sql,
+    before: &str,
+    value: T,
+    after: &str,
+) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    separator_optional(sql);
+    emit(sql, Token::Raw(before));
+    emit(sql, Token::Placeholder);
+    sql.push_value(value)?;
+    emit(sql, Token::Raw(after));
+    Ok(())
+}
+
+/// Write an alias, `AS <alias>`, for the previous column expression.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, column, as_alias};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+///
+/// select(&mut sql);
+/// column(&mut sql, "u.fullName");
+/// as_alias(&mut sql, "name");
+///
+/// assert_eq!(sql.as_command(), "SELECT u.fullName AS name");
+/// ```
+pub fn as_alias<Sql, Arg>(sql: &mut Sql, alias: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Keyword("AS"));
+    emit(sql, Token::Ident(alias));
+}
+
+/// Write a scalar subquery column, `(<subquery>) AS <alias>`.
+///
+/// The subquery is written by `build` into the same command, sharing the
+/// argument buffer, so the placeholders of the outer query continue the
+/// numbering after the subquery.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{
+/// #     select, column, subquery_column, item_separator, from_table_as, filter_where,
+/// #     lhs_binary_rhs, Cmp,
+/// # };
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+///
+/// select(&mut sql);
+/// column(&mut sql, "u.id");
+/// item_separator(&mut sql);
+/// subquery_column(&mut sql, "order_count", |sub| {
+///     select(sub);
+///     column(sub, "count(*)");
+///     from_table_as(sub, "order", "o");
+///     filter_where(sub);
+///     column(sub, "o.user_id = u.id");
+///     Ok::<_, Infallible>(())
+/// })?;
+/// from_table_as(&mut sql, "user", "u");
+/// filter_where(&mut sql);
+/// lhs_binary_rhs(&mut sql, sqlexpr::<bool>("u.active"), Cmp::Eq, sqlvalue(true))?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT u.id, (SELECT count(*) FROM order AS o WHERE o.user_id = u.id) AS order_count \
+///      FROM user AS u WHERE u.active = $1"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn subquery_column<Sql, Arg, F, E>(sql: &mut Sql, alias: &str, build: F) -> Result<(), E>
+where
+    Sql: WriteSql<Arg>,
+    F: FnOnce(&mut Group<'_, Sql, Arg>) -> Result<(), E>,
+{
+    subquery(sql, build)?;
+    as_alias(sql, alias);
+    Ok(())
+}
+
 #[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ColumnExpr<'c>(Cow<'c, str>);
@@ -118,35 +328,97 @@ impl<'c> Deref for ColumnExpr<'c> {
     }
 }
 
+/// Kind of a column recorded in a [ColumnExprList].
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct ColumnKind {
+    /// Length of the column expression, without the alias.
+    expr_len: usize,
+    aggregate: bool,
+}
+
 #[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
-pub struct ColumnExprList<'c>(Vec<Cow<'c, str>>);
+pub struct ColumnExprList<'c>(Vec<Cow<'c, str>>, Vec<ColumnKind>);
 
 pub const fn column_list<'c>() -> ColumnExprList<'c> {
     ColumnExprList::new()
 }
 
 impl<'c> ColumnExprList<'c> {
-    pub const ALIAS: &str = "AS";
+    pub const ALIAS: &'static str = "AS";
 
     pub const fn new() -> Self {
-        Self(Vec::new())
+        Self(Vec::new(), Vec::new())
     }
 
     pub fn column<C>(mut self, column: C) -> Self
     where
         C: Into<Cow<'c, str>>,
     {
-        self.0.push(column.into());
+        let column = column.into();
+        self.push(column.len(), column, false);
         self
     }
 
     pub fn column_as(mut self, column: &str, alias: &str) -> Self {
-        let mut col = String::with_capacity(column.len() + alias.len() + 4);
+        self.push_aliased(column, alias, false);
+        self
+    }
+
+    /// Push an aliased column, `<column> AS <alias>`, checking that the alias
+    /// is at most `limit` bytes long, see [check_ident_len].
+    pub fn column_as_checked(
+        self,
+        column: &str,
+        alias: &str,
+        limit: IdentLimit,
+    ) -> Result<Self, SqlError<Infallible>> {
+        check_ident_len(alias, limit)?;
+        Ok(self.column_as(column, alias))
+    }
+
+    /// Push an aggregate expression (e.g. `count(*)`), which is left out of
+    /// [non_aggregates](Self::non_aggregates).
+    pub fn aggregate<C>(mut self, expr: C) -> Self
+    where
+        C: Into<Cow<'c, str>>,
+    {
+        let expr = expr.into();
+        self.push(expr.len(), expr, true);
+        self
+    }
+
+    /// Push an aliased aggregate expression, `<expr> AS <alias>`.
+    pub fn aggregate_as(mut self, expr: &str, alias: &str) -> Self {
+        self.push_aliased(expr, alias, true);
+        self
+    }
+
+    /// Mark the last pushed column as an aggregate.
+    ///
+    /// Useful for raw column expressions containing an aggregate function.
+    pub fn mark_aggregate(mut self) -> Self {
+        if let Some(kind) = self.1.last_mut() {
+            kind.aggregate = true;
+        }
+        self
+    }
+
+    fn push_aliased(&mut self, column: &str, alias: &str, aggregate: bool) {
+        let mut col =
+            String::with_capacity(column.len().saturating_add(alias.len()).saturating_add(4));
         col.push_str(column);
         col.push_str(" AS ");
         col.push_str(alias);
-        self.0.push(Cow::Owned(col));
-        self
+        self.push(column.len(), Cow::Owned(col), aggregate);
+    }
+
+    fn push(&mut self, expr_len: usize, column: Cow<'c, str>, aggregate: bool) {
+        self.0.push(column);
+        self.1.push(ColumnKind {
+            expr_len,
+            aggregate,
+        });
     }
 
     pub fn as_slice(&self) -> &[Cow<'c, str>] {
@@ -158,6 +430,32 @@ impl<'c> ColumnExprList<'c> {
         'l: 'c,
     {
         self.0.extend_from_slice(other);
+        self.1.extend(other.iter().map(|col| ColumnKind {
+            expr_len: col.len(),
+            aggregate: false,
+        }));
+    }
+
+    /// Iterator over the expressions (without alias) of the columns not
+    /// marked as aggregates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::expr::column_list;
+    /// let columns = column_list()
+    ///     .column("id")
+    ///     .column_as("u.fullName", "name")
+    ///     .aggregate_as("count(*)", "total");
+    ///
+    /// assert!(columns.non_aggregates().eq(["id", "u.fullName"]));
+    /// ```
+    pub fn non_aggregates(&self) -> impl Iterator<Item = &str> + '_ {
+        self.0
+            .iter()
+            .zip(self.1.iter())
+            .filter(|(_, kind)| !kind.aggregate)
+            .map(|(col, kind)| col.get(..kind.expr_len).unwrap_or(col))
     }
 
     pub fn expr(&self) -> ColumnExpr<'c> {
@@ -165,6 +463,12 @@ impl<'c> ColumnExprList<'c> {
     }
 }
 
+impl<'c> Default for ColumnExprList<'c> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'c> From<&ColumnExprList<'c>> for ColumnExpr<'c> {
     fn from(value: &ColumnExprList<'c>) -> Self {
         Self::from_list(value)
@@ -195,8 +499,36 @@ macro_rules! static_columns {
         concat!($column, " AS ", $alias)
     };
 
-    ($fcolumn:literal $(AS $falias:literal)?, $($column:literal $(AS $alias:literal)?),* $(,)?) => {
-        concat!($fcolumn $(, " AS ", $falias)?, $(", ", $column $(, " AS ", $alias)?),*)
+    ($($columns:tt)+) => {
+        $crate::static_column_list!([] $($columns)+)
+    };
+}
+
+/// Joins the columns of [static_columns], where each column is a literal or
+/// a static expression macro, such as [static_aggregate](crate::static_aggregate).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! static_column_list {
+    ([$($acc:expr),*]) => {
+        concat!($($acc),*)
+    };
+    (@separator) => {
+        ""
+    };
+    (@separator $($acc:expr),+) => {
+        ", "
+    };
+    ([$($acc:expr),*] $column:literal $(AS $alias:literal)? $(, $($rest:tt)*)?) => {
+        $crate::static_column_list!(
+            [$($acc,)* $crate::static_column_list!(@separator $($acc),*), $column $(, " AS ", $alias)?]
+            $($($rest)*)?
+        )
+    };
+    ([$($acc:expr),*] $macro:ident ! $arguments:tt $(AS $alias:literal)? $(, $($rest:tt)*)?) => {
+        $crate::static_column_list!(
+            [$($acc,)* $crate::static_column_list!(@separator $($acc),*), $macro! $arguments $(, " AS ", $alias)?]
+            $($($rest)*)?
+        )
     };
 }
 
@@ -206,7 +538,11 @@ pub use static_columns;
 mod test {
     use super::*;
     use crate::{
-        expr::{from_tables, select, separator_optional},
+        expr::{
+            filter_where, from_table, from_tables, item_separator, lhs_binary_rhs, select,
+            separator_optional, Cmp,
+        },
+        sqlexpr, sqlvalue,
         test::{TestArgs, User},
         SqlCommand,
     };
@@ -286,6 +622,23 @@ mod test {
         );
     }
 
+    #[test]
+    fn columns_list_aggregates() {
+        let columns = column_list()
+            .column("id")
+            .aggregate("max(price)")
+            .column_as("firstName", "first_name")
+            .column("sum(total) AS total")
+            .mark_aggregate()
+            .aggregate_as("count(*)", "count");
+
+        assert_eq!(
+            columns.expr().as_str(),
+            "id, max(price), firstName AS first_name, sum(total) AS total, count(*) AS count"
+        );
+        assert!(columns.non_aggregates().eq(["id", "firstName"]));
+    }
+
     #[test]
     fn select_columns_from_table() {
         let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
@@ -306,4 +659,79 @@ mod test {
         );
         assert_eq!(sql.arguments.as_str(), "");
     }
+
+    #[test]
+    fn select_expression_value_columns() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column_expr_value(&mut sql, "price * ", 2, "").unwrap();
+        as_alias(&mut sql, "adjusted");
+        item_separator(&mut sql);
+        column_expr_value(&mut sql, "coalesce(discount,", 0, ")").unwrap();
+        item_separator(&mut sql);
+        column(&mut sql, "name");
+        from_table(&mut sql, "product");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT price * $1 AS adjusted, coalesce(discount, $2), name FROM product"
+        );
+        assert_eq!(sql.arguments.as_str(), "2;0;");
+    }
+
+    #[test]
+    fn subquery_column_shared_arguments() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column_expr_value(&mut sql, "", 1, "").unwrap();
+        as_alias(&mut sql, "one");
+        item_separator(&mut sql);
+        subquery_column(&mut sql, "order_count", |sub| {
+            select(sub);
+            column(sub, "count(*)");
+            from_table(sub, "order");
+            filter_where(sub);
+            lhs_binary_rhs(sub, sqlexpr::<u32>("total"), Cmp::Gt, sqlvalue(100))
+        })
+        .unwrap();
+        from_table(&mut sql, "user");
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(7)).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT $1 AS one, (SELECT count(*) FROM order WHERE total > $2) AS order_count FROM user WHERE id = $3"
+        );
+        assert_eq!(sql.arguments.as_str(), "1;100;7;");
+    }
+
+    #[test]
+    fn qualified_and_star_columns() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        table_star(&mut sql, "u");
+        item_separator(&mut sql);
+        column_qualified(&mut sql, "o", "total");
+        item_separator(&mut sql);
+        column(&mut sql, "name");
+        from_table(&mut sql, "user");
+        as_alias(&mut sql, "u");
+        sql.push_cmd(" JOIN order AS o ON o.user_id = u.id");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT u.*, o.total, name FROM user AS u JOIN order AS o ON o.user_id = u.id"
+        );
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        star(&mut sql);
+        from_table(&mut sql, "user");
+
+        assert_eq!(sql.as_command(), "SELECT * FROM user");
+    }
 }
@@ -1,4 +1,5 @@
-#[allow(unused_macros)]
+#[doc(hidden)]
+#[macro_export]
 macro_rules! static_comparison {
     (=) => {
         "="
@@ -20,7 +21,8 @@ macro_rules! static_comparison {
     };
 }
 
-#[allow(unused_macros)]
+#[doc(hidden)]
+#[macro_export]
 macro_rules! static_logical_op {
     (NOT) => {
         "NOT"
@@ -39,30 +41,24 @@ macro_rules! static_logical_op {
     };
 }
 
-#[allow(unused_imports)]
-pub(super) use static_comparison;
-
-#[allow(unused_imports)]
-pub(super) use static_logical_op;
-
 #[macro_export]
 macro_rules! static_condition {
     ($a:literal $op:tt $b:literal) => {
         concat!(
             $a,
             " ",
-            $crate::expr::static_comparison!($op),
+            $crate::static_comparison!($op),
             " ",
             $b
         )
     };
     ($pre_logic:tt $a:literal $op:tt $b:literal) => {
         concat!(
-            $crate::expr::static_logical_op!($pre_logic),
+            $crate::static_logical_op!($pre_logic),
             " ",
             $a,
             " ",
-            $crate::expr::static_comparison!($op),
+            $crate::static_comparison!($op),
             " ",
             $b
         )
@@ -73,22 +69,22 @@ macro_rules! static_condition {
             $crate::expr::static_condition!($a $op $b),
             $(
                 " ",
-                $crate::expr::static_logical_op!($logic_op),
+                $crate::static_logical_op!($logic_op),
                 " ",
-                $crate::expr::static_condition!($ax $opx $bx)
+                $crate::expr::static_condition!($ax $opx $bx),
             )+
         )
     };
     ($pre_logic:tt $a:literal $op:tt $b:literal $($logic_op:tt $ax:literal $opx:tt $bx:literal)+) => {
         concat!(
-            $crate::expr::static_logical_op!($pre_logic),
+            $crate::static_logical_op!($pre_logic),
             " ",
             $crate::expr::static_condition!($a $op $b),
             $(
                 " ",
-                $crate::expr::static_logical_op!($logic_op),
+                $crate::static_logical_op!($logic_op),
                 " ",
-                $crate::expr::static_condition!($ax $opx $bx)
+                $crate::expr::static_condition!($ax $opx $bx),
             )+
         )
     };
@@ -117,6 +113,10 @@ mod test {
             static_condition!("user.id" = "access_history.user_id" OR "user.updated" < "access_history.created"),
             "user.id = access_history.user_id OR user.updated < access_history.created"
         );
+        assert_eq!(
+            static_condition!("a.id" = "b.id" AND "a.kind" = "b.kind" OR_NOT "a.removed" = "true"),
+            "a.id = b.id AND a.kind = b.kind OR NOT a.removed = true"
+        );
         assert_eq!(
             static_condition!("user.id" != "access_history.user_id"),
             "user.id <> access_history.user_id"
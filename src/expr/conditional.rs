@@ -0,0 +1,494 @@
+use core::convert::Infallible;
+use core::marker::PhantomData;
+
+use super::{emit, separator_optional, string_literal, Token};
+use crate::{ArgumentBuffer, ArgumentError, SqlError, WriteSql};
+
+/// `CASE WHEN <condition> THEN <result> ... [ELSE <result>] END` expression.
+///
+/// The `WHEN` conditions are written by a closure with the condition
+/// functions, and the `THEN`/`ELSE` results can be bound values, raw sql
+/// expressions or string literals. The `END` keyword is only written by
+/// [close](Case::close), after checking that at least one `WHEN` arm was
+/// written and that every arm has a `THEN` result, so an expression dropped
+/// without closing is left incomplete.
+///
+/// A clause out of order, such as a second `THEN` result of an arm or a
+/// `WHEN` arm after the `ELSE` result, is not written, and makes
+/// [close](Case::close) return an error.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{select, as_alias, from_table, lhs_binary_rhs, Case, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+///
+/// let mut case = Case::open(&mut sql);
+/// case.when(|sql| lhs_binary_rhs(sql, sqlexpr::<u32>("score"), Cmp::Gte, sqlvalue(90)))?
+///     .then_value("gold")?;
+/// case.when(|sql| lhs_binary_rhs(sql, sqlexpr::<u32>("score"), Cmp::Gte, sqlvalue(50)))?
+///     .then_value("silver")?;
+/// case.else_value("bronze")?;
+/// case.close()?;
+///
+/// as_alias(&mut sql, "tier");
+/// from_table(&mut sql, "player");
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT CASE WHEN score >= $1 THEN $2 WHEN score >= $3 THEN $4 ELSE $5 END AS tier FROM player"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[must_use = "the expression is only ended by `close`"]
+pub struct Case<'cmd, Sql: WriteSql<Arg>, Arg> {
+    sql: &'cmd mut Sql,
+    state: CaseState,
+    error: Option<SqlError<Infallible>>,
+    _arg: PhantomData<Arg>,
+}
+
+/// Last clause written into a [Case] expression.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CaseState {
+    Opened,
+    When,
+    Then,
+    Else,
+}
+
+impl<'cmd, Sql, Arg> Case<'cmd, Sql, Arg>
+where
+    Sql: WriteSql<Arg>,
+{
+    /// Opens a `CASE` expression.
+    pub fn open(sql: &'cmd mut Sql) -> Self {
+        emit(sql, Token::Keyword("CASE"));
+        Self {
+            sql,
+            state: CaseState::Opened,
+            error: None,
+            _arg: PhantomData,
+        }
+    }
+
+    /// Moves to the `next` clause if it may follow the last one, otherwise
+    /// keeps the first error for [close](Self::close) and returns `false`.
+    fn enter(&mut self, next: CaseState) -> bool {
+        let error = match (self.state, next) {
+            (CaseState::Opened | CaseState::Then, CaseState::When)
+            | (CaseState::When, CaseState::Then)
+            | (CaseState::Then, CaseState::Else) => {
+                self.state = next;
+                return true;
+            }
+            (CaseState::When, _) => SqlError::MissingThen,
+            (CaseState::Opened, CaseState::Else) => SqlError::EmptyList,
+            _ => SqlError::MisplacedCaseClause,
+        };
+        self.error.get_or_insert(error);
+        false
+    }
+
+    /// Writes a `WHEN <condition>` arm, with the condition written by `build`.
+    pub fn when<F, E>(&mut self, build: F) -> Result<&mut Self, E>
+    where
+        F: FnOnce(&mut Sql) -> Result<(), E>,
+    {
+        if !self.enter(CaseState::When) {
+            return Ok(self);
+        }
+        emit(self.sql, Token::Keyword("WHEN"));
+        separator_optional(self.sql);
+        build(self.sql)?;
+        Ok(self)
+    }
+
+    /// Writes a `THEN $n` result with a bound value.
+    pub fn then_value<T>(&mut self, value: T) -> Result<&mut Self, Arg::Error>
+    where
+        Arg: ArgumentBuffer<T>,
+    {
+        if !self.enter(CaseState::Then) {
+            return Ok(self);
+        }
+        emit(self.sql, Token::Keyword("THEN"));
+        emit(self.sql, Token::Placeholder);
+        self.sql.push_value(value)?;
+        Ok(self)
+    }
+
+    /// Writes a `THEN <expr>` result with a raw sql expression.
+    pub fn then_expr(&mut self, expr: &str) -> &mut Self {
+        if self.enter(CaseState::Then) {
+            emit(self.sql, Token::Keyword("THEN"));
+            emit(self.sql, Token::Ident(expr));
+        }
+        self
+    }
+
+    /// Writes a `THEN '<text>'` result with a string literal.
+    pub fn then_literal(&mut self, text: &str) -> Result<&mut Self, SqlError<Arg::Error>>
+    where
+        Arg: ArgumentError,
+    {
+        if !self.enter(CaseState::Then) {
+            return Ok(self);
+        }
+        emit(self.sql, Token::Keyword("THEN"));
+        string_literal(self.sql, text)?;
+        Ok(self)
+    }
+
+    /// Writes an `ELSE $n` result with a bound value.
+    pub fn else_value<T>(&mut self, value: T) -> Result<&mut Self, Arg::Error>
+    where
+        Arg: ArgumentBuffer<T>,
+    {
+        if !self.enter(CaseState::Else) {
+            return Ok(self);
+        }
+        emit(self.sql, Token::Keyword("ELSE"));
+        emit(self.sql, Token::Placeholder);
+        self.sql.push_value(value)?;
+        Ok(self)
+    }
+
+    /// Writes an `ELSE <expr>` result with a raw sql expression.
+    pub fn else_expr(&mut self, expr: &str) -> &mut Self {
+        if self.enter(CaseState::Else) {
+            emit(self.sql, Token::Keyword("ELSE"));
+            emit(self.sql, Token::Ident(expr));
+        }
+        self
+    }
+
+    /// Writes an `ELSE '<text>'` result with a string literal.
+    pub fn else_literal(&mut self, text: &str) -> Result<&mut Self, SqlError<Arg::Error>>
+    where
+        Arg: ArgumentError,
+    {
+        if !self.enter(CaseState::Else) {
+            return Ok(self);
+        }
+        emit(self.sql, Token::Keyword("ELSE"));
+        string_literal(self.sql, text)?;
+        Ok(self)
+    }
+
+    /// Closes the expression with `END`.
+    ///
+    /// Returns an [EmptyList](SqlError::EmptyList) error if no `WHEN` arm was
+    /// written, a [MissingThen](SqlError::MissingThen) error if an arm has no
+    /// `THEN` result, and a
+    /// [MisplacedCaseClause](SqlError::MisplacedCaseClause) error if a `THEN`
+    /// result has no arm or a clause follows the `ELSE` result, without
+    /// writing `END`.
+    pub fn close(self) -> Result<(), SqlError<Arg::Error>>
+    where
+        Arg: ArgumentError,
+    {
+        if let Some(err) = self.error {
+            return Err(err.widen());
+        }
+        match self.state {
+            CaseState::Opened => Err(SqlError::EmptyList),
+            CaseState::When => Err(SqlError::MissingThen),
+            CaseState::Then | CaseState::Else => {
+                emit(self.sql, Token::Keyword("END"));
+                Ok(())
+            }
+        }
+    }
+}
+
+fn coalesce_columns<Sql, Arg>(sql: &mut Sql, first: &str, rest: &[&str])
+where
+    Sql: WriteSql<Arg>,
+{
+    separator_optional(sql);
+    sql.push_cmd("COALESCE(");
+    sql.push_cmd(first);
+    for column in rest {
+        emit(sql, Token::Comma);
+        sql.push_cmd(column);
+    }
+}
+
+/// Writes a `COALESCE(<column>, ...)` expression.
+///
+/// An empty list of columns returns an [EmptyList](SqlError::EmptyList) error.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{select, coalesce};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// coalesce(&mut sql, &["nickname", "name"])?;
+///
+/// assert_eq!(sql.as_command(), "SELECT COALESCE(nickname, name)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn coalesce<Sql, Arg>(sql: &mut Sql, columns: &[&str]) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+{
+    let (first, rest) = columns.split_first().ok_or(SqlError::EmptyList)?;
+    coalesce_columns(sql, first, rest);
+    sql.push_cmd(")");
+    Ok(())
+}
+
+/// Writes a `COALESCE(<column>, ..., $n)` expression, with a bound fallback
+/// value.
+///
+/// An empty list of columns returns an [EmptyList](SqlError::EmptyList) error.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{select, coalesce_with_value};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// coalesce_with_value(&mut sql, &["discount"], 0)?;
+///
+/// assert_eq!(sql.as_command(), "SELECT COALESCE(discount, $1)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn coalesce_with_value<Sql, Arg, T>(
+    sql: &mut Sql,
+    columns: &[&str],
+    fallback: T,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    let (first, rest) = columns.split_first().ok_or(SqlError::EmptyList)?;
+    coalesce_columns(sql, first, rest);
+    emit(sql, Token::Comma);
+    sql.push_value(fallback)?;
+    sql.push_cmd(")");
+    Ok(())
+}
+
+/// Writes a `NULLIF(<a>, <b>)` expression.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, nullif};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// nullif(&mut sql, "email", "''");
+///
+/// assert_eq!(sql.as_command(), "SELECT NULLIF(email, '')");
+/// ```
+pub fn nullif<Sql, Arg>(sql: &mut Sql, a: &str, b: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    separator_optional(sql);
+    sql.push_cmd("NULLIF(");
+    sql.push_cmd(a);
+    emit(sql, Token::Comma);
+    sql.push_cmd(b);
+    sql.push_cmd(")");
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        expr::{
+            as_alias, column, comparison::is_null, continue_condition, item_separator,
+            lhs_binary_rhs, select, Cmp, LogicBi,
+        },
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+        SqlCommand,
+    };
+
+    type Error = SqlError<core::fmt::Error>;
+
+    #[test]
+    fn case_two_arms_bound_else() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        column(&mut sql, "id");
+        item_separator(&mut sql);
+
+        let mut case = Case::open(&mut sql);
+        case.when(|sql| {
+            lhs_binary_rhs(sql, sqlexpr::<&str>("status"), Cmp::Eq, sqlvalue("paid"))?;
+            continue_condition(sql, LogicBi::And);
+            lhs_binary_rhs(sql, sqlexpr::<u32>("total"), Cmp::Gt, sqlvalue(100))
+        })
+        .unwrap()
+        .then_expr("total");
+        case.when(|sql| {
+            sql.push_cmd("refunded_at");
+            is_null(sql);
+            Ok::<_, core::fmt::Error>(())
+        })
+        .unwrap()
+        .then_literal("it's pending")
+        .unwrap();
+        case.else_value(0).unwrap();
+        case.close().unwrap();
+        as_alias(&mut sql, "amount");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT id, CASE WHEN status = $1 AND total > $2 THEN total \
+             WHEN refunded_at IS NULL THEN 'it''s pending' ELSE $3 END AS amount"
+        );
+        assert_eq!(sql.arguments.as_str(), "paid;100;0;");
+    }
+
+    #[test]
+    fn case_without_arms() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+
+        let mut case = Case::open(&mut sql);
+        case.else_expr("1");
+        let err: Error = case.close().unwrap_err();
+
+        assert_eq!(err, SqlError::EmptyList);
+        assert_eq!(sql.as_command(), "SELECT CASE");
+    }
+
+    #[test]
+    fn case_arm_without_then() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+
+        let mut case = Case::open(&mut sql);
+        case.when(|sql| {
+            sql.push_cmd("a");
+            Ok::<_, core::fmt::Error>(())
+        })
+        .unwrap()
+        .then_expr("1");
+        case.when(|sql| {
+            sql.push_cmd("b");
+            Ok::<_, core::fmt::Error>(())
+        })
+        .unwrap();
+        case.else_expr("0");
+        let err: Error = case.close().unwrap_err();
+
+        assert_eq!(err, SqlError::MissingThen);
+        assert_eq!(sql.as_command(), "SELECT CASE WHEN a THEN 1 WHEN b");
+    }
+
+    fn condition(sql: &mut SqlCommand<TestArgs>, text: &str) -> Result<(), core::fmt::Error> {
+        sql.push_cmd(text);
+        Ok(())
+    }
+
+    #[test]
+    fn case_clauses_out_of_order() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        let mut case = Case::open(&mut sql);
+        case.when(|sql| condition(sql, "c"))
+            .unwrap()
+            .then_value(1)
+            .unwrap()
+            .then_value(2)
+            .unwrap();
+        case.when(|sql| condition(sql, "d")).unwrap().then_expr("3");
+        let err: Error = case.close().unwrap_err();
+        assert_eq!(err, SqlError::MisplacedCaseClause);
+        assert_eq!(sql.as_command(), "CASE WHEN c THEN $1 WHEN d THEN 3");
+        assert_eq!(sql.arguments.as_str(), "1;");
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        let mut case = Case::open(&mut sql);
+        case.when(|sql| condition(sql, "c")).unwrap().then_expr("1");
+        case.else_expr("0");
+        case.when(|sql| condition(sql, "d")).unwrap().then_expr("2");
+        let err: Error = case.close().unwrap_err();
+        assert_eq!(err, SqlError::MisplacedCaseClause);
+        assert_eq!(sql.as_command(), "CASE WHEN c THEN 1 ELSE 0");
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        let mut case = Case::open(&mut sql);
+        case.when(|sql| condition(sql, "c")).unwrap().then_expr("1");
+        case.else_expr("0").else_literal("none").unwrap();
+        let err: Error = case.close().unwrap_err();
+        assert_eq!(err, SqlError::MisplacedCaseClause);
+        assert_eq!(sql.as_command(), "CASE WHEN c THEN 1 ELSE 0");
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        let mut case = Case::open(&mut sql);
+        case.then_expr("1");
+        case.when(|sql| condition(sql, "c")).unwrap().then_expr("1");
+        let err: Error = case.close().unwrap_err();
+        assert_eq!(err, SqlError::MisplacedCaseClause);
+        assert_eq!(sql.as_command(), "CASE WHEN c THEN 1");
+    }
+
+    #[test]
+    fn case_dropped_without_close() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+
+        let mut case = Case::open(&mut sql);
+        case.when(|sql| condition(sql, "a")).unwrap().then_expr("1");
+        drop(case);
+
+        assert_eq!(sql.as_command(), "SELECT CASE WHEN a THEN 1");
+    }
+
+    #[test]
+    fn coalesce_and_nullif_columns() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        coalesce(&mut sql, &["a", "b", "c"]).unwrap();
+        item_separator(&mut sql);
+        coalesce_with_value(&mut sql, &["price", "list_price"], 10).unwrap();
+        as_alias(&mut sql, "price");
+        item_separator(&mut sql);
+        nullif(&mut sql, "total", "0");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT COALESCE(a, b, c), COALESCE(price, list_price, $1) AS price, NULLIF(total, 0)"
+        );
+        assert_eq!(sql.arguments.as_str(), "10;");
+    }
+
+    #[test]
+    fn coalesce_empty_columns() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        let err: Error = coalesce(&mut sql, &[]).unwrap_err();
+        assert_eq!(err, SqlError::EmptyList);
+
+        let err = coalesce_with_value(&mut sql, &[], 1).unwrap_err();
+        assert_eq!(err, SqlError::EmptyList);
+
+        assert_eq!(sql.as_command(), "SELECT");
+        assert_eq!(sql.arguments.as_str(), "");
+    }
+}
@@ -1,5 +1,10 @@
-use super::separator_optional;
-use crate::WriteSql;
+use core::marker::PhantomData;
+
+use super::{
+    emit, filter_where, ident::check_idents, returning_all, returning_columns_checked, IdentLimit,
+    Token,
+};
+use crate::{ArgumentError, SqlCommand, SqlError, WriteSql};
 
 /// Writes a `DELETE FROM` command into the sql command buffer.
 ///
@@ -21,8 +26,7 @@ pub fn delete<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("DELETE FROM");
+    emit(sql, Token::Keyword("DELETE FROM"));
 }
 
 /// Writes a `DELETE FROM <table>` clause to start a delete command with a
@@ -46,9 +50,8 @@ pub fn delete_from<Sql, Arg>(sql: &mut Sql, table: &str)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("DELETE FROM ");
-    sql.push_cmd(table);
+    emit(sql, Token::Keyword("DELETE FROM"));
+    emit(sql, Token::Ident(table));
 }
 
 /// Writes a `USING` clause for specifying additional tables in the delete
@@ -72,8 +75,7 @@ pub fn delete_using<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("USING");
+    emit(sql, Token::Keyword("USING"));
 }
 
 /// Writes a `USING <table>` clause with a iterator of additional tables
@@ -98,17 +100,269 @@ where
     Sql: WriteSql<Arg>,
     I: IntoIterator<Item = &'tbl str>,
 {
-    separator_optional(sql);
-    sql.push_cmd("USING");
+    emit(sql, Token::Keyword("USING"));
 
     let mut tbls = tables.into_iter();
     if let Some(first) = tbls.next() {
-        sql.push_cmd(" ");
-        sql.push_cmd(first);
+        emit(sql, Token::Ident(first));
     }
 
     for table in tbls {
-        sql.push_cmd(", ");
-        sql.push_cmd(table);
+        emit(sql, Token::Comma);
+        emit(sql, Token::Raw(table));
+    }
+}
+
+/// States of a [DeleteBuilder].
+pub mod delete_state {
+    /// Table written, without `USING` sources.
+    pub struct Started;
+    /// `USING` sources written.
+    pub struct Using;
+    /// `WHERE` clause written.
+    pub struct Filtered;
+
+    /// States accepting a `USING` source, a `WHERE` clause or an
+    /// unconditional end of the command.
+    pub trait Unfiltered: private::Sealed {
+        /// Whether the source is the first one, written after `USING`.
+        const FIRST: bool;
+    }
+
+    impl Unfiltered for Started {
+        const FIRST: bool = true;
+    }
+
+    impl Unfiltered for Using {
+        const FIRST: bool = false;
+    }
+
+    mod private {
+        pub trait Sealed {}
+
+        impl Sealed for super::Started {}
+        impl Sealed for super::Using {}
+    }
+}
+
+use delete_state::{Filtered, Started, Unfiltered, Using};
+
+/// Builder of a `DELETE FROM <table> [USING <table>, ...] WHERE <condition>`
+/// command.
+///
+/// The command ends after the `WHERE` clause, with [end](Self::end) or a
+/// `RETURNING` clause. Deleting every row of the table requires the explicit
+/// [end_without_where](Self::end_without_where).
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void, WriteSql, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{DeleteBuilder, lhs_binary_rhs, continue_condition, Cmp, LogicBi};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let sql: SqlCommand<Void> = DeleteBuilder::new(Void::new(), "session")
+///     .using("user")
+///     .filter_where(|sql| {
+///         sql.push_cmd(" session.user_id = user.id");
+///         continue_condition(sql, LogicBi::And);
+///         lhs_binary_rhs(sql, sqlexpr::<bool>("user.banned"), Cmp::Eq, sqlvalue(true))
+///     })?
+///     .returning(&["session.id"])?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "DELETE FROM session USING user \
+///      WHERE session.user_id = user.id AND user.banned = $1 RETURNING session.id"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A delete without a `WHERE` clause does not end with [end](Self::end):
+///
+/// ```compile_fail
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::DeleteBuilder;
+/// let sql: SqlCommand<Void> = DeleteBuilder::new(Void::new(), "session").end();
+/// ```
+pub struct DeleteBuilder<Arg, S> {
+    sql: SqlCommand<Arg>,
+    _state: PhantomData<S>,
+}
+
+impl<Arg> DeleteBuilder<Arg, Started> {
+    /// Starts a `DELETE FROM <table>` command binding into `arguments`.
+    pub fn new(arguments: Arg, table: &str) -> Self {
+        let mut sql = SqlCommand::new(alloc::string::String::new(), arguments);
+        delete_from(&mut sql, table);
+        Self {
+            sql,
+            _state: PhantomData,
+        }
+    }
+
+    /// Starts a `DELETE FROM <table> AS <alias>` command binding into
+    /// `arguments`.
+    pub fn new_as(arguments: Arg, table: &str, alias: &str) -> Self {
+        let mut builder = Self::new(arguments, table);
+        emit(&mut builder.sql, Token::Keyword("AS"));
+        emit(&mut builder.sql, Token::Ident(alias));
+        builder
+    }
+
+    /// Starts a `DELETE FROM <table> AS <alias>` command, checking that the
+    /// table name and the alias are at most `limit` bytes long, see
+    /// [from_table_as_checked](super::from_table_as_checked).
+    pub fn new_as_checked(
+        arguments: Arg,
+        table: &str,
+        alias: &str,
+        limit: IdentLimit,
+    ) -> Result<Self, SqlError<Arg::Error>>
+    where
+        Arg: ArgumentError,
+    {
+        check_idents([table, alias], limit).map_err(SqlError::widen)?;
+        Ok(Self::new_as(arguments, table, alias))
+    }
+
+    /// Registers `sink` to receive the metrics of the command when it is
+    /// ended, see [with_metrics](SqlCommand::with_metrics).
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, sink: &'static (dyn crate::MetricsSink + Sync)) -> Self
+    where
+        Arg: crate::AppendArguments,
+    {
+        self.sql = self.sql.with_metrics(sink);
+        self
+    }
+}
+
+impl<Arg, S> DeleteBuilder<Arg, S>
+where
+    S: Unfiltered,
+{
+    /// Writes a `USING <table>` source, or a `, <table>` source after the
+    /// first one, joined in the `WHERE` clause.
+    pub fn using(mut self, table: &str) -> DeleteBuilder<Arg, Using> {
+        if S::FIRST {
+            delete_using(&mut self.sql);
+        } else {
+            emit(&mut self.sql, Token::Comma);
+        }
+        emit(&mut self.sql, Token::Ident(table));
+        self.next()
+    }
+
+    /// Writes the `WHERE` clause, with the condition written by `build`.
+    pub fn filter_where<F, E>(mut self, build: F) -> Result<DeleteBuilder<Arg, Filtered>, E>
+    where
+        F: FnOnce(&mut SqlCommand<Arg>) -> Result<(), E>,
+    {
+        filter_where(&mut self.sql);
+        build(&mut self.sql)?;
+        Ok(self.next())
+    }
+
+    /// Ends the command without a `WHERE` clause, deleting every row of the
+    /// table.
+    pub fn end_without_where(self) -> SqlCommand<Arg> {
+        self.sql.finished()
+    }
+}
+
+impl<Arg> DeleteBuilder<Arg, Filtered> {
+    /// Ends the command.
+    pub fn end(self) -> SqlCommand<Arg> {
+        self.sql.finished()
+    }
+
+    /// Ends the command with a `RETURNING <column>, ...` clause, see
+    /// [returning_columns_checked].
+    pub fn returning(mut self, columns: &[&str]) -> Result<SqlCommand<Arg>, SqlError<Arg::Error>>
+    where
+        Arg: ArgumentError,
+    {
+        returning_columns_checked(&mut self.sql, columns)?;
+        Ok(self.sql.finished())
+    }
+
+    /// Ends the command with a `RETURNING *` clause.
+    pub fn returning_all(mut self) -> SqlCommand<Arg> {
+        returning_all(&mut self.sql);
+        self.sql.finished()
+    }
+}
+
+impl<Arg, S> DeleteBuilder<Arg, S> {
+    fn next<N>(self) -> DeleteBuilder<Arg, N> {
+        DeleteBuilder {
+            sql: self.sql,
+            _state: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        expr::{continue_condition, lhs_binary_rhs, Cmp, LogicBi},
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+        SqlCommand,
+    };
+
+    #[test]
+    fn delete_returning() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        delete_from(&mut sql, "t");
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(1)).unwrap();
+        returning_all(&mut sql);
+        assert_eq!(sql.as_command(), "DELETE FROM t WHERE id = $1 RETURNING *");
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        delete_from(&mut sql, "t");
+        returning_columns_checked(&mut sql, &["id", "name"]).unwrap();
+        assert_eq!(sql.as_command(), "DELETE FROM t RETURNING id, name");
+    }
+
+    #[test]
+    fn delete_builder() {
+        let sql: SqlCommand<TestArgs> = DeleteBuilder::new(TestArgs::new(), "t")
+            .filter_where(|sql| lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(1)))
+            .unwrap()
+            .returning_all();
+        assert_eq!(sql.as_command(), "DELETE FROM t WHERE id = $1 RETURNING *");
+
+        let sql: SqlCommand<TestArgs> = DeleteBuilder::new_as(TestArgs::new(), "session", "s")
+            .using("user")
+            .using("device")
+            .filter_where(|sql| {
+                sql.push_cmd(" s.user_id = user.id");
+                continue_condition(sql, LogicBi::And);
+                sql.push_cmd(" s.device_id = device.id");
+                continue_condition(sql, LogicBi::And);
+                lhs_binary_rhs(sql, sqlexpr::<bool>("device.lost"), Cmp::Eq, sqlvalue(true))
+            })
+            .unwrap()
+            .end();
+        assert_eq!(
+            sql.as_command(),
+            "DELETE FROM session AS s USING user, device \
+             WHERE s.user_id = user.id AND s.device_id = device.id AND device.lost = $1"
+        );
+        assert_eq!(sql.arguments.as_str(), "true;");
+
+        let sql: SqlCommand<TestArgs> =
+            DeleteBuilder::new(TestArgs::new(), "t").end_without_where();
+        assert_eq!(sql.as_command(), "DELETE FROM t");
+
+        let builder = DeleteBuilder::new(TestArgs::new(), "t")
+            .filter_where(|sql| lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(1)))
+            .unwrap();
+        assert_eq!(builder.returning(&[]).err(), Some(SqlError::EmptyList));
     }
 }
@@ -5,6 +5,7 @@
 
 pub mod comparison;
 pub mod math;
+pub mod pattern;
 
 use super::{separator, separator_optional};
 use crate::{ArgumentBuffer, SqlExpr, WriteSql};
@@ -1,8 +1,12 @@
+use core::num::NonZeroUsize;
+
 use crate::{
-    expr::{separator_optional, Group},
-    ArgumentBuffer, SqlExpr, WriteSql,
+    expr::{emit, separator, separator_optional, value_row, Group, SortedEntries, Token},
+    AppendArguments, ArgumentBuffer, SqlCommand, SqlError, SqlExpr, WriteSql,
 };
 
+use super::{continue_condition, lhs_binary_rhs, BinaryOperator, Cmp, LogicBi};
+
 pub fn between<Sql, Arg, Lhs, Rhs>(
     sql: &mut Sql,
     lhs: SqlExpr<Lhs>,
@@ -13,11 +17,11 @@ where
     Arg: ArgumentBuffer<Lhs>,
     Arg: ArgumentBuffer<Rhs, Error = <Arg as ArgumentBuffer<Lhs>>::Error>,
 {
-    separator_optional(sql);
-
-    sql.push_cmd("BETWEEN ");
+    emit(sql, Token::Keyword("BETWEEN"));
+    emit(sql, Token::Placeholder);
     sql.push_expr(lhs)?;
-    sql.push_cmd(" AND ");
+    emit(sql, Token::Keyword("AND"));
+    emit(sql, Token::Placeholder);
     sql.push_expr(rhs)
 }
 
@@ -25,26 +29,747 @@ pub fn is_null<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
+    emit(sql, Token::Keyword("IS NULL"));
+}
+
+pub fn is_not_null<Sql, Arg>(sql: &mut Sql)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Keyword("IS NOT NULL"));
+}
+
+pub fn is_in<Sql, Arg>(sql: &mut Sql) -> Group<'_, Sql, Arg>
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Keyword("IN"));
+    Group::open(sql)
+}
+
+/// Writes a boolean column as a condition, e.g. `WHERE active`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{filter_where, comparison::is};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// filter_where(&mut sql);
+/// is(&mut sql, "active");
+///
+/// assert_eq!(sql.as_command(), "WHERE active");
+/// ```
+pub fn is<Sql, Arg>(sql: &mut Sql, column: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Ident(column));
+}
+
+/// Writes a negated boolean column as a condition, e.g. `WHERE NOT deleted`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{filter_where, comparison::is_not};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// filter_where(&mut sql);
+/// is_not(&mut sql, "deleted");
+///
+/// assert_eq!(sql.as_command(), "WHERE NOT deleted");
+/// ```
+pub fn is_not<Sql, Arg>(sql: &mut Sql, column: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Keyword("NOT"));
+    emit(sql, Token::Ident(column));
+}
+
+/// Writes a null-aware equality, `<column> = $n` for `Some` value and
+/// `<column> IS NULL` for `None`.
+///
+/// A `None` value does not consume an argument.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{filter_where, continue_condition, LogicBi, comparison::eq_nullable};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// filter_where(&mut sql);
+/// eq_nullable(&mut sql, "deleted", None::<u64>)?;
+/// continue_condition(&mut sql, LogicBi::And);
+/// eq_nullable(&mut sql, "team_id", Some(7))?;
+///
+/// assert_eq!(sql.as_command(), "WHERE deleted IS NULL AND team_id = $1");
+/// # Ok(())
+/// # }
+/// ```
+pub fn eq_nullable<Sql, Arg, T>(
+    sql: &mut Sql,
+    column: &str,
+    value: Option<T>,
+) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    match value {
+        Some(value) => lhs_binary_rhs(
+            sql,
+            SqlExpr::<T>::Expr(column),
+            Cmp::Eq,
+            SqlExpr::Value(value),
+        ),
+        None => {
+            separator_optional(sql);
+            sql.push_cmd(column);
+            is_null(sql);
+            Ok(())
+        }
+    }
+}
+
+/// Writes a null-aware inequality, `<column> <> $n` for `Some` value and
+/// `<column> IS NOT NULL` for `None`.
+///
+/// A `None` value does not consume an argument.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{filter_where, continue_condition, LogicBi, comparison::ne_nullable};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// filter_where(&mut sql);
+/// ne_nullable(&mut sql, "deleted", None::<u64>)?;
+/// continue_condition(&mut sql, LogicBi::And);
+/// ne_nullable(&mut sql, "team_id", Some(7))?;
+///
+/// assert_eq!(sql.as_command(), "WHERE deleted IS NOT NULL AND team_id <> $1");
+/// # Ok(())
+/// # }
+/// ```
+pub fn ne_nullable<Sql, Arg, T>(
+    sql: &mut Sql,
+    column: &str,
+    value: Option<T>,
+) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    match value {
+        Some(value) => lhs_binary_rhs(
+            sql,
+            SqlExpr::<T>::Expr(column),
+            Cmp::Neq,
+            SqlExpr::Value(value),
+        ),
+        None => {
+            separator_optional(sql);
+            sql.push_cmd(column);
+            is_not_null(sql);
+            Ok(())
+        }
+    }
+}
+
+/// Writes a `<column> = $n AND ...` condition for every entry of `entries`,
+/// in sorted column order.
+///
+/// The values are bound in the same order, so equal entries always write the
+/// same command, regardless of the iteration order of the map. An empty map
+/// returns an [EmptyList](SqlError::EmptyList) error.
+///
+/// # Example
+///
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{filter_where, comparison::compare_all_sorted};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let filters = BTreeMap::from([("status", "open"), ("owner", "rusty")]);
+///
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// filter_where(&mut sql);
+/// compare_all_sorted(&mut sql, &filters)?;
+///
+/// assert_eq!(sql.as_command(), "WHERE owner = $1 AND status = $2");
+/// # Ok(())
+/// # }
+/// ```
+pub fn compare_all_sorted<Sql, Arg, M>(
+    sql: &mut Sql,
+    entries: M,
+) -> Result<(), SqlError<<Arg as ArgumentBuffer<M::Value>>::Error>>
+where
+    Sql: WriteSql<Arg>,
+    M: SortedEntries,
+    Arg: ArgumentBuffer<M::Value>,
+{
+    let mut empty = true;
+
+    for (column, value) in entries.into_sorted_entries() {
+        empty = false;
+        continue_condition(sql, LogicBi::And);
+        lhs_binary_rhs(
+            sql,
+            SqlExpr::<M::Value>::Expr(column.as_ref()),
+            Cmp::Eq,
+            SqlExpr::Value(value),
+        )?;
+    }
+
+    if empty {
+        return Err(SqlError::EmptyList);
+    }
+    Ok(())
+}
+
+/// Writes a `<column> <op> ROW($n, ...)` comparison of a composite column,
+/// casting the row to `type_name` when present.
+///
+/// An empty list of values returns an [EmptyList](SqlError::EmptyList) error,
+/// without writing anything.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{filter_where, Cmp, comparison::compare_row_composite};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// filter_where(&mut sql);
+/// compare_row_composite(&mut sql, "destination", Cmp::Eq, ["Main St", "Lisbon"], Some("address"))?;
+///
+/// assert_eq!(sql.as_command(), "WHERE destination = ROW($1, $2)::address");
+/// # Ok(())
+/// # }
+/// ```
+pub fn compare_row_composite<Sql, Arg, I, T>(
+    sql: &mut Sql,
+    column: &str,
+    op: Cmp,
+    values: I,
+    type_name: Option<&str>,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+    I: IntoIterator<Item = T>,
+{
+    let mut values = values.into_iter().peekable();
+    if values.peek().is_none() {
+        return Err(SqlError::EmptyList);
+    }
+
     separator_optional(sql);
+    sql.push_cmd(column);
+    separator(sql);
+    op.push_operator(sql);
+    value_row(sql, values, type_name)
+}
 
-    sql.push_cmd("IS NULL");
+/// Quantifier of a comparison against the rows of a subquery.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Quantifier {
+    /// `ANY`, true if the comparison is true for at least one row.
+    Any,
+    /// `ALL`, true if the comparison is true for every row.
+    All,
 }
 
-pub fn is_not_null<Sql, Arg>(sql: &mut Sql)
+impl Quantifier {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Any => "ANY",
+            Self::All => "ALL",
+        }
+    }
+}
+
+/// Writes a `<column> <op> (<subquery>)` comparison against a scalar
+/// subquery.
+///
+/// The placeholders of the subquery are shifted to continue the numbering of
+/// the command, see [SqlCommand::push_command].
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+/// # use sqlstr::expr::comparison::compare_subquery;
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut average: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut average);
+/// column(&mut average, "avg(price)");
+/// from_table(&mut average, "product");
+///
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, "name");
+/// from_table(&mut sql, "product");
+/// filter_where(&mut sql);
+/// compare_subquery(&mut sql, "price", Cmp::Gt, average)?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT name FROM product WHERE price > (SELECT avg(price) FROM product)"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn compare_subquery<Arg>(
+    sql: &mut SqlCommand<Arg>,
+    column: &str,
+    op: Cmp,
+    subquery: SqlCommand<Arg>,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Arg: AppendArguments,
+{
+    separator_optional(sql);
+    sql.push_cmd(column);
+    separator(sql);
+    op.push_operator(sql);
+    emit(sql, Token::Open);
+    sql.push_command(subquery)?;
+    emit(sql, Token::Close);
+    Ok(())
+}
+
+/// Writes a `<column> <op> ANY|ALL (<subquery>)` comparison against the rows
+/// of a subquery.
+///
+/// The placeholders of the subquery are shifted to continue the numbering of
+/// the command, see [SqlCommand::push_command].
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+/// # use sqlstr::expr::comparison::{compare_quantified_subquery, Quantifier};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut bids: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut bids);
+/// column(&mut bids, "amount");
+/// from_table(&mut bids, "bid");
+/// filter_where(&mut bids);
+/// lhs_binary_rhs(&mut bids, sqlexpr::<u32>("lot_id"), Cmp::Eq, sqlvalue(7))?;
+///
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, "id");
+/// from_table(&mut sql, "bid");
+/// filter_where(&mut sql);
+/// compare_quantified_subquery(&mut sql, "amount", Cmp::Gte, Quantifier::All, bids)?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT id FROM bid WHERE amount >= ALL (SELECT amount FROM bid WHERE lot_id = $1)"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn compare_quantified_subquery<Arg>(
+    sql: &mut SqlCommand<Arg>,
+    column: &str,
+    op: Cmp,
+    quantifier: Quantifier,
+    subquery: SqlCommand<Arg>,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Arg: AppendArguments,
+{
+    separator_optional(sql);
+    sql.push_cmd(column);
+    separator(sql);
+    op.push_operator(sql);
+    separator(sql);
+    sql.push_cmd(quantifier.as_str());
+    emit(sql, Token::Open);
+    sql.push_command(subquery)?;
+    emit(sql, Token::Close);
+    Ok(())
+}
+
+/// Writes a `(<column> IN ($n, ...) OR <column> IN ($n, ...) ...)` condition,
+/// splitting the values into `IN` lists of at most `chunk_size` values.
+///
+/// Keeps each `IN` list under the size the planner handles well, with the
+/// same result as a single `IN` list. An empty list of values returns an
+/// [EmptyList](SqlError::EmptyList) error, without writing anything.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{filter_where, comparison::in_values_chunked};
+/// # use core::{convert::Infallible, num::NonZeroUsize};
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// filter_where(&mut sql);
+/// in_values_chunked(&mut sql, "id", [1, 2, 3, 4, 5], NonZeroUsize::new(2).unwrap())?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "WHERE (id IN ($1, $2) OR id IN ($3, $4) OR id IN ($5))"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn in_values_chunked<Sql, Arg, I, T>(
+    sql: &mut Sql,
+    column: &str,
+    values: I,
+    chunk_size: NonZeroUsize,
+) -> Result<(), SqlError<Arg::Error>>
 where
     Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+    I: IntoIterator<Item = T>,
 {
+    let mut values = values.into_iter().peekable();
+    if values.peek().is_none() {
+        return Err(SqlError::EmptyList);
+    }
+
     separator_optional(sql);
+    sql.push_cmd("(");
+    for (index, value) in values.enumerate() {
+        let position = index.checked_rem(chunk_size.get()).unwrap_or_default();
+        if position == 0 {
+            if index > 0 {
+                sql.push_cmd(") OR ");
+            }
+            sql.push_cmd(column);
+            emit(sql, Token::Keyword("IN"));
+            emit(sql, Token::Open);
+        } else {
+            sql.push_cmd(", ");
+        }
+        sql.push_value(value)?;
+    }
+    sql.push_cmd("))");
+    Ok(())
+}
 
-    sql.push_cmd("IS NOT NULL");
+/// Writes a case-insensitive equality, `lower(<column>) = lower($n)`.
+///
+/// The column side is written exactly as `lower(<column>)`, matching an
+/// expression index `CREATE INDEX ... (lower(<column>))`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{filter_where, comparison::eq_insensitive};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// filter_where(&mut sql);
+/// eq_insensitive(&mut sql, "email", "Rusty@Email.com")?;
+///
+/// assert_eq!(sql.as_command(), "WHERE lower(email) = lower($1)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn eq_insensitive<Sql, Arg, T>(sql: &mut Sql, column: &str, value: T) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    lower_column(sql, column);
+    emit(sql, Token::Keyword("="));
+    lower_value(sql, value)
 }
 
-pub fn is_in<Sql, Arg>(sql: &mut Sql) -> Group<'_, Sql, Arg>
+/// Writes a case-insensitive `lower(<column>) IN (lower($n), ...)`
+/// condition.
+///
+/// An empty list of values returns an [EmptyList](SqlError::EmptyList)
+/// error, without writing anything.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{filter_where, comparison::in_insensitive};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// filter_where(&mut sql);
+/// in_insensitive(&mut sql, "username", ["Rusty", "Ferris"])?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "WHERE lower(username) IN (lower($1), lower($2))"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn in_insensitive<Sql, Arg, I, T>(
+    sql: &mut Sql,
+    column: &str,
+    values: I,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+    I: IntoIterator<Item = T>,
+{
+    let mut values = values.into_iter().peekable();
+    if values.peek().is_none() {
+        return Err(SqlError::EmptyList);
+    }
+
+    lower_column(sql, column);
+    emit(sql, Token::Keyword("IN"));
+    emit(sql, Token::Open);
+    for (index, value) in values.enumerate() {
+        if index > 0 {
+            emit(sql, Token::Comma);
+        }
+        lower_value(sql, value)?;
+    }
+    emit(sql, Token::Close);
+    Ok(())
+}
+
+fn lower_column<Sql, Arg>(sql: &mut Sql, column: &str)
 where
     Sql: WriteSql<Arg>,
 {
     separator_optional(sql);
+    sql.push_cmd("lower(");
+    sql.push_cmd(column);
+    sql.push_cmd(")");
+}
 
-    sql.push_cmd("IN ");
-    Group::open(sql)
+fn lower_value<Sql, Arg, T>(sql: &mut Sql, value: T) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    separator_optional(sql);
+    sql.push_cmd("lower(");
+    sql.push_value(value)?;
+    sql.push_cmd(")");
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::collections::BTreeMap;
+
+    use super::{
+        compare_all_sorted, compare_quantified_subquery, compare_row_composite, compare_subquery,
+        eq_insensitive, eq_nullable, in_insensitive, in_values_chunked, is, ne_nullable,
+        Quantifier,
+    };
+    use crate::{
+        expr::{
+            column, continue_condition, filter_where, from_table, lhs_binary_rhs, select, Cmp,
+            LogicBi,
+        },
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+        SqlCommand, SqlError,
+    };
+
+    #[test]
+    fn nullable_first_condition() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        filter_where(&mut sql);
+        eq_nullable(&mut sql, "manager_id", None::<u32>).unwrap();
+        assert_eq!(sql.as_command(), "WHERE manager_id IS NULL");
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        filter_where(&mut sql);
+        eq_nullable(&mut sql, "manager_id", Some(3)).unwrap();
+        assert_eq!(sql.as_command(), "WHERE manager_id = $1");
+        assert_eq!(sql.arguments.as_str(), "3;");
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        filter_where(&mut sql);
+        ne_nullable(&mut sql, "manager_id", None::<u32>).unwrap();
+        assert_eq!(sql.as_command(), "WHERE manager_id IS NOT NULL");
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        filter_where(&mut sql);
+        ne_nullable(&mut sql, "manager_id", Some(3)).unwrap();
+        assert_eq!(sql.as_command(), "WHERE manager_id <> $1");
+    }
+
+    #[test]
+    fn compare_all_sorted_after_condition() {
+        let filters = BTreeMap::from([("team_id", 3), ("age", 30)]);
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        filter_where(&mut sql);
+        is(&mut sql, "active");
+        compare_all_sorted(&mut sql, filters).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "WHERE active AND age = $1 AND team_id = $2"
+        );
+        assert_eq!(sql.arguments.as_str(), "30;3;");
+    }
+
+    #[test]
+    fn compare_all_sorted_empty() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        filter_where(&mut sql);
+
+        assert_eq!(
+            compare_all_sorted(&mut sql, &BTreeMap::<&str, u32>::new()),
+            Err(SqlError::EmptyList)
+        );
+        assert_eq!(sql.as_command(), "WHERE");
+    }
+
+    #[test]
+    fn composite_row_comparison() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        filter_where(&mut sql);
+        compare_row_composite(&mut sql, "origin", Cmp::Neq, ["Rua A", "Porto"], None).unwrap();
+        continue_condition(&mut sql, LogicBi::And);
+        compare_row_composite(&mut sql, "size", Cmp::Gt, [10, 20], Some("dimension")).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "WHERE origin <> ROW($1, $2) AND size > ROW($3, $4)::dimension"
+        );
+        assert_eq!(sql.arguments.as_str(), "Rua A;Porto;10;20;");
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        filter_where(&mut sql);
+        assert_eq!(
+            compare_row_composite(&mut sql, "size", Cmp::Eq, [0u8; 0], None),
+            Err(SqlError::EmptyList)
+        );
+        assert_eq!(sql.as_command(), "WHERE");
+    }
+
+    fn prices(category: &str) -> SqlCommand<TestArgs> {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        column(&mut sql, "price");
+        from_table(&mut sql, "product");
+        filter_where(&mut sql);
+        lhs_binary_rhs(
+            &mut sql,
+            sqlexpr::<&str>("category"),
+            Cmp::Eq,
+            sqlvalue(category),
+        )
+        .unwrap();
+        sql
+    }
+
+    #[test]
+    fn scalar_subquery_comparison() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        column(&mut sql, "name");
+        from_table(&mut sql, "product");
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<bool>("active"), Cmp::Eq, sqlvalue(true)).unwrap();
+        continue_condition(&mut sql, LogicBi::And);
+
+        let mut max = prices("book");
+        max.command = max.command.replacen("price", "max(price)", 1);
+        compare_subquery(&mut sql, "price", Cmp::Lt, max).unwrap();
+        continue_condition(&mut sql, LogicBi::And);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("stock"), Cmp::Gt, sqlvalue(0)).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT name FROM product WHERE active = $1 \
+             AND price < (SELECT max(price) FROM product WHERE category = $2) AND stock > $3"
+        );
+        assert_eq!(sql.arguments.as_str(), "true;book;0;");
+    }
+
+    #[test]
+    fn quantified_subquery_comparison() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        filter_where(&mut sql);
+        compare_quantified_subquery(&mut sql, "price", Cmp::Gt, Quantifier::All, prices("book"))
+            .unwrap();
+        continue_condition(&mut sql, LogicBi::Or);
+        compare_quantified_subquery(&mut sql, "price", Cmp::Eq, Quantifier::Any, prices("toy"))
+            .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "WHERE price > ALL (SELECT price FROM product WHERE category = $1) \
+             OR price = ANY (SELECT price FROM product WHERE category = $2)"
+        );
+        assert_eq!(sql.arguments.as_str(), "book;toy;");
+    }
+
+    #[test]
+    fn in_values_chunks() {
+        let chunk = |size| core::num::NonZeroUsize::new(size).unwrap();
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        filter_where(&mut sql);
+        in_values_chunked(&mut sql, "id", [1, 2, 3, 4, 5, 6, 7], chunk(3)).unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "WHERE (id IN ($1, $2, $3) OR id IN ($4, $5, $6) OR id IN ($7))"
+        );
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        in_values_chunked(&mut sql, "id", [1, 2], chunk(10)).unwrap();
+        assert_eq!(sql.as_command(), "(id IN ($1, $2))");
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        assert_eq!(
+            in_values_chunked(&mut sql, "id", [0u32; 0], chunk(3)),
+            Err(SqlError::EmptyList)
+        );
+        assert_eq!(sql.as_command(), "");
+    }
+
+    #[test]
+    fn insensitive_comparisons() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        filter_where(&mut sql);
+        eq_insensitive(&mut sql, "u.email", "Rusty@Email.com").unwrap();
+        continue_condition(&mut sql, LogicBi::And);
+        in_insensitive(&mut sql, "u.username", ["Rusty", "FERRIS", "crab"]).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "WHERE lower(u.email) = lower($1) \
+             AND lower(u.username) IN (lower($2), lower($3), lower($4))"
+        );
+        assert_eq!(sql.arguments.as_str(), "Rusty@Email.com;Rusty;FERRIS;crab;");
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        filter_where(&mut sql);
+        assert_eq!(
+            in_insensitive(&mut sql, "username", core::iter::empty::<&str>()),
+            Err(SqlError::EmptyList)
+        );
+        assert_eq!(sql.as_command(), "WHERE");
+    }
 }
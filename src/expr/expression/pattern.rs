@@ -0,0 +1,207 @@
+use alloc::string::String;
+
+use crate::{
+    expr::{emit, Token},
+    ArgumentBuffer, WriteSql,
+};
+
+/// Escape character used by [contains], [starts_with] and [ends_with].
+pub const LIKE_ESCAPE: char = '\\';
+
+/// Escapes the `LIKE` wildcards (`%` and `_`) and the escape character itself
+/// from `input`, so it can be matched literally.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::expr::pattern::escape_like_pattern;
+/// assert_eq!(escape_like_pattern("50%_off\\", '\\'), "50\\%\\_off\\\\");
+/// assert_eq!(escape_like_pattern("50%_off!", '!'), "50!%!_off!!");
+/// ```
+pub fn escape_like_pattern(input: &str, escape_char: char) -> String {
+    let mut escaped = String::with_capacity(escaped_len(input, escape_char));
+    push_escaped(&mut escaped, input, escape_char);
+    escaped
+}
+
+fn escaped_len(input: &str, escape_char: char) -> usize {
+    let escapes = input
+        .chars()
+        .filter(|&ch| ch == '%' || ch == '_' || ch == escape_char)
+        .count();
+    input
+        .len()
+        .saturating_add(escapes.saturating_mul(escape_char.len_utf8()))
+}
+
+fn push_escaped(buf: &mut String, input: &str, escape_char: char) {
+    for ch in input.chars() {
+        if ch == '%' || ch == '_' || ch == escape_char {
+            buf.push(escape_char);
+        }
+        buf.push(ch);
+    }
+}
+
+fn like_term(term: &str, leading: bool, trailing: bool) -> String {
+    let wildcards = usize::from(leading).saturating_add(usize::from(trailing));
+    let mut pattern =
+        String::with_capacity(escaped_len(term, LIKE_ESCAPE).saturating_add(wildcards));
+    if leading {
+        pattern.push('%');
+    }
+    push_escaped(&mut pattern, term, LIKE_ESCAPE);
+    if trailing {
+        pattern.push('%');
+    }
+    pattern
+}
+
+fn like_escaped<Sql, Arg>(sql: &mut Sql, column: &str, pattern: String) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<String>,
+{
+    emit(sql, Token::Ident(column));
+    emit(sql, Token::Keyword("LIKE"));
+    emit(sql, Token::Placeholder);
+    sql.push_value(pattern)?;
+    let mut escape = [0; 4];
+    emit(sql, Token::Keyword("ESCAPE"));
+    emit(sql, Token::Quoted(LIKE_ESCAPE.encode_utf8(&mut escape)));
+    Ok(())
+}
+
+/// Writes a `<column> LIKE $n ESCAPE '\'` condition matching the columns
+/// containing `term`.
+///
+/// The term is escaped and bound as `%<term>%`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{filter_where, pattern::contains};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// filter_where(&mut sql);
+/// contains(&mut sql, "name", "100%")?;
+///
+/// assert_eq!(sql.as_command(), "WHERE name LIKE $1 ESCAPE '\\'");
+/// # Ok(())
+/// # }
+/// ```
+pub fn contains<Sql, Arg>(sql: &mut Sql, column: &str, term: &str) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<String>,
+{
+    like_escaped(sql, column, like_term(term, true, true))
+}
+
+/// Writes a `<column> LIKE $n ESCAPE '\'` condition matching the columns
+/// starting with `term`.
+///
+/// The term is escaped and bound as `<term>%`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{filter_where, pattern::starts_with};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// filter_where(&mut sql);
+/// starts_with(&mut sql, "name", "rust_")?;
+///
+/// assert_eq!(sql.as_command(), "WHERE name LIKE $1 ESCAPE '\\'");
+/// # Ok(())
+/// # }
+/// ```
+pub fn starts_with<Sql, Arg>(sql: &mut Sql, column: &str, term: &str) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<String>,
+{
+    like_escaped(sql, column, like_term(term, false, true))
+}
+
+/// Writes a `<column> LIKE $n ESCAPE '\'` condition matching the columns
+/// ending with `term`.
+///
+/// The term is escaped and bound as `%<term>`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{filter_where, pattern::ends_with};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// filter_where(&mut sql);
+/// ends_with(&mut sql, "email", "@email.com")?;
+///
+/// assert_eq!(sql.as_command(), "WHERE email LIKE $1 ESCAPE '\\'");
+/// # Ok(())
+/// # }
+/// ```
+pub fn ends_with<Sql, Arg>(sql: &mut Sql, column: &str, term: &str) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<String>,
+{
+    like_escaped(sql, column, like_term(term, true, false))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        expr::{continue_condition, filter_where, LogicBi},
+        test::TestArgs,
+        SqlCommand,
+    };
+
+    #[test]
+    fn escape_wildcards() {
+        assert_eq!(escape_like_pattern("", '\\'), "");
+        assert_eq!(escape_like_pattern("plain", '\\'), "plain");
+        assert_eq!(escape_like_pattern("%", '\\'), "\\%");
+        assert_eq!(escape_like_pattern("a_b", '\\'), "a\\_b");
+        assert_eq!(escape_like_pattern("\\", '\\'), "\\\\");
+        assert_eq!(escape_like_pattern("#%#", '#'), "###%##");
+        assert_eq!(escape_like_pattern("ação_%", '\\'), "ação\\_\\%");
+    }
+
+    #[test]
+    fn escape_exact_capacity() {
+        for (input, escape) in [("", '\\'), ("a%b_c\\", '\\'), ("%ç%", 'ç'), ("none", '#')] {
+            let escaped = escape_like_pattern(input, escape);
+            assert_eq!(escaped.capacity(), escaped.len());
+        }
+
+        let pattern = like_term("5%_", true, true);
+        assert_eq!(pattern, "%5\\%\\_%");
+        assert_eq!(pattern.capacity(), pattern.len());
+    }
+
+    #[test]
+    fn like_conditions() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        filter_where(&mut sql);
+        contains(&mut sql, "name", "50%").unwrap();
+        continue_condition(&mut sql, LogicBi::Or);
+        starts_with(&mut sql, "name", "a_b").unwrap();
+        continue_condition(&mut sql, LogicBi::Or);
+        ends_with(&mut sql, "name", "\\").unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "WHERE name LIKE $1 ESCAPE '\\' OR name LIKE $2 ESCAPE '\\' OR name LIKE $3 ESCAPE '\\'"
+        );
+        assert_eq!(sql.arguments.as_str(), r"%50\%%;a\_b%;%\\;");
+    }
+}
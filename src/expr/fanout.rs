@@ -0,0 +1,129 @@
+use super::{as_alias, emit, item_separator, select, string_literal, Token};
+use crate::{ArgumentError, SqlError, WriteSql};
+
+/// Writes the same query against a list of schemas, combining the results
+/// with `UNION ALL`.
+///
+/// Every branch starts with `SELECT '<schema>' AS <tag>, ` and is completed
+/// by `build`, which receives the schema name and writes the remaining select
+/// list and clauses. All branches share the argument buffer, so the
+/// placeholders are numbered across the whole command.
+///
+/// An empty list of schemas returns an [EmptyList](SqlError::EmptyList) error,
+/// and a schema containing a NUL character an
+/// [InvalidText](SqlError::InvalidText) error.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{column, from_table, filter_where, lhs_binary_rhs, fanout_schemas, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// fanout_schemas(&mut sql, &["acme", "globex"], "tenant", |sql, schema| {
+///     column(sql, "id");
+///     from_table(sql, &format!("{schema}.user"));
+///     filter_where(sql);
+///     lhs_binary_rhs(sql, sqlexpr::<bool>("active"), Cmp::Eq, sqlvalue(true))?;
+///     Ok(())
+/// })?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT 'acme' AS tenant, id FROM acme.user WHERE active = $1 \
+///      UNION ALL SELECT 'globex' AS tenant, id FROM globex.user WHERE active = $2"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn fanout_schemas<Sql, Arg, F>(
+    sql: &mut Sql,
+    schemas: &[&str],
+    tag: &str,
+    mut build: F,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+    F: FnMut(&mut Sql, &str) -> Result<(), SqlError<Arg::Error>>,
+{
+    if schemas.is_empty() {
+        return Err(SqlError::EmptyList);
+    }
+
+    for (i, schema) in schemas.iter().enumerate() {
+        if i > 0 {
+            emit(sql, Token::Keyword("UNION ALL"));
+        }
+        select(sql);
+        string_literal(sql, schema)?;
+        as_alias(sql, tag);
+        item_separator(sql);
+        build(sql, schema)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::format;
+
+    use super::*;
+    use crate::{
+        expr::{column, filter_where, from_table, lhs_binary_rhs, Cmp},
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+        SqlCommand,
+    };
+
+    #[test]
+    fn fanout_two_schemas() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        fanout_schemas(
+            &mut sql,
+            &["tenant_a", "tenant_b"],
+            "schema",
+            |sql, schema| {
+                column(sql, "id, name");
+                from_table(sql, &format!("{schema}.customer"));
+                filter_where(sql);
+                lhs_binary_rhs(sql, sqlexpr::<u32>("age"), Cmp::Gt, sqlvalue(schema.len()))?;
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT 'tenant_a' AS schema, id, name FROM tenant_a.customer WHERE age > $1 \
+             UNION ALL SELECT 'tenant_b' AS schema, id, name FROM tenant_b.customer WHERE age > $2"
+        );
+        assert_eq!(sql.arguments.as_str(), "8;8;");
+    }
+
+    #[test]
+    fn fanout_quoted_schema_tag() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        fanout_schemas(&mut sql, &["o'neil"], "schema", |sql, _| {
+            column(sql, "1");
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(sql.as_command(), "SELECT 'o''neil' AS schema, 1");
+    }
+
+    #[test]
+    fn fanout_no_schemas() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        let err = fanout_schemas(&mut sql, &[], "schema", |_, _| Ok(())).unwrap_err();
+
+        assert_eq!(err, SqlError::EmptyList);
+        assert_eq!(sql.as_command(), "");
+    }
+}
@@ -1,6 +1,9 @@
-use crate::WriteSql;
+use core::marker::PhantomData;
+use core::ops::Range;
 
-use super::separator_optional;
+use crate::{ArgumentBuffer, WriteSql};
+
+use super::{continue_condition, emit, tail_state::Start, LogicBi, Tail, Token};
 
 /// Write a `WHERE` clause in the sql command.
 ///
@@ -21,8 +24,7 @@ pub fn filter_where<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("WHERE");
+    emit(sql, Token::Keyword("WHERE"));
 }
 
 /// Write a `HAVING` clause in the sql command.
@@ -44,6 +46,461 @@ pub fn filter_having<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("HAVING");
+    emit(sql, Token::Keyword("HAVING"));
+}
+
+/// `WHERE` clause built from optional filters, writing only the conditions
+/// of the `Some` values, joined with `AND`.
+///
+/// The `WHERE` keyword is written before the first condition, so a clause
+/// without any condition is skipped entirely.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, columns_iter, from_table, Filters};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let status = Some("active");
+/// let name: Option<&str> = None;
+/// let created = Some(20230101..20240101);
+///
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// columns_iter(&mut sql, ["id", "name"]);
+/// from_table(&mut sql, "user");
+/// let count = Filters::new(&mut sql)
+///     .eq("status", status)?
+///     .like("name", name)?
+///     .range("created_at", created)?
+///     .end();
+///
+/// assert_eq!(count, 2);
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT id, name FROM user WHERE status = $1 AND created_at >= $2 AND created_at < $3"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub struct Filters<'cmd, Sql: WriteSql<Arg>, Arg> {
+    sql: &'cmd mut Sql,
+    count: usize,
+    open: bool,
+    _arg: PhantomData<Arg>,
+}
+
+impl<'cmd, Sql, Arg> Filters<'cmd, Sql, Arg>
+where
+    Sql: WriteSql<Arg>,
+{
+    /// Starts the filters of the command.
+    pub fn new(sql: &'cmd mut Sql) -> Self {
+        Self {
+            sql,
+            count: 0,
+            open: false,
+            _arg: PhantomData,
+        }
+    }
+
+    /// Writes a `<column> = $n` condition if `value` is `Some`.
+    pub fn eq<T>(self, column: &str, value: Option<T>) -> Result<Self, Arg::Error>
+    where
+        Arg: ArgumentBuffer<T>,
+    {
+        self.compare(column, "=", value)
+    }
+
+    /// Writes a `<column> LIKE $n` condition if `pattern` is `Some`.
+    ///
+    /// The pattern is bound as is, see [pattern](super::pattern) to match
+    /// escaped terms.
+    pub fn like<T>(self, column: &str, pattern: Option<T>) -> Result<Self, Arg::Error>
+    where
+        Arg: ArgumentBuffer<T>,
+    {
+        self.compare(column, "LIKE", pattern)
+    }
+
+    /// Writes a `<column> >= $n AND <column> < $m` condition if `range` is
+    /// `Some`, matching the half-open range `start..end`.
+    pub fn range<T>(mut self, column: &str, range: Option<Range<T>>) -> Result<Self, Arg::Error>
+    where
+        Arg: ArgumentBuffer<T>,
+    {
+        if let Some(range) = range {
+            self.condition(column, ">=", range.start)?;
+            self.condition(column, "<", range.end)?;
+            self.count = self.count.saturating_add(1);
+        }
+        Ok(self)
+    }
+
+    /// Ends the filters, returning the number of filters written.
+    pub fn end(self) -> usize {
+        self.count
+    }
+
+    /// Ends the filters, continuing the command with its [Tail] clauses.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, Void};
+    /// # use sqlstr::expr::{select, columns_iter, from_table, Filters};
+    /// # use core::convert::Infallible;
+    /// # fn main() -> Result<(), Infallible> {
+    /// let mut sql: SqlCommand<Void> = SqlCommand::default();
+    /// select(&mut sql);
+    /// columns_iter(&mut sql, ["id"]);
+    /// from_table(&mut sql, "user");
+    /// Filters::new(&mut sql)
+    ///     .eq("status", Some("active"))?
+    ///     .into_tail()
+    ///     .group_by(["id"])
+    ///     .limit(10)?;
+    ///
+    /// assert_eq!(
+    ///     sql.as_command(),
+    ///     "SELECT id FROM user WHERE status = $1 GROUP BY id LIMIT $2"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_tail(self) -> Tail<'cmd, Sql, Arg, Start> {
+        Tail::new(self.sql)
+    }
+
+    fn compare<T>(mut self, column: &str, op: &str, value: Option<T>) -> Result<Self, Arg::Error>
+    where
+        Arg: ArgumentBuffer<T>,
+    {
+        if let Some(value) = value {
+            self.condition(column, op, value)?;
+            self.count = self.count.saturating_add(1);
+        }
+        Ok(self)
+    }
+
+    fn condition<T>(&mut self, column: &str, op: &str, value: T) -> Result<(), Arg::Error>
+    where
+        Arg: ArgumentBuffer<T>,
+    {
+        if self.open {
+            continue_condition(self.sql, LogicBi::And);
+        } else {
+            filter_where(self.sql);
+            self.open = true;
+        }
+
+        emit(self.sql, Token::Ident(column));
+        emit(self.sql, Token::Keyword(op));
+        emit(self.sql, Token::Placeholder);
+        self.sql.push_value(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Filters;
+    use crate::ArgumentBuffer;
+    use crate::{
+        expr::{
+            comparison::{
+                between, eq_nullable, in_values_chunked, is, is_in, is_not, is_null, ne_nullable,
+            },
+            continue_condition, delete_from, filter_where, from_table, lhs_binary_rhs,
+            pattern::contains,
+            select, set_column, set_update, update_table, Cmp, LogicBi,
+        },
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+        SqlCommand, WriteSql,
+    };
+
+    type Error = crate::SqlError<core::fmt::Error>;
+
+    fn select_head(sql: &mut SqlCommand<TestArgs>) -> Result<(), Error> {
+        select(sql);
+        sql.push_cmd(" *");
+        from_table(sql, "item");
+        Ok(())
+    }
+
+    fn update_head(sql: &mut SqlCommand<TestArgs>) -> Result<(), Error> {
+        update_table(sql, "item");
+        set_update(sql);
+        set_column(sql, "seen");
+        sql.push_cmd(" ");
+        sql.push_value(true)?;
+        Ok(())
+    }
+
+    fn delete_head(sql: &mut SqlCommand<TestArgs>) -> Result<(), Error> {
+        delete_from(sql, "item");
+        Ok(())
+    }
+
+    /// Writes `build` as the `WHERE` clause of the command written by
+    /// `head`, checking the command and the arguments bound after the head.
+    fn check<F>(
+        head: fn(&mut SqlCommand<TestArgs>) -> Result<(), Error>,
+        build: F,
+        expected: &str,
+        args: &str,
+    ) where
+        F: FnOnce(&mut SqlCommand<TestArgs>) -> Result<(), Error>,
+    {
+        let mut sql = SqlCommand::default();
+        head(&mut sql).unwrap();
+        let bound = sql.arguments.as_str().len();
+        filter_where(&mut sql);
+        build(&mut sql).unwrap();
+
+        assert_eq!(sql.as_command(), expected);
+        assert_eq!(sql.arguments.as_str().get(bound..), Some(args));
+    }
+
+    /// Runs each `WHERE` test against a SELECT, an UPDATE and a DELETE,
+    /// with the expected command of each statement kind.
+    macro_rules! where_matrix {
+        ($(
+            $name:ident($build:expr) {
+                select: $select:literal,
+                update: $update:literal,
+                delete: $delete:literal,
+                args: $args:literal,
+            }
+        )+) => {
+            mod select_where {
+                use super::*;
+                $(#[test] fn $name() { check(select_head, $build, $select, $args) })+
+            }
+
+            mod update_where {
+                use super::*;
+                $(#[test] fn $name() { check(update_head, $build, $update, $args) })+
+            }
+
+            mod delete_where {
+                use super::*;
+                $(#[test] fn $name() { check(delete_head, $build, $delete, $args) })+
+            }
+        };
+    }
+
+    where_matrix! {
+        single_comparison(|sql| {
+            lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(7))?;
+            Ok(())
+        }) {
+            select: "SELECT * FROM item WHERE id = $1",
+            update: "UPDATE item SET seen = $1 WHERE id = $2",
+            delete: "DELETE FROM item WHERE id = $1",
+            args: "7;",
+        }
+
+        chained_conditions(|sql| {
+            lhs_binary_rhs(sql, sqlexpr::<u32>("stock"), Cmp::Gt, sqlvalue(0))?;
+            continue_condition(sql, LogicBi::And);
+            sql.push_cmd(" price");
+            between(sql, sqlvalue(10), sqlvalue(20))?;
+            continue_condition(sql, LogicBi::Or);
+            sql.push_cmd(" removed_at");
+            is_null(sql);
+            Ok(())
+        }) {
+            select: "SELECT * FROM item \
+                     WHERE stock > $1 AND price BETWEEN $2 AND $3 OR removed_at IS NULL",
+            update: "UPDATE item SET seen = $1 \
+                     WHERE stock > $2 AND price BETWEEN $3 AND $4 OR removed_at IS NULL",
+            delete: "DELETE FROM item \
+                     WHERE stock > $1 AND price BETWEEN $2 AND $3 OR removed_at IS NULL",
+            args: "0;10;20;",
+        }
+
+        in_list_and_pattern(|sql| {
+            sql.push_cmd(" kind");
+            let mut group = is_in(sql);
+            group.push_value(1)?;
+            group.push_cmd(", ");
+            group.push_value(2)?;
+            group.close();
+            continue_condition(sql, LogicBi::And);
+            contains(sql, "name", "50%")?;
+            Ok(())
+        }) {
+            select: "SELECT * FROM item WHERE kind IN ($1, $2) AND name LIKE $3 ESCAPE '\\'",
+            update: "UPDATE item SET seen = $1 \
+                     WHERE kind IN ($2, $3) AND name LIKE $4 ESCAPE '\\'",
+            delete: "DELETE FROM item WHERE kind IN ($1, $2) AND name LIKE $3 ESCAPE '\\'",
+            args: "1;2;%50\\%%;",
+        }
+
+        nullable_comparison(|sql| {
+            eq_nullable(sql, "owner_id", None::<u32>)?;
+            continue_condition(sql, LogicBi::And);
+            eq_nullable(sql, "team_id", Some(3))?;
+            Ok(())
+        }) {
+            select: "SELECT * FROM item WHERE owner_id IS NULL AND team_id = $1",
+            update: "UPDATE item SET seen = $1 WHERE owner_id IS NULL AND team_id = $2",
+            delete: "DELETE FROM item WHERE owner_id IS NULL AND team_id = $1",
+            args: "3;",
+        }
+
+        nullable_continuation_numbering(|sql| {
+            eq_nullable(sql, "a", Some(1))?;
+            continue_condition(sql, LogicBi::And);
+            eq_nullable(sql, "b", None::<u32>)?;
+            continue_condition(sql, LogicBi::And);
+            ne_nullable(sql, "c", None::<u32>)?;
+            continue_condition(sql, LogicBi::And);
+            ne_nullable(sql, "d", Some(4))?;
+            Ok(())
+        }) {
+            select: "SELECT * FROM item WHERE a = $1 AND b IS NULL AND c IS NOT NULL AND d <> $2",
+            update: "UPDATE item SET seen = $1 \
+                     WHERE a = $2 AND b IS NULL AND c IS NOT NULL AND d <> $3",
+            delete: "DELETE FROM item WHERE a = $1 AND b IS NULL AND c IS NOT NULL AND d <> $2",
+            args: "1;4;",
+        }
+
+        boolean_column_first_condition(|sql| {
+            continue_condition(sql, LogicBi::And);
+            is(sql, "active");
+            Ok(())
+        }) {
+            select: "SELECT * FROM item WHERE active",
+            update: "UPDATE item SET seen = $1 WHERE active",
+            delete: "DELETE FROM item WHERE active",
+            args: "",
+        }
+
+        negated_column_first_condition(|sql| {
+            continue_condition(sql, LogicBi::And);
+            is_not(sql, "active");
+            Ok(())
+        }) {
+            select: "SELECT * FROM item WHERE NOT active",
+            update: "UPDATE item SET seen = $1 WHERE NOT active",
+            delete: "DELETE FROM item WHERE NOT active",
+            args: "",
+        }
+
+        boolean_column_continuation(|sql| {
+            lhs_binary_rhs(sql, sqlexpr::<u8>("age"), Cmp::Gt, sqlvalue(18))?;
+            continue_condition(sql, LogicBi::And);
+            is(sql, "active");
+            continue_condition(sql, LogicBi::Or);
+            is_not(sql, "deleted");
+            Ok(())
+        }) {
+            select: "SELECT * FROM item WHERE age > $1 AND active OR NOT deleted",
+            update: "UPDATE item SET seen = $1 WHERE age > $2 AND active OR NOT deleted",
+            delete: "DELETE FROM item WHERE age > $1 AND active OR NOT deleted",
+            args: "18;",
+        }
+
+        boolean_column_chained_with_comparison(|sql| {
+            is(sql, "active");
+            continue_condition(sql, LogicBi::And);
+            lhs_binary_rhs(sql, sqlexpr::<u8>("age"), Cmp::Gt, sqlvalue(21))?;
+            Ok(())
+        }) {
+            select: "SELECT * FROM item WHERE active AND age > $1",
+            update: "UPDATE item SET seen = $1 WHERE active AND age > $2",
+            delete: "DELETE FROM item WHERE active AND age > $1",
+            args: "21;",
+        }
+
+        chunked_in_values(|sql| {
+            let chunk = core::num::NonZeroUsize::new(3).unwrap();
+            in_values_chunked(sql, "id", [1, 2, 3, 4, 5, 6], chunk)?;
+            continue_condition(sql, LogicBi::And);
+            lhs_binary_rhs(sql, sqlexpr::<bool>("active"), Cmp::Eq, sqlvalue(true))?;
+            Ok(())
+        }) {
+            select: "SELECT * FROM item \
+                     WHERE (id IN ($1, $2, $3) OR id IN ($4, $5, $6)) AND active = $7",
+            update: "UPDATE item SET seen = $1 \
+                     WHERE (id IN ($2, $3, $4) OR id IN ($5, $6, $7)) AND active = $8",
+            delete: "DELETE FROM item \
+                     WHERE (id IN ($1, $2, $3) OR id IN ($4, $5, $6)) AND active = $7",
+            args: "1;2;3;4;5;6;true;",
+        }
+    }
+
+    mod filters {
+        use super::*;
+
+        fn search(
+            status: Option<&str>,
+            name: Option<&str>,
+            created: Option<core::ops::Range<u32>>,
+        ) -> (SqlCommand<TestArgs>, usize) {
+            let mut sql = SqlCommand::default();
+            select_head(&mut sql).unwrap();
+            let count = Filters::new(&mut sql)
+                .eq("status", status)
+                .unwrap()
+                .like("name", name)
+                .unwrap()
+                .range("created_at", created)
+                .unwrap()
+                .end();
+            (sql, count)
+        }
+
+        #[test]
+        fn no_filter() {
+            let (sql, count) = search(None, None, None);
+            assert_eq!(count, 0);
+            assert_eq!(sql.as_command(), "SELECT * FROM item");
+            assert_eq!(ArgumentBuffer::<u32>::count(&sql.arguments), 0);
+        }
+
+        #[test]
+        fn one_filter() {
+            let (sql, count) = search(None, Some("rust%"), None);
+            assert_eq!(count, 1);
+            assert_eq!(sql.as_command(), "SELECT * FROM item WHERE name LIKE $1");
+            assert_eq!(ArgumentBuffer::<u32>::count(&sql.arguments), 1);
+            assert_eq!(sql.arguments.as_str(), "rust%;");
+        }
+
+        #[test]
+        fn every_filter() {
+            let (sql, count) = search(Some("active"), Some("rust%"), Some(10..20));
+            assert_eq!(count, 3);
+            assert_eq!(
+                sql.as_command(),
+                "SELECT * FROM item WHERE status = $1 AND name LIKE $2 \
+                 AND created_at >= $3 AND created_at < $4"
+            );
+            assert_eq!(ArgumentBuffer::<u32>::count(&sql.arguments), 4);
+            assert_eq!(sql.arguments.as_str(), "active;rust%;10;20;");
+        }
+
+        #[test]
+        fn into_tail() {
+            let mut sql = SqlCommand::default();
+            select_head(&mut sql).unwrap();
+            Filters::new(&mut sql)
+                .eq("status", Some("active"))
+                .unwrap()
+                .eq("name", None::<&str>)
+                .unwrap()
+                .into_tail()
+                .limit(5)
+                .unwrap();
+            assert_eq!(
+                sql.as_command(),
+                "SELECT * FROM item WHERE status = $1 LIMIT $2"
+            );
+            assert_eq!(sql.arguments.as_str(), "active;5;");
+        }
+    }
 }
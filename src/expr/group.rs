@@ -46,9 +46,34 @@ where
         self.0.push_cmd(expr)
     }
 
+    fn push_string(&mut self, expr: alloc::string::String) {
+        self.0.push_string(expr)
+    }
+
     fn as_command(&self) -> &str {
         self.0.as_command()
     }
+
+    fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional)
+    }
+
+    fn arguments(&self) -> Option<&Arg> {
+        self.0.arguments()
+    }
+
+    fn text_check(&self) -> crate::TextCheck {
+        self.0.text_check()
+    }
+
+    /// Forwards the records to the enclosing writer, except the columns of a
+    /// `RETURNING` clause, which are returned by the grouped statement and
+    /// not by the enclosing command.
+    fn record(&mut self, record: crate::Record<'_>) {
+        if !matches!(record, crate::Record::Returning(_)) {
+            self.0.record(record)
+        }
+    }
 }
 
 impl<'cmd, Sql, Arg> Drop for Group<'cmd, Sql, Arg>
@@ -17,7 +17,7 @@ macro_rules! static_group_by {
 
 pub use static_group_by;
 
-use super::separator_optional;
+use super::{emit, ColumnExprList, Token};
 use crate::WriteSql;
 
 /// Writes a `GROUP BY` clause.
@@ -41,20 +41,52 @@ where
     Sql: WriteSql<Arg>,
     I: IntoIterator<Item = &'col str>,
 {
-    separator_optional(sql);
-    sql.push_cmd("GROUP BY");
+    emit(sql, Token::Keyword("GROUP BY"));
 
     let mut cols = columns.into_iter();
     if let Some(col) = cols.next() {
-        sql.push_cmd(" ");
-        sql.push_cmd(col);
+        emit(sql, Token::Ident(col));
     }
     for col in cols {
-        sql.push_cmd(", ");
-        sql.push_cmd(col);
+        emit(sql, Token::Comma);
+        emit(sql, Token::Raw(col));
     }
 }
 
+/// Writes a `GROUP BY` clause with every column of the select list not
+/// marked as an aggregate.
+///
+/// Aliases are not included, only the column expressions.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{column, column_list, from_table, group_by_non_aggregates, select};
+/// let columns = column_list()
+///     .column("customer_id")
+///     .column_as("date_trunc('day', created)", "day")
+///     .aggregate_as("sum(total)", "total");
+///
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, &columns.expr());
+/// from_table(&mut sql, "sale");
+/// group_by_non_aggregates(&mut sql, &columns);
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT customer_id, date_trunc('day', created) AS day, sum(total) AS total \
+///      FROM sale GROUP BY customer_id, date_trunc('day', created)"
+/// );
+/// ```
+pub fn group_by_non_aggregates<Sql, Arg>(sql: &mut Sql, columns: &ColumnExprList<'_>)
+where
+    Sql: WriteSql<Arg>,
+{
+    group_by(sql, columns.non_aggregates());
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -69,6 +101,21 @@ mod test {
         assert_eq!(sql.as_command(), "GROUP BY id");
     }
 
+    #[test]
+    fn group_by_non_aggregate_columns() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        let columns = crate::expr::column_list()
+            .column("id")
+            .column_as("u.name", "name")
+            .aggregate("count(*)")
+            .column("max(age) AS oldest")
+            .mark_aggregate();
+
+        group_by_non_aggregates(&mut sql, &columns);
+
+        assert_eq!(sql.as_command(), "GROUP BY id, u.name");
+    }
+
     #[test]
     fn group_by_no_column() {
         let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
@@ -0,0 +1,396 @@
+use alloc::{format, string::String};
+use core::convert::Infallible;
+
+use super::{as_alias, from_table_as, insert_into_as, join, on_conflict_constraint, JoinType};
+use crate::{ArgumentError, SqlError, WriteSql};
+
+/// Maximum length in bytes of a Postgres identifier (`NAMEDATALEN - 1`).
+///
+/// Postgres silently truncates longer identifiers, which may turn distinct
+/// names into the same one.
+pub const POSTGRES_IDENT_MAX_LEN: usize = 63;
+
+/// Maximum length in bytes of the identifiers checked.
+///
+/// Defaults to [POSTGRES_IDENT_MAX_LEN], a server built with another
+/// `NAMEDATALEN` can set its own limit.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct IdentLimit(pub usize);
+
+impl Default for IdentLimit {
+    fn default() -> Self {
+        Self(POSTGRES_IDENT_MAX_LEN)
+    }
+}
+
+/// Checks that the identifier is at most `limit` bytes long, otherwise
+/// returns an [IdentifierTooLong](SqlError::IdentifierTooLong) error.
+///
+/// The length is counted in bytes, not in chars.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::SqlError;
+/// # use sqlstr::expr::{check_ident_len, IdentLimit};
+/// assert_eq!(check_ident_len("user_id", IdentLimit::default()), Ok(()));
+/// assert_eq!(
+///     check_ident_len("ação", IdentLimit(4)),
+///     Err(SqlError::IdentifierTooLong { len: 6, max: 4 })
+/// );
+/// ```
+pub fn check_ident_len(ident: &str, limit: IdentLimit) -> Result<(), SqlError<Infallible>> {
+    let IdentLimit(max) = limit;
+    if ident.len() > max {
+        return Err(SqlError::IdentifierTooLong {
+            len: ident.len(),
+            max,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that a name written at the byte `position` of a command, such as a
+/// savepoint or a prepared statement, is a plain identifier: a letter or `_`,
+/// followed by letters, digits, `_` or `$`.
+pub(crate) fn check_plain_ident(name: &str, position: usize) -> Result<(), SqlError<Infallible>> {
+    check_ident_len(name, IdentLimit::default())?;
+
+    let mut chars = name.char_indices();
+    let first = chars
+        .next()
+        .filter(|&(_, ch)| ch.is_alphabetic() || ch == '_');
+    let invalid = match first {
+        None => Some(0),
+        Some(_) => chars
+            .find(|&(_, ch)| !(ch.is_alphanumeric() || ch == '_' || ch == '$'))
+            .map(|(index, _)| index),
+    };
+    match invalid {
+        Some(index) => Err(SqlError::InvalidText {
+            position: position.saturating_add(index),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Checks each part of the identifiers, a schema qualified name being checked
+/// by each of its parts.
+pub(crate) fn check_idents<'i, I>(idents: I, limit: IdentLimit) -> Result<(), SqlError<Infallible>>
+where
+    I: IntoIterator<Item = &'i str>,
+{
+    idents
+        .into_iter()
+        .flat_map(|ident| ident.split('.'))
+        .try_for_each(|part| check_ident_len(part, limit))
+}
+
+/// FNV-1a offset basis, the hash of no bytes.
+pub(crate) const FNV1A_OFFSET: u32 = 0x811c_9dc5;
+
+/// Continues the FNV-1a `hash` with `bytes`, stable across builds and
+/// platforms.
+pub(crate) fn fnv1a(hash: u32, bytes: &[u8]) -> u32 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Shortens an identifier to at most [POSTGRES_IDENT_MAX_LEN] bytes.
+///
+/// Identifiers within the limit are returned unchanged. Longer identifiers are
+/// cut at a char boundary and suffixed with `_` and a hash of the whole
+/// identifier, so distinct identifiers sharing a long prefix stay distinct.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::expr::{truncate_ident_hash, POSTGRES_IDENT_MAX_LEN};
+/// let long = "order_line_item_with_discount_applied_per_customer_segment_total_amount";
+/// let short = truncate_ident_hash(long);
+///
+/// assert_eq!(short.len(), POSTGRES_IDENT_MAX_LEN);
+/// assert!(short.starts_with("order_line_item_with_discount_applied_per_customer_"));
+/// assert_eq!(truncate_ident_hash("total"), "total");
+/// ```
+pub fn truncate_ident_hash(ident: &str) -> String {
+    const SUFFIX_LEN: usize = 9;
+
+    if ident.len() <= POSTGRES_IDENT_MAX_LEN {
+        return ident.into();
+    }
+
+    let prefix = (0..=POSTGRES_IDENT_MAX_LEN.saturating_sub(SUFFIX_LEN))
+        .rev()
+        .find_map(|end| ident.get(..end))
+        .unwrap_or_default();
+
+    format!("{prefix}_{:08x}", fnv1a(FNV1A_OFFSET, ident.as_bytes()))
+}
+
+/// Writes an alias, `AS <alias>`, checking that it is at most `limit` bytes
+/// long.
+///
+/// A longer alias returns an
+/// [IdentifierTooLong](SqlError::IdentifierTooLong) error without writing
+/// anything.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{select, column, as_alias_checked, IdentLimit};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, "u.fullName");
+/// as_alias_checked(&mut sql, "name", IdentLimit::default())?;
+///
+/// assert_eq!(sql.as_command(), "SELECT u.fullName AS name");
+/// # Ok(())
+/// # }
+/// ```
+pub fn as_alias_checked<Sql, Arg>(
+    sql: &mut Sql,
+    alias: &str,
+    limit: IdentLimit,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+{
+    check_ident_len(alias, limit).map_err(SqlError::widen)?;
+    as_alias(sql, alias);
+    Ok(())
+}
+
+/// Writes a `FROM <table> AS <alias>` clause, checking that the table name and
+/// the alias are at most `limit` bytes long.
+///
+/// A longer identifier returns an
+/// [IdentifierTooLong](SqlError::IdentifierTooLong) error without writing
+/// anything. A schema qualified table name is checked by each of its parts.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{from_table_as_checked, IdentLimit};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// from_table_as_checked(&mut sql, "auth.user", "u", IdentLimit::default())?;
+///
+/// assert_eq!(sql.as_command(), "FROM auth.user AS u");
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_table_as_checked<Sql, Arg>(
+    sql: &mut Sql,
+    table: &str,
+    alias: &str,
+    limit: IdentLimit,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+{
+    check_idents([table, alias], limit).map_err(SqlError::widen)?;
+    from_table_as(sql, table, alias);
+    Ok(())
+}
+
+/// Writes an `INSERT INTO <table> AS <alias>` clause, checking that the table
+/// name and the alias are at most `limit` bytes long, see
+/// [from_table_as_checked].
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{insert_into_as_checked, IdentLimit};
+/// # use core::convert::Infallible;
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// let err = insert_into_as_checked(&mut sql, "user", "u".repeat(64).as_str(), IdentLimit::default());
+///
+/// assert_eq!(err, Err(SqlError::<Infallible>::IdentifierTooLong { len: 64, max: 63 }));
+/// assert_eq!(sql.as_command(), "");
+/// ```
+pub fn insert_into_as_checked<Sql, Arg>(
+    sql: &mut Sql,
+    table: &str,
+    alias: &str,
+    limit: IdentLimit,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+{
+    check_idents([table, alias], limit).map_err(SqlError::widen)?;
+    insert_into_as(sql, table, alias);
+    Ok(())
+}
+
+/// Writes a `<type> JOIN <table> [AS <alias>]` clause, checking that the table
+/// name and the alias are at most `limit` bytes long, see
+/// [from_table_as_checked].
+pub fn join_checked<Sql, Arg>(
+    sql: &mut Sql,
+    typ: JoinType,
+    table: &str,
+    alias: Option<&str>,
+    limit: IdentLimit,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+{
+    check_idents([table].into_iter().chain(alias), limit).map_err(SqlError::widen)?;
+    join(sql, typ, table, alias);
+    Ok(())
+}
+
+/// Writes an `ON CONFLICT ON CONSTRAINT <constraint>` clause, checking that
+/// the constraint name is at most `limit` bytes long.
+///
+/// A longer name returns an [IdentifierTooLong](SqlError::IdentifierTooLong)
+/// error without writing anything.
+pub fn on_conflict_constraint_checked<Sql, Arg>(
+    sql: &mut Sql,
+    constraint: &str,
+    limit: IdentLimit,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+{
+    check_ident_len(constraint, limit).map_err(SqlError::widen)?;
+    on_conflict_constraint(sql, constraint);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::String;
+
+    use super::{
+        as_alias_checked, check_ident_len, from_table_as_checked, insert_into_as_checked,
+        join_checked, on_conflict_constraint_checked, truncate_ident_hash, IdentLimit,
+    };
+    use crate::{
+        expr::{column, column_list, select, DeleteBuilder, JoinType},
+        test::TestArgs,
+        SqlCommand, SqlError, Void,
+    };
+
+    type Error = SqlError<core::fmt::Error>;
+
+    /// Identifier of `len` bytes ending with the 2 bytes char `é`.
+    fn ident_ending_multibyte(len: usize) -> String {
+        let mut ident = "a".repeat(len - 2);
+        ident.push('é');
+        ident
+    }
+
+    #[test]
+    fn multibyte_ident_boundary() {
+        let at_max = ident_ending_multibyte(63);
+        let over_max = ident_ending_multibyte(64);
+
+        assert_eq!(at_max.chars().count(), 62);
+        assert_eq!(check_ident_len(&at_max, IdentLimit(63)), Ok(()));
+        assert_eq!(
+            check_ident_len(&over_max, IdentLimit(63)),
+            Err(SqlError::IdentifierTooLong { len: 64, max: 63 })
+        );
+    }
+
+    #[test]
+    fn checked_writers() {
+        let long = ident_ending_multibyte(64);
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "id");
+        let err: Error = as_alias_checked(&mut sql, &long, IdentLimit::default()).unwrap_err();
+        assert_eq!(err, SqlError::IdentifierTooLong { len: 64, max: 63 });
+        let err: Error = from_table_as_checked(
+            &mut sql,
+            &alloc::format!("auth.{long}"),
+            "u",
+            IdentLimit(63),
+        )
+        .unwrap_err();
+        assert_eq!(err, SqlError::IdentifierTooLong { len: 64, max: 63 });
+        from_table_as_checked(&mut sql, "auth.user", "u", IdentLimit(4)).unwrap();
+
+        assert_eq!(sql.as_command(), "SELECT id FROM auth.user AS u");
+    }
+
+    #[test]
+    fn checked_names_and_aliases() {
+        let long = ident_ending_multibyte(64);
+        let too_long = Err(SqlError::IdentifierTooLong { len: 64, max: 63 });
+        let limit = IdentLimit::default();
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        let result: Result<(), Error> = insert_into_as_checked(&mut sql, "user", &long, limit);
+        assert_eq!(result, too_long);
+        let result: Result<(), Error> = join_checked(&mut sql, JoinType::Left, &long, None, limit);
+        assert_eq!(result, too_long);
+        let result: Result<(), Error> =
+            join_checked(&mut sql, JoinType::Left, "team", Some(&long), limit);
+        assert_eq!(result, too_long);
+        let result: Result<(), Error> = on_conflict_constraint_checked(&mut sql, &long, limit);
+        assert_eq!(result, too_long);
+        assert_eq!(sql.as_command(), "");
+
+        let at_max = ident_ending_multibyte(63);
+        insert_into_as_checked(&mut sql, "user", &at_max, limit).unwrap();
+        assert_eq!(
+            sql.as_command(),
+            alloc::format!("INSERT INTO user AS {at_max}")
+        );
+
+        let list = column_list().column_as_checked("name", &long, limit);
+        assert_eq!(
+            list.err(),
+            Some(SqlError::IdentifierTooLong { len: 64, max: 63 })
+        );
+        let list = column_list()
+            .column_as_checked("name", &at_max, limit)
+            .unwrap();
+        assert_eq!(list.as_slice(), [alloc::format!("name AS {at_max}")]);
+
+        let delete = DeleteBuilder::new_as_checked(Void::new(), "session", &long, limit);
+        assert_eq!(
+            delete.err(),
+            Some(SqlError::IdentifierTooLong { len: 64, max: 63 })
+        );
+    }
+
+    #[test]
+    fn truncate_hash_deterministic() {
+        // 'é' straddles the cut at byte 54
+        let mut prefix = "a".repeat(53);
+        prefix.push('é');
+        let first = alloc::format!("{prefix}_first_long_suffix");
+        let second = alloc::format!("{prefix}_second_long_suffix");
+
+        let short_first = truncate_ident_hash(&first);
+        let short_second = truncate_ident_hash(&second);
+
+        assert_eq!(short_first, truncate_ident_hash(&first));
+        assert_ne!(short_first, short_second);
+        assert_eq!(short_first.len(), 62);
+        assert!(short_first.starts_with(&"a".repeat(53)));
+        assert!(!short_first.contains('é'));
+        assert_eq!(check_ident_len(&short_first, IdentLimit::default()), Ok(()));
+
+        let at_max = ident_ending_multibyte(63);
+        assert_eq!(truncate_ident_hash(&at_max), at_max);
+    }
+}
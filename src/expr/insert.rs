@@ -1,5 +1,5 @@
-use super::separator_optional;
-use crate::WriteSql;
+use super::{emit, separator_optional, Token};
+use crate::{ArgumentError, SqlError, WriteSql};
 
 /// Write a `INSERT INTO` clause to start a insert sql command.
 ///
@@ -21,8 +21,7 @@ pub fn insert<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("INSERT INTO");
+    emit(sql, Token::Keyword("INSERT INTO"));
 }
 
 /// Write an `INSERT INTO <table>` clause to start an insert command with a
@@ -46,9 +45,8 @@ pub fn insert_into<Sql, Arg>(sql: &mut Sql, table: &str)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("INSERT INTO ");
-    sql.push_cmd(table);
+    emit(sql, Token::Keyword("INSERT INTO"));
+    emit(sql, Token::Ident(table));
 }
 
 /// Write an `INSERT INTO <table> AS <alias>` clause to start an insert command with a
@@ -69,14 +67,153 @@ where
 /// # }
 /// ```
 pub fn insert_into_as<Sql, Arg>(sql: &mut Sql, table: &str, alias: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Keyword("INSERT INTO"));
+    emit(sql, Token::Ident(table));
+    emit(sql, Token::Keyword("AS"));
+    emit(sql, Token::Ident(alias));
+}
+
+/// Write the `(<column>, ...)` column list of an `INSERT` command from an
+/// iterator.
+///
+/// Unlike [columns_iter](super::columns_iter), the list is wrapped in
+/// parentheses. Returns the number of columns written, the arity of the rows
+/// of the `VALUES` list. An empty list of columns returns an
+/// [EmptyList](SqlError::EmptyList) error, without writing anything.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{insert_into, insert_columns_iter};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// insert_into(&mut sql, "user");
+/// insert_columns_iter(&mut sql, ["name", "email"])?;
+///
+/// assert_eq!(sql.as_command(), "INSERT INTO user (name, email)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn insert_columns_iter<'c, Sql, Arg, I>(
+    sql: &mut Sql,
+    columns: I,
+) -> Result<usize, SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+    I: IntoIterator<Item = &'c str>,
+{
+    let mut columns = columns.into_iter().peekable();
+    if columns.peek().is_none() {
+        return Err(SqlError::EmptyList);
+    }
+    Ok(insert_column_list(sql, columns))
+}
+
+/// Writes the `(<column>, ...)` list of [insert_columns_iter], of columns
+/// already checked not to be empty.
+pub(crate) fn insert_column_list<'c, Sql, Arg, I>(sql: &mut Sql, columns: I) -> usize
+where
+    Sql: WriteSql<Arg>,
+    I: IntoIterator<Item = &'c str>,
+{
+    emit(sql, Token::Open);
+
+    let mut count: usize = 0;
+    let mut cols = columns.into_iter();
+    if let Some(first) = cols.next() {
+        sql.push_cmd(first);
+        count = 1;
+    }
+    for col in cols {
+        emit(sql, Token::Comma);
+        sql.push_cmd(col);
+        count = count.saturating_add(1);
+    }
+
+    emit(sql, Token::Close);
+    count
+}
+
+/// Write the `(<columns>)` column list of an `INSERT` command from a column
+/// expression, such as the output of [static_columns](crate::static_columns)
+/// or a [ColumnExpr](super::ColumnExpr).
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, SqlExpr, static_columns};
+/// # use sqlstr::expr::{insert_into, insert_columns};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// insert_into(&mut sql, "user");
+/// insert_columns(&mut sql, static_columns!("name", "email"));
+///
+/// assert_eq!(sql.as_command(), "INSERT INTO user (name, email)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn insert_columns<Sql, Arg>(sql: &mut Sql, columns: &str)
 where
     Sql: WriteSql<Arg>,
 {
     separator_optional(sql);
-    sql.push_cmd("INSERT INTO ");
-    sql.push_cmd(table);
-    sql.push_cmd(" AS ");
-    sql.push_cmd(alias);
+    sql.push_cmd("(");
+    sql.push_cmd(columns);
+    sql.push_cmd(")");
+}
+
+/// Source of the values of an identity column in a `INSERT` command.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Overriding {
+    /// `OVERRIDING SYSTEM VALUE`, the inserted values override the values
+    /// generated for `GENERATED ALWAYS` identity columns.
+    SystemValue,
+    /// `OVERRIDING USER VALUE`, the inserted values are ignored and the
+    /// identity columns are generated.
+    UserValue,
+}
+
+/// Writes a `OVERRIDING SYSTEM VALUE` or `OVERRIDING USER VALUE` clause,
+/// between the column list and the `VALUES` of a `INSERT` command.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{insert_into, insert_columns_iter, overriding, values, values_rows, Overriding};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// insert_into(&mut sql, "user");
+/// let arity = insert_columns_iter(&mut sql, ["id", "name"])?;
+/// overriding(&mut sql, Overriding::SystemValue);
+/// values(&mut sql);
+/// values_rows(&mut sql, arity, [[1, 2]])?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "INSERT INTO user (id, name) OVERRIDING SYSTEM VALUE VALUES ($1, $2)"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn overriding<Sql, Arg>(sql: &mut Sql, kind: Overriding)
+where
+    Sql: WriteSql<Arg>,
+{
+    let keyword = match kind {
+        Overriding::SystemValue => "OVERRIDING SYSTEM VALUE",
+        Overriding::UserValue => "OVERRIDING USER VALUE",
+    };
+    emit(sql, Token::Keyword(keyword));
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -115,28 +252,484 @@ pub fn on_conflict<Sql, Arg>(
 ) where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("ON CONFLICT");
+    emit(sql, Token::Keyword("ON CONFLICT"));
 
     if let Some(target) = target {
         match target {
             ConflictTarget::Constraint(constr_name) => {
-                sql.push_cmd(" ON CONSTRAINT ");
-                sql.push_cmd(constr_name);
+                emit(sql, Token::Keyword("ON CONSTRAINT"));
+                emit(sql, Token::Ident(constr_name));
             }
             ConflictTarget::IndexColumn(indx_expr) => {
-                sql.push_cmd(" ");
-                sql.push_cmd(indx_expr);
+                emit(sql, Token::Ident(indx_expr));
             }
         }
     }
 
     match action {
-        ConflictAction::Nothing => {
-            sql.push_cmd(" DO NOTHING");
-        }
-        ConflictAction::Update => {
-            sql.push_cmd(" DO UPDATE");
+        ConflictAction::Nothing => emit(sql, Token::Keyword("DO NOTHING")),
+        ConflictAction::Update => emit(sql, Token::Keyword("DO UPDATE")),
+    }
+}
+
+/// Writes a `ON CONFLICT DO NOTHING` clause, skipping the rows conflicting
+/// with any unique constraint.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{insert_into, insert_columns_iter, values, values_rows, on_conflict_do_nothing};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// insert_into(&mut sql, "tag");
+/// insert_columns_iter(&mut sql, ["name"])?;
+/// values(&mut sql);
+/// values_rows(&mut sql, 1, [["rust"]])?;
+/// on_conflict_do_nothing(&mut sql);
+///
+/// assert_eq!(sql.as_command(), "INSERT INTO tag (name) VALUES ($1) ON CONFLICT DO NOTHING");
+/// # Ok(())
+/// # }
+/// ```
+pub fn on_conflict_do_nothing<Sql, Arg>(sql: &mut Sql)
+where
+    Sql: WriteSql<Arg>,
+{
+    on_conflict(sql, None, ConflictAction::Nothing);
+}
+
+/// Writes a `ON CONFLICT (<column>, ...) DO NOTHING` clause, skipping the rows
+/// conflicting on the unique index of `columns`.
+///
+/// An empty list of columns returns an [EmptyList](SqlError::EmptyList)
+/// error, without writing anything.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::on_conflict_columns_do_nothing;
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// on_conflict_columns_do_nothing(&mut sql, &["tenant_id", "email"])?;
+///
+/// assert_eq!(sql.as_command(), "ON CONFLICT (tenant_id, email) DO NOTHING");
+/// # Ok(())
+/// # }
+/// ```
+pub fn on_conflict_columns_do_nothing<Sql, Arg>(
+    sql: &mut Sql,
+    columns: &[&str],
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+{
+    if columns.is_empty() {
+        return Err(SqlError::EmptyList);
+    }
+
+    emit(sql, Token::Keyword("ON CONFLICT"));
+    insert_column_list(sql, columns.iter().copied());
+    emit(sql, Token::Keyword("DO NOTHING"));
+    Ok(())
+}
+
+/// Writes a `ON CONFLICT (<column>, ...) DO UPDATE SET` clause, updating the
+/// rows conflicting on the unique index of `columns`.
+///
+/// The assignments follow, written with [set_column](super::set_column) or
+/// [set_excluded](super::set_excluded), and can be restricted by a `WHERE`
+/// condition. The values bound in the assignments continue the numbering of
+/// the inserted values.
+///
+/// An empty list of columns returns an [EmptyList](SqlError::EmptyList)
+/// error, without writing anything.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{
+/// #     insert_into, insert_columns_iter, values, values_rows, on_conflict_columns_do_update,
+/// #     set_column, set_excluded, item_separator,
+/// # };
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// insert_into(&mut sql, "user");
+/// let arity = insert_columns_iter(&mut sql, ["id", "name", "updated_at"])?;
+/// values(&mut sql);
+/// values_rows(&mut sql, arity, [[1, 2, 3]])?;
+/// on_conflict_columns_do_update(&mut sql, &["id"])?;
+/// set_column(&mut sql, "name");
+/// sql.push_cmd(" ");
+/// sql.push_value("Rusty")?;
+/// item_separator(&mut sql);
+/// set_excluded(&mut sql, "updated_at");
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "INSERT INTO user (id, name, updated_at) VALUES ($1, $2, $3) \
+///      ON CONFLICT (id) DO UPDATE SET name = $4, updated_at = EXCLUDED.updated_at"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn on_conflict_columns_do_update<Sql, Arg>(
+    sql: &mut Sql,
+    columns: &[&str],
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+{
+    if columns.is_empty() {
+        return Err(SqlError::EmptyList);
+    }
+
+    emit(sql, Token::Keyword("ON CONFLICT"));
+    insert_column_list(sql, columns.iter().copied());
+    emit(sql, Token::Keyword("DO UPDATE SET"));
+    Ok(())
+}
+
+/// Writes a `ON CONFLICT ON CONSTRAINT <constraint>` conflict target, naming
+/// the unique or exclusion constraint checked for conflicts.
+///
+/// The action follows, written with [conflict_do_nothing] or
+/// [conflict_do_update_set].
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{
+/// #     insert_into, insert_columns_iter, values, values_rows, on_conflict_constraint,
+/// #     conflict_do_update_set, set_excluded,
+/// # };
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// insert_into(&mut sql, "user");
+/// let arity = insert_columns_iter(&mut sql, ["email", "name"])?;
+/// values(&mut sql);
+/// values_rows(&mut sql, arity, [["rusty@email.com", "Rusty"]])?;
+/// on_conflict_constraint(&mut sql, "uq_user_email");
+/// conflict_do_update_set(&mut sql);
+/// set_excluded(&mut sql, "name");
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "INSERT INTO user (email, name) VALUES ($1, $2) \
+///      ON CONFLICT ON CONSTRAINT uq_user_email DO UPDATE SET name = EXCLUDED.name"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn on_conflict_constraint<Sql, Arg>(sql: &mut Sql, constraint: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Keyword("ON CONFLICT ON CONSTRAINT"));
+    emit(sql, Token::Ident(constraint));
+}
+
+/// Writes a `ON CONFLICT (<column>, ...) WHERE <predicate>` conflict target,
+/// inferring a partial unique index of `columns`.
+///
+/// The index predicate is written by `build`, and the action follows,
+/// written with [conflict_do_nothing] or [conflict_do_update_set].
+///
+/// An empty list of columns returns an [EmptyList](SqlError::EmptyList)
+/// error, without writing anything.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void, WriteSql};
+/// # use sqlstr::expr::{on_conflict_columns_where, conflict_do_nothing};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// on_conflict_columns_where(&mut sql, &["email"], |sql| {
+///     sql.push_cmd(" deleted_at IS NULL");
+///     Ok(())
+/// })?;
+/// conflict_do_nothing(&mut sql);
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "ON CONFLICT (email) WHERE deleted_at IS NULL DO NOTHING"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn on_conflict_columns_where<Sql, Arg, F>(
+    sql: &mut Sql,
+    columns: &[&str],
+    build: F,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+    F: FnOnce(&mut Sql) -> Result<(), SqlError<Arg::Error>>,
+{
+    if columns.is_empty() {
+        return Err(SqlError::EmptyList);
+    }
+
+    emit(sql, Token::Keyword("ON CONFLICT"));
+    insert_column_list(sql, columns.iter().copied());
+    emit(sql, Token::Keyword("WHERE"));
+    build(sql)
+}
+
+/// Writes a `DO NOTHING` conflict action, after a conflict target.
+pub fn conflict_do_nothing<Sql, Arg>(sql: &mut Sql)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Keyword("DO NOTHING"));
+}
+
+/// Writes a `DO UPDATE SET` conflict action, after a conflict target.
+///
+/// The assignments follow, as in
+/// [on_conflict_columns_do_update].
+pub fn conflict_do_update_set<Sql, Arg>(sql: &mut Sql)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Keyword("DO UPDATE SET"));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        expr::{
+            column_list, continue_condition, filter_where, item_separator, lhs_binary_rhs,
+            returning_columns, set_column, set_excluded, values, values_rows, write_iter, Cmp,
+            LogicBi,
+        },
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+        SqlCommand,
+    };
+
+    #[test]
+    fn insert_static_and_iter_columns() {
+        let mut static_sql: SqlCommand<TestArgs> = SqlCommand::default();
+        insert_into(&mut static_sql, "user");
+        insert_columns(
+            &mut static_sql,
+            crate::static_columns!("id", "name", "email"),
+        );
+
+        let mut iter_sql: SqlCommand<TestArgs> = SqlCommand::default();
+        insert_into(&mut iter_sql, "user");
+        insert_columns_iter(&mut iter_sql, ["id", "name", "email"]).unwrap();
+
+        assert_eq!(
+            static_sql.as_command(),
+            "INSERT INTO user (id, name, email)"
+        );
+        assert_eq!(static_sql.as_command(), iter_sql.as_command());
+    }
+
+    #[test]
+    fn insert_column_expr_values() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        let columns = column_list().column("id").column("name").expr();
+
+        insert_into(&mut sql, "user");
+        insert_columns(&mut sql, &columns);
+        values(&mut sql);
+        {
+            let mut group = crate::expr::Group::open(&mut sql);
+            write_iter(&mut group, [1, 2]).unwrap();
         }
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO user (id, name) VALUES ($1, $2)"
+        );
+        assert_eq!(sql.arguments.as_str(), "1;2;");
+    }
+
+    #[test]
+    fn insert_no_columns() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        insert_into(&mut sql, "user");
+        assert_eq!(insert_columns_iter(&mut sql, []), Err(SqlError::EmptyList));
+        assert_eq!(sql.as_command(), "INSERT INTO user");
+    }
+
+    #[test]
+    fn conflict_do_nothing_before_returning() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        insert_into(&mut sql, "tag");
+        let arity = insert_columns_iter(&mut sql, ["name"]).unwrap();
+        values(&mut sql);
+        values_rows(&mut sql, arity, [["rust"], ["sql"]]).unwrap();
+        on_conflict_do_nothing(&mut sql);
+        returning_columns(&mut sql, ["id"]);
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO tag (name) VALUES ($1), ($2) ON CONFLICT DO NOTHING RETURNING id"
+        );
+        assert_eq!(sql.arguments.as_str(), "rust;sql;");
+    }
+
+    #[test]
+    fn conflict_columns_do_nothing() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        insert_into(&mut sql, "member");
+        let arity = insert_columns_iter(&mut sql, ["team_id", "user_id"]).unwrap();
+        values(&mut sql);
+        values_rows(&mut sql, arity, [[1, 2]]).unwrap();
+        on_conflict_columns_do_nothing(&mut sql, &["team_id", "user_id"]).unwrap();
+        returning_columns(&mut sql, ["team_id"]);
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO member (team_id, user_id) VALUES ($1, $2) \
+             ON CONFLICT (team_id, user_id) DO NOTHING RETURNING team_id"
+        );
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        assert_eq!(
+            on_conflict_columns_do_nothing(&mut sql, &[]),
+            Err(SqlError::EmptyList)
+        );
+        assert_eq!(sql.as_command(), "");
+    }
+
+    #[test]
+    fn upsert_with_condition_and_returning() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        insert_into(&mut sql, "stock");
+        let arity = insert_columns_iter(&mut sql, ["sku", "quantity", "updated_at"]).unwrap();
+        values(&mut sql);
+        values_rows(&mut sql, arity, [["a-1", "4", "now"], ["b-2", "9", "now"]]).unwrap();
+        on_conflict_columns_do_update(&mut sql, &["sku"]).unwrap();
+        set_excluded(&mut sql, "quantity");
+        item_separator(&mut sql);
+        set_excluded(&mut sql, "updated_at");
+        item_separator(&mut sql);
+        set_column(&mut sql, "source");
+        sql.push_cmd(" ");
+        sql.push_value("import").unwrap();
+        filter_where(&mut sql);
+        sql.push_cmd(" stock.quantity <> EXCLUDED.quantity");
+        continue_condition(&mut sql, LogicBi::And);
+        lhs_binary_rhs(
+            &mut sql,
+            sqlexpr::<bool>("stock.locked"),
+            Cmp::Eq,
+            sqlvalue(false),
+        )
+        .unwrap();
+        returning_columns(&mut sql, ["sku", "quantity"]);
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO stock (sku, quantity, updated_at) VALUES ($1, $2, $3), ($4, $5, $6) \
+             ON CONFLICT (sku) DO UPDATE SET quantity = EXCLUDED.quantity, \
+             updated_at = EXCLUDED.updated_at, source = $7 \
+             WHERE stock.quantity <> EXCLUDED.quantity AND stock.locked = $8 \
+             RETURNING sku, quantity"
+        );
+        assert_eq!(sql.arguments.as_str(), "a-1;4;now;b-2;9;now;import;false;");
+        assert_eq!(
+            on_conflict_columns_do_update(&mut sql, &[]),
+            Err(SqlError::EmptyList)
+        );
+    }
+
+    #[test]
+    fn conflict_constraint_actions() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        insert_into(&mut sql, "user");
+        let arity = insert_columns_iter(&mut sql, ["email"]).unwrap();
+        values(&mut sql);
+        values_rows(&mut sql, arity, [["a@email.com"]]).unwrap();
+        on_conflict_constraint(&mut sql, "uq_user_email");
+        conflict_do_nothing(&mut sql);
+        returning_columns(&mut sql, ["id"]);
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO user (email) VALUES ($1) \
+             ON CONFLICT ON CONSTRAINT uq_user_email DO NOTHING RETURNING id"
+        );
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        on_conflict_constraint(&mut sql, "uq_user_email");
+        conflict_do_update_set(&mut sql);
+        set_column(&mut sql, "visits");
+        sql.push_cmd(" user.visits + 1");
+
+        assert_eq!(
+            sql.as_command(),
+            "ON CONFLICT ON CONSTRAINT uq_user_email DO UPDATE SET visits = user.visits + 1"
+        );
+    }
+
+    #[test]
+    fn conflict_partial_index() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        insert_into(&mut sql, "user");
+        let arity = insert_columns_iter(&mut sql, ["email", "tenant_id"]).unwrap();
+        values(&mut sql);
+        values_rows(&mut sql, arity, [["a@email.com", "2"]]).unwrap();
+        on_conflict_columns_where(&mut sql, &["email"], |sql| {
+            sql.push_cmd(" deleted_at IS NULL");
+            continue_condition(sql, LogicBi::And);
+            lhs_binary_rhs(sql, sqlexpr::<u32>("tenant_id"), Cmp::Eq, sqlvalue(2))?;
+            Ok(())
+        })
+        .unwrap();
+        conflict_do_update_set(&mut sql);
+        set_excluded(&mut sql, "tenant_id");
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO user (email, tenant_id) VALUES ($1, $2) \
+             ON CONFLICT (email) WHERE deleted_at IS NULL AND tenant_id = $3 \
+             DO UPDATE SET tenant_id = EXCLUDED.tenant_id"
+        );
+        assert_eq!(sql.arguments.as_str(), "a@email.com;2;2;");
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        assert_eq!(
+            on_conflict_columns_where(&mut sql, &[], |_| Ok(())),
+            Err(SqlError::EmptyList)
+        );
+        assert_eq!(sql.as_command(), "");
+    }
+
+    #[test]
+    fn insert_overriding_user_value() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        insert_into(&mut sql, "user");
+        let arity = insert_columns_iter(&mut sql, ["id", "name"]).unwrap();
+        overriding(&mut sql, Overriding::UserValue);
+        values(&mut sql);
+        values_rows(&mut sql, arity, [["7", "Rusty"]]).unwrap();
+        returning_columns(&mut sql, ["id"]);
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO user (id, name) OVERRIDING USER VALUE VALUES ($1, $2) RETURNING id"
+        );
     }
 }
@@ -1,4 +1,6 @@
-use crate::WriteSql;
+use super::table::table_name;
+use super::{continue_condition, emit, lhs_binary_rhs, separator_optional, Cmp, LogicBi, Token};
+use crate::{ArgumentBuffer, Record, SqlExpr, WriteSql};
 
 pub enum JoinType {
     Cross,
@@ -40,9 +42,9 @@ pub fn cross_join<Sql, Arg>(sql: &mut Sql, table: &str)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("CROSS JOIN ");
-    sql.push_cmd(table);
+    emit(sql, Token::Keyword("CROSS JOIN"));
+    emit(sql, Token::Ident(table));
+    sql.record(Record::Table(table_name(table)));
 }
 
 /// Starts a `JOIN` clause.
@@ -65,13 +67,13 @@ pub fn join<Sql, Arg>(sql: &mut Sql, typ: JoinType, table: &str, alias: Option<&
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd(typ.as_str());
-    sql.push_cmd(" JOIN ");
-    sql.push_cmd(table);
+    emit(sql, Token::Ident(typ.as_str()));
+    emit(sql, Token::Keyword("JOIN"));
+    emit(sql, Token::Ident(table));
+    sql.record(Record::Table(table_name(table)));
     if let Some(alias) = alias {
-        sql.push_cmd(" AS ");
-        sql.push_cmd(alias);
+        emit(sql, Token::Keyword("AS"));
+        emit(sql, Token::Ident(alias));
     }
 }
 
@@ -96,8 +98,43 @@ pub fn join_on<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("ON");
+    emit(sql, Token::Keyword("ON"));
+}
+
+/// Continues a join condition comparing a column against a bound value,
+/// `AND <column> <op> $n`.
+///
+/// The `AND` is omitted when the condition is the first one after `ON`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, SqlExpr};
+/// # use sqlstr::expr::{join, join_on, and_on_value, JoinType, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// join(&mut sql, JoinType::Inner, "events", Some("e"));
+/// join_on(&mut sql);
+/// sql.push_cmd(" e.user_id = u.id");
+/// and_on_value(&mut sql, "e.kind", Cmp::Eq, "login")?;
+///
+/// assert_eq!(sql.as_command(), "INNER JOIN events AS e ON e.user_id = u.id AND e.kind = $1");
+/// # Ok(())
+/// # }
+/// ```
+pub fn and_on_value<Sql, Arg, T>(
+    sql: &mut Sql,
+    column: &str,
+    op: Cmp,
+    value: T,
+) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    continue_condition(sql, LogicBi::And);
+    lhs_binary_rhs(sql, SqlExpr::<T>::Expr(column), op, SqlExpr::Value(value))
 }
 
 /// Writes a join condition with the `USING` form.
@@ -131,7 +168,7 @@ where
     }
 
     for tbl in tbls {
-        sql.push_cmd(", ");
+        emit(sql, Token::Comma);
         sql.push_cmd(tbl);
     }
     sql.push_cmd(")");
@@ -259,11 +296,16 @@ macro_rules! static_join {
 pub use static_join;
 pub use static_join_using;
 
-use super::separator_optional;
-
 #[cfg(test)]
 mod test {
-    use crate::{expr::join_using, test::TestArgs, SqlCommand};
+    use crate::{
+        expr::{
+            and_on_value, column, from_table, item_separator, join, join_on, join_using, select,
+            Cmp, JoinType,
+        },
+        test::TestArgs,
+        SqlCommand,
+    };
 
     #[test]
     fn join_using_single_column() {
@@ -283,6 +325,40 @@ mod test {
         assert_eq!(sql.as_command(), "USING (id, sale_id, customer_id)");
     }
 
+    #[test]
+    fn join_on_first_value() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        join(&mut sql, JoinType::Left, "events", Some("e"));
+        join_on(&mut sql);
+        and_on_value(&mut sql, "e.kind", Cmp::Neq, "logout").unwrap();
+
+        assert_eq!(sql.as_command(), "LEFT JOIN events AS e ON e.kind <> $1");
+        assert_eq!(sql.arguments.as_str(), "logout;");
+    }
+
+    #[test]
+    fn join_on_values_after_select_values() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        column(&mut sql, "u.id");
+        item_separator(&mut sql);
+        sql.push_value(10).unwrap();
+        item_separator(&mut sql);
+        sql.push_value(20).unwrap();
+        from_table(&mut sql, "user AS u");
+        join(&mut sql, JoinType::Inner, "events", Some("e"));
+        join_on(&mut sql);
+        sql.push_cmd(" e.user_id = u.id");
+        and_on_value(&mut sql, "e.kind", Cmp::Eq, "login").unwrap();
+        and_on_value(&mut sql, "e.attempts", Cmp::Gte, 3).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT u.id, $1, $2 FROM user AS u INNER JOIN events AS e ON e.user_id = u.id AND e.kind = $3 AND e.attempts >= $4"
+        );
+        assert_eq!(sql.arguments.as_str(), "10;20;login;3;");
+    }
+
     #[test]
     fn static_using_macro() {
         assert_eq!(static_join_using!("id"), "USING (id)");
@@ -1,10 +1,13 @@
-use super::separator_optional;
+use super::{emit, Token};
 use crate::{ArgumentBuffer, WriteSql};
 
 /// Writes a LIMIT clause into a sql writer.
 ///
 /// `LIMIT <count>`
 ///
+/// The count is unsigned, so a negative limit can not be represented. A
+/// `LIMIT 0` is valid sql, although it always returns an empty result.
+///
 /// # Example
 ///
 /// ```
@@ -23,8 +26,8 @@ where
     Sql: WriteSql<Arg>,
     Arg: ArgumentBuffer<usize>,
 {
-    separator_optional(sql);
-    sql.push_cmd("LIMIT ");
+    emit(sql, Token::Keyword("LIMIT"));
+    emit(sql, Token::Placeholder);
     sql.push_value(count)
 }
 
@@ -50,11 +53,41 @@ where
     Sql: WriteSql<Arg>,
     Arg: ArgumentBuffer<usize>,
 {
-    separator_optional(sql);
-    sql.push_cmd("OFFSET ");
+    emit(sql, Token::Keyword("OFFSET"));
+    emit(sql, Token::Placeholder);
     sql.push_value(start)
 }
 
+/// Writes a `FETCH FIRST` clause into a sql writer, the standard sql
+/// counterpart of [limit].
+///
+/// `FETCH FIRST <count> ROWS ONLY`
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, SqlExpr, expr::fetch_first};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// fetch_first(&mut sql, 10);
+///
+/// assert_eq!(sql.as_command(), "FETCH FIRST $1 ROWS ONLY");
+/// # Ok(())
+/// # }
+/// ```
+pub fn fetch_first<Sql, Arg>(sql: &mut Sql, count: usize) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<usize>,
+{
+    emit(sql, Token::Keyword("FETCH FIRST"));
+    emit(sql, Token::Placeholder);
+    sql.push_value(count)?;
+    emit(sql, Token::Keyword("ROWS ONLY"));
+    Ok(())
+}
+
 #[macro_export]
 macro_rules! static_limit {
     (LIMIT $count:literal) => {
@@ -0,0 +1,266 @@
+use alloc::string::String;
+use core::convert::Infallible;
+
+use super::{as_alias, separator_optional};
+use crate::{ArgumentError, SqlError, TextCheck, WriteSql};
+
+fn check_text(check: TextCheck, text: &str) -> Result<(), SqlError<Infallible>> {
+    match check.find(text) {
+        Some(position) => Err(SqlError::InvalidText { position }),
+        None => Ok(()),
+    }
+}
+
+/// Escapes a text into a sql string literal, `'<text>'`, doubling the single
+/// quotes.
+///
+/// Backslashes have no special meaning in standard conforming strings, so they
+/// are kept as is. A text containing a NUL character returns an
+/// [InvalidText](SqlError::InvalidText) error.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::expr::escape_string_literal;
+/// # use sqlstr::SqlError;
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// assert_eq!(escape_string_literal("it's")?, "'it''s'");
+/// assert_eq!(
+///     escape_string_literal("a\0b"),
+///     Err(SqlError::InvalidText { position: 1 })
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn escape_string_literal(text: &str) -> Result<String, SqlError<Infallible>> {
+    let quotes = text.matches('\'').count();
+    let mut literal = String::with_capacity(text.len().saturating_add(quotes).saturating_add(2));
+    literal.push('\'');
+    for (position, ch) in text.char_indices() {
+        if TextCheck::Nul.rejects_char(ch) {
+            return Err(SqlError::InvalidText { position });
+        }
+        if ch == '\'' {
+            literal.push('\'');
+        }
+        literal.push(ch);
+    }
+    literal.push('\'');
+    Ok(literal)
+}
+
+/// Escapes a list of texts into a sql text array literal, `'{<text>,...}'`.
+///
+/// The elements containing array delimiters, quotes, backslashes or
+/// whitespace, the empty elements and the `NULL` elements are double quoted,
+/// escaping the double quotes and backslashes. The whole literal is escaped as
+/// a [string literal](escape_string_literal).
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::expr::text_array_literal;
+/// # use sqlstr::SqlError;
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// assert_eq!(text_array_literal(["address", "city"])?, "'{address,city}'");
+/// assert_eq!(text_array_literal(["a,b", "it's"])?, r#"'{"a,b",it''s}'"#);
+/// # Ok(())
+/// # }
+/// ```
+pub fn text_array_literal<'t, I>(elements: I) -> Result<String, SqlError<Infallible>>
+where
+    I: IntoIterator<Item = &'t str>,
+{
+    let mut array = String::from("{");
+    for (index, element) in elements.into_iter().enumerate() {
+        check_text(TextCheck::Nul, element)?;
+        if index > 0 {
+            array.push(',');
+        }
+
+        let quoted = element.is_empty()
+            || element.eq_ignore_ascii_case("NULL")
+            || element
+                .chars()
+                .any(|ch| matches!(ch, '{' | '}' | ',' | '"' | '\\') || ch.is_whitespace());
+        if !quoted {
+            array.push_str(element);
+            continue;
+        }
+
+        array.push('"');
+        for ch in element.chars() {
+            if ch == '"' || ch == '\\' {
+                array.push('\\');
+            }
+            array.push(ch);
+        }
+        array.push('"');
+    }
+    array.push('}');
+
+    escape_string_literal(&array)
+}
+
+/// Writes a text as a sql string literal, `'<text>'`, doubling the single
+/// quotes.
+///
+/// A text containing a character rejected by the
+/// [text check](crate::SqlCommand::with_text_check) of the command returns an
+/// [InvalidText](SqlError::InvalidText) error, without writing anything.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{select, string_literal};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// string_literal(&mut sql, "O'Reilly")?;
+///
+/// assert_eq!(sql.as_command(), "SELECT 'O''Reilly'");
+/// # Ok(())
+/// # }
+/// ```
+pub fn string_literal<Sql, Arg>(sql: &mut Sql, text: &str) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+{
+    check_text(sql.text_check(), text).map_err(SqlError::widen)?;
+
+    separator_optional(sql);
+    sql.push_cmd("'");
+    let mut parts = text.split('\'');
+    if let Some(part) = parts.next() {
+        sql.push_cmd(part);
+    }
+    for part in parts {
+        sql.push_cmd("''");
+        sql.push_cmd(part);
+    }
+    sql.push_cmd("'");
+    Ok(())
+}
+
+/// Writes a string literal column, `'<text>' AS <alias>`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{select, column, item_separator, literal_text, from_table};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// literal_text(&mut sql, "customer", "kind")?;
+/// item_separator(&mut sql);
+/// column(&mut sql, "id");
+/// from_table(&mut sql, "customer");
+///
+/// assert_eq!(sql.as_command(), "SELECT 'customer' AS kind, id FROM customer");
+/// # Ok(())
+/// # }
+/// ```
+pub fn literal_text<Sql, Arg>(
+    sql: &mut Sql,
+    text: &str,
+    alias: &str,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+{
+    string_literal(sql, text)?;
+    as_alias(sql, alias);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{expr::select, test::TestArgs, SqlCommand};
+
+    type Error = SqlError<core::fmt::Error>;
+
+    #[test]
+    fn escape_quotes() {
+        assert_eq!(escape_string_literal("").unwrap(), "''");
+        assert_eq!(escape_string_literal("plain").unwrap(), "'plain'");
+        assert_eq!(
+            escape_string_literal("'quoted' ''twice''").unwrap(),
+            "'''quoted'' ''''twice'''''"
+        );
+    }
+
+    #[test]
+    fn escape_keeps_backslashes() {
+        assert_eq!(
+            escape_string_literal(r"C:\path\n'x'").unwrap(),
+            r"'C:\path\n''x'''"
+        );
+    }
+
+    #[test]
+    fn reject_nul() {
+        assert_eq!(
+            escape_string_literal("ab\0"),
+            Err(SqlError::InvalidText { position: 2 })
+        );
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        let err: Error = literal_text(&mut sql, "\0", "tag").unwrap_err();
+
+        assert_eq!(err, SqlError::InvalidText { position: 0 });
+        assert_eq!(sql.as_command(), "SELECT");
+    }
+
+    #[test]
+    fn reject_with_command_check() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        string_literal(&mut sql, "bell\x07").unwrap();
+        assert_eq!(sql.check_text(), Ok(()));
+
+        let mut sql: SqlCommand<TestArgs> =
+            SqlCommand::default().with_text_check(crate::TextCheck::Control);
+        select(&mut sql);
+        let err: Error = string_literal(&mut sql, "bell\x07").unwrap_err();
+
+        assert_eq!(err, SqlError::InvalidText { position: 4 });
+        assert_eq!(sql.as_command(), "SELECT");
+    }
+
+    #[test]
+    fn literal_text_matches_escape() {
+        let text = r"it's a \'test\'";
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        string_literal(&mut sql, text).unwrap();
+
+        assert_eq!(sql.as_command(), escape_string_literal(text).unwrap());
+    }
+
+    #[test]
+    fn text_array_elements() {
+        assert_eq!(text_array_literal([]).unwrap(), "'{}'");
+        assert_eq!(text_array_literal(["a", "b", "c"]).unwrap(), "'{a,b,c}'");
+        assert_eq!(
+            text_array_literal(["", "null", "two words", "{x}"]).unwrap(),
+            r#"'{"","null","two words","{x}"}'"#
+        );
+        assert_eq!(
+            text_array_literal([r#"say "hi""#, r"back\slash", "o'k"]).unwrap(),
+            r#"'{"say \"hi\"","back\\slash",o''k}'"#
+        );
+        assert_eq!(
+            text_array_literal(["ok", "a\0b"]),
+            Err(SqlError::InvalidText { position: 1 })
+        );
+    }
+}
@@ -1,5 +1,5 @@
-use super::separator_optional;
-use crate::WriteSql;
+use super::{emit, Token};
+use crate::{ArgumentError, SqlError, WriteSql};
 
 /// Row-level lock strength
 ///
@@ -48,23 +48,21 @@ pub fn row_lock<'t, Sql, Arg, I>(
     Sql: WriteSql<Arg>,
     I: IntoIterator<Item = &'t str>,
 {
-    separator_optional(sql);
-    sql.push_cmd("FOR ");
-    sql.push_cmd(strength.as_str());
+    emit(sql, Token::Keyword("FOR"));
+    emit(sql, Token::Ident(strength.as_str()));
 
     let mut tables = tables.into_iter();
     if let Some(table) = tables.next() {
-        sql.push_cmd(" OF ");
-        sql.push_cmd(table);
+        emit(sql, Token::Keyword("OF"));
+        emit(sql, Token::Ident(table));
     }
     for table in tables {
-        sql.push_cmd(", ");
-        sql.push_cmd(table);
+        emit(sql, Token::Comma);
+        emit(sql, Token::Raw(table));
     }
 
     if let Some(concurr) = concurrency {
-        sql.push_cmd(" ");
-        sql.push_cmd(concurr.as_str());
+        emit(sql, Token::Keyword(concurr.as_str()));
     }
 }
 
@@ -122,6 +120,9 @@ impl TableLockWait {
 
 /// LOCK sql command
 ///
+/// An empty list of tables writes nothing, see [table_lock_checked] to report
+/// it as an error.
+///
 /// - [Postgres table lock](https://www.postgresql.org/docs/current/sql-lock.html)
 pub fn table_lock<Sql, Arg>(
     sql: &mut Sql,
@@ -131,33 +132,56 @@ pub fn table_lock<Sql, Arg>(
 ) where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("LOCK TABLE ");
+    if matches!(tables, TableLock::Tables([])) {
+        return;
+    }
+
+    emit(sql, Token::Keyword("LOCK TABLE"));
 
     match tables {
         TableLock::Only(table) => {
-            sql.push_cmd("ONLY ");
-            sql.push_cmd(table);
+            emit(sql, Token::Keyword("ONLY"));
+            emit(sql, Token::Ident(table));
         }
         TableLock::Tables(tbls) => {
-            if let Some(tbl) = tbls.first() {
-                sql.push_cmd(tbl);
-            }
-            for tbl in &tbls[1..] {
-                sql.push_cmd(", ");
-                sql.push_cmd(tbl);
+            if let Some((first, rest)) = tbls.split_first() {
+                emit(sql, Token::Ident(first));
+                for tbl in rest {
+                    emit(sql, Token::Comma);
+                    emit(sql, Token::Raw(tbl));
+                }
             }
         }
     }
 
     if let Some(mode) = lock_mode {
-        sql.push_cmd(" ");
-        sql.push_cmd(mode.as_str());
+        emit(sql, Token::Keyword(mode.as_str()));
     }
 
     if nowait == TableLockWait::NoWait {
-        sql.push_cmd(" NOWAIT");
+        emit(sql, Token::Keyword("NOWAIT"));
+    }
+}
+
+/// LOCK sql command, see [table_lock].
+///
+/// An empty list of tables returns an [EmptyList](SqlError::EmptyList) error,
+/// without writing anything.
+pub fn table_lock_checked<Sql, Arg>(
+    sql: &mut Sql,
+    tables: TableLock<'_>,
+    lock_mode: Option<TableLockMode>,
+    nowait: TableLockWait,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+{
+    if matches!(tables, TableLock::Tables([])) {
+        return Err(SqlError::EmptyList);
     }
+    table_lock(sql, tables, lock_mode, nowait);
+    Ok(())
 }
 
 #[allow(unused_macros)]
@@ -352,10 +376,27 @@ mod row_lock_test {
 #[cfg(test)]
 mod table_lock_test {
     use crate::{
-        expr::{table_lock, TableLock, TableLockMode, TableLockWait},
-        SqlCommand, Void,
+        expr::{table_lock, table_lock_checked, TableLock, TableLockMode, TableLockWait},
+        SqlCommand, SqlError, Void,
     };
 
+    #[test]
+    fn lock_no_tables() {
+        let mut sql: SqlCommand<Void> = SqlCommand::default();
+
+        assert_eq!(
+            table_lock_checked(&mut sql, TableLock::Tables(&[]), None, TableLockWait::None),
+            Err(SqlError::EmptyList)
+        );
+        assert_eq!(sql.as_command(), "");
+
+        table_lock(&mut sql, TableLock::Tables(&[]), None, TableLockWait::None);
+        assert_eq!(sql.as_command(), "");
+
+        table_lock_checked(&mut sql, TableLock::Only("user"), None, TableLockWait::None).unwrap();
+        assert_eq!(sql.as_command(), "LOCK TABLE ONLY user");
+    }
+
     #[test]
     fn lock_table_only() {
         let mut sql: SqlCommand<Void> = SqlCommand::default();
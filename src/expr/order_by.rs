@@ -1,4 +1,4 @@
-use super::separator_optional;
+use super::{emit, Token};
 use crate::WriteSql;
 
 #[derive(Clone, Default, PartialEq, Eq)]
@@ -56,17 +56,15 @@ where
     Sql: WriteSql<Arg>,
     I: IntoIterator<Item = (&'col str, OrderByOrd<'ord_expr>, OrderByNulls)>,
 {
-    separator_optional(sql);
-    sql.push_cmd("ORDER BY");
+    emit(sql, Token::Keyword("ORDER BY"));
 
     let mut orderexpr = order_exprs.into_iter();
     if let Some(expr) = orderexpr.next() {
-        sql.push_cmd(" ");
         order_by_expr(sql, expr.0, expr.1, expr.2);
     }
 
     for expr in orderexpr {
-        sql.push_cmd(", ");
+        emit(sql, Token::Comma);
         order_by_expr(sql, expr.0, expr.1, expr.2);
     }
 }
@@ -91,26 +89,27 @@ pub fn order_by_expr<Sql, Arg>(sql: &mut Sql, column: &str, order: OrderByOrd, n
 where
     Sql: WriteSql<Arg>,
 {
-    sql.push_cmd(column);
+    emit(sql, Token::Ident(column));
 
     match order {
-        OrderByOrd::Desc => sql.push_cmd(" DESC"),
-        OrderByOrd::Asc => sql.push_cmd(" ASC"),
+        OrderByOrd::Desc => emit(sql, Token::Keyword("DESC")),
+        OrderByOrd::Asc => emit(sql, Token::Keyword("ASC")),
         OrderByOrd::Using(op) => {
-            sql.push_cmd(" USING ");
-            sql.push_cmd(op);
+            emit(sql, Token::Keyword("USING"));
+            emit(sql, Token::Ident(op));
         }
         OrderByOrd::Default => {}
     }
 
     match nulls {
-        OrderByNulls::First => sql.push_cmd(" NULLS FIRST"),
-        OrderByNulls::Last => sql.push_cmd(" NULLS LAST"),
+        OrderByNulls::First => emit(sql, Token::Keyword("NULLS FIRST")),
+        OrderByNulls::Last => emit(sql, Token::Keyword("NULLS LAST")),
         OrderByNulls::Default => {}
     }
 }
 
-#[allow(unused_macros)]
+#[doc(hidden)]
+#[macro_export]
 macro_rules! order_by_criteria {
     () => {
         ""
@@ -122,7 +121,7 @@ macro_rules! order_by_criteria {
         "DESC"
     };
     (USING $op:tt) => {
-        concat!("USING ", $crate::expr::static_comparison!($op))
+        concat!("USING ", $crate::static_comparison!($op))
     };
 
     (NULLS FIRST) => {
@@ -139,11 +138,7 @@ macro_rules! order_by_criteria {
         "DESC NULLS FIRST"
     };
     (USING $op:tt NULLS FIRST) => {
-        concat!(
-            "USING ",
-            $crate::expr::static_comparison!($op),
-            " NULLS FIRST"
-        )
+        concat!("USING ", $crate::static_comparison!($op), " NULLS FIRST")
     };
 
     (ASC NULLS LAST) => {
@@ -153,24 +148,18 @@ macro_rules! order_by_criteria {
         "DESC NULLS LAST"
     };
     (USING $op:tt NULLS LAST) => {
-        concat!(
-            "USING ",
-            $crate::expr::static_comparison!($op),
-            " NULLS LAST"
-        )
+        concat!("USING ", $crate::static_comparison!($op), " NULLS LAST")
     };
 }
 
-#[allow(unused_imports)]
-pub(super) use order_by_criteria;
-
-#[allow(unused_macros)]
+#[doc(hidden)]
+#[macro_export]
 macro_rules! nested_order_by {
     ($col:literal$(,)? $($colx:literal $(ord($($rest:tt)+))?),*) => {
         concat!(
             ", ",
             $col,
-			$($crate::expr::nested_order_by!($colx $(ord($($rest)+))?)),*
+			$($crate::nested_order_by!($colx $(ord($($rest)+))?)),*
         )
     };
     ($col:literal ord($($criteria:tt)+)$(,)? $($colx:literal $(ord($($rest:tt)+))?),*) => {
@@ -178,22 +167,19 @@ macro_rules! nested_order_by {
             ", ",
             $col,
 			" ",
-    		$crate::expr::order_by_criteria!($($criteria)+),
-    		$($crate::expr::nested_order_by!($colx $(ord($($rest)+))?)),*
+    		$crate::order_by_criteria!($($criteria)+),
+    		$($crate::nested_order_by!($colx $(ord($($rest)+))?)),*
         )
     };
 }
 
-#[allow(unused_imports)]
-pub(super) use nested_order_by;
-
 #[macro_export]
 macro_rules! static_order_by {
     ($col:literal$(,)? $($colx:literal $(ord($($rest:tt)+))?),*) => {
         concat!(
             "ORDER BY ",
             $col,
-			$($crate::expr::nested_order_by!($colx $(ord($($rest)+))?)),*
+			$($crate::nested_order_by!($colx $(ord($($rest)+))?)),*
         )
     };
     ($col:literal ord($($criteria:tt)+)$(,)? $($colx:literal $(ord($($rest:tt)+))?),*) => {
@@ -201,8 +187,8 @@ macro_rules! static_order_by {
             "ORDER BY ",
             $col,
 			" ",
-    		$crate::expr::order_by_criteria!($($criteria)+),
-    		$($crate::expr::nested_order_by!($colx $(ord($($rest)+))?)),*
+    		$crate::order_by_criteria!($($criteria)+),
+    		$($crate::nested_order_by!($colx $(ord($($rest)+))?)),*
         )
     };
 }
@@ -1,6 +1,6 @@
-use crate::WriteSql;
+use crate::{ArgumentError, Record, SqlError, WriteSql};
 
-use super::separator_optional;
+use super::{emit, ColumnExprList, Token};
 
 /// Write a `RETURNING` clause to compute the values that will be returned from
 /// the query.
@@ -22,6 +22,380 @@ pub fn returning<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("RETURNING");
+    emit(sql, Token::Keyword("RETURNING"));
+}
+
+/// Name of the column returned by a `RETURNING` item, the alias if present.
+///
+/// A qualified column (`u.id`) is named by the column alone. An expression
+/// without an alias, such as `count(*)`, is named by the database and
+/// returned as `*`, recording the returned columns as unknown.
+fn output_name(item: &str) -> &str {
+    const AS: &[u8] = b" as ";
+
+    let item = item.trim();
+    let alias = item
+        .as_bytes()
+        .windows(AS.len())
+        .rposition(|window| window.eq_ignore_ascii_case(AS))
+        .map(|position| item.split_at(position.saturating_add(AS.len())).1);
+    if let Some(alias) = alias {
+        return alias.trim();
+    }
+
+    let column = item.rsplit('.').next().unwrap_or(item);
+    let is_ident = column.bytes().enumerate().all(|(index, byte)| {
+        byte.is_ascii_alphabetic()
+            || byte == b'_'
+            || byte == b'"'
+            || (index > 0 && (byte.is_ascii_digit() || byte == b'$'))
+    });
+    if column.is_empty() || !is_ident {
+        return "*";
+    }
+    column
+}
+
+/// Write a `RETURNING <column>, ...` clause, recording the returned columns.
+///
+/// The name of each column without its table, or its alias, is recorded. A
+/// `*` item or an expression without an alias records the returned columns
+/// as unknown. See
+/// [returning_columns](crate::SqlCommand::returning_columns).
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{delete_from, returning_columns};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// delete_from(&mut sql, "session");
+/// returning_columns(&mut sql, ["id", "user_id AS owner"]);
+///
+/// assert_eq!(sql.as_command(), "DELETE FROM session RETURNING id, user_id AS owner");
+/// assert_eq!(sql.returning_columns(), Some(["id".to_owned(), "owner".to_owned()].as_slice()));
+/// ```
+pub fn returning_columns<'c, Sql, Arg, I>(sql: &mut Sql, columns: I)
+where
+    Sql: WriteSql<Arg>,
+    I: IntoIterator<Item = &'c str>,
+{
+    returning(sql);
+
+    for (index, column) in columns.into_iter().enumerate() {
+        if index > 0 {
+            emit(sql, Token::Comma);
+        }
+        emit(sql, Token::Ident(column));
+        sql.record(Record::Returning(output_name(column)));
+    }
+}
+
+/// Write a `RETURNING <column>, ...` clause like [returning_columns],
+/// returning an [EmptyList](SqlError::EmptyList) error without any column.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{insert_into, default_values, returning_columns_checked};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// insert_into(&mut sql, "user");
+/// default_values(&mut sql);
+/// returning_columns_checked(&mut sql, &["id", "created_at"])?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "INSERT INTO user DEFAULT VALUES RETURNING id, created_at"
+/// );
+/// assert_eq!(returning_columns_checked(&mut sql, &[]), Err(SqlError::EmptyList));
+/// # Ok(())
+/// # }
+/// ```
+pub fn returning_columns_checked<Sql, Arg>(
+    sql: &mut Sql,
+    columns: &[&str],
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+{
+    if columns.is_empty() {
+        return Err(SqlError::EmptyList);
+    }
+    returning_columns(sql, columns.iter().copied());
+    Ok(())
+}
+
+/// Write a `RETURNING *` clause, recording the returned columns as unknown.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{delete_from, returning_all};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// delete_from(&mut sql, "session");
+/// returning_all(&mut sql);
+///
+/// assert_eq!(sql.as_command(), "DELETE FROM session RETURNING *");
+/// assert_eq!(sql.returning_columns(), None);
+/// ```
+pub fn returning_all<Sql, Arg>(sql: &mut Sql)
+where
+    Sql: WriteSql<Arg>,
+{
+    returning_columns(sql, ["*"]);
+}
+
+/// Write a `RETURNING` clause from a [ColumnExprList], recording the returned
+/// columns.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{insert_into, returning_list, column_list};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// insert_into(&mut sql, "user");
+/// sql.push_cmd(" DEFAULT VALUES");
+/// returning_list(&mut sql, &column_list().column("id").column_as("created_at", "created"));
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "INSERT INTO user DEFAULT VALUES RETURNING id, created_at AS created"
+/// );
+/// assert_eq!(sql.returning_columns(), Some(["id".to_owned(), "created".to_owned()].as_slice()));
+/// ```
+pub fn returning_list<Sql, Arg>(sql: &mut Sql, columns: &ColumnExprList<'_>)
+where
+    Sql: WriteSql<Arg>,
+{
+    returning_columns(sql, columns.iter().map(|column| column.as_ref()));
+}
+
+/// Write a static `RETURNING` clause from a column list fragment, such as one
+/// built with [static_columns](crate::static_columns), recording the returned
+/// columns.
+///
+/// The columns are taken from each comma separated item of the fragment.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, static_columns};
+/// # use sqlstr::expr::{delete_from, static_returning};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// delete_from(&mut sql, "session");
+/// static_returning(&mut sql, static_columns!("id", "expires_at" AS "expires"));
+///
+/// assert_eq!(sql.as_command(), "DELETE FROM session RETURNING id, expires_at AS expires");
+/// assert_eq!(sql.returning_columns(), Some(["id".to_owned(), "expires".to_owned()].as_slice()));
+/// ```
+pub fn static_returning<Sql, Arg>(sql: &mut Sql, fragment: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    returning(sql);
+    emit(sql, Token::Ident(fragment));
+
+    let mut rest = fragment;
+    while let Some(comma) = top_level_comma(rest) {
+        let (item, tail) = rest.split_at(comma);
+        sql.record(Record::Returning(output_name(item)));
+        // the comma is a single byte
+        (_, rest) = tail.split_at(1);
+    }
+    sql.record(Record::Returning(output_name(rest)));
+}
+
+/// Returns the byte position of the first comma of `fragment` outside
+/// parentheses.
+fn top_level_comma(fragment: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    fragment.char_indices().find_map(|(index, ch)| {
+        match ch {
+            '(' => depth = depth.saturating_add(1),
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => return Some(index),
+            _ => {}
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{borrow::ToOwned, string::String, vec::Vec};
+
+    use super::{
+        returning, returning_all, returning_columns, returning_columns_checked, returning_list,
+        static_returning,
+    };
+    use crate::{
+        expr::{
+            column_list, delete_from, insert_columns_iter, insert_into, on_conflict, update_table,
+            values, values_rows, ConflictAction, ConflictTarget,
+        },
+        static_columns,
+        test::TestArgs,
+        SqlCommand, SqlError,
+    };
+
+    fn names(columns: &[&str]) -> Vec<String> {
+        columns.iter().map(|&col| col.to_owned()).collect()
+    }
+
+    #[test]
+    fn returning_slice_columns() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        delete_from(&mut sql, "session");
+        returning_columns(&mut sql, ["id", "count(*) AS total", "user_id"]);
+
+        assert_eq!(
+            sql.as_command(),
+            "DELETE FROM session RETURNING id, count(*) AS total, user_id"
+        );
+        assert_eq!(
+            sql.returning_columns(),
+            Some(names(&["id", "total", "user_id"]).as_slice())
+        );
+    }
+
+    #[test]
+    fn returning_newtype_columns() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        update_table(&mut sql, "user");
+        sql.push_cmd(" SET active = false");
+        returning_list(
+            &mut sql,
+            &column_list().column("id").column_as("u.name", "name"),
+        );
+
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE user SET active = false RETURNING id, u.name AS name"
+        );
+        assert_eq!(
+            sql.returning_columns(),
+            Some(names(&["id", "name"]).as_slice())
+        );
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        delete_from(&mut sql, "user");
+        static_returning(
+            &mut sql,
+            static_columns!("id", "coalesce(a, b)" AS "ab", "email"),
+        );
+
+        assert_eq!(
+            sql.as_command(),
+            "DELETE FROM user RETURNING id, coalesce(a, b) AS ab, email"
+        );
+        assert_eq!(
+            sql.returning_columns(),
+            Some(names(&["id", "ab", "email"]).as_slice())
+        );
+    }
+
+    #[test]
+    fn returning_output_names() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        delete_from(&mut sql, "session");
+        returning_columns(&mut sql, ["s.id", "s.user_id as owner", "\"s\".\"token\""]);
+
+        assert_eq!(
+            sql.returning_columns(),
+            Some(names(&["id", "owner", "\"token\""]).as_slice())
+        );
+
+        for item in ["count(*)", "u.*", "id + 1", "1"] {
+            let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+            delete_from(&mut sql, "session");
+            returning_columns(&mut sql, ["id", item]);
+
+            assert_eq!(sql.returning_columns(), None, "{item}");
+        }
+    }
+
+    #[test]
+    fn returning_wildcard_columns() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        delete_from(&mut sql, "session");
+        assert_eq!(sql.returning_columns(), None);
+
+        returning_columns(&mut sql, ["id", "*"]);
+
+        assert_eq!(sql.as_command(), "DELETE FROM session RETURNING id, *");
+        assert_eq!(sql.returning_columns(), None);
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        delete_from(&mut sql, "session");
+        returning(&mut sql);
+        sql.push_cmd(" id");
+
+        assert_eq!(sql.returning_columns(), None);
+    }
+
+    #[test]
+    fn insert_returning_after_values() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        insert_into(&mut sql, "user");
+        let arity = insert_columns_iter(&mut sql, ["name"]).unwrap();
+        values(&mut sql);
+        values_rows(&mut sql, arity, [["Rusty"]]).unwrap();
+        returning_columns_checked(&mut sql, &["id", "created_at"]).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO user (name) VALUES ($1) RETURNING id, created_at"
+        );
+        assert_eq!(
+            sql.returning_columns(),
+            Some(names(&["id", "created_at"]).as_slice())
+        );
+    }
+
+    #[test]
+    fn insert_returning_after_on_conflict() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        insert_into(&mut sql, "user");
+        let arity = insert_columns_iter(&mut sql, ["email"]).unwrap();
+        values(&mut sql);
+        values_rows(&mut sql, arity, [["rusty@email.com"]]).unwrap();
+        on_conflict(
+            &mut sql,
+            Some(ConflictTarget::IndexColumn("(email)")),
+            ConflictAction::Nothing,
+        );
+        returning_all(&mut sql);
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO user (email) VALUES ($1) ON CONFLICT (email) DO NOTHING RETURNING *"
+        );
+        assert_eq!(sql.returning_columns(), None);
+    }
+
+    #[test]
+    fn returning_checked_empty() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        insert_into(&mut sql, "user");
+        let err = returning_columns_checked(&mut sql, &[]).unwrap_err();
+
+        assert_eq!(err, SqlError::EmptyList);
+        assert_eq!(sql.as_command(), "INSERT INTO user");
+    }
 }
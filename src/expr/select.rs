@@ -1,5 +1,10 @@
-use super::separator_optional;
-use crate::WriteSql;
+use alloc::string::String;
+
+use super::{
+    columns_iter, emit, filter_where, from_table, order_by, OrderByNulls, OrderByOrd,
+    RowLockConcurrency, RowLockStrength, Tail, Token,
+};
+use crate::{ArgumentBuffer, SqlCommand, WriteSql};
 
 /// SELECT
 ///
@@ -22,8 +27,7 @@ pub fn select<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("SELECT");
+    emit(sql, Token::Keyword("SELECT"));
 }
 
 /// SELECT ALL
@@ -47,8 +51,7 @@ pub fn select_all<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("SELECT ALL");
+    emit(sql, Token::Keyword("SELECT ALL"));
 }
 
 /// SELECT DISTINCT
@@ -72,8 +75,191 @@ pub fn select_distinct<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("SELECT DISTINCT");
+    emit(sql, Token::Keyword("SELECT DISTINCT"));
+}
+
+/// Writes a `SELECT DISTINCT ON (<expr>, ...)` clause.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::select_distinct_on;
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select_distinct_on(&mut sql, ["customer_id", "product_id"]);
+///
+/// assert_eq!(sql.as_command(), "SELECT DISTINCT ON (customer_id, product_id)");
+/// ```
+pub fn select_distinct_on<'e, Sql, Arg, I>(sql: &mut Sql, exprs: I)
+where
+    Sql: WriteSql<Arg>,
+    I: IntoIterator<Item = &'e str>,
+{
+    emit(sql, Token::Keyword("SELECT DISTINCT ON"));
+    emit(sql, Token::Open);
+
+    let mut exprs = exprs.into_iter();
+    if let Some(expr) = exprs.next() {
+        sql.push_cmd(expr);
+    }
+    for expr in exprs {
+        emit(sql, Token::Comma);
+        sql.push_cmd(expr);
+    }
+
+    emit(sql, Token::Close);
+}
+
+/// Latest row per key query, `DISTINCT ON (<key>, ...)` ordered by the keys
+/// and then by the most recent rows.
+///
+/// Postgres requires the `DISTINCT ON` expressions to match the leading
+/// `ORDER BY` expressions, both clauses are written from the same keys.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{latest_per, columns_iter, from_table};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// let latest = latest_per(&["customer_id"], "created_at");
+///
+/// latest.select_distinct_on(&mut sql);
+/// columns_iter(&mut sql, ["customer_id", "total", "created_at"]);
+/// from_table(&mut sql, "order");
+/// latest.order_by(&mut sql);
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT DISTINCT ON (customer_id) customer_id, total, created_at FROM order \
+///      ORDER BY customer_id ASC, created_at DESC NULLS LAST"
+/// );
+/// ```
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct LatestPer<'k> {
+    keys: &'k [&'k str],
+    recency: &'k str,
+}
+
+pub const fn latest_per<'k>(keys: &'k [&'k str], recency: &'k str) -> LatestPer<'k> {
+    LatestPer { keys, recency }
+}
+
+impl<'k> LatestPer<'k> {
+    /// Writes the `SELECT DISTINCT ON (<key>, ...)` clause.
+    pub fn select_distinct_on<Sql, Arg>(&self, sql: &mut Sql)
+    where
+        Sql: WriteSql<Arg>,
+    {
+        select_distinct_on(sql, self.keys.iter().copied());
+    }
+
+    /// Writes the `ORDER BY <key> ASC, ..., <recency> DESC NULLS LAST` clause.
+    pub fn order_by<Sql, Arg>(&self, sql: &mut Sql)
+    where
+        Sql: WriteSql<Arg>,
+    {
+        let keys = self
+            .keys
+            .iter()
+            .map(|key| (*key, OrderByOrd::Asc, OrderByNulls::Default));
+        let recency = (self.recency, OrderByOrd::Desc, OrderByNulls::Last);
+        order_by(sql, keys.chain([recency]));
+    }
+}
+
+/// Builder of a job queue dequeue query, selecting the oldest rows not
+/// locked by other transactions and locking them, started with
+/// [select_for_dequeue].
+///
+/// The conditions of the `WHERE` clause are written with
+/// [filter_where](Self::filter_where), and the command ends with the
+/// `ORDER BY <order_column> LIMIT $n FOR UPDATE SKIP LOCKED` clauses written
+/// by [lock](Self::lock).
+pub struct DequeueBuilder<'d, Arg> {
+    sql: SqlCommand<Arg>,
+    order_column: &'d str,
+}
+
+/// Starts a job queue dequeue query, `SELECT <column>, ... FROM <table>`,
+/// binding into `arguments`, see [DequeueBuilder].
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{select_for_dequeue, lhs_binary_rhs, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let sql: SqlCommand<Void> = select_for_dequeue(Void::new(), "job", &["id", "payload"], "created_at")
+///     .filter_where(|sql| lhs_binary_rhs(sql, sqlexpr::<&str>("queue"), Cmp::Eq, sqlvalue("email")))?
+///     .lock(10)?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT id, payload FROM job WHERE queue = $1 \
+///      ORDER BY created_at LIMIT $2 FOR UPDATE SKIP LOCKED"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn select_for_dequeue<'d, Arg>(
+    arguments: Arg,
+    table: &str,
+    columns: &[&str],
+    order_column: &'d str,
+) -> DequeueBuilder<'d, Arg> {
+    let mut sql = SqlCommand::new(String::new(), arguments);
+    select(&mut sql);
+    columns_iter(&mut sql, columns.iter().copied());
+    from_table(&mut sql, table);
+    DequeueBuilder { sql, order_column }
+}
+
+impl<'d, Arg> DequeueBuilder<'d, Arg> {
+    /// Registers `sink` to receive the metrics of the command when it is
+    /// ended, see [with_metrics](SqlCommand::with_metrics).
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, sink: &'static (dyn crate::MetricsSink + Sync)) -> Self
+    where
+        Arg: crate::AppendArguments,
+    {
+        self.sql = self.sql.with_metrics(sink);
+        self
+    }
+
+    /// Writes the `WHERE` clause, with the condition written by `build`.
+    pub fn filter_where<F, E>(mut self, build: F) -> Result<Self, E>
+    where
+        F: FnOnce(&mut SqlCommand<Arg>) -> Result<(), E>,
+    {
+        filter_where(&mut self.sql);
+        build(&mut self.sql)?;
+        Ok(self)
+    }
+
+    /// Ends the command with the
+    /// `ORDER BY <order_column> LIMIT $n FOR UPDATE SKIP LOCKED` clauses,
+    /// binding the `count` of rows as a value.
+    pub fn lock(mut self, count: usize) -> Result<SqlCommand<Arg>, Arg::Error>
+    where
+        Arg: ArgumentBuffer<usize>,
+    {
+        Tail::new(&mut self.sql)
+            .order_by([(
+                self.order_column,
+                OrderByOrd::Default,
+                OrderByNulls::Default,
+            )])
+            .limit(count)?
+            .row_lock(
+                RowLockStrength::Update,
+                [],
+                Some(RowLockConcurrency::SkipLocked),
+            );
+        Ok(self.sql.finished())
+    }
 }
 
 #[macro_export]
@@ -93,6 +279,89 @@ pub use static_select;
 
 #[cfg(test)]
 mod test {
+    use super::*;
+    use crate::{
+        expr::{lhs_binary_rhs, Cmp},
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+    };
+
+    fn order_by_prefix(cmd: &str) -> &str {
+        let order = cmd.split(" ORDER BY ").nth(1).unwrap();
+        order.rsplit_once(", ").unwrap().0
+    }
+
+    fn distinct_on_list(cmd: &str) -> &str {
+        let on = cmd.strip_prefix("SELECT DISTINCT ON (").unwrap();
+        on.split_once(')').unwrap().0
+    }
+
+    #[test]
+    fn latest_per_single_key() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        let latest = latest_per(&["device_id"], "recorded_at");
+
+        latest.select_distinct_on(&mut sql);
+        columns_iter(&mut sql, ["device_id", "value"]);
+        from_table(&mut sql, "reading");
+        latest.order_by(&mut sql);
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT DISTINCT ON (device_id) device_id, value FROM reading ORDER BY device_id ASC, recorded_at DESC NULLS LAST"
+        );
+        assert_eq!(
+            order_by_prefix(sql.as_command()).replace(" ASC", ""),
+            distinct_on_list(sql.as_command())
+        );
+    }
+
+    #[test]
+    fn latest_per_composite_key() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        let latest = latest_per(&["tenant_id", "device_id"], "recorded_at");
+
+        latest.select_distinct_on(&mut sql);
+        columns_iter(&mut sql, ["*"]);
+        from_table(&mut sql, "reading");
+        latest.order_by(&mut sql);
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT DISTINCT ON (tenant_id, device_id) * FROM reading ORDER BY tenant_id ASC, device_id ASC, recorded_at DESC NULLS LAST"
+        );
+        assert_eq!(
+            order_by_prefix(sql.as_command()).replace(" ASC", ""),
+            distinct_on_list(sql.as_command())
+        );
+    }
+
+    #[test]
+    fn dequeue_jobs() {
+        let sql = select_for_dequeue(TestArgs::new(), "job", &["id", "payload"], "run_at")
+            .filter_where(|sql| {
+                lhs_binary_rhs(sql, sqlexpr::<&str>("status"), Cmp::Eq, sqlvalue("pending"))
+            })
+            .unwrap()
+            .lock(5)
+            .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT id, payload FROM job WHERE status = $1 ORDER BY run_at LIMIT $2 FOR UPDATE SKIP LOCKED"
+        );
+        assert_eq!(sql.arguments.as_str(), "pending;5;");
+
+        let sql = select_for_dequeue(TestArgs::new(), "job", &["id", "payload"], "run_at")
+            .lock(1)
+            .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT id, payload FROM job ORDER BY run_at LIMIT $1 FOR UPDATE SKIP LOCKED"
+        );
+    }
+
     #[test]
     fn static_select_macro() {
         assert_eq!(static_select!(SELECT), "SELECT");
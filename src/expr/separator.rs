@@ -190,3 +190,179 @@ where
 {
     sql.push_cmd(" ");
 }
+
+/// Token of a sql command written with [emit].
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Token<'t> {
+    /// Sql keyword (e.g. `FROM`, `ORDER BY`), separated by a space from the
+    /// previous token.
+    Keyword(&'t str),
+    /// Identifier or expression (e.g. a table or column), separated by a space
+    /// from the previous token.
+    Ident(&'t str),
+    /// Item separator `", "`.
+    Comma,
+    /// Open parenthesis of a group or list (e.g. `IN (`), separated by a space
+    /// from the previous token.
+    Open,
+    /// Close parenthesis, written without any separation.
+    Close,
+    /// Separation of a placeholder about to be pushed with
+    /// [push_value](WriteSql::push_value).
+    Placeholder,
+    /// Separation of an operand about to be written by other means (e.g. a
+    /// nested expression or command).
+    Space,
+    /// Quoted text `'<text>'`, separated by a space from the previous token.
+    /// The text is written as is, without escaping.
+    Quoted(&'t str),
+    /// Raw sql, written as is without any separation.
+    Raw(&'t str),
+}
+
+/// Writes a token into the command buffer, owning the spacing rules between
+/// tokens.
+///
+/// Keywords, identifiers, quoted text, open parenthesis, placeholders and
+/// spaces are preceded by a space, unless the command is empty or already ends with a space or an open
+/// parenthesis.
+pub(crate) fn emit<Sql, Arg>(sql: &mut Sql, token: Token<'_>)
+where
+    Sql: WriteSql<Arg>,
+{
+    match token {
+        Token::Keyword(text) | Token::Ident(text) => {
+            separator_optional(sql);
+            sql.push_cmd(text);
+        }
+        Token::Comma => sql.push_cmd(", "),
+        Token::Open => {
+            separator_optional(sql);
+            sql.push_cmd("(");
+        }
+        Token::Close => sql.push_cmd(")"),
+        Token::Placeholder | Token::Space => separator_optional(sql),
+        Token::Quoted(text) => {
+            separator_optional(sql);
+            sql.push_cmd("'");
+            sql.push_cmd(text);
+            sql.push_cmd("'");
+        }
+        Token::Raw(text) => sql.push_cmd(text),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{emit, Token};
+    use crate::{
+        expr::{
+            columns_iter, continue_condition, filter_where, from_as, from_table, from_table_as,
+            from_tables, group_by, join, join_on, lhs_binary_rhs, limit, offset, returning, select,
+            values, write_iter, Cmp, JoinType, LogicBi,
+        },
+        test::TestArgs,
+        SqlCommand, SqlExpr,
+    };
+
+    /// Linear congruential generator, enough to produce reproducible call
+    /// sequences without a dependency.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self, bound: usize) -> usize {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 33) as usize) % bound
+        }
+    }
+
+    const IDENTS: [&str; 4] = ["id", "u.name", "created_at", "total"];
+
+    fn idents(rng: &mut Lcg) -> &'static [&'static str] {
+        let len = rng.next(IDENTS.len()) + 1;
+        &IDENTS[..len]
+    }
+
+    fn random_step(sql: &mut SqlCommand<TestArgs>, rng: &mut Lcg) {
+        match rng.next(19) {
+            0 => select(sql),
+            1 => columns_iter(sql, idents(rng).iter().copied()),
+            2 => from_table(sql, "user"),
+            3 => from_tables(sql, idents(rng).iter().copied()),
+            4 => from_table_as(sql, "user", "u"),
+            5 => from_as(sql, "order", "o"),
+            6 => join(sql, JoinType::Left, "post", Some("p")),
+            7 => join_on(sql),
+            8 => filter_where(sql),
+            9 => continue_condition(sql, LogicBi::And),
+            10 => {
+                lhs_binary_rhs(sql, SqlExpr::<u8>::Expr("id"), Cmp::Eq, SqlExpr::Value(1)).unwrap()
+            }
+            11 => group_by(sql, idents(rng).iter().copied()),
+            12 => limit(sql, 10).unwrap(),
+            13 => offset(sql, 5).unwrap(),
+            14 => returning(sql),
+            15 => values(sql),
+            16 => write_iter(sql, idents(rng).iter().copied()).unwrap(),
+            17 => emit(sql, Token::Keyword("DISTINCT")),
+            _ => {
+                emit(sql, Token::Placeholder);
+                sql.push_value(0).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn emit_tokens() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        emit(&mut sql, Token::Keyword("SELECT"));
+        emit(&mut sql, Token::Ident("id"));
+        emit(&mut sql, Token::Comma);
+        emit(&mut sql, Token::Placeholder);
+        sql.push_value(1).unwrap();
+        emit(&mut sql, Token::Keyword("FROM"));
+        emit(&mut sql, Token::Ident("user"));
+        emit(&mut sql, Token::Raw("(x)"));
+        emit(&mut sql, Token::Keyword("WHERE"));
+        emit(&mut sql, Token::Ident("id IN"));
+        emit(&mut sql, Token::Open);
+        emit(&mut sql, Token::Placeholder);
+        sql.push_value(2).unwrap();
+        emit(&mut sql, Token::Close);
+        emit(&mut sql, Token::Keyword("AND"));
+        emit(&mut sql, Token::Ident("name LIKE"));
+        emit(&mut sql, Token::Quoted("a%"));
+        emit(&mut sql, Token::Space);
+        emit(&mut sql, Token::Space);
+        sql.push_cmd("ESCAPE");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT id, $1 FROM user(x) WHERE id IN ($2) AND name LIKE 'a%' ESCAPE"
+        );
+    }
+
+    #[test]
+    fn random_sequences_spacing() {
+        for seed in 0..512 {
+            let mut rng = Lcg(seed);
+            let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+            for _ in 0..rng.next(16) + 1 {
+                random_step(&mut sql, &mut rng);
+            }
+
+            let cmd = sql.as_command();
+            assert!(!cmd.contains("  "), "double space in {cmd:?} (seed {seed})");
+            assert!(
+                !cmd.contains(" ,"),
+                "space before comma in {cmd:?} (seed {seed})"
+            );
+        }
+    }
+}
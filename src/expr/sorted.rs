@@ -0,0 +1,127 @@
+use alloc::collections::{btree_map, BTreeMap};
+
+/// Map-like collection of `(column, value)` entries iterated in sorted column
+/// order, so the same entries always write the same command.
+///
+/// A [BTreeMap] is already sorted and is iterated as is. With the `std`
+/// feature, a `HashMap` is collected and sorted before being iterated.
+pub trait SortedEntries {
+    type Key: AsRef<str>;
+    type Value;
+    type IntoIter: Iterator<Item = (Self::Key, Self::Value)>;
+
+    /// Iterates the entries in sorted key order.
+    fn into_sorted_entries(self) -> Self::IntoIter;
+}
+
+impl<K, V> SortedEntries for BTreeMap<K, V>
+where
+    K: AsRef<str>,
+{
+    type Key = K;
+    type Value = V;
+    type IntoIter = btree_map::IntoIter<K, V>;
+
+    fn into_sorted_entries(self) -> Self::IntoIter {
+        self.into_iter()
+    }
+}
+
+impl<'m, K, V> SortedEntries for &'m BTreeMap<K, V>
+where
+    K: AsRef<str>,
+{
+    type Key = &'m K;
+    type Value = &'m V;
+    type IntoIter = btree_map::Iter<'m, K, V>;
+
+    fn into_sorted_entries(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "std")]
+mod hash_map {
+    use alloc::vec::{self, Vec};
+    use core::hash::BuildHasher;
+    use std::collections::HashMap;
+
+    use super::SortedEntries;
+
+    impl<K, V, S> SortedEntries for HashMap<K, V, S>
+    where
+        K: AsRef<str> + Ord,
+        S: BuildHasher,
+    {
+        type Key = K;
+        type Value = V;
+        type IntoIter = vec::IntoIter<(K, V)>;
+
+        fn into_sorted_entries(self) -> Self::IntoIter {
+            let mut entries: Vec<_> = self.into_iter().collect();
+            entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+            entries.into_iter()
+        }
+    }
+
+    impl<'m, K, V, S> SortedEntries for &'m HashMap<K, V, S>
+    where
+        K: AsRef<str> + Ord,
+        S: BuildHasher,
+    {
+        type Key = &'m K;
+        type Value = &'m V;
+        type IntoIter = vec::IntoIter<(&'m K, &'m V)>;
+
+        fn into_sorted_entries(self) -> Self::IntoIter {
+            let mut entries: Vec<_> = self.iter().collect();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            entries.into_iter()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use alloc::{string::String, vec::Vec};
+    use std::collections::HashMap;
+
+    use crate::{
+        expr::{comparison::compare_all_sorted, filter_where, set_all_sorted, update_table},
+        test::TestArgs,
+        SqlCommand,
+    };
+
+    fn update(entries: &HashMap<String, u32>) -> SqlCommand<TestArgs> {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        update_table(&mut sql, "item");
+        set_all_sorted(&mut sql, entries).unwrap();
+        filter_where(&mut sql);
+        compare_all_sorted(&mut sql, entries).unwrap();
+        sql
+    }
+
+    #[test]
+    fn hash_map_deterministic_command() {
+        let commands: Vec<SqlCommand<TestArgs>> = (0..16)
+            .map(|_| {
+                // every map has its own random hasher state
+                let entries: HashMap<String, u32> = ["stock", "price", "shelf", "batch", "color"]
+                    .into_iter()
+                    .zip(1..)
+                    .map(|(column, value)| (column.into(), value))
+                    .collect();
+                update(&entries)
+            })
+            .collect();
+
+        for sql in &commands {
+            assert_eq!(
+                sql.as_command(),
+                "UPDATE item SET batch = $1, color = $2, price = $3, shelf = $4, stock = $5 \
+                 WHERE batch = $6 AND color = $7 AND price = $8 AND shelf = $9 AND stock = $10"
+            );
+            assert_eq!(sql.arguments.as_str(), "4;5;2;3;1;4;5;2;3;1;");
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Stable names of the half-built commands.
+//!
+//! The states of the type-state builders are re-exported here as `tail`,
+//! `update` and `delete`, and the common builder types have aliases, so
+//! functions extending a command can name the type they take and return
+//! without depending on where each state is defined. New states are added
+//! alongside, without renaming these.
+//!
+//! # Example
+//!
+//! ```
+//! # use sqlstr::{ArgumentBuffer, SqlCommand, Void};
+//! # use sqlstr::expr::{select, columns_iter, from_table, Filters};
+//! # use sqlstr::expr::states::AfterFrom;
+//! # use core::convert::Infallible;
+//! fn tenant_scope<Arg>(filters: AfterFrom<'_, Arg>, tenant: u64) -> Result<AfterFrom<'_, Arg>, Arg::Error>
+//! where
+//!     Arg: ArgumentBuffer<u64>,
+//! {
+//!     filters.eq("tenant_id", Some(tenant))
+//! }
+//!
+//! # fn main() -> Result<(), Infallible> {
+//! let mut sql: SqlCommand<Void> = SqlCommand::default();
+//! select(&mut sql);
+//! columns_iter(&mut sql, ["id"]);
+//! from_table(&mut sql, "invoice");
+//! tenant_scope(Filters::new(&mut sql), 7)?.eq("paid", Some(false))?;
+//!
+//! assert_eq!(sql.as_command(), "SELECT id FROM invoice WHERE tenant_id = $1 AND paid = $2");
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{DeleteBuilder, Filters, Tail, UpdateBuilder};
+use crate::SqlCommand;
+
+pub use super::delete_state as delete;
+pub use super::tail_state as tail;
+pub use super::update_state as update;
+
+/// Conditions of a command after its `FROM` clause, the `WHERE` clause
+/// being written with the first one.
+pub type AfterFrom<'cmd, Arg> = Filters<'cmd, SqlCommand<Arg>, Arg>;
+
+/// Tail of a `SELECT` command after its `WHERE` clause, before any other
+/// clause.
+pub type AfterWhere<'cmd, Arg> = Tail<'cmd, SqlCommand<Arg>, Arg, tail::Start>;
+/// Tail of a command after its `GROUP BY` clause.
+pub type AfterGroupBy<'cmd, Arg> = Tail<'cmd, SqlCommand<Arg>, Arg, tail::GroupBy>;
+/// Tail of a command after its `HAVING` clause.
+pub type AfterHaving<'cmd, Arg> = Tail<'cmd, SqlCommand<Arg>, Arg, tail::Having>;
+/// Tail of a command after its `ORDER BY` clause.
+pub type AfterOrderBy<'cmd, Arg> = Tail<'cmd, SqlCommand<Arg>, Arg, tail::OrderBy>;
+/// Tail of a command after its `LIMIT` clause.
+pub type AfterLimit<'cmd, Arg> = Tail<'cmd, SqlCommand<Arg>, Arg, tail::Limit>;
+/// Tail of a command after its `OFFSET` clause.
+pub type AfterOffset<'cmd, Arg> = Tail<'cmd, SqlCommand<Arg>, Arg, tail::Offset>;
+/// Tail of a command after its row locking clause.
+pub type AfterLocking<'cmd, Arg> = Tail<'cmd, SqlCommand<Arg>, Arg, tail::Locking>;
+/// Tail of a `VALUES` command after its rows.
+pub type AfterValues<'cmd, Arg> = Tail<'cmd, SqlCommand<Arg>, Arg, tail::Values>;
+
+/// `UPDATE` command without any column assigned.
+pub type UpdateUnassigned<Arg> = UpdateBuilder<Arg, update::Unassigned>;
+/// `UPDATE` command with at least one column assigned.
+pub type UpdateAssigned<Arg> = UpdateBuilder<Arg, update::Assigned>;
+/// `UPDATE` command with only optional columns assigned.
+pub type UpdatePatched<Arg> = UpdateBuilder<Arg, update::Patched>;
+/// `UPDATE` command after its `FROM` sources.
+pub type UpdateSourced<Arg> = UpdateBuilder<Arg, update::Sourced>;
+/// `UPDATE` command after its `WHERE` clause.
+pub type UpdateFiltered<Arg> = UpdateBuilder<Arg, update::Filtered>;
+
+/// `DELETE` command with its table only.
+pub type DeleteStarted<Arg> = DeleteBuilder<Arg, delete::Started>;
+/// `DELETE` command after its `USING` sources.
+pub type DeleteUsing<Arg> = DeleteBuilder<Arg, delete::Using>;
+/// `DELETE` command after its `WHERE` clause.
+pub type DeleteFiltered<Arg> = DeleteBuilder<Arg, delete::Filtered>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        expr::{
+            from_table, lhs_binary_rhs, select, Cmp, OrderByNulls, OrderByOrd, RowLockStrength,
+        },
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+        ArgumentBuffer,
+    };
+
+    type Error = core::fmt::Error;
+
+    /// Repository layer scoping every query to a tenant.
+    fn tenant_scope<Arg>(
+        filters: AfterFrom<'_, Arg>,
+        tenant: u32,
+    ) -> Result<AfterFrom<'_, Arg>, Arg::Error>
+    where
+        Arg: ArgumentBuffer<u32>,
+    {
+        filters.eq("tenant_id", Some(tenant))
+    }
+
+    fn grouped(tail: AfterWhere<'_, TestArgs>) -> AfterGroupBy<'_, TestArgs> {
+        tail.group_by(["team_id"])
+    }
+
+    fn having(tail: AfterGroupBy<'_, TestArgs>) -> Result<AfterHaving<'_, TestArgs>, Error> {
+        tail.having(|sql| {
+            sql.push_cmd(" count(*) > 1");
+            Ok(())
+        })
+    }
+
+    fn ordered(tail: AfterHaving<'_, TestArgs>) -> AfterOrderBy<'_, TestArgs> {
+        tail.order_by([("team_id", OrderByOrd::Asc, OrderByNulls::Default)])
+    }
+
+    fn limited(tail: AfterOrderBy<'_, TestArgs>) -> Result<AfterLimit<'_, TestArgs>, Error> {
+        tail.limit(10)
+    }
+
+    fn offset(tail: AfterLimit<'_, TestArgs>) -> Result<AfterOffset<'_, TestArgs>, Error> {
+        tail.offset(20)
+    }
+
+    fn locked(tail: AfterOffset<'_, TestArgs>) -> AfterLocking<'_, TestArgs> {
+        tail.row_lock(RowLockStrength::Update, [], None)
+    }
+
+    fn values_limited(tail: AfterValues<'_, TestArgs>) -> Result<AfterLimit<'_, TestArgs>, Error> {
+        tail.limit(1)
+    }
+
+    fn assigned(update: UpdateUnassigned<TestArgs>) -> Result<UpdateAssigned<TestArgs>, Error> {
+        update.set("name", "Rusty")
+    }
+
+    fn patched(update: UpdateUnassigned<TestArgs>) -> Result<UpdatePatched<TestArgs>, Error> {
+        update.set_opt("email", Some("rusty@email.com"))
+    }
+
+    fn sourced(update: UpdateAssigned<TestArgs>) -> UpdateSourced<TestArgs> {
+        update.from("team")
+    }
+
+    fn update_filtered(update: UpdateSourced<TestArgs>) -> Result<UpdateFiltered<TestArgs>, Error> {
+        update.filter_where(|sql| lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(1)))
+    }
+
+    fn using(delete: DeleteStarted<TestArgs>) -> DeleteUsing<TestArgs> {
+        delete.using("team")
+    }
+
+    fn delete_filtered(delete: DeleteUsing<TestArgs>) -> Result<DeleteFiltered<TestArgs>, Error> {
+        delete.filter_where(|sql| lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(2)))
+    }
+
+    #[test]
+    fn tenant_filter_extension() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        sql.push_cmd(" *");
+        from_table(&mut sql, "invoice");
+        let count = tenant_scope(Filters::new(&mut sql), 7)
+            .unwrap()
+            .eq("paid", Some(true))
+            .unwrap()
+            .end();
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM invoice WHERE tenant_id = $1 AND paid = $2"
+        );
+        assert_eq!(sql.arguments.as_str(), "7;true;");
+    }
+
+    #[test]
+    fn tail_aliases() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        sql.push_cmd(" team_id");
+        from_table(&mut sql, "member");
+        let tail = grouped(Tail::new(&mut sql));
+        let tail = ordered(having(tail).unwrap());
+        locked(offset(limited(tail).unwrap()).unwrap()).end();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT team_id FROM member GROUP BY team_id HAVING count(*) > 1 \
+             ORDER BY team_id ASC LIMIT $1 OFFSET $2 FOR UPDATE"
+        );
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        sql.push_cmd("VALUES (1)");
+        values_limited(Tail::values(&mut sql)).unwrap().end();
+        assert_eq!(sql.as_command(), "VALUES (1) LIMIT $1");
+    }
+
+    #[test]
+    fn builder_aliases() {
+        let update = UpdateBuilder::new(TestArgs::new(), "member");
+        let sql = update_filtered(sourced(assigned(update).unwrap()))
+            .unwrap()
+            .end();
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE member SET name = $1 FROM team WHERE id = $2"
+        );
+
+        let sql = patched(UpdateBuilder::new(TestArgs::new(), "member"))
+            .unwrap()
+            .end()
+            .unwrap();
+        assert_eq!(sql.as_command(), "UPDATE member SET email = $1");
+
+        let delete = DeleteBuilder::new(TestArgs::new(), "member");
+        let sql = delete_filtered(using(delete)).unwrap().end();
+        assert_eq!(
+            sql.as_command(),
+            "DELETE FROM member USING team WHERE id = $1"
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use super::Group;
+use crate::WriteSql;
+
+/// Writes a parenthesized subquery `(<subquery>)`.
+///
+/// The subquery is written by `build` directly into the same command, sharing
+/// the argument buffer, so the placeholders continue the numbering of the
+/// outer query.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, SqlExpr, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{subquery, select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// subquery(&mut sql, |sub| {
+///     select(sub);
+///     column(sub, "id");
+///     from_table(sub, "user");
+///     filter_where(sub);
+///     lhs_binary_rhs(sub, sqlexpr::<u8>("age"), Cmp::Gt, sqlvalue(18))
+/// })?;
+///
+/// assert_eq!(sql.as_command(), "(SELECT id FROM user WHERE age > $1)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn subquery<Sql, Arg, F, E>(sql: &mut Sql, build: F) -> Result<(), E>
+where
+    Sql: WriteSql<Arg>,
+    F: FnOnce(&mut Group<'_, Sql, Arg>) -> Result<(), E>,
+{
+    let mut group = Group::open(sql);
+    build(&mut group)
+}
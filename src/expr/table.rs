@@ -1,34 +1,76 @@
-use super::separator_optional;
-use crate::WriteSql;
+use super::{emit, separator_optional, subquery, values, values_rows, write_iter, Group, Token};
+use crate::format_num::format_percent;
+use crate::{ArgumentBuffer, ArgumentError, Record, SqlError, SqlExpr, WriteSql};
 
+#[doc(hidden)]
 #[macro_export]
-macro_rules! static_tables {
-    ($table:literal) => {
-        $table
+macro_rules! static_only_keyword {
+    () => {
+        ""
     };
 
-    ($table:literal AS $alias:literal) => {
-        concat!($table, " AS ", $alias)
+    (ONLY) => {
+        "ONLY "
     };
+}
 
-    ($ftable:literal $(AS $falias:literal)?, $($table:literal $(AS $alias:literal)?),* $(,)?) => {
-        concat!($ftable $(, " AS ", $falias)?, $(", ", $table $(, " AS ", $alias)?),*)
+#[macro_export]
+macro_rules! static_tables {
+    ($($fonly:ident)? $ftable:literal $(AS $falias:literal)? $(, $($only:ident)? $table:literal $(AS $alias:literal)?)* $(,)?) => {
+        concat!(
+            $crate::static_only_keyword!($($fonly)?), $ftable $(, " AS ", $falias)?
+            $(, ", ", $crate::static_only_keyword!($($only)?), $table $(, " AS ", $alias)?)*
+        )
     };
 }
 
 #[macro_export]
 macro_rules! static_from_tables {
-    (FROM $table:literal) => {
-        concat!("FROM ", $table)
+    (FROM $($fonly:ident)? $ftable:literal $(AS $falias:literal)? $(, $($only:ident)? $table:literal $(AS $alias:literal)?)* $(,)?) => {
+        concat!(
+            "FROM ", $crate::static_only_keyword!($($fonly)?), $ftable $(, " AS ", $falias)?
+            $(, ", ", $crate::static_only_keyword!($($only)?), $table $(, " AS ", $alias)?)*
+        )
     };
+}
 
-    (FROM $table:literal AS $alias:literal) => {
-        concat!("FROM ", $table, " AS ", $alias)
-    };
+/// Name of the table from a `FROM` item, without the `ONLY` keyword and alias.
+pub(crate) fn table_name(item: &str) -> &str {
+    let item = item.trim_start();
+    let item = item.strip_prefix("ONLY ").unwrap_or(item);
+    item.split_whitespace().next().unwrap_or(item)
+}
 
-    (FROM $ftable:literal $(AS $falias:literal)?, $($table:literal $(AS $alias:literal)?),* $(,)?) => {
-        concat!("FROM ", $ftable $(, " AS ", $falias)?, $(", ", $table $(, " AS ", $alias)?),*)
-    };
+/// Writes a static `FROM` clause fragment, such as one built with
+/// [static_from_tables], recording its tables.
+///
+/// The tables are taken from each comma separated item of the fragment,
+/// without the `ONLY` keyword and the alias.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, static_from_tables};
+/// # use sqlstr::expr::{select, column, static_from};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, "*");
+/// static_from(&mut sql, static_from_tables!(FROM "user" AS "u", ONLY "post"));
+///
+/// assert_eq!(sql.as_command(), "SELECT * FROM user AS u, ONLY post");
+/// ```
+pub fn static_from<Sql, Arg>(sql: &mut Sql, fragment: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    separator_optional(sql);
+    sql.push_cmd(fragment);
+
+    let items = fragment.trim_start();
+    let items = items.strip_prefix("FROM").unwrap_or(items);
+    for item in items.split(',') {
+        sql.record(Record::Table(table_name(item)));
+    }
 }
 
 pub fn from_tables<'t, Sql, Arg, I>(sql: &mut Sql, tables: I)
@@ -36,17 +78,17 @@ where
     Sql: WriteSql<Arg>,
     I: IntoIterator<Item = &'t str>,
 {
-    separator_optional(sql);
-    sql.push_cmd("FROM");
+    emit(sql, Token::Keyword("FROM"));
 
     let mut tbls = tables.into_iter();
     if let Some(tbl) = tbls.next() {
-        sql.push_cmd(" ");
-        sql.push_cmd(tbl);
+        emit(sql, Token::Ident(tbl));
+        sql.record(Record::Table(table_name(tbl)));
     }
     for tbl in tbls {
-        sql.push_cmd(", ");
-        sql.push_cmd(tbl);
+        emit(sql, Token::Comma);
+        emit(sql, Token::Raw(tbl));
+        sql.record(Record::Table(table_name(tbl)));
     }
 }
 
@@ -54,19 +96,639 @@ pub fn from_table<Sql, Arg>(sql: &mut Sql, table: &str)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
+    emit(sql, Token::Keyword("FROM"));
+    emit(sql, Token::Ident(table));
+    sql.record(Record::Table(table_name(table)));
+}
+
+/// Writes a `FROM ONLY <table>` clause, excluding the rows of the
+/// inheriting (or partition) tables.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{from_only, from_as};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// from_only(&mut sql, "measurement");
+/// from_as(&mut sql, "city", "c");
+///
+/// assert_eq!(sql.as_command(), "FROM ONLY measurement, city AS c");
+/// ```
+pub fn from_only<Sql, Arg>(sql: &mut Sql, table: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Keyword("FROM ONLY"));
+    emit(sql, Token::Ident(table));
+    sql.record(Record::Table(table_name(table)));
+}
+
+/// Writes a `FROM ONLY <table> AS <alias>` clause.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::from_only_as;
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// from_only_as(&mut sql, "measurement", "m");
+///
+/// assert_eq!(sql.as_command(), "FROM ONLY measurement AS m");
+/// ```
+pub fn from_only_as<Sql, Arg>(sql: &mut Sql, table: &str, alias: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    from_only(sql, table);
+    emit(sql, Token::Keyword("AS"));
+    emit(sql, Token::Ident(alias));
+}
+
+/// Writes an additional `, ONLY <table>` item into a `FROM` clause.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{from_table, also_only};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// from_table(&mut sql, "city");
+/// also_only(&mut sql, "measurement");
+///
+/// assert_eq!(sql.as_command(), "FROM city, ONLY measurement");
+/// ```
+pub fn also_only<Sql, Arg>(sql: &mut Sql, table: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Comma);
+    emit(sql, Token::Keyword("ONLY"));
+    emit(sql, Token::Ident(table));
+    sql.record(Record::Table(table_name(table)));
+}
+
+/// Writes a `FROM <table> AS <alias>` clause.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{from_table_as, from_as};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// from_table_as(&mut sql, "user", "u");
+/// from_as(&mut sql, "order", "o");
+///
+/// assert_eq!(sql.as_command(), "FROM user AS u, order AS o");
+/// ```
+pub fn from_table_as<Sql, Arg>(sql: &mut Sql, table: &str, alias: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    from_table(sql, table);
+    emit(sql, Token::Keyword("AS"));
+    emit(sql, Token::Ident(alias));
+}
+
+/// Writes an additional `, <table> AS <alias>` item into a `FROM` clause.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{from_table, from_as};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// from_table(&mut sql, "user");
+/// from_as(&mut sql, "order", "o");
+///
+/// assert_eq!(sql.as_command(), "FROM user, order AS o");
+/// ```
+pub fn from_as<Sql, Arg>(sql: &mut Sql, table: &str, alias: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Comma);
+    emit(sql, Token::Ident(table));
+    sql.record(Record::Table(table_name(table)));
+    emit(sql, Token::Keyword("AS"));
+    emit(sql, Token::Ident(alias));
+}
+
+/// Writes a `FROM <table> AS <alias>(<column>, ...)` clause, aliasing the
+/// table columns.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::from_table_as_columns;
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// from_table_as_columns(&mut sql, "user", "u", ["uid", "uname"])?;
+///
+/// assert_eq!(sql.as_command(), "FROM user AS u(uid, uname)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_table_as_columns<'c, Sql, Arg, I>(
+    sql: &mut Sql,
+    table: &str,
+    alias: &str,
+    columns: I,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+    I: IntoIterator<Item = &'c str>,
+{
+    let mut columns = columns.into_iter().peekable();
+    if columns.peek().is_none() {
+        return Err(SqlError::EmptyList);
+    }
+    from_table(sql, table);
+    alias_column_list(sql, alias, columns);
+    Ok(())
+}
+
+/// Writes a `AS <alias>(<column>, ...)` table alias with column aliases, used
+/// after a table, a function call or a `VALUES` list.
+///
+/// An empty list of columns returns an [EmptyList](SqlError::EmptyList)
+/// error, without writing anything.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::table_alias_columns;
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// sql.push_cmd("FROM user");
+/// table_alias_columns(&mut sql, "u", ["uid", "uname"])?;
+///
+/// assert_eq!(sql.as_command(), "FROM user AS u(uid, uname)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn table_alias_columns<'c, Sql, Arg, I>(
+    sql: &mut Sql,
+    alias: &str,
+    columns: I,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+    I: IntoIterator<Item = &'c str>,
+{
+    let mut columns = columns.into_iter().peekable();
+    if columns.peek().is_none() {
+        return Err(SqlError::EmptyList);
+    }
+    alias_column_list(sql, alias, columns);
+    Ok(())
+}
+
+/// Writes the alias of [table_alias_columns], of columns already checked not
+/// to be empty.
+pub(crate) fn alias_column_list<'c, Sql, Arg, I>(sql: &mut Sql, alias: &str, columns: I)
+where
+    Sql: WriteSql<Arg>,
+    I: IntoIterator<Item = &'c str>,
+{
+    emit(sql, Token::Keyword("AS"));
+    emit(sql, Token::Ident(alias));
+    emit(sql, Token::Raw("("));
+
+    let mut cols = columns.into_iter();
+    if let Some(col) = cols.next() {
+        sql.push_cmd(col);
+    }
+    for col in cols {
+        emit(sql, Token::Comma);
+        sql.push_cmd(col);
+    }
+
+    emit(sql, Token::Close);
+}
+
+/// Writes a `FROM <function>($1, ...)` clause calling a set-returning
+/// function, with each argument bound as a value.
+///
+/// Use [with_ordinality] and [table_alias_columns] to complete the item.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{select, column, from_function, table_alias_columns};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, "*");
+/// from_function(&mut sql, "generate_series", [1, 10])?;
+/// table_alias_columns(&mut sql, "g", ["n"])?;
+///
+/// assert_eq!(sql.as_command(), "SELECT * FROM generate_series($1, $2) AS g(n)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_function<Sql, Arg, I, T>(
+    sql: &mut Sql,
+    function: &str,
+    args: I,
+) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+    I: IntoIterator<Item = T>,
+{
+    emit(sql, Token::Keyword("FROM"));
+    emit(sql, Token::Ident(function));
+    emit(sql, Token::Raw("("));
+    write_iter(sql, args)?;
+    emit(sql, Token::Close);
+    Ok(())
+}
+
+/// Writes a `WITH ORDINALITY` modifier after a function in the `FROM` clause.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{from_function, with_ordinality, table_alias_columns};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// from_function(&mut sql, "unnest", ["a"])?;
+/// with_ordinality(&mut sql);
+/// table_alias_columns(&mut sql, "t", ["elem", "n"])?;
+///
+/// assert_eq!(sql.as_command(), "FROM unnest($1) WITH ORDINALITY AS t(elem, n)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn with_ordinality<Sql, Arg>(sql: &mut Sql)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Keyword("WITH ORDINALITY"));
+}
 
-    sql.push_cmd("FROM ");
-    sql.push_cmd(table);
+/// Sampling method of a `TABLESAMPLE` clause.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SampleMethod {
+    /// Samples each row with the given probability
+    Bernoulli,
+    /// Samples each table block with the given probability
+    System,
+}
+
+impl SampleMethod {
+    pub const fn as_str(&self) -> &'static str {
+        match *self {
+            Self::Bernoulli => "BERNOULLI",
+            Self::System => "SYSTEM",
+        }
+    }
+}
+
+/// Writes a `TABLESAMPLE <method> (<percent>)` clause after a table in the
+/// `FROM` clause.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, SqlExpr};
+/// # use sqlstr::expr::{from_table, tablesample, SampleMethod};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// from_table(&mut sql, "event");
+/// tablesample(&mut sql, SampleMethod::Bernoulli, SqlExpr::Value(2.5))?;
+///
+/// assert_eq!(sql.as_command(), "FROM event TABLESAMPLE BERNOULLI ($1)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn tablesample<Sql, Arg, T>(
+    sql: &mut Sql,
+    method: SampleMethod,
+    percent: SqlExpr<'_, T>,
+) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    emit(sql, Token::Keyword("TABLESAMPLE"));
+    emit(sql, Token::Ident(method.as_str()));
+    emit(sql, Token::Open);
+    sql.push_expr(percent)?;
+    emit(sql, Token::Close);
+    Ok(())
+}
+
+/// Writes a `TABLESAMPLE <method> (<percent>)` clause with the percentage as a
+/// literal.
+///
+/// The percentage is clamped into `0..=100` and written with at most 4
+/// decimal digits.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, SqlExpr};
+/// # use sqlstr::expr::{from_table, tablesample_literal, SampleMethod};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// from_table(&mut sql, "event");
+/// tablesample_literal(&mut sql, SampleMethod::System, 10.0);
+///
+/// assert_eq!(sql.as_command(), "FROM event TABLESAMPLE SYSTEM (10)");
+/// ```
+pub fn tablesample_literal<Sql, Arg>(sql: &mut Sql, method: SampleMethod, percent: f32)
+where
+    Sql: WriteSql<Arg>,
+{
+    let mut buf = [0; 10];
+    emit(sql, Token::Keyword("TABLESAMPLE"));
+    emit(sql, Token::Ident(method.as_str()));
+    emit(sql, Token::Open);
+    sql.push_cmd(format_percent(percent, &mut buf));
+    emit(sql, Token::Close);
+}
+
+/// Writes a `REPEATABLE (<seed>)` suffix of a `TABLESAMPLE` clause.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, SqlExpr};
+/// # use sqlstr::expr::{from_table, tablesample_literal, repeatable, SampleMethod};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// from_table(&mut sql, "event");
+/// tablesample_literal(&mut sql, SampleMethod::System, 0.5);
+/// repeatable(&mut sql, SqlExpr::Value(42))?;
+///
+/// assert_eq!(sql.as_command(), "FROM event TABLESAMPLE SYSTEM (0.5) REPEATABLE ($1)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn repeatable<Sql, Arg, T>(sql: &mut Sql, seed: SqlExpr<'_, T>) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    emit(sql, Token::Keyword("REPEATABLE"));
+    emit(sql, Token::Open);
+    sql.push_expr(seed)?;
+    emit(sql, Token::Close);
+    Ok(())
+}
+
+/// Writes a `FROM (<subquery>) AS <alias>` clause with a derived table.
+///
+/// The subquery shares the argument buffer with the outer query.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, SqlExpr, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{from_subquery, select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, "t.id");
+/// from_subquery(&mut sql, "t", |sub| {
+///     select(sub);
+///     column(sub, "id");
+///     from_table(sub, "user");
+///     filter_where(sub);
+///     lhs_binary_rhs(sub, sqlexpr::<u8>("age"), Cmp::Gt, sqlvalue(18))
+/// })?;
+///
+/// assert_eq!(sql.as_command(), "SELECT t.id FROM (SELECT id FROM user WHERE age > $1) AS t");
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_subquery<Sql, Arg, F, E>(sql: &mut Sql, alias: &str, build: F) -> Result<(), E>
+where
+    Sql: WriteSql<Arg>,
+    F: FnOnce(&mut Group<'_, Sql, Arg>) -> Result<(), E>,
+{
+    emit(sql, Token::Keyword("FROM"));
+
+    subquery(sql, build)?;
+
+    emit(sql, Token::Keyword("AS"));
+    emit(sql, Token::Ident(alias));
+    Ok(())
+}
+
+/// Writes an additional `, (<subquery>) AS <alias>` derived table item into a
+/// `FROM` clause.
+///
+/// The subquery shares the argument buffer with the outer query.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{also_subquery, select, column, from_table_as, filter_where, lhs_binary_rhs, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, "u.name, s.total");
+/// from_table_as(&mut sql, "user", "u");
+/// also_subquery(&mut sql, "s", |sub| {
+///     select(sub);
+///     column(sub, "sum(total) AS total");
+///     from_table_as(sub, "order", "o");
+///     filter_where(sub);
+///     lhs_binary_rhs(sub, sqlexpr::<u32>("o.year"), Cmp::Eq, sqlvalue(2024))
+/// })?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT u.name, s.total FROM user AS u, \
+///      (SELECT sum(total) AS total FROM order AS o WHERE o.year = $1) AS s"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn also_subquery<Sql, Arg, F, E>(sql: &mut Sql, alias: &str, build: F) -> Result<(), E>
+where
+    Sql: WriteSql<Arg>,
+    F: FnOnce(&mut Group<'_, Sql, Arg>) -> Result<(), E>,
+{
+    emit(sql, Token::Comma);
+
+    subquery(sql, build)?;
+
+    emit(sql, Token::Keyword("AS"));
+    emit(sql, Token::Ident(alias));
+    Ok(())
+}
+
+/// Writes an additional `, <table>` item into a `FROM` clause.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{from_table, also_table};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// from_table(&mut sql, "user");
+/// also_table(&mut sql, "order");
+///
+/// assert_eq!(sql.as_command(), "FROM user, order");
+/// ```
+pub fn also_table<Sql, Arg>(sql: &mut Sql, table: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Comma);
+    emit(sql, Token::Ident(table));
+    sql.record(Record::Table(table_name(table)));
+}
+
+/// Writes a `FROM (VALUES (<value>, ...), ...) AS <alias>(<column>, ...)`
+/// derived table, pushing every value as an argument row by row.
+///
+/// Every row must have one value per column, otherwise a
+/// [RowLengthMismatch](SqlError::RowLengthMismatch) error is returned. An
+/// empty list of rows or of columns returns an
+/// [EmptyList](SqlError::EmptyList) error.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{select, column, from_values};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, "t.a");
+/// from_values(&mut sql, "t", &["a", "b"], [[1, 2], [3, 4]])?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT t.a FROM (VALUES ($1, $2), ($3, $4)) AS t(a, b)"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_values<Sql, Arg, R, I, T>(
+    sql: &mut Sql,
+    alias: &str,
+    columns: &[&str],
+    rows: R,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+    R: IntoIterator<Item = I>,
+    I: IntoIterator<Item = T>,
+{
+    if columns.is_empty() {
+        return Err(SqlError::EmptyList);
+    }
+
+    emit(sql, Token::Keyword("FROM"));
+
+    subquery(sql, |sql| {
+        values(sql);
+        values_rows(sql, columns.len(), rows)
+    })?;
+
+    alias_column_list(sql, alias, columns.iter().copied());
+    Ok(())
+}
+
+/// Intervals accepted as the step of a [date_series].
+pub const DATE_SERIES_STEPS: &[&str] = &[
+    "1 second", "1 minute", "1 hour", "1 day", "1 week", "1 month", "1 year",
+];
+
+/// Writes a `FROM generate_series($1, $2, interval '<step>') AS g(d)` calendar
+/// spine, binding the `from` and `to` endpoints as values.
+///
+/// The `step` must be one of the [DATE_SERIES_STEPS], otherwise an
+/// [InvalidInterval](SqlError::InvalidInterval) error is returned without
+/// writing anything. Each generated timestamp is available as `g.d`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{select, column, date_series};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, "d::date");
+/// date_series(&mut sql, "2024-01-01", "2024-01-31", "1 day")?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT d::date FROM generate_series($1, $2, interval '1 day') AS g(d)"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn date_series<Sql, Arg, T>(
+    sql: &mut Sql,
+    from: T,
+    to: T,
+    step: &str,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    if !DATE_SERIES_STEPS.contains(&step) {
+        return Err(SqlError::InvalidInterval);
+    }
+
+    emit(sql, Token::Keyword("FROM"));
+    emit(sql, Token::Ident("generate_series"));
+    emit(sql, Token::Raw("("));
+    sql.push_value(from)?;
+    emit(sql, Token::Comma);
+    sql.push_value(to)?;
+    emit(sql, Token::Comma);
+    emit(sql, Token::Keyword("interval"));
+    emit(sql, Token::Quoted(step));
+    emit(sql, Token::Close);
+    emit(sql, Token::Keyword("AS"));
+    emit(sql, Token::Ident("g(d)"));
+    Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use super::from_tables;
+    use super::{
+        also_only, also_subquery, also_table, date_series, from_as, from_function, from_only,
+        from_only_as, from_subquery, from_table_as, from_table_as_columns, from_tables,
+        from_values, repeatable, table_alias_columns, tablesample, tablesample_literal,
+        with_ordinality, SampleMethod,
+    };
     use crate::{
-        expr::{select, separator},
+        expr::{
+            column, filter_where, from_table, group_by, lhs_binary_rhs, select, separator, Cmp,
+            LogicBi,
+        },
+        sqlexpr, sqlvalue,
         test::TestArgs,
-        SqlCommand,
+        SqlCommand, SqlError,
     };
 
     #[test]
@@ -96,6 +758,238 @@ mod test {
         assert_eq!(sql.as_command(), "FROM user, access_history");
     }
 
+    #[test]
+    fn from_tables_aliased() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "u.name");
+        from_table_as(&mut sql, "public.user", "u");
+        from_as(&mut sql, "order", "o");
+        from_as(&mut sql, "product", "p");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT u.name FROM public.user AS u, order AS o, product AS p"
+        );
+    }
+
+    #[test]
+    fn from_only_mixed_tables() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "m.value");
+        from_only_as(&mut sql, "measurement", "m");
+        from_as(&mut sql, "city", "c");
+        also_only(&mut sql, "sensor");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT m.value FROM ONLY measurement AS m, city AS c, ONLY sensor"
+        );
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "*");
+        from_only(&mut sql, "measurement");
+
+        assert_eq!(sql.as_command(), "SELECT * FROM ONLY measurement");
+    }
+
+    #[test]
+    fn from_values_join() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "u.name, t.rank");
+        from_values(&mut sql, "t", &["id", "rank"], [[7, 1], [3, 2], [9, 3]]).unwrap();
+        sql.push_cmd(" JOIN user AS u ON u.id = t.id");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT u.name, t.rank FROM (VALUES ($1, $2), ($3, $4), ($5, $6)) AS t(id, rank) JOIN user AS u ON u.id = t.id"
+        );
+        assert_eq!(sql.arguments.as_str(), "7;1;3;2;9;3;");
+    }
+
+    #[test]
+    fn from_values_row_length_mismatch() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        let rows: [&[u8]; 2] = [&[1, 2], &[3]];
+        let err = from_values(&mut sql, "t", &["a", "b"], rows).unwrap_err();
+
+        assert_eq!(
+            err,
+            SqlError::RowLengthMismatch {
+                expected: 2,
+                found: 1
+            }
+        );
+    }
+
+    #[test]
+    fn from_values_empty_rows() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        let rows: [[u8; 2]; 0] = [];
+        let err = from_values(&mut sql, "t", &["a", "b"], rows).unwrap_err();
+
+        assert_eq!(err, SqlError::EmptyList);
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        let err = from_values(&mut sql, "t", &[], [[0u8; 0]]).unwrap_err();
+
+        assert_eq!(err, SqlError::EmptyList);
+        assert_eq!(sql.as_command(), "");
+    }
+
+    #[test]
+    fn from_table_aliased_columns() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        from_table_as_columns(&mut sql, "user", "u", ["a", "b"]).unwrap();
+        assert_eq!(sql.as_command(), "FROM user AS u(a, b)");
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        assert_eq!(
+            from_table_as_columns(&mut sql, "user", "u", []),
+            Err(SqlError::EmptyList)
+        );
+        assert_eq!(
+            table_alias_columns(&mut sql, "u", []),
+            Err(SqlError::EmptyList)
+        );
+        assert_eq!(sql.as_command(), "");
+    }
+
+    #[test]
+    fn from_function_placeholders() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        separator(&mut sql);
+        sql.push_value(0).unwrap();
+        from_function(&mut sql, "generate_series", [1, 100, 5]).unwrap();
+        with_ordinality(&mut sql);
+        table_alias_columns(&mut sql, "g", ["n", "i"]).unwrap();
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("g.n"), Cmp::Gt, sqlvalue(50)).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT $1 FROM generate_series($2, $3, $4) WITH ORDINALITY AS g(n, i) WHERE g.n > $5"
+        );
+        assert_eq!(sql.arguments.as_str(), "0;1;100;5;50;");
+    }
+
+    #[test]
+    fn from_function_no_arguments() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        from_function(&mut sql, "now", [] as [u8; 0]).unwrap();
+
+        assert_eq!(sql.as_command(), "FROM now()");
+    }
+
+    #[test]
+    fn from_tablesample() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "*");
+        from_table_as(&mut sql, "event", "e");
+        tablesample(&mut sql, SampleMethod::System, sqlvalue(10)).unwrap();
+        repeatable(&mut sql, sqlexpr::<u8>("7")).unwrap();
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("e.kind"), Cmp::Eq, sqlvalue(3)).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM event AS e TABLESAMPLE SYSTEM ($1) REPEATABLE (7) WHERE e.kind = $2"
+        );
+        assert_eq!(sql.arguments.as_str(), "10;3;");
+    }
+
+    #[test]
+    fn from_tablesample_literal() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        from_table(&mut sql, "event");
+        tablesample_literal(&mut sql, SampleMethod::Bernoulli, 12.25);
+        repeatable(&mut sql, sqlvalue(99)).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "FROM event TABLESAMPLE BERNOULLI (12.25) REPEATABLE ($1)"
+        );
+        assert_eq!(sql.arguments.as_str(), "99;");
+    }
+
+    #[test]
+    fn from_subquery_placeholders() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        separator(&mut sql);
+        sql.push_value("tag").unwrap();
+        from_subquery(&mut sql, "recent", |sub| {
+            select(sub);
+            column(sub, "id");
+            from_table(sub, "access_history");
+            filter_where(sub);
+            lhs_binary_rhs(sub, sqlexpr::<u32>("created"), Cmp::Gt, sqlvalue(2040))
+        })
+        .unwrap();
+        filter_where(&mut sql);
+        lhs_binary_rhs(
+            &mut sql,
+            sqlexpr::<u32>("recent.id"),
+            Cmp::Lt,
+            sqlvalue(100),
+        )
+        .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT $1 FROM (SELECT id FROM access_history WHERE created > $2) AS recent WHERE recent.id < $3"
+        );
+        assert_eq!(sql.arguments.as_str(), "tag;2040;100;");
+    }
+
+    #[test]
+    fn from_nested_subquery() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "*");
+        from_subquery(&mut sql, "a", |sub| {
+            select(sub);
+            column(sub, "*");
+            from_subquery(sub, "b", |sub| {
+                select(sub);
+                column(sub, "id");
+                from_table(sub, "user");
+                filter_where(sub);
+                lhs_binary_rhs(sub, sqlexpr::<u32>("id"), Cmp::Gt, sqlvalue(1))
+            })?;
+            filter_where(sub);
+            lhs_binary_rhs(sub, sqlexpr::<u32>("b.id"), Cmp::Lt, sqlvalue(9))?;
+            crate::expr::continue_condition(sub, LogicBi::And);
+            lhs_binary_rhs(sub, sqlexpr::<u32>("b.id"), Cmp::Neq, sqlvalue(5))
+        })
+        .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM (SELECT * FROM (SELECT id FROM user WHERE id > $1) AS b WHERE b.id < $2 AND b.id <> $3) AS a"
+        );
+        assert_eq!(sql.arguments.as_str(), "1;9;5;");
+    }
+
     #[test]
     fn static_tables_test() {
         assert_eq!(static_tables!("user"), "user");
@@ -114,6 +1008,11 @@ mod test {
             static_tables!("user", "customer" AS "c", "organization", "product" AS "p",),
             "user, customer AS c, organization, product AS p"
         );
+        assert_eq!(static_tables!(ONLY "user"), "ONLY user");
+        assert_eq!(
+            static_tables!(ONLY "user" AS "u", "customer", ONLY "product" AS "p"),
+            "ONLY user AS u, customer, ONLY product AS p"
+        );
     }
 
     #[test]
@@ -140,6 +1039,14 @@ mod test {
             static_from_tables!(FROM "user", "customer" AS "c", "organization", "product" AS "p",),
             "FROM user, customer AS c, organization, product AS p"
         );
+        assert_eq!(
+            static_from_tables!(FROM ONLY "user" AS "u", "customer", ONLY "product",),
+            "FROM ONLY user AS u, customer, ONLY product"
+        );
+        assert_eq!(
+            static_from_tables!(FROM "user", ONLY "customer" AS "c"),
+            "FROM user, ONLY customer AS c"
+        );
     }
 
     #[test]
@@ -155,4 +1062,101 @@ mod test {
         assert_eq!(sql.command, "SELECT name FROM user, product");
         assert_eq!(sql.arguments.as_str(), "");
     }
+
+    #[test]
+    fn date_series_day_left_join() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "g.d::date, count(o.id)");
+        date_series(&mut sql, "2024-01-01", "2024-01-31", "1 day").unwrap();
+        sql.push_cmd(" LEFT JOIN order AS o ON o.created_at::date = g.d::date");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT g.d::date, count(o.id) FROM generate_series($1, $2, interval '1 day') AS g(d) LEFT JOIN order AS o ON o.created_at::date = g.d::date"
+        );
+        assert_eq!(sql.arguments.as_str(), "2024-01-01;2024-01-31;");
+    }
+
+    #[test]
+    fn date_series_hour() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        date_series(&mut sql, 1, 2, "1 hour").unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "FROM generate_series($1, $2, interval '1 hour') AS g(d)"
+        );
+        assert_eq!(sql.arguments.as_str(), "1;2;");
+    }
+
+    #[test]
+    fn date_series_invalid_step() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        let err = date_series(&mut sql, 1, 2, "1 day'); DROP TABLE user; --").unwrap_err();
+
+        assert_eq!(err, SqlError::InvalidInterval);
+        assert_eq!(sql.as_command(), "");
+        assert_eq!(sql.arguments.as_str(), "");
+    }
+
+    #[test]
+    fn table_then_subquery() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "u.name, s.total");
+        from_table_as(&mut sql, "user", "u");
+        also_subquery(&mut sql, "s", |sub| {
+            select(sub);
+            column(sub, "user_id, sum(total) AS total");
+            from_table(sub, "order");
+            filter_where(sub);
+            lhs_binary_rhs(sub, sqlexpr::<u32>("year"), Cmp::Eq, sqlvalue(2024))?;
+            group_by(sub, ["user_id"]);
+            Ok::<_, core::fmt::Error>(())
+        })
+        .unwrap();
+        filter_where(&mut sql);
+        sql.push_cmd(" s.user_id = u.id AND");
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("s.total"), Cmp::Gt, sqlvalue(100)).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT u.name, s.total FROM user AS u, \
+             (SELECT user_id, sum(total) AS total FROM order WHERE year = $1 GROUP BY user_id) AS s \
+             WHERE s.user_id = u.id AND s.total > $2"
+        );
+        assert_eq!(sql.arguments.as_str(), "2024;100;");
+    }
+
+    #[test]
+    fn subquery_then_table() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "*");
+        from_subquery(&mut sql, "a", |sub| {
+            select(sub);
+            column(sub, "id");
+            from_table(sub, "account");
+            filter_where(sub);
+            lhs_binary_rhs(sub, sqlexpr::<bool>("active"), Cmp::Eq, sqlvalue(true))
+        })
+        .unwrap();
+        also_table(&mut sql, "plan");
+        from_as(&mut sql, "region", "r");
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<&str>("r.code"), Cmp::Eq, sqlvalue("eu")).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM (SELECT id FROM account WHERE active = $1) AS a, plan, region AS r \
+             WHERE r.code = $2"
+        );
+        assert_eq!(sql.arguments.as_str(), "true;eu;");
+    }
 }
@@ -0,0 +1,541 @@
+use core::marker::PhantomData;
+
+use super::{
+    fetch_first, filter_having, group_by, limit, offset, order_by, row_lock, OrderByNulls,
+    OrderByOrd, RowLockConcurrency, RowLockStrength,
+};
+use crate::{ArgumentBuffer, WriteSql};
+
+/// Clauses of the tail of a `SELECT` command, the states of a [Tail].
+pub mod tail_state {
+    pub struct Start;
+    pub struct GroupBy;
+    pub struct Having;
+    pub struct OrderBy;
+    pub struct Limit;
+    pub struct Offset;
+    /// `FETCH FIRST` clause, the standard sql counterpart of `LIMIT`, written
+    /// instead of it.
+    pub struct Fetch;
+    pub struct Locking;
+    /// Start of the tail of a `VALUES` command, without `GROUP BY` and
+    /// `HAVING`.
+    pub struct Values;
+    /// Start of the tail of a finished command, without `GROUP BY` and
+    /// `HAVING`.
+    pub struct Continued;
+}
+
+use tail_state::*;
+
+/// Implemented by the [tail states](tail_state) written before `S`.
+pub trait Precedes<S>: private::Sealed {}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Implements [Precedes] for every state written before each of the states,
+/// listed in clause order.
+macro_rules! tail_states {
+    () => {};
+    ($first:ident $(, $rest:ident)*) => {
+        impl private::Sealed for $first {}
+        $(impl Precedes<$rest> for $first {})*
+        tail_states!($($rest),*);
+    };
+}
+
+tail_states!(Start, GroupBy, Having, OrderBy, Limit, Offset, Locking);
+
+impl private::Sealed for Fetch {}
+impl Precedes<Fetch> for Start {}
+impl Precedes<Fetch> for GroupBy {}
+impl Precedes<Fetch> for Having {}
+impl Precedes<Fetch> for OrderBy {}
+impl Precedes<Fetch> for Offset {}
+impl Precedes<Locking> for Fetch {}
+impl Precedes<Locking> for Locking {}
+
+impl private::Sealed for Values {}
+impl Precedes<OrderBy> for Values {}
+impl Precedes<Limit> for Values {}
+impl Precedes<Offset> for Values {}
+impl Precedes<Fetch> for Values {}
+
+impl private::Sealed for Continued {}
+impl Precedes<OrderBy> for Continued {}
+impl Precedes<Limit> for Continued {}
+impl Precedes<Offset> for Continued {}
+impl Precedes<Fetch> for Continued {}
+impl Precedes<Locking> for Continued {}
+
+/// Tail of a `SELECT` command, written after the `WHERE` clause.
+///
+/// The clauses are written in the order
+/// `GROUP BY`, `HAVING`, `ORDER BY`, `LIMIT`, `OFFSET`, `FETCH FIRST` and row
+/// locking. Each clause can be skipped, but a clause can not be written after
+/// a later one, which is checked at compile time. `FETCH FIRST` replaces
+/// `LIMIT`, so both can not be written. Row locking is the only clause that
+/// can be repeated, locking different tables with different strengths.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, column, from_table, Tail, OrderByOrd, OrderByNulls};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, "team_id, count(*)");
+/// from_table(&mut sql, "member");
+/// Tail::new(&mut sql)
+///     .group_by(["team_id"])
+///     .order_by([("team_id", OrderByOrd::Asc, OrderByNulls::Default)])
+///     .limit(10)?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT team_id, count(*) FROM member GROUP BY team_id ORDER BY team_id ASC LIMIT $1"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// An `ORDER BY` after the `LIMIT` does not compile:
+///
+/// ```compile_fail
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{Tail, OrderByOrd, OrderByNulls};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// Tail::new(&mut sql)
+///     .limit(10)?
+///     .order_by([("id", OrderByOrd::Asc, OrderByNulls::Default)]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Neither does a `GROUP BY` after the `HAVING`:
+///
+/// ```compile_fail
+/// # use sqlstr::{SqlCommand, Void, WriteSql};
+/// # use sqlstr::expr::Tail;
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// Tail::new(&mut sql)
+///     .having(|sql| Ok::<_, Infallible>(sql.push_cmd(" count(*) > 1")))?
+///     .group_by(["team_id"]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A `FETCH FIRST` can not follow the `LIMIT` it replaces:
+///
+/// ```compile_fail
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::Tail;
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// Tail::new(&mut sql).limit(10)?.fetch_first(10)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Nor a clause written twice:
+///
+/// ```compile_fail
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::Tail;
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// Tail::new(&mut sql).offset(10)?.offset(20)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Tail<'cmd, Sql: WriteSql<Arg>, Arg, S> {
+    sql: &'cmd mut Sql,
+    _state: PhantomData<(Arg, S)>,
+}
+
+impl<'cmd, Sql, Arg> Tail<'cmd, Sql, Arg, Start>
+where
+    Sql: WriteSql<Arg>,
+{
+    /// Starts the tail of the command.
+    pub fn new(sql: &'cmd mut Sql) -> Self {
+        Self {
+            sql,
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<'cmd, Sql, Arg> Tail<'cmd, Sql, Arg, Values>
+where
+    Sql: WriteSql<Arg>,
+{
+    /// Starts the tail of a `VALUES` command, after the list of rows.
+    ///
+    /// Only the `ORDER BY`, `LIMIT` and `OFFSET` clauses can follow the rows,
+    /// ordered by the column position (e.g. `ORDER BY 1`). The finished
+    /// command can be combined as any query, with
+    /// [union](crate::SqlCommand::union) or as the query of a
+    /// [CTE](super::With::cte).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, Void};
+    /// # use sqlstr::expr::{values, values_rows, Tail, OrderByOrd, OrderByNulls};
+    /// # use core::convert::Infallible;
+    /// # fn main() -> Result<(), SqlError<Infallible>> {
+    /// let mut sql: SqlCommand<Void> = SqlCommand::default();
+    /// values(&mut sql);
+    /// values_rows(&mut sql, 1, [[1], [2], [3]])?;
+    /// Tail::values(&mut sql)
+    ///     .order_by([("1", OrderByOrd::Desc, OrderByNulls::Default)])
+    ///     .limit(2)?;
+    ///
+    /// assert_eq!(
+    ///     sql.as_command(),
+    ///     "VALUES ($1), ($2), ($3) ORDER BY 1 DESC LIMIT $4"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// A `GROUP BY` does not follow the rows:
+    ///
+    /// ```compile_fail
+    /// # use sqlstr::{SqlCommand, Void};
+    /// # use sqlstr::expr::Tail;
+    /// let mut sql: SqlCommand<Void> = SqlCommand::default();
+    /// Tail::values(&mut sql).group_by(["1"]);
+    /// ```
+    pub fn values(sql: &'cmd mut Sql) -> Self {
+        Self {
+            sql,
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<'cmd, Sql, Arg> Tail<'cmd, Sql, Arg, Continued>
+where
+    Sql: WriteSql<Arg>,
+{
+    /// Continues a finished command, such as one built elsewhere, with the
+    /// `ORDER BY`, `LIMIT`, `OFFSET` and row locking clauses, see
+    /// [continue_building](crate::SqlCommand::continue_building).
+    pub fn continued(sql: &'cmd mut Sql) -> Self {
+        Self {
+            sql,
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<'cmd, Sql, Arg, S> Tail<'cmd, Sql, Arg, S>
+where
+    Sql: WriteSql<Arg>,
+{
+    fn next<N>(self) -> Tail<'cmd, Sql, Arg, N> {
+        Tail {
+            sql: self.sql,
+            _state: PhantomData,
+        }
+    }
+
+    /// Writes a `GROUP BY <column>, ...` clause, see [group_by].
+    pub fn group_by<'col, I>(self, columns: I) -> Tail<'cmd, Sql, Arg, GroupBy>
+    where
+        S: Precedes<GroupBy>,
+        I: IntoIterator<Item = &'col str>,
+    {
+        group_by(self.sql, columns);
+        self.next()
+    }
+
+    /// Writes a `HAVING` clause with the condition written by `build`.
+    pub fn having<F, E>(self, build: F) -> Result<Tail<'cmd, Sql, Arg, Having>, E>
+    where
+        S: Precedes<Having>,
+        F: FnOnce(&mut Sql) -> Result<(), E>,
+    {
+        filter_having(self.sql);
+        build(self.sql)?;
+        Ok(self.next())
+    }
+
+    /// Writes a `ORDER BY` clause, see [order_by].
+    pub fn order_by<'col, 'ord_expr, I>(self, order_exprs: I) -> Tail<'cmd, Sql, Arg, OrderBy>
+    where
+        S: Precedes<OrderBy>,
+        I: IntoIterator<Item = (&'col str, OrderByOrd<'ord_expr>, OrderByNulls)>,
+    {
+        order_by(self.sql, order_exprs);
+        self.next()
+    }
+
+    /// Writes a `LIMIT $n` clause, see [limit].
+    pub fn limit(self, count: usize) -> Result<Tail<'cmd, Sql, Arg, Limit>, Arg::Error>
+    where
+        S: Precedes<Limit>,
+        Arg: ArgumentBuffer<usize>,
+    {
+        limit(self.sql, count)?;
+        Ok(self.next())
+    }
+
+    /// Writes a `OFFSET $n` clause, see [offset].
+    pub fn offset(self, start: usize) -> Result<Tail<'cmd, Sql, Arg, Offset>, Arg::Error>
+    where
+        S: Precedes<Offset>,
+        Arg: ArgumentBuffer<usize>,
+    {
+        offset(self.sql, start)?;
+        Ok(self.next())
+    }
+
+    /// Writes a `FETCH FIRST $n ROWS ONLY` clause, see [fetch_first].
+    pub fn fetch_first(self, count: usize) -> Result<Tail<'cmd, Sql, Arg, Fetch>, Arg::Error>
+    where
+        S: Precedes<Fetch>,
+        Arg: ArgumentBuffer<usize>,
+    {
+        fetch_first(self.sql, count)?;
+        Ok(self.next())
+    }
+
+    /// Writes a `FOR <strength> [OF <table>, ...] [NOWAIT | SKIP LOCKED]`
+    /// row locking clause, see [row_lock].
+    pub fn row_lock<'t, I>(
+        self,
+        strength: RowLockStrength,
+        tables: I,
+        concurrency: Option<RowLockConcurrency>,
+    ) -> Tail<'cmd, Sql, Arg, Locking>
+    where
+        S: Precedes<Locking>,
+        I: IntoIterator<Item = &'t str>,
+    {
+        row_lock(self.sql, strength, tables, concurrency);
+        self.next()
+    }
+
+    /// Ends the tail, returning the command.
+    pub fn end(self) -> &'cmd mut Sql {
+        self.sql
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        expr::{
+            column, filter_where, from_table, lhs_binary_rhs, select, values, values_rows, Cmp,
+        },
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+        SqlCommand,
+    };
+
+    type Error = core::fmt::Error;
+
+    fn command() -> SqlCommand<TestArgs> {
+        let mut sql = SqlCommand::default();
+        sql.push_cmd("SELECT * FROM t");
+        sql
+    }
+
+    const ORDER: [(&str, OrderByOrd, OrderByNulls); 1] =
+        [("a", OrderByOrd::Asc, OrderByNulls::Default)];
+
+    fn having(sql: &mut SqlCommand<TestArgs>) -> Result<(), Error> {
+        lhs_binary_rhs(sql, sqlexpr::<u32>("count(*)"), Cmp::Gt, sqlvalue(1))
+    }
+
+    #[test]
+    fn every_clause() {
+        let mut sql = command();
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("b"), Cmp::Eq, sqlvalue(2)).unwrap();
+        Tail::new(&mut sql)
+            .group_by(["a"])
+            .having(having)
+            .unwrap()
+            .order_by(ORDER)
+            .limit(10)
+            .unwrap()
+            .offset(20)
+            .unwrap()
+            .row_lock(RowLockStrength::Update, [], None)
+            .end()
+            .push_cmd(";");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM t WHERE b = $1 GROUP BY a HAVING count(*) > $2 ORDER BY a ASC \
+             LIMIT $3 OFFSET $4 FOR UPDATE;"
+        );
+        assert_eq!(sql.arguments.as_str(), "2;1;10;20;");
+    }
+
+    #[test]
+    fn skipped_clauses() {
+        let mut sql = command();
+        Tail::new(&mut sql).group_by(["a"]).order_by(ORDER);
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM t GROUP BY a ORDER BY a ASC"
+        );
+
+        let mut sql = command();
+        Tail::new(&mut sql)
+            .having(having)
+            .unwrap()
+            .limit(1)
+            .unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM t HAVING count(*) > $1 LIMIT $2"
+        );
+
+        let mut sql = command();
+        Tail::new(&mut sql).group_by(["a"]).offset(5).unwrap();
+        assert_eq!(sql.as_command(), "SELECT * FROM t GROUP BY a OFFSET $1");
+
+        let mut sql = command();
+        Tail::new(&mut sql).order_by(ORDER).row_lock(
+            RowLockStrength::Share,
+            [],
+            Some(RowLockConcurrency::NoWait),
+        );
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM t ORDER BY a ASC FOR SHARE NOWAIT"
+        );
+
+        let mut sql = command();
+        Tail::new(&mut sql).limit(1).unwrap().row_lock(
+            RowLockStrength::Update,
+            ["t"],
+            Some(RowLockConcurrency::SkipLocked),
+        );
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM t LIMIT $1 FOR UPDATE OF t SKIP LOCKED"
+        );
+
+        let mut sql = command();
+        Tail::new(&mut sql).row_lock(RowLockStrength::KeyShare, [], None);
+        assert_eq!(sql.as_command(), "SELECT * FROM t FOR KEY SHARE");
+
+        let mut sql = command();
+        Tail::new(&mut sql).end();
+        assert_eq!(sql.as_command(), "SELECT * FROM t");
+    }
+
+    #[test]
+    fn fetch_clause() {
+        let mut sql = command();
+        Tail::new(&mut sql)
+            .order_by(ORDER)
+            .offset(20)
+            .unwrap()
+            .fetch_first(10)
+            .unwrap()
+            .row_lock(RowLockStrength::Update, [], None);
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM t ORDER BY a ASC OFFSET $1 FETCH FIRST $2 ROWS ONLY FOR UPDATE"
+        );
+        assert_eq!(sql.arguments.as_str(), "20;10;");
+
+        let mut sql = command();
+        Tail::new(&mut sql).group_by(["a"]).fetch_first(1).unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM t GROUP BY a FETCH FIRST $1 ROWS ONLY"
+        );
+
+        let mut sql = values_command(&[[1], [2]]);
+        Tail::values(&mut sql).fetch_first(1).unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "VALUES ($1), ($2) FETCH FIRST $3 ROWS ONLY"
+        );
+    }
+
+    #[test]
+    fn repeated_row_lock() {
+        let mut sql = command();
+        Tail::new(&mut sql)
+            .limit(1)
+            .unwrap()
+            .row_lock(RowLockStrength::Update, ["t"], None)
+            .row_lock(
+                RowLockStrength::Share,
+                ["u"],
+                Some(RowLockConcurrency::SkipLocked),
+            );
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM t LIMIT $1 FOR UPDATE OF t FOR SHARE OF u SKIP LOCKED"
+        );
+    }
+
+    fn values_command(rows: &[[u32; 1]]) -> SqlCommand<TestArgs> {
+        let mut sql = SqlCommand::default();
+        values(&mut sql);
+        values_rows(&mut sql, 1, rows.iter().copied()).unwrap();
+        sql
+    }
+
+    #[test]
+    fn values_tail() {
+        let mut sql = values_command(&[[1], [2], [3]]);
+        Tail::values(&mut sql)
+            .order_by([("1", OrderByOrd::Desc, OrderByNulls::Default)])
+            .limit(2)
+            .unwrap()
+            .offset(1)
+            .unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "VALUES ($1), ($2), ($3) ORDER BY 1 DESC LIMIT $4 OFFSET $5"
+        );
+        assert_eq!(sql.arguments.as_str(), "1;2;3;2;1;");
+
+        let mut sql = values_command(&[[1]]);
+        Tail::values(&mut sql).limit(1).unwrap();
+        assert_eq!(sql.as_command(), "VALUES ($1) LIMIT $2");
+    }
+
+    #[test]
+    fn values_union_branch() {
+        let mut first = values_command(&[[5], [9]]);
+        Tail::values(&mut first).order_by([("1", OrderByOrd::Desc, OrderByNulls::Default)]);
+
+        let mut second: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut second);
+        column(&mut second, "x");
+        from_table(&mut second, "t");
+        filter_where(&mut second);
+        lhs_binary_rhs(&mut second, sqlexpr::<u32>("x"), Cmp::Gt, sqlvalue(3)).unwrap();
+
+        let sql = first.parenthesized().union(second).unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "(VALUES ($1), ($2) ORDER BY 1 DESC) UNION SELECT x FROM t WHERE x > $3"
+        );
+        assert_eq!(sql.arguments.as_str(), "5;9;3;");
+    }
+}
@@ -0,0 +1,174 @@
+use super::{emit, Token};
+use crate::{ArgumentError, SqlCommand, SqlError};
+
+/// Identity columns handling of a `TRUNCATE` command.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TruncateIdentity {
+    /// `RESTART IDENTITY`, resets the sequences owned by the columns.
+    Restart,
+    /// `CONTINUE IDENTITY`, keeps the sequences as is.
+    Continue,
+}
+
+impl TruncateIdentity {
+    pub const fn as_str(&self) -> &'static str {
+        match *self {
+            Self::Restart => "RESTART IDENTITY",
+            Self::Continue => "CONTINUE IDENTITY",
+        }
+    }
+}
+
+/// Handling of the tables referencing the truncated tables.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TruncateReferences {
+    /// `CASCADE`, also truncates the referencing tables.
+    Cascade,
+    /// `RESTRICT`, refuses to truncate referenced tables.
+    Restrict,
+}
+
+impl TruncateReferences {
+    pub const fn as_str(&self) -> &'static str {
+        match *self {
+            Self::Cascade => "CASCADE",
+            Self::Restrict => "RESTRICT",
+        }
+    }
+}
+
+/// Builder of a `TRUNCATE TABLE <table>, ... [RESTART IDENTITY | CONTINUE
+/// IDENTITY] [CASCADE | RESTRICT]` command, see [truncate].
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Truncate<'t> {
+    tables: &'t [&'t str],
+    identity: Option<TruncateIdentity>,
+    references: Option<TruncateReferences>,
+}
+
+impl<'t> Truncate<'t> {
+    /// Resets the sequences owned by the columns of the tables.
+    pub const fn restart_identity(self) -> Self {
+        self.identity(TruncateIdentity::Restart)
+    }
+
+    /// Keeps the sequences owned by the columns of the tables.
+    pub const fn continue_identity(self) -> Self {
+        self.identity(TruncateIdentity::Continue)
+    }
+
+    /// Sets the handling of the identity columns.
+    pub const fn identity(mut self, identity: TruncateIdentity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Also truncates the tables referencing the tables.
+    pub const fn cascade(self) -> Self {
+        self.references(TruncateReferences::Cascade)
+    }
+
+    /// Refuses to truncate the tables referenced by other tables.
+    pub const fn restrict(self) -> Self {
+        self.references(TruncateReferences::Restrict)
+    }
+
+    /// Sets the handling of the referencing tables.
+    pub const fn references(mut self, references: TruncateReferences) -> Self {
+        self.references = Some(references);
+        self
+    }
+
+    /// Ends the command, without any argument bound.
+    ///
+    /// An empty list of tables returns an [EmptyList](SqlError::EmptyList)
+    /// error.
+    pub fn end<Arg>(self) -> Result<SqlCommand<Arg>, SqlError<Arg::Error>>
+    where
+        Arg: Default + ArgumentError,
+    {
+        let mut tables = self.tables.iter();
+        let Some(first) = tables.next() else {
+            return Err(SqlError::EmptyList);
+        };
+
+        let mut sql = SqlCommand::default();
+        emit(&mut sql, Token::Keyword("TRUNCATE TABLE"));
+        emit(&mut sql, Token::Ident(first));
+        for table in tables {
+            emit(&mut sql, Token::Comma);
+            emit(&mut sql, Token::Ident(table));
+        }
+        if let Some(identity) = self.identity {
+            emit(&mut sql, Token::Keyword(identity.as_str()));
+        }
+        if let Some(references) = self.references {
+            emit(&mut sql, Token::Keyword(references.as_str()));
+        }
+        Ok(sql)
+    }
+}
+
+/// Starts a `TRUNCATE TABLE <table>, ...` command emptying `tables`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::truncate;
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let sql: SqlCommand<Void> = truncate(&["a", "b"]).restart_identity().cascade().end()?;
+///
+/// assert_eq!(sql.as_command(), "TRUNCATE TABLE a, b RESTART IDENTITY CASCADE");
+/// # Ok(())
+/// # }
+/// ```
+pub const fn truncate<'t>(tables: &'t [&'t str]) -> Truncate<'t> {
+    Truncate {
+        tables,
+        identity: None,
+        references: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{test::TestArgs, ArgumentBuffer};
+
+    #[test]
+    fn truncate_options() {
+        let sql: SqlCommand<TestArgs> = truncate(&["event"]).end().unwrap();
+        assert_eq!(sql.as_command(), "TRUNCATE TABLE event");
+        assert_eq!(ArgumentBuffer::<u32>::count(&sql.arguments), 0);
+
+        let sql: SqlCommand<TestArgs> = truncate(&["a", "b"])
+            .continue_identity()
+            .restrict()
+            .end()
+            .unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "TRUNCATE TABLE a, b CONTINUE IDENTITY RESTRICT"
+        );
+
+        let sql: SqlCommand<TestArgs> =
+            truncate(&["a"]).cascade().restart_identity().end().unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "TRUNCATE TABLE a RESTART IDENTITY CASCADE"
+        );
+    }
+
+    #[test]
+    fn truncate_without_tables() {
+        assert_eq!(
+            truncate(&[]).cascade().end::<TestArgs>(),
+            Err(SqlError::EmptyList)
+        );
+    }
+}
@@ -1,5 +1,13 @@
-use super::separator_optional;
-use crate::WriteSql;
+use core::marker::PhantomData;
+
+use super::{
+    alias_column_list, also_table, emit, filter_where, from_as, from_table, from_table_as,
+    item_separator, returning_all, returning_columns_checked, text_array_literal, value_cast,
+    values, values_rows, SortedEntries, Token,
+};
+use crate::{
+    format_num::format_u32_base10, ArgumentBuffer, ArgumentError, SqlCommand, SqlError, WriteSql,
+};
 
 /// Write a `UPDATE` command into the sql buffer.
 ///
@@ -20,8 +28,7 @@ pub fn update<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("UPDATE");
+    emit(sql, Token::Keyword("UPDATE"));
 }
 
 /// Write a `UPDATE <table>` command with a table into the sql buffer.
@@ -43,9 +50,8 @@ pub fn update_table<Sql, Arg>(sql: &mut Sql, table: &str)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("UPDATE ");
-    sql.push_cmd(table);
+    emit(sql, Token::Keyword("UPDATE"));
+    emit(sql, Token::Ident(table));
 }
 
 /// Write a `UPDATE <table> AS <alias>` command with a table and an
@@ -68,11 +74,10 @@ pub fn update_table_as<Sql, Arg>(sql: &mut Sql, table: &str, alias: &str)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("UPDATE ");
-    sql.push_cmd(table);
-    sql.push_cmd(" AS ");
-    sql.push_cmd(alias);
+    emit(sql, Token::Keyword("UPDATE"));
+    emit(sql, Token::Ident(table));
+    emit(sql, Token::Keyword("AS"));
+    emit(sql, Token::Ident(alias));
 }
 
 /// Write a `SET` expression of a update clause.
@@ -94,8 +99,7 @@ pub fn set_update<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("SET");
+    emit(sql, Token::Keyword("SET"));
 }
 
 /// Write a `<column> =` expression for setting the column value of a update clause.
@@ -117,9 +121,31 @@ pub fn set_column<Sql, Arg>(sql: &mut Sql, column: &str)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
+    emit(sql, Token::Ident(column));
+    emit(sql, Token::Keyword("="));
+}
+
+/// Write a `<column> = EXCLUDED.<column>` assignment of a
+/// `ON CONFLICT DO UPDATE` clause, updating the column with the value
+/// proposed for insertion.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, expr::set_excluded};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// set_excluded(&mut sql, "updated_at");
+///
+/// assert_eq!(sql.as_command(), "updated_at = EXCLUDED.updated_at");
+/// ```
+pub fn set_excluded<Sql, Arg>(sql: &mut Sql, column: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    set_column(sql, column);
+    emit(sql, Token::Ident("EXCLUDED"));
+    sql.push_cmd(".");
     sql.push_cmd(column);
-    sql.push_cmd(" =");
 }
 
 /// Write a `(<column>, ...) =` expression for setting the tuple value of
@@ -143,17 +169,1036 @@ where
     Sql: WriteSql<Arg>,
     I: IntoIterator<Item = &'t str>,
 {
-    separator_optional(sql);
-    sql.push_cmd("(");
+    emit(sql, Token::Open);
 
     let mut tpl = tuple.into_iter();
     if let Some(first) = tpl.next() {
         sql.push_cmd(first);
     }
     for column in tpl {
-        sql.push_cmd(", ");
+        emit(sql, Token::Comma);
         sql.push_cmd(column);
     }
 
-    sql.push_cmd(") =");
+    emit(sql, Token::Close);
+    emit(sql, Token::Keyword("="));
+}
+
+/// Write a `SET <column> = $n, ...` clause assigning every entry of
+/// `entries`, in sorted column order.
+///
+/// The values are bound in the same order, so equal entries always write the
+/// same command, regardless of the iteration order of the map. An empty map
+/// returns an [EmptyList](SqlError::EmptyList) error.
+///
+/// # Example
+///
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{update_table, set_all_sorted};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let changes = BTreeMap::from([("name", "Rusty"), ("email", "rusty@email.com")]);
+///
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// update_table(&mut sql, "user");
+/// set_all_sorted(&mut sql, changes)?;
+///
+/// assert_eq!(sql.as_command(), "UPDATE user SET email = $1, name = $2");
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_all_sorted<Sql, Arg, M>(
+    sql: &mut Sql,
+    entries: M,
+) -> Result<(), SqlError<<Arg as ArgumentBuffer<M::Value>>::Error>>
+where
+    Sql: WriteSql<Arg>,
+    M: SortedEntries,
+    Arg: ArgumentBuffer<M::Value>,
+{
+    let mut entries = entries.into_sorted_entries().peekable();
+    if entries.peek().is_none() {
+        return Err(SqlError::EmptyList);
+    }
+
+    set_update(sql);
+    for (index, (column, value)) in entries.enumerate() {
+        if index > 0 {
+            emit(sql, Token::Comma);
+        }
+        set_column(sql, column.as_ref());
+        emit(sql, Token::Placeholder);
+        sql.push_value(value)?;
+    }
+    Ok(())
+}
+
+/// Write a `<column> = jsonb_set(<column>, '{<path>}', $n::jsonb, <create_missing>)`
+/// assignment, replacing the value at `path` of a jsonb column.
+///
+/// The path elements are escaped into a text array literal, see
+/// [text_array_literal](super::text_array_literal). A path element containing
+/// a NUL character returns an [InvalidText](SqlError::InvalidText) error,
+/// without writing anything.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{update_table, set_update, set_jsonb_path};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// update_table(&mut sql, "customer");
+/// set_update(&mut sql);
+/// set_jsonb_path(&mut sql, "data", &["address", "city"], r#""Lisbon""#, true)?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "UPDATE customer SET data = jsonb_set(data, '{address,city}', $1::jsonb, true)"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_jsonb_path<Sql, Arg, T>(
+    sql: &mut Sql,
+    column: &str,
+    path: &[&str],
+    value: T,
+    create_missing: bool,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    let path = text_array_literal(path.iter().copied()).map_err(SqlError::widen)?;
+
+    set_column(sql, column);
+    emit(sql, Token::Ident("jsonb_set"));
+    emit(sql, Token::Raw("("));
+    sql.push_cmd(column);
+    sql.push_cmd(", ");
+    sql.push_cmd(&path);
+    sql.push_cmd(",");
+    value_cast(sql, value, "jsonb")?;
+    sql.push_cmd(if create_missing {
+        ", true)"
+    } else {
+        ", false)"
+    });
+    Ok(())
+}
+
+/// Write a `<column>[<index>] = $n` assignment, replacing an element of an
+/// array column.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{update_table, set_update, set_array_element};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// update_table(&mut sql, "board");
+/// set_update(&mut sql);
+/// set_array_element(&mut sql, "cells", 3, "x")?;
+///
+/// assert_eq!(sql.as_command(), "UPDATE board SET cells[3] = $1");
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_array_element<Sql, Arg, T>(
+    sql: &mut Sql,
+    column: &str,
+    index: u32,
+    value: T,
+) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    let mut buf = [0; 10];
+    emit(sql, Token::Ident(column));
+    sql.push_cmd("[");
+    sql.push_cmd(format_u32_base10(index, &mut buf));
+    sql.push_cmd("] = ");
+    sql.push_value(value)
+}
+
+/// States of an [UpdateBuilder].
+pub mod update_state {
+    /// No column assigned yet.
+    pub struct Unassigned;
+    /// At least one column assigned.
+    pub struct Assigned;
+    /// Only optional columns assigned, possibly none.
+    pub struct Patched;
+    /// `FROM` sources written.
+    pub struct Sourced;
+    /// `WHERE` clause written.
+    pub struct Filtered;
+
+    /// States accepting a column assignment.
+    pub trait Assignable: private::Sealed {
+        /// State after an optional assignment.
+        type Optional;
+    }
+
+    impl Assignable for Unassigned {
+        type Optional = Patched;
+    }
+
+    impl Assignable for Assigned {
+        type Optional = Assigned;
+    }
+
+    impl Assignable for Patched {
+        type Optional = Patched;
+    }
+
+    /// States accepting a `FROM` source, a `WHERE` clause or the end of
+    /// the command.
+    pub trait Sourceable: private::Sealed {
+        /// Whether the source is the first one, written after `FROM`.
+        const FIRST: bool;
+    }
+
+    impl Sourceable for Assigned {
+        const FIRST: bool = true;
+    }
+
+    impl Sourceable for Sourced {
+        const FIRST: bool = false;
+    }
+
+    /// States ending the command.
+    pub trait Terminal: private::Sealed {}
+
+    impl Terminal for Assigned {}
+    impl Terminal for Sourced {}
+    impl Terminal for Filtered {}
+
+    mod private {
+        pub trait Sealed {}
+
+        impl Sealed for super::Unassigned {}
+        impl Sealed for super::Assigned {}
+        impl Sealed for super::Patched {}
+        impl Sealed for super::Sourced {}
+        impl Sealed for super::Filtered {}
+    }
+}
+
+use update_state::{Assigned, Filtered, Patched, Sourced, Unassigned};
+
+/// Builder of a `UPDATE <table> SET <column> = $n, ... [WHERE <condition>]`
+/// command.
+///
+/// At least one column must be assigned before the `WHERE` clause or the end
+/// of the command, which is checked at compile time.
+/// Other tables can be joined after the assignments with
+/// [from](Self::from), `UPDATE ... SET ... FROM <table>, ... WHERE ...`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{UpdateBuilder, lhs_binary_rhs, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let sql: SqlCommand<Void> = UpdateBuilder::new(Void::new(), "user")
+///     .set("name", "Rusty")?
+///     .set("age", 7)?
+///     .filter_where(|sql| lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(1)))?
+///     .end();
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "UPDATE user SET name = $1, age = $2 WHERE id = $3"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// An update without assignments does not compile:
+///
+/// ```compile_fail
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::UpdateBuilder;
+/// let sql: SqlCommand<Void> = UpdateBuilder::new(Void::new(), "user").end();
+/// ```
+///
+/// Optional columns, as in a PATCH request, are assigned with
+/// [set_opt](Self::set_opt). When every assignment may be skipped, the
+/// missing assignments are only known at runtime, and the `WHERE` clause or
+/// the end of the command returns a [NoAssignments](SqlError::NoAssignments)
+/// error instead.
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{UpdateBuilder, lhs_binary_rhs, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let name: Option<&str> = None;
+/// let email = Some("rusty@email.com");
+///
+/// let sql: SqlCommand<Void> = UpdateBuilder::new(Void::new(), "user")
+///     .set_opt("name", name)?
+///     .set_opt("email", email)?
+///     .filter_where(|sql| {
+///         lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(1))?;
+///         Ok::<_, SqlError<Infallible>>(())
+///     })?
+///     .end();
+///
+/// assert_eq!(sql.as_command(), "UPDATE user SET email = $1 WHERE id = $2");
+///
+/// let empty = UpdateBuilder::new(Void::new(), "user")
+///     .set_opt("name", name)?
+///     .end();
+/// assert!(matches!(empty, Err(SqlError::NoAssignments)));
+/// # Ok(())
+/// # }
+/// ```
+pub struct UpdateBuilder<Arg, S> {
+    sql: SqlCommand<Arg>,
+    assigned: bool,
+    _state: PhantomData<S>,
+}
+
+impl<Arg> UpdateBuilder<Arg, Unassigned> {
+    /// Starts a `UPDATE <table>` command binding into `arguments`.
+    pub fn new(arguments: Arg, table: &str) -> Self {
+        let mut sql = SqlCommand::new(alloc::string::String::new(), arguments);
+        update_table(&mut sql, table);
+        Self {
+            sql,
+            assigned: false,
+            _state: PhantomData,
+        }
+    }
+
+    /// Registers `sink` to receive the metrics of the command when it is
+    /// ended, see [with_metrics](SqlCommand::with_metrics).
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, sink: &'static (dyn crate::MetricsSink + Sync)) -> Self
+    where
+        Arg: crate::AppendArguments,
+    {
+        self.sql = self.sql.with_metrics(sink);
+        self
+    }
+
+    /// Builds a `UPDATE <table> SET <column> = v.<column>, ... FROM (VALUES
+    /// (<key>, <value>, ...), ...) AS v(<key>, <column>, ...) WHERE
+    /// <table>.<key> = v.<key>` command, updating each row matching the key
+    /// with the values of its row.
+    ///
+    /// Every row must have the key followed by one value per column of
+    /// `set_columns`, otherwise a
+    /// [RowLengthMismatch](SqlError::RowLengthMismatch) error is returned,
+    /// see [values_rows]. Empty `set_columns` return a
+    /// [NoAssignments](SqlError::NoAssignments) error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, Void};
+    /// # use sqlstr::expr::UpdateBuilder;
+    /// # use core::convert::Infallible;
+    /// # fn main() -> Result<(), SqlError<Infallible>> {
+    /// let rows = [[1, 10], [2, 20]];
+    /// let sql: SqlCommand<Void> =
+    ///     UpdateBuilder::bulk_update_from_values(Void::new(), "stock", "id", &["count"], rows)?
+    ///         .end();
+    ///
+    /// assert_eq!(
+    ///     sql.as_command(),
+    ///     "UPDATE stock SET count = v.count FROM (VALUES ($1, $2), ($3, $4)) AS v(id, count) \
+    ///      WHERE stock.id = v.id"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bulk_update_from_values<R, I, T>(
+        arguments: Arg,
+        table: &str,
+        key_column: &str,
+        set_columns: &[&str],
+        rows: R,
+    ) -> Result<UpdateBuilder<Arg, Filtered>, SqlError<Arg::Error>>
+    where
+        Arg: ArgumentBuffer<T>,
+        R: IntoIterator<Item = I>,
+        I: IntoIterator<Item = T>,
+    {
+        /// Alias of the `VALUES` list.
+        const ALIAS: &str = "v";
+
+        if set_columns.is_empty() {
+            return Err(SqlError::NoAssignments);
+        }
+
+        let mut builder = Self::new(arguments, table);
+        let sql = &mut builder.sql;
+        set_update(sql);
+        for (index, column) in set_columns.iter().enumerate() {
+            if index > 0 {
+                item_separator(sql);
+            }
+            set_column(sql, column);
+            emit(sql, Token::Ident(ALIAS));
+            sql.push_cmd(".");
+            sql.push_cmd(column);
+        }
+
+        emit(sql, Token::Keyword("FROM"));
+        emit(sql, Token::Open);
+        values(sql);
+        values_rows(sql, set_columns.len().saturating_add(1), rows)?;
+        emit(sql, Token::Close);
+        let columns = core::iter::once(key_column).chain(set_columns.iter().copied());
+        alias_column_list(sql, ALIAS, columns);
+
+        filter_where(sql);
+        emit(sql, Token::Ident(table));
+        sql.push_cmd(".");
+        sql.push_cmd(key_column);
+        emit(sql, Token::Keyword("="));
+        emit(sql, Token::Ident(ALIAS));
+        sql.push_cmd(".");
+        sql.push_cmd(key_column);
+
+        builder.assigned = true;
+        Ok(builder.next())
+    }
+}
+
+impl<Arg, S> UpdateBuilder<Arg, S>
+where
+    S: update_state::Assignable,
+{
+    /// Writes a `<column> = $n` assignment, binding `value`.
+    pub fn set<T>(
+        mut self,
+        column: &str,
+        value: T,
+    ) -> Result<UpdateBuilder<Arg, Assigned>, Arg::Error>
+    where
+        Arg: ArgumentBuffer<T>,
+    {
+        self.open_assignment(column);
+        emit(&mut self.sql, Token::Placeholder);
+        self.sql.push_value(value)?;
+        Ok(self.next())
+    }
+
+    /// Writes a `<column> = <expr>` assignment with a raw sql expression,
+    /// e.g. `counter = counter + 1`.
+    pub fn set_expr(mut self, column: &str, expr: &str) -> UpdateBuilder<Arg, Assigned> {
+        self.open_assignment(column);
+        emit(&mut self.sql, Token::Ident(expr));
+        self.next()
+    }
+
+    /// Writes a `<column> = <source>` assignment copying another column.
+    pub fn set_column(self, column: &str, source: &str) -> UpdateBuilder<Arg, Assigned> {
+        self.set_expr(column, source)
+    }
+
+    /// Writes a `<column> = $n` assignment binding the `Some` value, or
+    /// skips the column entirely on `None`.
+    pub fn set_opt<T>(
+        mut self,
+        column: &str,
+        value: Option<T>,
+    ) -> Result<UpdateBuilder<Arg, S::Optional>, Arg::Error>
+    where
+        Arg: ArgumentBuffer<T>,
+    {
+        if let Some(value) = value {
+            self.open_assignment(column);
+            emit(&mut self.sql, Token::Placeholder);
+            self.sql.push_value(value)?;
+        }
+        Ok(self.next())
+    }
+
+    /// Writes a `<column> = $n` assignment for every pair of `pairs`, for
+    /// columns only known at runtime.
+    ///
+    /// An empty iterator returns a [NoAssignments](SqlError::NoAssignments)
+    /// error if no column was assigned before.
+    pub fn set_all<'col, T, I>(
+        mut self,
+        pairs: I,
+    ) -> Result<UpdateBuilder<Arg, Assigned>, SqlError<Arg::Error>>
+    where
+        Arg: ArgumentBuffer<T>,
+        I: IntoIterator<Item = (&'col str, T)>,
+    {
+        /// Bytes of a `, <column> = $nn` assignment, without the column.
+        const ASSIGNMENT_LEN: usize = 8;
+        /// Estimated bytes of a column name.
+        const COLUMN_LEN: usize = 8;
+
+        let pairs = pairs.into_iter();
+        let (min_pairs, _) = pairs.size_hint();
+        self.sql
+            .reserve(min_pairs.saturating_mul(ASSIGNMENT_LEN.saturating_add(COLUMN_LEN)));
+
+        for (column, value) in pairs {
+            self.open_assignment(column);
+            emit(&mut self.sql, Token::Placeholder);
+            self.sql.push_value(value)?;
+        }
+        if !self.assigned {
+            return Err(SqlError::NoAssignments);
+        }
+        Ok(self.next())
+    }
+
+    fn open_assignment(&mut self, column: &str) {
+        if self.assigned {
+            item_separator(&mut self.sql);
+        } else {
+            set_update(&mut self.sql);
+            self.assigned = true;
+        }
+        set_column(&mut self.sql, column);
+    }
+}
+
+impl<Arg> UpdateBuilder<Arg, Patched>
+where
+    Arg: ArgumentError,
+{
+    /// Writes a `FROM <table>` source, see
+    /// [from](UpdateBuilder::<Arg, Assigned>::from).
+    ///
+    /// Returns a [NoAssignments](SqlError::NoAssignments) error if every
+    /// optional assignment was skipped.
+    pub fn from(self, table: &str) -> Result<UpdateBuilder<Arg, Sourced>, SqlError<Arg::Error>> {
+        Ok(self.assigned()?.from(table))
+    }
+
+    /// Writes a `FROM <table> AS <alias>` source, see
+    /// [from_as](UpdateBuilder::<Arg, Assigned>::from_as).
+    ///
+    /// Returns a [NoAssignments](SqlError::NoAssignments) error if every
+    /// optional assignment was skipped.
+    pub fn from_as(
+        self,
+        table: &str,
+        alias: &str,
+    ) -> Result<UpdateBuilder<Arg, Sourced>, SqlError<Arg::Error>> {
+        Ok(self.assigned()?.from_as(table, alias))
+    }
+
+    /// Writes the `WHERE` clause, with the condition written by `build`.
+    ///
+    /// Returns a [NoAssignments](SqlError::NoAssignments) error, converted
+    /// into the error of `build`, if every optional assignment was skipped.
+    pub fn filter_where<F, E>(self, build: F) -> Result<UpdateBuilder<Arg, Filtered>, E>
+    where
+        F: FnOnce(&mut SqlCommand<Arg>) -> Result<(), E>,
+        E: From<SqlError<Arg::Error>>,
+    {
+        self.assigned()?.filter_where(build)
+    }
+
+    /// Ends the command, updating every row of the table.
+    ///
+    /// Returns a [NoAssignments](SqlError::NoAssignments) error if every
+    /// optional assignment was skipped.
+    pub fn end(self) -> Result<SqlCommand<Arg>, SqlError<Arg::Error>> {
+        Ok(self.assigned()?.end())
+    }
+
+    /// Ends the command with a `RETURNING <column>, ...` clause, see
+    /// [returning_columns_checked].
+    ///
+    /// Returns a [NoAssignments](SqlError::NoAssignments) error if every
+    /// optional assignment was skipped.
+    pub fn returning(self, columns: &[&str]) -> Result<SqlCommand<Arg>, SqlError<Arg::Error>> {
+        self.assigned()?.returning(columns)
+    }
+
+    /// Ends the command with a `RETURNING *` clause.
+    ///
+    /// Returns a [NoAssignments](SqlError::NoAssignments) error if every
+    /// optional assignment was skipped.
+    pub fn returning_all(self) -> Result<SqlCommand<Arg>, SqlError<Arg::Error>> {
+        Ok(self.assigned()?.returning_all())
+    }
+
+    /// Continues as an assigned command, if any optional assignment was
+    /// written.
+    fn assigned(self) -> Result<UpdateBuilder<Arg, Assigned>, SqlError<Arg::Error>> {
+        if !self.assigned {
+            return Err(SqlError::NoAssignments);
+        }
+        Ok(self.next())
+    }
+}
+
+impl<Arg, S> UpdateBuilder<Arg, S>
+where
+    S: update_state::Sourceable,
+{
+    /// Writes a `FROM <table>` source, or a `, <table>` source after the
+    /// first one, joined in the `WHERE` clause.
+    pub fn from(mut self, table: &str) -> UpdateBuilder<Arg, Sourced> {
+        if S::FIRST {
+            from_table(&mut self.sql, table);
+        } else {
+            also_table(&mut self.sql, table);
+        }
+        self.next()
+    }
+
+    /// Writes a `FROM <table> AS <alias>` source, or a `, <table> AS <alias>`
+    /// source after the first one.
+    pub fn from_as(mut self, table: &str, alias: &str) -> UpdateBuilder<Arg, Sourced> {
+        if S::FIRST {
+            from_table_as(&mut self.sql, table, alias);
+        } else {
+            from_as(&mut self.sql, table, alias);
+        }
+        self.next()
+    }
+
+    /// Writes the `WHERE` clause, with the condition written by `build`.
+    pub fn filter_where<F, E>(mut self, build: F) -> Result<UpdateBuilder<Arg, Filtered>, E>
+    where
+        F: FnOnce(&mut SqlCommand<Arg>) -> Result<(), E>,
+    {
+        filter_where(&mut self.sql);
+        build(&mut self.sql)?;
+        Ok(self.next())
+    }
+}
+
+impl<Arg, S> UpdateBuilder<Arg, S>
+where
+    S: update_state::Terminal,
+{
+    /// Ends the command. Without a `WHERE` clause, every row of the table is
+    /// updated.
+    pub fn end(self) -> SqlCommand<Arg> {
+        self.sql.finished()
+    }
+
+    /// Ends the command with a `RETURNING <column>, ...` clause, see
+    /// [returning_columns_checked].
+    pub fn returning(mut self, columns: &[&str]) -> Result<SqlCommand<Arg>, SqlError<Arg::Error>>
+    where
+        Arg: ArgumentError,
+    {
+        returning_columns_checked(&mut self.sql, columns)?;
+        Ok(self.sql.finished())
+    }
+
+    /// Ends the command with a `RETURNING *` clause.
+    pub fn returning_all(mut self) -> SqlCommand<Arg> {
+        returning_all(&mut self.sql);
+        self.sql.finished()
+    }
+}
+
+impl<Arg, S> UpdateBuilder<Arg, S> {
+    fn next<N>(self) -> UpdateBuilder<Arg, N> {
+        UpdateBuilder {
+            sql: self.sql,
+            assigned: self.assigned,
+            _state: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        expr::{continue_condition, lhs_binary_rhs, Cmp, LogicBi},
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+        SqlCommand,
+    };
+
+    type Error = SqlError<core::fmt::Error>;
+
+    #[test]
+    fn jsonb_path_with_other_assignments() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        update_table(&mut sql, "customer");
+        set_update(&mut sql);
+        set_column(&mut sql, "name");
+        sql.push_cmd(" ");
+        sql.push_value("Rusty").unwrap();
+        item_separator(&mut sql);
+        set_jsonb_path(&mut sql, "data", &["address", "geo", "lat"], 38.7, false).unwrap();
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(7)).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE customer SET name = $1, \
+             data = jsonb_set(data, '{address,geo,lat}', $2::jsonb, false) WHERE id = $3"
+        );
+        assert_eq!(sql.arguments.as_str(), "Rusty;38.7;7;");
+    }
+
+    #[test]
+    fn jsonb_path_escaped_elements() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        set_jsonb_path(&mut sql, "data", &["tags", "a,b", "it's"], "true", true).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            r#"data = jsonb_set(data, '{tags,"a,b",it''s}', $1::jsonb, true)"#
+        );
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        let err = set_jsonb_path(&mut sql, "data", &["a\0"], "1", true).unwrap_err();
+        assert_eq!(err, Error::InvalidText { position: 1 });
+        assert_eq!(sql.as_command(), "");
+    }
+
+    #[test]
+    fn array_element_assignments() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        update_table(&mut sql, "board");
+        set_update(&mut sql);
+        set_array_element(&mut sql, "cells", 1, "x").unwrap();
+        item_separator(&mut sql);
+        set_array_element(&mut sql, "cells", 10, "o").unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE board SET cells[1] = $1, cells[10] = $2"
+        );
+        assert_eq!(sql.arguments.as_str(), "x;o;");
+    }
+
+    #[test]
+    fn update_builder_assignments() {
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::new(TestArgs::new(), "account")
+            .set("frozen", true)
+            .unwrap()
+            .end();
+        assert_eq!(sql.as_command(), "UPDATE account SET frozen = $1");
+        assert_eq!(sql.arguments.as_str(), "true;");
+
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::new(TestArgs::new(), "account")
+            .set("name", "Rusty")
+            .unwrap()
+            .set("age", 7)
+            .unwrap()
+            .set("score", 1.5)
+            .unwrap()
+            .filter_where(|sql| {
+                lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(3))?;
+                continue_condition(sql, LogicBi::And);
+                sql.push_cmd(" deleted_at IS NULL");
+                Ok::<_, core::fmt::Error>(())
+            })
+            .unwrap()
+            .end();
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE account SET name = $1, age = $2, score = $3 \
+             WHERE id = $4 AND deleted_at IS NULL"
+        );
+        assert_eq!(sql.arguments.as_str(), "Rusty;7;1.5;3;");
+    }
+
+    #[test]
+    fn update_builder_mixed_assignments() {
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::new(TestArgs::new(), "post")
+            .set_expr("views", "views + 1")
+            .set("title", "Rust")
+            .unwrap()
+            .set_column("published_at", "created_at")
+            .set("score", 9)
+            .unwrap()
+            .filter_where(|sql| lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(4)))
+            .unwrap()
+            .end();
+
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE post SET views = views + 1, title = $1, \
+             published_at = created_at, score = $2 WHERE id = $3"
+        );
+        assert_eq!(sql.arguments.as_str(), "Rust;9;4;");
+
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::new(TestArgs::new(), "post")
+            .set_column("title", "draft_title")
+            .end();
+        assert_eq!(sql.as_command(), "UPDATE post SET title = draft_title");
+    }
+
+    #[test]
+    fn update_builder_from_sources() {
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::new(TestArgs::new(), "account")
+            .set_column("balance", "s.balance")
+            .set("synced", true)
+            .unwrap()
+            .from_as("snapshot", "s")
+            .from("region")
+            .filter_where(|sql| {
+                sql.push_cmd(" s.id = account.id AND region.id = account.region_id");
+                continue_condition(sql, LogicBi::And);
+                lhs_binary_rhs(sql, sqlexpr::<u32>("s.batch"), Cmp::Eq, sqlvalue(12))
+            })
+            .unwrap()
+            .end();
+
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE account SET balance = s.balance, synced = $1 FROM snapshot AS s, region \
+             WHERE s.id = account.id AND region.id = account.region_id AND s.batch = $2"
+        );
+        assert_eq!(sql.arguments.as_str(), "true;12;");
+
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::new(TestArgs::new(), "t")
+            .set_column("x", "s.x")
+            .from("src")
+            .from_as("other", "o")
+            .end();
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE t SET x = s.x FROM src, other AS o"
+        );
+    }
+
+    #[test]
+    fn update_builder_returning() {
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::new(TestArgs::new(), "account")
+            .set("frozen", true)
+            .unwrap()
+            .filter_where(|sql| lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(3)))
+            .unwrap()
+            .returning(&["id", "frozen"])
+            .unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE account SET frozen = $1 WHERE id = $2 RETURNING id, frozen"
+        );
+
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::new(TestArgs::new(), "t")
+            .set_column("x", "s.x")
+            .from_as("src", "s")
+            .returning_all();
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE t SET x = s.x FROM src AS s RETURNING *"
+        );
+
+        let builder = UpdateBuilder::new(TestArgs::new(), "t").set_expr("n", "n + 1");
+        assert!(matches!(builder.returning(&[]), Err(SqlError::EmptyList)));
+    }
+
+    #[test]
+    fn update_builder_optional_assignments() {
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::new(TestArgs::new(), "user")
+            .set_opt("name", None::<&str>)
+            .unwrap()
+            .set_opt("email", Some("rusty@email.com"))
+            .unwrap()
+            .set_opt("age", Some(7))
+            .unwrap()
+            .filter_where(|sql| {
+                lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(1))?;
+                Ok::<_, Error>(())
+            })
+            .unwrap()
+            .end();
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE user SET email = $1, age = $2 WHERE id = $3"
+        );
+        assert_eq!(sql.arguments.as_str(), "rusty@email.com;7;1;");
+
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::new(TestArgs::new(), "user")
+            .set_opt("name", None::<&str>)
+            .unwrap()
+            .set_expr("version", "version + 1")
+            .set_opt("age", None::<u32>)
+            .unwrap()
+            .end();
+        assert_eq!(sql.as_command(), "UPDATE user SET version = version + 1");
+
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::new(TestArgs::new(), "user")
+            .set_opt("name", Some("Rusty"))
+            .unwrap()
+            .end()
+            .unwrap();
+        assert_eq!(sql.as_command(), "UPDATE user SET name = $1");
+
+        let builder = UpdateBuilder::<TestArgs, _>::new(TestArgs::new(), "user")
+            .set_opt("name", None::<&str>)
+            .unwrap()
+            .set_opt("age", None::<u32>)
+            .unwrap();
+        let err = builder
+            .filter_where(|sql| {
+                lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(1))?;
+                Ok::<_, Error>(())
+            })
+            .err();
+        assert_eq!(err, Some(SqlError::NoAssignments));
+
+        let builder = UpdateBuilder::<TestArgs, _>::new(TestArgs::new(), "user")
+            .set_opt("name", None::<&str>)
+            .unwrap();
+        assert_eq!(builder.end().err(), Some(SqlError::NoAssignments));
+    }
+
+    #[test]
+    fn update_builder_patched_sources_and_returning() {
+        let patched = || {
+            UpdateBuilder::new(TestArgs::new(), "account")
+                .set_opt("balance", Some(10))
+                .unwrap()
+        };
+
+        let sql = patched()
+            .from_as("snapshot", "s")
+            .unwrap()
+            .from("region")
+            .end();
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE account SET balance = $1 FROM snapshot AS s, region"
+        );
+
+        let sql = patched().from("snapshot").unwrap().returning_all();
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE account SET balance = $1 FROM snapshot RETURNING *"
+        );
+
+        let sql = patched().returning(&["id", "balance"]).unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE account SET balance = $1 RETURNING id, balance"
+        );
+
+        let sql = patched().returning_all().unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE account SET balance = $1 RETURNING *"
+        );
+
+        let skipped = || {
+            UpdateBuilder::new(TestArgs::new(), "account")
+                .set_opt("balance", None::<u32>)
+                .unwrap()
+        };
+        assert!(matches!(
+            skipped().from("region"),
+            Err(SqlError::NoAssignments)
+        ));
+        assert!(matches!(
+            skipped().returning(&["id"]),
+            Err(SqlError::NoAssignments)
+        ));
+        assert!(matches!(
+            skipped().returning_all(),
+            Err(SqlError::NoAssignments)
+        ));
+    }
+
+    #[test]
+    fn update_builder_set_all() {
+        let changes = [("name", "Rusty"), ("email", "rusty@email.com")];
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::new(TestArgs::new(), "user")
+            .set_all(changes)
+            .unwrap()
+            .set("age", 7)
+            .unwrap()
+            .end();
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE user SET name = $1, email = $2, age = $3"
+        );
+        assert_eq!(sql.arguments.as_str(), "Rusty;rusty@email.com;7;");
+
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::new(TestArgs::new(), "user")
+            .set_expr("version", "version + 1")
+            .set_all(core::iter::empty::<(&str, u32)>())
+            .unwrap()
+            .end();
+        assert_eq!(sql.as_command(), "UPDATE user SET version = version + 1");
+
+        let builder = UpdateBuilder::<TestArgs, _>::new(TestArgs::new(), "user");
+        assert_eq!(
+            builder.set_all(core::iter::empty::<(&str, u32)>()).err(),
+            Some(SqlError::NoAssignments)
+        );
+    }
+
+    #[test]
+    fn bulk_update_from_values_rows() {
+        let rows = [[1, 10, 100], [2, 20, 200], [3, 30, 300]];
+        let sql: SqlCommand<TestArgs> = UpdateBuilder::bulk_update_from_values(
+            TestArgs::new(),
+            "item",
+            "id",
+            &["stock", "price"],
+            rows,
+        )
+        .unwrap()
+        .returning_all();
+
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE item SET stock = v.stock, price = v.price \
+             FROM (VALUES ($1, $2, $3), ($4, $5, $6), ($7, $8, $9)) AS v(id, stock, price) \
+             WHERE item.id = v.id RETURNING *"
+        );
+        assert_eq!(ArgumentBuffer::<u32>::count(&sql.arguments), 9);
+        assert_eq!(sql.arguments.as_str(), "1;10;100;2;20;200;3;30;300;");
+    }
+
+    #[test]
+    fn bulk_update_from_values_errors() {
+        let err = UpdateBuilder::bulk_update_from_values(
+            TestArgs::new(),
+            "item",
+            "id",
+            &["stock", "price"],
+            [&[1, 10, 100][..], &[2, 20]],
+        )
+        .err();
+        assert_eq!(
+            err,
+            Some(SqlError::RowLengthMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+
+        let err =
+            UpdateBuilder::bulk_update_from_values(TestArgs::new(), "item", "id", &[], [[1]]).err();
+        assert_eq!(err, Some(SqlError::NoAssignments));
+
+        let err = UpdateBuilder::bulk_update_from_values(
+            TestArgs::new(),
+            "item",
+            "id",
+            &["stock"],
+            core::iter::empty::<[u32; 2]>(),
+        )
+        .err();
+        assert_eq!(err, Some(SqlError::EmptyList));
+    }
 }
@@ -1,5 +1,11 @@
-use super::{item_separator_optional, separator_optional};
-use crate::{ArgumentBuffer, WriteSql};
+use core::marker::PhantomData;
+
+use alloc::string::String;
+
+use super::{emit, item_separator_optional, separator_optional, tail_state, Tail, Token};
+use crate::{
+    bulk::MAX_BIND_PARAMETERS, ArgumentBuffer, ArgumentError, SqlCommand, SqlError, WriteSql,
+};
 
 /// Write a `VALUES` clause to start a list of expressions to be used in the
 /// `INSERT` command.
@@ -22,8 +28,7 @@ pub fn values<Sql, Arg>(sql: &mut Sql)
 where
     Sql: WriteSql<Arg>,
 {
-    separator_optional(sql);
-    sql.push_cmd("VALUES");
+    emit(sql, Token::Keyword("VALUES"));
 }
 
 /// Write a list of values into the sql command.
@@ -58,13 +63,511 @@ where
     }
 
     for val in val_iter {
-        sql.push_cmd(", ");
+        emit(sql, Token::Comma);
         sql.push_value(val)?;
     }
 
     Ok(())
 }
 
+/// Write a `DEFAULT VALUES` clause, inserting a row with the default value of
+/// every column.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{insert_into, default_values};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// insert_into(&mut sql, "event");
+/// default_values(&mut sql);
+///
+/// assert_eq!(sql.as_command(), "INSERT INTO event DEFAULT VALUES");
+/// ```
+pub fn default_values<Sql, Arg>(sql: &mut Sql)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Keyword("DEFAULT VALUES"));
+}
+
+/// Write a `ROW($n, ...)` composite value, pushing every value as an
+/// argument, cast to `type_name` when present, `ROW($n, ...)::<type_name>`.
+///
+/// An empty list of values returns an [EmptyList](SqlError::EmptyList) error,
+/// without writing anything.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{update_table, set_update, set_column, value_row};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// update_table(&mut sql, "shipment");
+/// set_update(&mut sql);
+/// set_column(&mut sql, "destination");
+/// value_row(&mut sql, ["Main St", "Lisbon"], Some("address"))?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "UPDATE shipment SET destination = ROW($1, $2)::address"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn value_row<Sql, Arg, I, T>(
+    sql: &mut Sql,
+    values: I,
+    type_name: Option<&str>,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+    I: IntoIterator<Item = T>,
+{
+    let mut values = values.into_iter().peekable();
+    if values.peek().is_none() {
+        return Err(SqlError::EmptyList);
+    }
+
+    separator_optional(sql);
+    sql.push_cmd("ROW(");
+    for (index, value) in values.enumerate() {
+        if index > 0 {
+            sql.push_cmd(", ");
+        }
+        sql.push_value(value)?;
+    }
+    sql.push_cmd(")");
+
+    if let Some(type_name) = type_name {
+        sql.push_cmd("::");
+        sql.push_cmd(type_name);
+    }
+    Ok(())
+}
+
+/// Row of a `VALUES` list, `(<cell>, ...)`.
+///
+/// Each cell can be a bound value, a raw sql expression or the `DEFAULT`
+/// keyword, only the bound values consume a placeholder. The number of cells
+/// is checked against the row arity when the row is closed.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{insert_into, insert_columns_iter, values, ValuesRow};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// insert_into(&mut sql, "user");
+/// insert_columns_iter(&mut sql, ["name", "created", "id", "email"])?;
+/// values(&mut sql);
+///
+/// let mut row = ValuesRow::open(&mut sql, 4);
+/// row.value("Rusty")?
+///     .raw("now()")
+///     .default()
+///     .value("rusty@email.com")?;
+/// row.close()?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "INSERT INTO user (name, created, id, email) VALUES ($1, now(), DEFAULT, $2)"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub struct ValuesRow<'cmd, Sql: WriteSql<Arg>, Arg> {
+    sql: &'cmd mut Sql,
+    arity: usize,
+    cells: usize,
+    _arg: PhantomData<Arg>,
+}
+
+impl<'cmd, Sql, Arg> ValuesRow<'cmd, Sql, Arg>
+where
+    Sql: WriteSql<Arg>,
+{
+    /// Opens a row expecting `arity` cells.
+    pub fn open(sql: &'cmd mut Sql, arity: usize) -> Self {
+        separator_optional(sql);
+        sql.push_cmd("(");
+        Self {
+            sql,
+            arity,
+            cells: 0,
+            _arg: PhantomData,
+        }
+    }
+
+    fn next_cell(&mut self) {
+        if self.cells > 0 {
+            self.sql.push_cmd(", ");
+        }
+        self.cells = self.cells.saturating_add(1);
+    }
+
+    /// Writes a bound value cell.
+    pub fn value<T>(&mut self, value: T) -> Result<&mut Self, Arg::Error>
+    where
+        Arg: ArgumentBuffer<T>,
+    {
+        self.next_cell();
+        self.sql.push_value(value)?;
+        Ok(self)
+    }
+
+    /// Writes a bound `NULL` cell of type `T`, binding `None::<T>`.
+    pub fn value_null<T>(&mut self) -> Result<&mut Self, Arg::Error>
+    where
+        Arg: ArgumentBuffer<Option<T>>,
+    {
+        self.value(None::<T>)
+    }
+
+    /// Writes a composite value cell, `ROW($n, ...)`, see [value_row].
+    pub fn value_row<I, T>(
+        &mut self,
+        values: I,
+        type_name: Option<&str>,
+    ) -> Result<&mut Self, SqlError<Arg::Error>>
+    where
+        Arg: ArgumentBuffer<T>,
+        I: IntoIterator<Item = T>,
+    {
+        let mut values = values.into_iter().peekable();
+        if values.peek().is_none() {
+            return Err(SqlError::EmptyList);
+        }
+
+        self.next_cell();
+        value_row(self.sql, values, type_name)?;
+        Ok(self)
+    }
+
+    /// Writes a raw sql expression cell.
+    pub fn raw(&mut self, expr: &str) -> &mut Self {
+        self.next_cell();
+        self.sql.push_cmd(expr);
+        self
+    }
+
+    /// Writes a `DEFAULT` cell.
+    pub fn default(&mut self) -> &mut Self {
+        self.raw("DEFAULT")
+    }
+
+    /// Number of cells written.
+    pub fn len(&self) -> usize {
+        self.cells
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells == 0
+    }
+
+    /// Closes the row, checking the number of cells written against the row
+    /// arity.
+    pub fn close(self) -> Result<(), SqlError<Arg::Error>>
+    where
+        Arg: ArgumentError,
+    {
+        self.finish()
+    }
+
+    /// Closes the row as [close](Self::close), for the callers bound by an
+    /// [ArgumentBuffer] instead of [ArgumentError].
+    pub(crate) fn finish<E>(self) -> Result<(), SqlError<E>> {
+        let (expected, found) = (self.arity, self.cells);
+        core::mem::drop(self);
+
+        if expected != found {
+            return Err(SqlError::RowLengthMismatch { expected, found });
+        }
+        Ok(())
+    }
+}
+
+impl<'cmd, Sql, Arg> Drop for ValuesRow<'cmd, Sql, Arg>
+where
+    Sql: WriteSql<Arg>,
+{
+    fn drop(&mut self) {
+        self.sql.push_cmd(")");
+    }
+}
+
+/// Write a row of a `VALUES` list with `build`, checking the number of cells
+/// written against `arity`, see [ValuesRow].
+///
+/// Returns a [RowLengthMismatch](SqlError::RowLengthMismatch) error if the
+/// row does not have `arity` cells.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{insert_into, insert_columns_iter, values, values_row};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// insert_into(&mut sql, "user");
+/// let arity = insert_columns_iter(&mut sql, ["name", "email"])?;
+/// values(&mut sql);
+/// values_row(&mut sql, arity, |row| {
+///     row.value("Rusty")?.value("rusty@email.com")?;
+///     Ok(())
+/// })?;
+///
+/// assert_eq!(sql.as_command(), "INSERT INTO user (name, email) VALUES ($1, $2)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn values_row<Sql, Arg, F>(
+    sql: &mut Sql,
+    arity: usize,
+    build: F,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentError,
+    F: FnOnce(&mut ValuesRow<'_, Sql, Arg>) -> Result<(), SqlError<Arg::Error>>,
+{
+    let mut row = ValuesRow::open(sql, arity);
+    build(&mut row)?;
+    row.close()
+}
+
+/// Write a comma separated list of rows, `(<value>, ...), ...`, pushing every
+/// value as an argument.
+///
+/// Every row must have `arity` values, otherwise a
+/// [RowLengthMismatch](SqlError::RowLengthMismatch) error is returned. An
+/// empty list of rows returns an [EmptyList](SqlError::EmptyList) error.
+///
+/// The command capacity is reserved from the size hint of `rows`. A command
+/// binding more than [MAX_BIND_PARAMETERS] values, counting the arguments
+/// already bound to the command, returns a
+/// [TooManyArguments](SqlError::TooManyArguments) error, before writing any
+/// row when the size hint tells the list is too long, or else before writing
+/// the first row over the limit. See [split_rows](crate::bulk::split_rows) to
+/// split the rows between commands.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{insert_into, insert_columns_iter, values, values_rows};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// insert_into(&mut sql, "point");
+/// insert_columns_iter(&mut sql, ["x", "y"])?;
+/// values(&mut sql);
+/// values_rows(&mut sql, 2, [[0, 1], [2, 3]])?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "INSERT INTO point (x, y) VALUES ($1, $2), ($3, $4)"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn values_rows<Sql, Arg, R, I, T>(
+    sql: &mut Sql,
+    arity: usize,
+    rows: R,
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+    R: IntoIterator<Item = I>,
+    I: IntoIterator<Item = T>,
+{
+    /// Bytes of a `$nnnnn, ` cell.
+    const CELL_LEN: usize = 8;
+    /// Bytes of the parentheses and separator of a row.
+    const ROW_LEN: usize = 4;
+
+    let rows = rows.into_iter();
+    let (min_rows, _) = rows.size_hint();
+    let max = MAX_BIND_PARAMETERS as usize;
+    let mut bound = sql
+        .arguments()
+        .map_or(0, |args| ArgumentBuffer::<T>::count(args) as usize);
+    if bound.saturating_add(min_rows.saturating_mul(arity)) > max {
+        return Err(SqlError::TooManyArguments {
+            max: MAX_BIND_PARAMETERS,
+        });
+    }
+    sql.reserve(
+        arity
+            .saturating_mul(CELL_LEN)
+            .saturating_add(ROW_LEN)
+            .saturating_mul(min_rows),
+    );
+
+    let mut empty = true;
+
+    for row in rows {
+        if bound.saturating_add(arity) > max {
+            return Err(SqlError::TooManyArguments {
+                max: MAX_BIND_PARAMETERS,
+            });
+        }
+        if !empty {
+            emit(sql, Token::Comma);
+        }
+        empty = false;
+
+        let mut values_row = ValuesRow::open(sql, arity);
+        for value in row {
+            bound = bound.saturating_add(1);
+            if bound > max {
+                return Err(SqlError::TooManyArguments {
+                    max: MAX_BIND_PARAMETERS,
+                });
+            }
+            values_row.value(value)?;
+        }
+        values_row.finish()?;
+    }
+
+    if empty {
+        return Err(SqlError::EmptyList);
+    }
+    Ok(())
+}
+
+/// Builder of a standalone `VALUES (<value>, ...), ...` command, returning a
+/// constant set of rows.
+///
+/// Every row has the same `arity`, checked as in [values_row]. The command
+/// can be ended with `ORDER BY`, `LIMIT` and `OFFSET` clauses with
+/// [end_with](Self::end_with), and combined as any query, such as with
+/// [union](SqlCommand::union).
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{ValuesBuilder, OrderByOrd, OrderByNulls};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let sql: SqlCommand<Void> = ValuesBuilder::new(Void::new(), 2)
+///     .row(|row| {
+///         row.value(1)?.value("one")?;
+///         Ok(())
+///     })?
+///     .rows([[2, 3], [4, 5]])?
+///     .end_with(|tail| {
+///         tail.order_by([("1", OrderByOrd::Desc, OrderByNulls::Default)])
+///             .limit(2)?;
+///         Ok::<_, Infallible>(())
+///     })?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "VALUES ($1, $2), ($3, $4), ($5, $6) ORDER BY 1 DESC LIMIT $7"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub struct ValuesBuilder<Arg> {
+    sql: SqlCommand<Arg>,
+    arity: usize,
+    empty: bool,
+}
+
+impl<Arg> ValuesBuilder<Arg> {
+    /// Starts a `VALUES` command of rows with `arity` values, binding into
+    /// `arguments`.
+    pub fn new(arguments: Arg, arity: usize) -> Self {
+        let mut sql = SqlCommand::new(String::new(), arguments);
+        values(&mut sql);
+        Self {
+            sql,
+            arity,
+            empty: true,
+        }
+    }
+
+    /// Registers `sink` to receive the metrics of the command when it is
+    /// ended, see [with_metrics](SqlCommand::with_metrics).
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, sink: &'static (dyn crate::MetricsSink + Sync)) -> Self
+    where
+        Arg: crate::AppendArguments,
+    {
+        self.sql = self.sql.with_metrics(sink);
+        self
+    }
+
+    /// Writes a row with the cells written by `build`, see [values_row].
+    pub fn row<F>(mut self, build: F) -> Result<Self, SqlError<Arg::Error>>
+    where
+        Arg: ArgumentError,
+        F: FnOnce(&mut ValuesRow<'_, SqlCommand<Arg>, Arg>) -> Result<(), SqlError<Arg::Error>>,
+    {
+        self.next_row();
+        values_row(&mut self.sql, self.arity, build)?;
+        Ok(self)
+    }
+
+    /// Writes every row of `rows`, binding each value, see [values_rows].
+    pub fn rows<R, I, T>(mut self, rows: R) -> Result<Self, SqlError<Arg::Error>>
+    where
+        Arg: ArgumentBuffer<T>,
+        R: IntoIterator<Item = I>,
+        I: IntoIterator<Item = T>,
+    {
+        let mut rows = rows.into_iter().peekable();
+        if rows.peek().is_none() {
+            return Ok(self);
+        }
+        self.next_row();
+        values_rows(&mut self.sql, self.arity, rows)?;
+        Ok(self)
+    }
+
+    /// Ends the command.
+    ///
+    /// A command without rows returns an [EmptyList](SqlError::EmptyList)
+    /// error.
+    pub fn end(self) -> Result<SqlCommand<Arg>, SqlError<Arg::Error>>
+    where
+        Arg: ArgumentError,
+    {
+        self.end_with(|_| Ok(()))
+    }
+
+    /// Ends the command with the clauses written by `tail` after the rows.
+    ///
+    /// A command without rows returns an [EmptyList](SqlError::EmptyList)
+    /// error, without calling `tail`.
+    pub fn end_with<F, E>(mut self, tail: F) -> Result<SqlCommand<Arg>, SqlError<E>>
+    where
+        F: FnOnce(Tail<'_, SqlCommand<Arg>, Arg, tail_state::Values>) -> Result<(), E>,
+    {
+        if self.empty {
+            return Err(SqlError::EmptyList);
+        }
+        tail(Tail::values(&mut self.sql))?;
+        Ok(self.sql.finished())
+    }
+
+    fn next_row(&mut self) {
+        if !self.empty {
+            emit(&mut self.sql, Token::Comma);
+        }
+        self.empty = false;
+    }
+}
+
 #[macro_export]
 macro_rules! write_variadic {
     (ArgumentBufferError = $argbuf_error_type:ty; $sql:expr, $value1:expr) => {{
@@ -109,15 +612,18 @@ pub use write_variadic;
 
 #[cfg(test)]
 mod test {
-    use alloc::string::String;
-
     use super::*;
     use crate::{
-        expr::{select, separator},
+        expr::{
+            insert_columns_iter, insert_into, item_separator, select, separator, OrderByNulls,
+            OrderByOrd,
+        },
         test::{display_iter, TestArgs},
         SqlCommand,
     };
 
+    type Error = SqlError<core::fmt::Error>;
+
     #[test]
     fn select_values_iter() {
         let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
@@ -177,4 +683,344 @@ mod test {
         assert_eq!(sql.as_command(), "SELECT $1, $2, $3, $4, $5");
         assert_eq!(sql.arguments.as_str(), "str;false;string;10;[120,360,0];");
     }
+
+    #[test]
+    fn values_row_mixed_cells() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        insert_into(&mut sql, "user");
+        insert_columns_iter(&mut sql, ["id", "name", "created", "role"]).unwrap();
+        values(&mut sql);
+
+        let mut row = ValuesRow::open(&mut sql, 4);
+        row.raw("gen_random_uuid()")
+            .value("Rusty")
+            .unwrap()
+            .raw("now()")
+            .default();
+        row.close().unwrap();
+
+        values_row_second(&mut sql).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO user (id, name, created, role) VALUES (gen_random_uuid(), $1, now(), DEFAULT), ($2, DEFAULT, now(), $3)"
+        );
+        assert_eq!(sql.arguments.as_str(), "Rusty;9;admin;");
+    }
+
+    fn values_row_second(sql: &mut SqlCommand<TestArgs>) -> Result<(), SqlError<core::fmt::Error>> {
+        sql.push_cmd(",");
+        let mut row = ValuesRow::open(sql, 4);
+        row.value(9)?.default().raw("now()").value("admin")?;
+        row.close()
+    }
+
+    #[test]
+    fn values_row_length_mismatch() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        let mut row = ValuesRow::open(&mut sql, 3);
+        row.value(1).unwrap().default();
+        assert_eq!(row.len(), 2);
+
+        assert_eq!(
+            row.close(),
+            Err(SqlError::RowLengthMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(sql.as_command(), "($1, DEFAULT)");
+
+        let mut row = ValuesRow::open(&mut sql, 1);
+        row.value(1).unwrap().value(2).unwrap();
+
+        assert_eq!(
+            row.close(),
+            Err(SqlError::RowLengthMismatch {
+                expected: 1,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn insert_values_row() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        insert_into(&mut sql, "user");
+        let arity = insert_columns_iter(&mut sql, ["name", "created", "email"]).unwrap();
+        values(&mut sql);
+        values_row(&mut sql, arity, |row| {
+            row.value("Rusty")?.raw("now()").value("rusty@email.com")?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO user (name, created, email) VALUES ($1, now(), $2)"
+        );
+        assert_eq!(sql.arguments.as_str(), "Rusty;rusty@email.com;");
+    }
+
+    #[test]
+    fn insert_values_row_length_mismatch() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        insert_into(&mut sql, "point");
+        let arity = insert_columns_iter(&mut sql, ["x", "y"]).unwrap();
+        values(&mut sql);
+        let err: Error = values_row(&mut sql, arity, |row| {
+            row.value(1)?;
+            Ok(())
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            SqlError::RowLengthMismatch {
+                expected: 2,
+                found: 1
+            }
+        );
+    }
+
+    #[test]
+    fn values_rows_sequential_placeholders() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        insert_into(&mut sql, "point");
+        let arity = insert_columns_iter(&mut sql, ["x", "y"]).unwrap();
+        values(&mut sql);
+        values_rows(&mut sql, arity, [[1, 2], [3, 4], [5, 6]]).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO point (x, y) VALUES ($1, $2), ($3, $4), ($5, $6)"
+        );
+        assert_eq!(sql.arguments.as_str(), "1;2;3;4;5;6;");
+    }
+
+    #[test]
+    fn values_rows_argument_limit() {
+        use crate::Void;
+
+        let mut sql: SqlCommand<Void> = SqlCommand::default();
+        values_rows(&mut sql, 5, alloc::vec![[0; 5]; 13107]).unwrap();
+        assert_eq!(sql.arguments.count(), MAX_BIND_PARAMETERS);
+
+        let mut sql: SqlCommand<Void> = SqlCommand::default();
+        let err = values_rows(&mut sql, 5, alloc::vec![[0; 5]; 13108]).unwrap_err();
+        assert_eq!(err, SqlError::TooManyArguments { max: 65535 });
+        assert_eq!(sql.as_command(), "");
+
+        // without size hint, fails before the first row over the limit
+        let mut sql: SqlCommand<Void> = SqlCommand::default();
+        let rows = (0..13108).filter(|_| true).map(|_| [0; 5]);
+        let err = values_rows(&mut sql, 5, rows).unwrap_err();
+        assert_eq!(err, SqlError::TooManyArguments { max: 65535 });
+        assert_eq!(sql.arguments.count(), MAX_BIND_PARAMETERS);
+        assert!(sql.as_command().ends_with("$65535)"));
+
+        // counts the arguments bound before the list
+        let mut sql = SqlCommand::new(alloc::string::String::new(), Void::with_count(65533));
+        let err = values_rows(&mut sql, 2, [[0, 1], [2, 3]]).unwrap_err();
+        assert_eq!(err, SqlError::TooManyArguments { max: 65535 });
+        assert_eq!(sql.as_command(), "");
+
+        let mut sql = SqlCommand::new(alloc::string::String::new(), Void::with_count(65533));
+        let rows = [[0, 1], [2, 3]].into_iter().filter(|_| true);
+        let err = values_rows(&mut sql, 2, rows).unwrap_err();
+        assert_eq!(err, SqlError::TooManyArguments { max: 65535 });
+        assert_eq!(sql.as_command(), "($65534, $65535)");
+    }
+
+    #[test]
+    fn insert_default_values() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        insert_into(&mut sql, "event");
+        default_values(&mut sql);
+
+        assert_eq!(sql.as_command(), "INSERT INTO event DEFAULT VALUES");
+        assert_eq!(sql.arguments.as_str(), "");
+    }
+
+    #[test]
+    fn rows_mixing_default_cells() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        insert_into(&mut sql, "user");
+        let arity = insert_columns_iter(&mut sql, ["id", "name", "role"]).unwrap();
+        values(&mut sql);
+        values_row(&mut sql, arity, |row| {
+            row.default().value("Rusty")?.default();
+            Ok(())
+        })
+        .unwrap();
+        item_separator(&mut sql);
+        values_row(&mut sql, arity, |row| {
+            row.value(7)?.default().value("admin")?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO user (id, name, role) VALUES (DEFAULT, $1, DEFAULT), ($2, DEFAULT, $3)"
+        );
+        assert_eq!(sql.arguments.as_str(), "Rusty;7;admin;");
+    }
+
+    #[test]
+    fn insert_row_values() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        insert_into(&mut sql, "shipment");
+        let arity = insert_columns_iter(&mut sql, ["id", "destination", "origin"]).unwrap();
+        values(&mut sql);
+        values_row(&mut sql, arity, |row| {
+            row.value(1)?
+                .value_row(["Main St", "Lisbon"], Some("address"))?
+                .value_row(["Rua A", "Porto"], None)?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO shipment (id, destination, origin) \
+             VALUES ($1, ROW($2, $3)::address, ROW($4, $5))"
+        );
+        assert_eq!(sql.arguments.as_str(), "1;Main St;Lisbon;Rua A;Porto;");
+    }
+
+    #[test]
+    fn empty_row_value() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        let err = value_row(&mut sql, [0u8; 0], Some("address")).unwrap_err();
+
+        assert_eq!(err, SqlError::EmptyList);
+        assert_eq!(sql.as_command(), "SELECT");
+    }
+
+    #[test]
+    fn values_builder_rows() {
+        let sql: SqlCommand<TestArgs> = ValuesBuilder::new(TestArgs::new(), 2)
+            .rows([[1, 2], [3, 4]])
+            .unwrap()
+            .row(|row| {
+                row.value(5)?.raw("now()");
+                Ok(())
+            })
+            .unwrap()
+            .rows(core::iter::empty::<[u32; 2]>())
+            .unwrap()
+            .end()
+            .unwrap();
+
+        assert_eq!(sql.as_command(), "VALUES ($1, $2), ($3, $4), ($5, now())");
+        assert_eq!(sql.arguments.as_str(), "1;2;3;4;5;");
+    }
+
+    #[test]
+    fn values_builder_tail_and_union() {
+        let sql: SqlCommand<TestArgs> = ValuesBuilder::new(TestArgs::new(), 1)
+            .rows([[7], [8], [9]])
+            .unwrap()
+            .end_with(|tail| {
+                tail.order_by([("1", OrderByOrd::Asc, OrderByNulls::Default)])
+                    .limit(2)?
+                    .offset(1)?;
+                Ok::<_, core::fmt::Error>(())
+            })
+            .unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "VALUES ($1), ($2), ($3) ORDER BY 1 ASC LIMIT $4 OFFSET $5"
+        );
+
+        let other: SqlCommand<TestArgs> = ValuesBuilder::new(TestArgs::new(), 1)
+            .rows([[10]])
+            .unwrap()
+            .end()
+            .unwrap();
+        let sql = sql.parenthesized().union_all(other).unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "(VALUES ($1), ($2), ($3) ORDER BY 1 ASC LIMIT $4 OFFSET $5) UNION ALL VALUES ($6)"
+        );
+        assert_eq!(sql.arguments.as_str(), "7;8;9;2;1;10;");
+    }
+
+    #[test]
+    fn values_builder_errors() {
+        let err = ValuesBuilder::new(TestArgs::new(), 2).end().err();
+        assert_eq!(err, Some(SqlError::EmptyList));
+
+        let err = ValuesBuilder::new(TestArgs::new(), 2)
+            .rows([&[1, 2][..], &[3]])
+            .err();
+        assert_eq!(
+            err,
+            Some(SqlError::RowLengthMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn nullable_values() {
+        use crate::{
+            expr::{filter_where, lhs_binary_rhs, Cmp},
+            sqlexpr, sqlnull, sqlvalue, VecArguments,
+        };
+
+        let mut sql = SqlCommand::new(String::new(), VecArguments::<Option<u32>>::new());
+        insert_into(&mut sql, "score");
+        insert_columns_iter(&mut sql, ["player", "points", "bonus", "penalty"]).unwrap();
+        values(&mut sql);
+        for (index, (points, bonus)) in [(Some(10), None), (None, Some(2))].into_iter().enumerate()
+        {
+            if index > 0 {
+                item_separator(&mut sql);
+            }
+            let mut row = ValuesRow::open(&mut sql, 4);
+            row.value(7_u32)
+                .unwrap()
+                .value(points)
+                .unwrap()
+                .value(bonus)
+                .unwrap()
+                .value_null::<u32>()
+                .unwrap();
+            row.close().unwrap();
+        }
+
+        assert_eq!(
+            sql.as_command(),
+            "INSERT INTO score (player, points, bonus, penalty) \
+             VALUES ($1, $2, $3, $4), ($5, $6, $7, $8)"
+        );
+        assert_eq!(
+            sql.arguments.into_inner(),
+            [Some(7), Some(10), None, None, Some(7), None, Some(2), None]
+        );
+
+        let mut sql = SqlCommand::new(String::new(), VecArguments::<Option<u32>>::new());
+        sql.push_cmd("UPDATE score SET bonus = ");
+        sql.push_expr(sqlnull::<u32>()).unwrap();
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("player"), Cmp::Eq, sqlvalue(7)).unwrap();
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE score SET bonus = $1 WHERE player = $2"
+        );
+        assert_eq!(sql.arguments.into_inner(), [None, Some(7)]);
+    }
 }
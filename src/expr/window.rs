@@ -0,0 +1,604 @@
+use super::{
+    as_alias, emit, filter_where, lhs_binary_rhs, order_by, separator_optional, subquery, Cmp,
+    Group, OrderByNulls, OrderByOrd, Token,
+};
+use crate::format_num::format_u32_base10;
+use crate::{sqlexpr, sqlvalue, ArgumentBuffer, SqlCommand, SqlError, Void, WriteSql};
+
+/// Writes a window function call without arguments, `<name>()`.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, window_fn};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// window_fn(&mut sql, "percent_rank");
+///
+/// assert_eq!(sql.as_command(), "SELECT percent_rank()");
+/// ```
+pub fn window_fn<Sql, Arg>(sql: &mut Sql, name: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    separator_optional(sql);
+    sql.push_cmd(name);
+    sql.push_cmd("()");
+}
+
+/// Writes a `row_number()` window function.
+pub fn row_number<Sql, Arg>(sql: &mut Sql)
+where
+    Sql: WriteSql<Arg>,
+{
+    window_fn(sql, "row_number");
+}
+
+/// Writes a `rank()` window function.
+pub fn rank<Sql, Arg>(sql: &mut Sql)
+where
+    Sql: WriteSql<Arg>,
+{
+    window_fn(sql, "rank");
+}
+
+/// Writes a `dense_rank()` window function.
+pub fn dense_rank<Sql, Arg>(sql: &mut Sql)
+where
+    Sql: WriteSql<Arg>,
+{
+    window_fn(sql, "dense_rank");
+}
+
+fn offset_fn<Sql, Arg, T>(
+    sql: &mut Sql,
+    name: &str,
+    column: &str,
+    offset: Option<T>,
+) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    separator_optional(sql);
+    sql.push_cmd(name);
+    sql.push_cmd("(");
+    sql.push_cmd(column);
+    if let Some(offset) = offset {
+        emit(sql, Token::Comma);
+        sql.push_value(offset)?;
+    }
+    sql.push_cmd(")");
+    Ok(())
+}
+
+/// Writes a `lag(<column>, $n)` window function, with the offset bound as an
+/// argument. Without offset, the database default (1) is used.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{select, lag, over, order_by, OrderByOrd, OrderByNulls};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// lag(&mut sql, "price", Some(2))?;
+/// over(&mut sql, |w| {
+///     order_by(w, [("day", OrderByOrd::Default, OrderByNulls::Default)]);
+///     Ok::<_, Infallible>(())
+/// })?;
+///
+/// assert_eq!(sql.as_command(), "SELECT lag(price, $1) OVER (ORDER BY day)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn lag<Sql, Arg, T>(sql: &mut Sql, column: &str, offset: Option<T>) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    offset_fn(sql, "lag", column, offset)
+}
+
+/// Writes a `lead(<column>, $n)` window function, with the offset bound as an
+/// argument. Without offset, the database default (1) is used.
+pub fn lead<Sql, Arg, T>(sql: &mut Sql, column: &str, offset: Option<T>) -> Result<(), Arg::Error>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+{
+    offset_fn(sql, "lead", column, offset)
+}
+
+/// Writes an `OVER (<window definition>)` clause for the previous window
+/// function.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, row_number, over, partition_by, order_by, OrderByOrd, OrderByNulls};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// row_number(&mut sql);
+/// over(&mut sql, |w| {
+///     partition_by(w, ["user_id"]);
+///     order_by(w, [("created_at", OrderByOrd::Desc, OrderByNulls::Default)]);
+///     Ok::<_, Infallible>(())
+/// })?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT row_number() OVER (PARTITION BY user_id ORDER BY created_at DESC)"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn over<Sql, Arg, F, E>(sql: &mut Sql, build: F) -> Result<(), E>
+where
+    Sql: WriteSql<Arg>,
+    F: FnOnce(&mut Group<'_, Sql, Arg>) -> Result<(), E>,
+{
+    emit(sql, Token::Keyword("OVER"));
+    subquery(sql, build)
+}
+
+/// Writes an `OVER <name>` clause referencing a window defined in the
+/// `WINDOW` clause.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, row_number, over_named};
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// row_number(&mut sql);
+/// over_named(&mut sql, "w");
+///
+/// assert_eq!(sql.as_command(), "SELECT row_number() OVER w");
+/// ```
+pub fn over_named<Sql, Arg>(sql: &mut Sql, name: &str)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Keyword("OVER"));
+    emit(sql, Token::Ident(name));
+}
+
+/// Writes a `WINDOW <name> AS (<window definition>)` clause.
+///
+/// Additional windows are written with [also_window].
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{window, also_window, partition_by, order_by, OrderByOrd, OrderByNulls};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// window(&mut sql, "w", |w| {
+///     partition_by(w, ["user_id"]);
+///     Ok::<_, Infallible>(())
+/// })?;
+/// also_window(&mut sql, "recent", |w| {
+///     order_by(w, [("created_at", OrderByOrd::Desc, OrderByNulls::Default)]);
+///     Ok::<_, Infallible>(())
+/// })?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "WINDOW w AS (PARTITION BY user_id), recent AS (ORDER BY created_at DESC)"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn window<Sql, Arg, F, E>(sql: &mut Sql, name: &str, build: F) -> Result<(), E>
+where
+    Sql: WriteSql<Arg>,
+    F: FnOnce(&mut Group<'_, Sql, Arg>) -> Result<(), E>,
+{
+    emit(sql, Token::Keyword("WINDOW"));
+    window_definition(sql, name, build)
+}
+
+/// Writes an additional `, <name> AS (<window definition>)` item into a
+/// `WINDOW` clause.
+pub fn also_window<Sql, Arg, F, E>(sql: &mut Sql, name: &str, build: F) -> Result<(), E>
+where
+    Sql: WriteSql<Arg>,
+    F: FnOnce(&mut Group<'_, Sql, Arg>) -> Result<(), E>,
+{
+    emit(sql, Token::Comma);
+    window_definition(sql, name, build)
+}
+
+fn window_definition<Sql, Arg, F, E>(sql: &mut Sql, name: &str, build: F) -> Result<(), E>
+where
+    Sql: WriteSql<Arg>,
+    F: FnOnce(&mut Group<'_, Sql, Arg>) -> Result<(), E>,
+{
+    emit(sql, Token::Ident(name));
+    emit(sql, Token::Keyword("AS"));
+    subquery(sql, build)
+}
+
+/// Writes a `PARTITION BY <expr>, ...` clause of a window definition.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::partition_by;
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// partition_by(&mut sql, ["tenant_id", "user_id"]);
+///
+/// assert_eq!(sql.as_command(), "PARTITION BY tenant_id, user_id");
+/// ```
+pub fn partition_by<'e, Sql, Arg, I>(sql: &mut Sql, exprs: I)
+where
+    Sql: WriteSql<Arg>,
+    I: IntoIterator<Item = &'e str>,
+{
+    emit(sql, Token::Keyword("PARTITION BY"));
+
+    let mut exprs = exprs.into_iter();
+    if let Some(expr) = exprs.next() {
+        emit(sql, Token::Ident(expr));
+    }
+    for expr in exprs {
+        emit(sql, Token::Comma);
+        sql.push_cmd(expr);
+    }
+}
+
+/// Bound of a window frame.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FrameBound {
+    UnboundedPreceding,
+    /// `<offset> PRECEDING`
+    Preceding(u32),
+    CurrentRow,
+    /// `<offset> FOLLOWING`
+    Following(u32),
+    UnboundedFollowing,
+}
+
+fn frame_bound<Sql, Arg>(sql: &mut Sql, bound: FrameBound)
+where
+    Sql: WriteSql<Arg>,
+{
+    let mut buf = [0; 10];
+    match bound {
+        FrameBound::UnboundedPreceding => emit(sql, Token::Keyword("UNBOUNDED PRECEDING")),
+        FrameBound::Preceding(offset) => {
+            emit(sql, Token::Ident(format_u32_base10(offset, &mut buf)));
+            emit(sql, Token::Keyword("PRECEDING"));
+        }
+        FrameBound::CurrentRow => emit(sql, Token::Keyword("CURRENT ROW")),
+        FrameBound::Following(offset) => {
+            emit(sql, Token::Ident(format_u32_base10(offset, &mut buf)));
+            emit(sql, Token::Keyword("FOLLOWING"));
+        }
+        FrameBound::UnboundedFollowing => emit(sql, Token::Keyword("UNBOUNDED FOLLOWING")),
+    }
+}
+
+fn frame_between<Sql, Arg>(sql: &mut Sql, mode: &str, start: FrameBound, end: FrameBound)
+where
+    Sql: WriteSql<Arg>,
+{
+    emit(sql, Token::Keyword(mode));
+    emit(sql, Token::Keyword("BETWEEN"));
+    frame_bound(sql, start);
+    emit(sql, Token::Keyword("AND"));
+    frame_bound(sql, end);
+}
+
+/// Writes a `ROWS BETWEEN <start> AND <end>` window frame.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, Void};
+/// # use sqlstr::expr::{select, avg, over, order_by, rows_between, FrameBound, OrderByOrd, OrderByNulls};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// avg(&mut sql, "price");
+/// over(&mut sql, |w| {
+///     order_by(w, [("day", OrderByOrd::Default, OrderByNulls::Default)]);
+///     rows_between(w, FrameBound::Preceding(6), FrameBound::CurrentRow);
+///     Ok::<_, Infallible>(())
+/// })?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT avg(price) OVER (ORDER BY day ROWS BETWEEN 6 PRECEDING AND CURRENT ROW)"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn rows_between<Sql, Arg>(sql: &mut Sql, start: FrameBound, end: FrameBound)
+where
+    Sql: WriteSql<Arg>,
+{
+    frame_between(sql, "ROWS", start, end);
+}
+
+/// Writes a `RANGE BETWEEN <start> AND <end>` window frame.
+pub fn range_between<Sql, Arg>(sql: &mut Sql, start: FrameBound, end: FrameBound)
+where
+    Sql: WriteSql<Arg>,
+{
+    frame_between(sql, "RANGE", start, end);
+}
+
+/// Writes a `GROUPS BETWEEN <start> AND <end>` window frame.
+pub fn groups_between<Sql, Arg>(sql: &mut Sql, start: FrameBound, end: FrameBound)
+where
+    Sql: WriteSql<Arg>,
+{
+    frame_between(sql, "GROUPS", start, end);
+}
+
+/// Keeps the first `n` rows of each group of a `SELECT` command, the
+/// top-N per group query.
+///
+/// The rows of `sql` are numbered with `row_number()` over the partition of
+/// `partition_by`, ordered by `order_by`, into a `__rn` column, and the rows
+/// numbered above `n` are filtered out:
+///
+/// `SELECT * FROM (SELECT *, row_number() OVER (PARTITION BY <partition>
+/// ORDER BY <order>) AS __rn FROM (<sql>) AS __base) AS __ranked
+/// WHERE __rn <= $n`
+///
+/// The result includes the `__rn` column. An empty partition returns an
+/// [EmptyList](SqlError::EmptyList) error.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void};
+/// # use sqlstr::expr::{select, columns_iter, from_table, top_n_per_group, OrderByOrd};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// columns_iter(&mut sql, ["user_id", "total", "created_at"]);
+/// from_table(&mut sql, "order");
+///
+/// let sql = top_n_per_group(sql, &["user_id"], ("created_at", OrderByOrd::Desc), 3)?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT * FROM (SELECT *, row_number() OVER (PARTITION BY user_id ORDER BY created_at DESC) AS __rn \
+///      FROM (SELECT user_id, total, created_at FROM order) AS __base) AS __ranked WHERE __rn <= $1"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn top_n_per_group<Arg>(
+    sql: SqlCommand<Arg>,
+    partition: &[&str],
+    order: (&str, OrderByOrd<'_>),
+    n: u64,
+) -> Result<SqlCommand<Arg>, SqlError<Arg::Error>>
+where
+    Arg: ArgumentBuffer<u64>,
+{
+    const STAR: &str = "SELECT *";
+
+    if partition.is_empty() {
+        return Err(SqlError::EmptyList);
+    }
+
+    let mut rank: SqlCommand<Void> = SqlCommand::default();
+    rank.push_cmd(", ");
+    row_number(&mut rank);
+    over(&mut rank, |w| {
+        partition_by(w, partition.iter().copied());
+        order_by(w, [(order.0, order.1, OrderByNulls::Default)]);
+        Ok::<_, core::convert::Infallible>(())
+    })
+    .unwrap_or_default();
+    as_alias(&mut rank, "__rn");
+
+    // the wrapped command starts with `SELECT *`, the rank column follows the star
+    let mut ranked = sql.wrapped("__base");
+    ranked.insert_cmd(STAR.len(), rank.as_command());
+
+    let mut top = ranked.wrapped("__ranked");
+    filter_where(&mut top);
+    lhs_binary_rhs(&mut top, sqlexpr::<u64>("__rn"), Cmp::Lte, sqlvalue(n))?;
+    Ok(top)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        expr::{
+            column, columns_iter, count_star, from_table, group_by, item_separator, select, sum,
+        },
+        test::TestArgs,
+    };
+
+    #[test]
+    fn window_functions_over() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "user_id");
+        item_separator(&mut sql);
+        rank(&mut sql);
+        over(&mut sql, |w| {
+            partition_by(w, ["user_id"]);
+            order_by(w, [("score", OrderByOrd::Desc, OrderByNulls::Last)]);
+            Ok::<_, core::fmt::Error>(())
+        })
+        .unwrap();
+        as_alias(&mut sql, "position");
+        item_separator(&mut sql);
+        dense_rank(&mut sql);
+        over(&mut sql, |w| {
+            order_by(w, [("score", OrderByOrd::Desc, OrderByNulls::Default)]);
+            Ok::<_, core::fmt::Error>(())
+        })
+        .unwrap();
+        item_separator(&mut sql);
+        lead(&mut sql, "score", None::<u32>).unwrap();
+        over(&mut sql, |w| {
+            partition_by(w, ["user_id", "game_id"]);
+            Ok::<_, core::fmt::Error>(())
+        })
+        .unwrap();
+        item_separator(&mut sql);
+        lag(&mut sql, "score", Some(3)).unwrap();
+        over(&mut sql, |_| Ok::<_, core::fmt::Error>(())).unwrap();
+        from_table(&mut sql, "match");
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT user_id, rank() OVER (PARTITION BY user_id ORDER BY score DESC NULLS LAST) AS position, \
+             dense_rank() OVER (ORDER BY score DESC), \
+             lead(score) OVER (PARTITION BY user_id, game_id), \
+             lag(score, $1) OVER () FROM match"
+        );
+        assert_eq!(sql.arguments.as_str(), "3;");
+    }
+
+    #[test]
+    fn named_windows() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column(&mut sql, "user_id");
+        item_separator(&mut sql);
+        sum(&mut sql, "amount");
+        over_named(&mut sql, "by_user");
+        item_separator(&mut sql);
+        row_number(&mut sql);
+        over_named(&mut sql, "by_day");
+        as_alias(&mut sql, "day_position");
+        from_table(&mut sql, "payment");
+        group_by(&mut sql, ["user_id", "amount", "day"]);
+        window(&mut sql, "by_user", |w| {
+            partition_by(w, ["user_id"]);
+            Ok::<_, core::fmt::Error>(())
+        })
+        .unwrap();
+        also_window(&mut sql, "by_day", |w| {
+            partition_by(w, ["day"]);
+            order_by(w, [("amount", OrderByOrd::Desc, OrderByNulls::Default)]);
+            Ok::<_, core::fmt::Error>(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT user_id, sum(amount) OVER by_user, row_number() OVER by_day AS day_position \
+             FROM payment GROUP BY user_id, amount, day \
+             WINDOW by_user AS (PARTITION BY user_id), by_day AS (PARTITION BY day ORDER BY amount DESC)"
+        );
+    }
+
+    #[test]
+    fn window_frames() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        sum(&mut sql, "amount");
+        over(&mut sql, |w| {
+            order_by(w, [("day", OrderByOrd::Default, OrderByNulls::Default)]);
+            rows_between(w, FrameBound::UnboundedPreceding, FrameBound::CurrentRow);
+            Ok::<_, core::fmt::Error>(())
+        })
+        .unwrap();
+        item_separator(&mut sql);
+        sum(&mut sql, "amount");
+        over(&mut sql, |w| {
+            order_by(w, [("day", OrderByOrd::Default, OrderByNulls::Default)]);
+            range_between(w, FrameBound::Preceding(7), FrameBound::Following(1000));
+            Ok::<_, core::fmt::Error>(())
+        })
+        .unwrap();
+        item_separator(&mut sql);
+        count_star(&mut sql);
+        over(&mut sql, |w| {
+            groups_between(w, FrameBound::CurrentRow, FrameBound::UnboundedFollowing);
+            Ok::<_, core::fmt::Error>(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT sum(amount) OVER (ORDER BY day ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW), \
+             sum(amount) OVER (ORDER BY day RANGE BETWEEN 7 PRECEDING AND 1000 FOLLOWING), \
+             count(*) OVER (GROUPS BETWEEN CURRENT ROW AND UNBOUNDED FOLLOWING)"
+        );
+        assert_eq!(sql.arguments.as_str(), "");
+    }
+
+    #[test]
+    fn top_n_per_group_two_column_partition() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        columns_iter(&mut sql, ["tenant_id", "user_id", "score"]);
+        from_table(&mut sql, "play");
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("season"), Cmp::Eq, sqlvalue(2)).unwrap();
+
+        let sql = top_n_per_group(
+            sql,
+            &["tenant_id", "user_id"],
+            ("score", OrderByOrd::Desc),
+            5,
+        )
+        .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT * FROM (\
+             SELECT *, row_number() OVER (PARTITION BY tenant_id, user_id ORDER BY score DESC) AS __rn \
+             FROM (SELECT tenant_id, user_id, score FROM play WHERE season = $1) AS __base\
+             ) AS __ranked WHERE __rn <= $2"
+        );
+        assert_eq!(sql.arguments.as_str(), "2;5;");
+
+        let sql: SqlCommand<TestArgs> = SqlCommand::default();
+        assert!(matches!(
+            top_n_per_group(sql, &[], ("score", OrderByOrd::Desc), 5),
+            Err(SqlError::EmptyList)
+        ));
+    }
+
+    #[test]
+    fn top_n_per_group_invalid_text() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        columns_iter(&mut sql, ["na\0me"]);
+        from_table(&mut sql, "play");
+
+        let sql = top_n_per_group(sql, &["team_id"], ("score", OrderByOrd::Desc), 1).unwrap();
+        let position = sql.as_command().find('\0').unwrap();
+        assert_eq!(sql.check_text(), Err(SqlError::InvalidText { position }));
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        select(&mut sql);
+        columns_iter(&mut sql, ["name"]);
+        from_table(&mut sql, "play\0");
+
+        let sql = top_n_per_group(sql, &["te\0am"], ("score", OrderByOrd::Desc), 1).unwrap();
+        let position = sql.as_command().find('\0').unwrap();
+        assert_eq!(sql.check_text(), Err(SqlError::InvalidText { position }));
+    }
+}
@@ -0,0 +1,525 @@
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::convert::Infallible;
+use core::marker::PhantomData;
+
+use super::{emit, subquery, Group, Token};
+use crate::{SqlError, WriteSql};
+
+/// Names of the common table expressions defined in a `WITH` clause.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Ctes(Vec<String>);
+
+impl Ctes {
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Returns whether a CTE named `name` was defined.
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.iter().any(|cte| cte == name)
+    }
+
+    /// Names of the defined CTEs, in definition order.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.0.iter().map(String::as_str)
+    }
+
+    /// Reference to a defined CTE, to be used where a table is accepted.
+    ///
+    /// Returns an [UnknownCte](SqlError::UnknownCte) error if no CTE named
+    /// `name` was defined, which can be converted into the error of any
+    /// argument buffer with [widen](SqlError::widen).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, Void};
+    /// # use sqlstr::expr::{With, select, column, from_table};
+    /// # use core::convert::Infallible;
+    /// # fn main() -> Result<(), SqlError<Infallible>> {
+    /// let mut sql: SqlCommand<Void> = SqlCommand::default();
+    /// let mut with = With::open(&mut sql);
+    /// with.cte("active", |sql, _| {
+    ///     select(sql);
+    ///     column(sql, "*");
+    ///     from_table(sql, "user");
+    ///     Ok::<_, SqlError<Infallible>>(())
+    /// })?;
+    /// let ctes = with.close();
+    ///
+    /// select(&mut sql);
+    /// column(&mut sql, "*");
+    /// from_table(&mut sql, ctes.cte_ref("active")?);
+    ///
+    /// assert_eq!(
+    ///     sql.as_command(),
+    ///     "WITH active AS (SELECT * FROM user) SELECT * FROM active"
+    /// );
+    /// assert_eq!(ctes.cte_ref("actives"), Err(SqlError::UnknownCte));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cte_ref<'n>(&self, name: &'n str) -> Result<&'n str, SqlError<Infallible>> {
+        if self.contains(name) {
+            Ok(name)
+        } else {
+            Err(SqlError::UnknownCte)
+        }
+    }
+}
+
+/// Materialization hint of a common table expression (Postgres 12+).
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Materialization {
+    /// `AS MATERIALIZED`, the query is computed once.
+    Materialized,
+    /// `AS NOT MATERIALIZED`, the query may be folded into the main query.
+    NotMaterialized,
+    /// `AS`, the planner decides.
+    #[default]
+    Default,
+}
+
+impl Materialization {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Materialized => "MATERIALIZED",
+            Self::NotMaterialized => "NOT MATERIALIZED",
+            Self::Default => "",
+        }
+    }
+}
+
+/// `WITH <name> AS (<query>), ...` clause.
+///
+/// Each CTE is written by a closure sharing the argument buffer of the
+/// command, so the placeholders are numbered across all the CTEs and the main
+/// query. The closure receives the CTEs defined before it, which can be
+/// referenced with [Ctes::cte_ref].
+///
+/// The query of a CTE can be any statement, including a data-modifying
+/// `INSERT`, `UPDATE` or `DELETE` with a `RETURNING` clause, whose returned
+/// rows are read by the following CTEs and the main query. The columns
+/// returned by a CTE are not recorded as the columns returned by the command.
+pub struct With<'cmd, Sql: WriteSql<Arg>, Arg> {
+    sql: &'cmd mut Sql,
+    ctes: Ctes,
+    _arg: PhantomData<Arg>,
+}
+
+impl<'cmd, Sql, Arg> With<'cmd, Sql, Arg>
+where
+    Sql: WriteSql<Arg>,
+{
+    /// Opens a `WITH` clause, written with the first common table
+    /// expression, so a clause without any is left out of the command.
+    pub fn open(sql: &'cmd mut Sql) -> Self {
+        Self {
+            sql,
+            ctes: Ctes::new(),
+            _arg: PhantomData,
+        }
+    }
+
+    /// Writes a `<name> AS (<query>)` common table expression.
+    pub fn cte<F, E>(&mut self, name: &str, build: F) -> Result<&mut Self, E>
+    where
+        F: FnOnce(&mut Group<'_, Sql, Arg>, &Ctes) -> Result<(), E>,
+    {
+        self.cte_with(name, Materialization::Default, build)
+    }
+
+    /// Writes a `<name> AS [NOT] MATERIALIZED (<query>)` common table
+    /// expression with a materialization hint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, Void};
+    /// # use sqlstr::expr::{with, select, column, from_table, Materialization};
+    /// # use core::convert::Infallible;
+    /// # fn main() -> Result<(), Infallible> {
+    /// let mut sql: SqlCommand<Void> = SqlCommand::default();
+    /// with(&mut sql).cte_with("totals", Materialization::Materialized, |sql, _| {
+    ///     select(sql);
+    ///     column(sql, "sum(total)");
+    ///     from_table(sql, "order");
+    ///     Ok::<_, Infallible>(())
+    /// })?;
+    ///
+    /// assert_eq!(
+    ///     sql.as_command(),
+    ///     "WITH totals AS MATERIALIZED (SELECT sum(total) FROM order)"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cte_with<F, E>(
+        &mut self,
+        name: &str,
+        materialization: Materialization,
+        build: F,
+    ) -> Result<&mut Self, E>
+    where
+        F: FnOnce(&mut Group<'_, Sql, Arg>, &Ctes) -> Result<(), E>,
+    {
+        if self.ctes.0.is_empty() {
+            emit(self.sql, Token::Keyword("WITH"));
+        } else {
+            emit(self.sql, Token::Comma);
+        }
+        emit(self.sql, Token::Ident(name));
+        emit(self.sql, Token::Keyword("AS"));
+        if materialization != Materialization::Default {
+            emit(self.sql, Token::Keyword(materialization.as_str()));
+        }
+
+        let ctes = &self.ctes;
+        subquery(self.sql, |sql| build(sql, ctes))?;
+
+        self.ctes.0.push(name.to_owned());
+        Ok(self)
+    }
+
+    /// Closes the `WITH` clause, returning the defined CTEs.
+    pub fn close(self) -> Ctes {
+        self.ctes
+    }
+
+    /// Closes the `WITH` clause and writes the primary statement with
+    /// `build`, which receives the defined CTEs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, Void, sqlexpr, sqlvalue};
+    /// # use sqlstr::expr::{with, select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+    /// # use core::convert::Infallible;
+    /// # fn main() -> Result<(), SqlError<Infallible>> {
+    /// let mut sql: SqlCommand<Void> = SqlCommand::default();
+    /// with(&mut sql)
+    ///     .cte("active_users", |sql, _| {
+    ///         select(sql);
+    ///         column(sql, "id");
+    ///         from_table(sql, "user");
+    ///         filter_where(sql);
+    ///         lhs_binary_rhs(sql, sqlexpr::<bool>("active"), Cmp::Eq, sqlvalue(true))?;
+    ///         Ok::<_, SqlError<Infallible>>(())
+    ///     })?
+    ///     .main(|sql, ctes| {
+    ///         select(sql);
+    ///         column(sql, "count(*)");
+    ///         from_table(sql, ctes.cte_ref("active_users")?);
+    ///         Ok::<_, SqlError<Infallible>>(())
+    ///     })?;
+    ///
+    /// assert_eq!(
+    ///     sql.as_command(),
+    ///     "WITH active_users AS (SELECT id FROM user WHERE active = $1) \
+    ///      SELECT count(*) FROM active_users"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn main<F, E>(&mut self, build: F) -> Result<(), E>
+    where
+        F: FnOnce(&mut Sql, &Ctes) -> Result<(), E>,
+    {
+        build(self.sql, &self.ctes)
+    }
+}
+
+/// Opens a `WITH` clause, see [With].
+pub fn with<Sql, Arg>(sql: &mut Sql) -> With<'_, Sql, Arg>
+where
+    Sql: WriteSql<Arg>,
+{
+    With::open(sql)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        expr::{
+            column, delete_from, filter_where, from_table, insert_into, join, join_on,
+            lhs_binary_rhs, returning_columns, select, values, values_rows, Cmp, JoinType,
+        },
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+        SqlCommand,
+    };
+
+    type Error = SqlError<core::fmt::Error>;
+
+    #[test]
+    fn cte_valid_reference() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        let mut with = With::open(&mut sql);
+        with.cte("recent", |sql, _| {
+            select(sql);
+            column(sql, "*");
+            from_table(sql, "order");
+            filter_where(sql);
+            lhs_binary_rhs(sql, sqlexpr::<u32>("age_days"), Cmp::Lt, sqlvalue(7))?;
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+        let ctes = with.close();
+
+        select(&mut sql);
+        column(&mut sql, "count(*)");
+        from_table(&mut sql, ctes.cte_ref("recent").unwrap());
+
+        assert_eq!(
+            sql.as_command(),
+            "WITH recent AS (SELECT * FROM order WHERE age_days < $1) SELECT count(*) FROM recent"
+        );
+        assert_eq!(sql.arguments.as_str(), "7;");
+    }
+
+    #[test]
+    fn with_main_placeholders() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        with(&mut sql)
+            .cte("paid", |sql, _| {
+                select(sql);
+                column(sql, "customer_id, total");
+                from_table(sql, "invoice");
+                filter_where(sql);
+                lhs_binary_rhs(sql, sqlexpr::<&str>("status"), Cmp::Eq, sqlvalue("paid"))?;
+                Ok::<_, Error>(())
+            })
+            .unwrap()
+            .cte("big", |sql, ctes| {
+                select(sql);
+                column(sql, "customer_id");
+                from_table(sql, ctes.cte_ref("paid").map_err(SqlError::widen)?);
+                filter_where(sql);
+                lhs_binary_rhs(sql, sqlexpr::<u32>("total"), Cmp::Gt, sqlvalue(1000))?;
+                Ok::<_, Error>(())
+            })
+            .unwrap()
+            .main(|sql, ctes| {
+                select(sql);
+                column(sql, "count(*)");
+                from_table(sql, ctes.cte_ref("big").map_err(SqlError::widen)?);
+                filter_where(sql);
+                lhs_binary_rhs(sql, sqlexpr::<u32>("customer_id"), Cmp::Lt, sqlvalue(50))?;
+                Ok::<_, Error>(())
+            })
+            .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "WITH paid AS (SELECT customer_id, total FROM invoice WHERE status = $1), \
+             big AS (SELECT customer_id FROM paid WHERE total > $2) \
+             SELECT count(*) FROM big WHERE customer_id < $3"
+        );
+        assert_eq!(sql.arguments.as_str(), "paid;1000;50;");
+    }
+
+    #[test]
+    fn cte_materialization_hints() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        with(&mut sql)
+            .cte_with("a", Materialization::Materialized, |sql, _| {
+                select(sql);
+                column(sql, "1");
+                Ok::<_, Error>(())
+            })
+            .unwrap()
+            .cte_with("b", Materialization::NotMaterialized, |sql, _| {
+                select(sql);
+                column(sql, "2");
+                Ok::<_, Error>(())
+            })
+            .unwrap()
+            .cte_with("c", Materialization::Default, |sql, _| {
+                select(sql);
+                column(sql, "3");
+                Ok::<_, Error>(())
+            })
+            .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "WITH a AS MATERIALIZED (SELECT 1), b AS NOT MATERIALIZED (SELECT 2), c AS (SELECT 3)"
+        );
+    }
+
+    #[test]
+    fn with_without_ctes() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        with(&mut sql)
+            .main(|sql, _| {
+                select(sql);
+                column(sql, "1");
+                Ok::<_, Error>(())
+            })
+            .unwrap();
+
+        assert_eq!(sql.as_command(), "SELECT 1");
+    }
+
+    #[test]
+    fn cte_typo_reference() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        let mut with = With::open(&mut sql);
+        with.cte("recent", |sql, _| {
+            select(sql);
+            column(sql, "1");
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+        let ctes = with.close();
+
+        let err = ctes.cte_ref("recnet").unwrap_err();
+        assert_eq!(err, SqlError::UnknownCte);
+    }
+
+    #[test]
+    fn cte_referencing_previous_cte() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        let mut with = With::open(&mut sql);
+        with.cte("paid", |sql, _| {
+            select(sql);
+            column(sql, "customer_id, total");
+            from_table(sql, "invoice");
+            filter_where(sql);
+            lhs_binary_rhs(sql, sqlexpr::<&str>("status"), Cmp::Eq, sqlvalue("paid"))?;
+            Ok::<_, Error>(())
+        })
+        .unwrap()
+        .cte("top", |sql, ctes| {
+            assert!(ctes.cte_ref("top").is_err());
+            select(sql);
+            column(sql, "customer_id");
+            from_table(sql, ctes.cte_ref("paid").map_err(SqlError::widen)?);
+            filter_where(sql);
+            lhs_binary_rhs(sql, sqlexpr::<u32>("total"), Cmp::Gt, sqlvalue(1000))?;
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+        let ctes = with.close();
+        assert!(ctes.names().eq(["paid", "top"]));
+
+        select(&mut sql);
+        column(&mut sql, "c.name");
+        from_table(&mut sql, "customer c");
+        join(
+            &mut sql,
+            JoinType::Inner,
+            ctes.cte_ref("top").unwrap(),
+            Some("t"),
+        );
+        join_on(&mut sql);
+        sql.push_cmd(" t.customer_id = c.id");
+
+        assert_eq!(
+            sql.as_command(),
+            "WITH paid AS (SELECT customer_id, total FROM invoice WHERE status = $1), \
+             top AS (SELECT customer_id FROM paid WHERE total > $2) \
+             SELECT c.name FROM customer c INNER JOIN top AS t ON t.customer_id = c.id"
+        );
+        assert_eq!(sql.arguments.as_str(), "paid;1000;");
+    }
+
+    #[test]
+    fn cte_delete_returning() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        with(&mut sql)
+            .cte("moved", |sql, _| {
+                delete_from(sql, "queue");
+                filter_where(sql);
+                lhs_binary_rhs(sql, sqlexpr::<&str>("state"), Cmp::Eq, sqlvalue("done"))?;
+                returning_columns(sql, ["*"]);
+                Ok::<_, Error>(())
+            })
+            .unwrap()
+            .main(|sql, ctes| {
+                insert_into(sql, "queue_archive");
+                select(sql);
+                column(sql, "*");
+                from_table(sql, ctes.cte_ref("moved").map_err(SqlError::widen)?);
+                returning_columns(sql, ["id"]);
+                Ok::<_, Error>(())
+            })
+            .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "WITH moved AS (DELETE FROM queue WHERE state = $1 RETURNING *) \
+             INSERT INTO queue_archive SELECT * FROM moved RETURNING id"
+        );
+        assert_eq!(sql.arguments.as_str(), "done;");
+        assert_eq!(sql.returning_columns(), Some(["id".into()].as_slice()));
+    }
+
+    #[test]
+    fn cte_delete_returning_select() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        with(&mut sql)
+            .cte("moved", |sql, _| {
+                delete_from(sql, "queue");
+                filter_where(sql);
+                lhs_binary_rhs(sql, sqlexpr::<u32>("attempts"), Cmp::Gt, sqlvalue(5))?;
+                returning_columns(sql, ["id", "payload"]);
+                Ok::<_, Error>(())
+            })
+            .unwrap()
+            .main(|sql, ctes| {
+                select(sql);
+                column(sql, "*");
+                from_table(sql, ctes.cte_ref("moved").map_err(SqlError::widen)?);
+                Ok::<_, Error>(())
+            })
+            .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "WITH moved AS (DELETE FROM queue WHERE attempts > $1 RETURNING id, payload) \
+             SELECT * FROM moved"
+        );
+        assert_eq!(sql.arguments.as_str(), "5;");
+        assert_eq!(sql.returning_columns(), None);
+    }
+
+    #[test]
+    fn values_cte() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        with(&mut sql)
+            .cte("v(x)", |sql, _| {
+                values(sql);
+                values_rows(sql, 1, [[1], [2]])?;
+                Ok::<_, Error>(())
+            })
+            .unwrap()
+            .main(|sql, _| {
+                select(sql);
+                column(sql, "x");
+                from_table(sql, "v");
+                filter_where(sql);
+                lhs_binary_rhs(sql, sqlexpr::<u32>("x"), Cmp::Gt, sqlvalue(1))?;
+                Ok::<_, Error>(())
+            })
+            .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "WITH v(x) AS (VALUES ($1), ($2)) SELECT x FROM v WHERE x > $3"
+        );
+        assert_eq!(sql.arguments.as_str(), "1;2;1;");
+    }
+}
@@ -1,58 +1,105 @@
-// A lookup table to prevent the need for conditional branching
-// The value of the remainder of each step will be used as the index
-const LOOKUP: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+// A lookup table optimized for decimal lookups. Each item represents one possible two digits number.
+const DEC_LOOKUP: [[u8; 2]; 100] = dec_lookup();
 
-// A lookup table optimized for decimal lookups. Each two indices represents one possible number.
-const DEC_LOOKUP: &[u8; 200] = b"0001020304050607080910111213141516171819\
-                                 2021222324252627282930313233343536373839\
-                                 4041424344454647484950515253545556575859\
-                                 6061626364656667686970717273747576777879\
-                                 8081828384858687888990919293949596979899";
+// Evaluated at compile time, where an out of bounds index fails the build.
+#[allow(clippy::indexing_slicing, clippy::arithmetic_side_effects)]
+const fn dec_lookup() -> [[u8; 2]; 100] {
+    let mut lookup = [[b'0'; 2]; 100];
+    let mut tens = 0;
+    while tens < 10 {
+        let mut units = 0;
+        while units < 10 {
+            lookup[tens * 10 + units] = [b'0' + tens as u8, b'0' + units as u8];
+            units += 1;
+        }
+        tens += 1;
+    }
+    lookup
+}
+
+/// Two ASCII digits of a number in `0..100`.
+fn dec_pair(number: u32) -> [u8; 2] {
+    DEC_LOOKUP
+        .get(number as usize)
+        .copied()
+        .unwrap_or([b'0'; 2])
+}
+
+/// Returns the number of decimal digits needed to format `number`.
+pub const fn digits_u64(number: u64) -> usize {
+    match number.checked_ilog10() {
+        Some(log) => (log as usize).saturating_add(1),
+        None => 1,
+    }
+}
 
 /// Formats the u32 number into the `buf` and return a reference from the written buffer.
 pub fn format_u32_base10(mut number: u32, buf: &mut [u8; 10]) -> &str {
-    let mut index = buf.len() - 1;
-    if number == 0 {
-        buf[index] = b'0';
-        // SAFETY:
-        // The sliced `buf` contains a valid `'0'` character
-        return unsafe { core::str::from_utf8_unchecked(&buf[index..]) };
-    }
+    let digits = digits_u64(number as u64);
 
-    // Convert using optimized base 10 algorithm
-    while number > 9999 {
-        let rem = (number % 10000) as u16;
-        let (frst, scnd) = ((rem / 100) * 2, (rem % 100) * 2);
-        buf[index - 3..index - 1].copy_from_slice(&DEC_LOOKUP[frst as usize..frst as usize + 2]);
-        buf[index - 1..index + 1].copy_from_slice(&DEC_LOOKUP[scnd as usize..scnd as usize + 2]);
-        index = index.wrapping_sub(4);
-        number /= 10000;
+    // Convert using optimized base 10 algorithm, two digits at a time
+    for pair in buf.rchunks_exact_mut(2) {
+        if number < 10 {
+            let [_, digit] = dec_pair(number);
+            if let Some(last) = pair.last_mut() {
+                *last = digit;
+            }
+            break;
+        }
+        pair.copy_from_slice(&dec_pair(number % 100));
+        number /= 100;
+        if number == 0 {
+            break;
+        }
     }
 
-    if number > 999 {
-        let (frst, scnd) = ((number / 100) * 2, (number % 100) * 2);
-        buf[index - 3..index - 1].copy_from_slice(&DEC_LOOKUP[frst as usize..frst as usize + 2]);
-        buf[index - 1..index + 1].copy_from_slice(&DEC_LOOKUP[scnd as usize..scnd as usize + 2]);
-        index = index.wrapping_sub(4);
-    } else if number > 99 {
-        let section = (number as u16 / 10) * 2;
-        buf[index - 2..index].copy_from_slice(&DEC_LOOKUP[section as usize..section as usize + 2]);
-        buf[index] = LOOKUP[(number % 10) as usize];
-        index = index.wrapping_sub(3);
-    } else if number > 9 {
-        number *= 2;
-        buf[index - 1..index + 1]
-            .copy_from_slice(&DEC_LOOKUP[number as usize..number as usize + 2]);
-        index = index.wrapping_sub(2);
+    // a u32 has at most 10 digits, all within `buf`
+    let (_, number) = buf.split_at(buf.len().saturating_sub(digits));
+    // SAFETY:
+    // The sliced `buf` contains a formatted positive number (`u32`) represented
+    // through (`b'0'..=b'9'`)
+    unsafe { core::str::from_utf8_unchecked(number) }
+}
+
+/// Formats a percentage into the `buf` with at most 4 decimal digits and return a reference from
+/// the written buffer.
+///
+/// The percentage is clamped into `0..=100`, and a `NaN` is formatted as `0`.
+pub fn format_percent(percent: f32, buf: &mut [u8; 10]) -> &str {
+    let percent = if percent.is_nan() {
+        0.0
     } else {
-        buf[index] = LOOKUP[number as usize];
-        index = index.wrapping_sub(1);
+        percent.clamp(0.0, 100.0)
+    };
+    // Fixed-point with 4 decimal digits, rounded half up (at most 1_000_000)
+    let scaled = (percent * 10_000.0 + 0.5) as u32;
+
+    let mut int_buf = [0; 10];
+    let int = format_u32_base10(scaled / 10_000, &mut int_buf).as_bytes();
+
+    let frac = scaled % 10_000;
+    let [a, b] = dec_pair(frac / 100);
+    let [c, d] = dec_pair(frac % 100);
+    let frac_digits = [a, b, c, d];
+    let frac_len = frac_digits
+        .iter()
+        .rposition(|&digit| digit != b'0')
+        .map_or(0, |last| last.saturating_add(1));
+
+    let (frac, _) = frac_digits.split_at(frac_len);
+    let dot: &[u8] = if frac.is_empty() { b"" } else { b"." };
+
+    let mut len: usize = 0;
+    for (slot, &byte) in buf.iter_mut().zip(int.iter().chain(dot).chain(frac)) {
+        *slot = byte;
+        len = len.saturating_add(1);
     }
 
+    let (percent, _) = buf.split_at(len);
     // SAFETY:
-    // The sliced `buf` contains a formatted positive number (`u32`) represented
-    // through (`b'0'..=b'9'`)
-    unsafe { core::str::from_utf8_unchecked(&buf[index.wrapping_add(1)..]) }
+    // The sliced `buf` contains the formatted integer part and optionally a `'.'` followed by
+    // decimal digits (`b'0'..=b'9'`)
+    unsafe { core::str::from_utf8_unchecked(percent) }
 }
 
 #[cfg(test)]
@@ -88,4 +135,62 @@ mod test {
         assert_eq!(formated, "8293742");
         assert_eq!(buf, [0, 0, 0, b'8', b'2', b'9', b'3', b'7', b'4', b'2']);
     }
+
+    #[test]
+    fn u32_matches_display() {
+        use alloc::string::ToString;
+
+        let mut buf = [0; 10];
+        let mut number: u32 = 1;
+        for _ in 0..10_000 {
+            number = number.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            for value in [number, number >> 8, number >> 16, number >> 24] {
+                assert_eq!(format_u32_base10(value, &mut buf), value.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn u64_digits() {
+        assert_eq!(digits_u64(0), 1);
+        assert_eq!(digits_u64(7), 1);
+        assert_eq!(digits_u64(9), 1);
+        assert_eq!(digits_u64(10), 2);
+        assert_eq!(digits_u64(999), 3);
+        assert_eq!(digits_u64(1000), 4);
+        assert_eq!(digits_u64(u32::MAX as u64), 10);
+        assert_eq!(digits_u64(10_000_000_000_000_000_000), 20);
+        assert_eq!(digits_u64(u64::MAX), 20);
+    }
+
+    #[test]
+    fn u64_digits_match_u32_format() {
+        let mut buf = [0; 10];
+        for number in [0, 1, 9, 10, 99, 100, 12345, 999_999, u32::MAX] {
+            let formated = format_u32_base10(number, &mut buf);
+            assert_eq!(digits_u64(number as u64), formated.len());
+        }
+    }
+
+    #[test]
+    fn percent_inputs() {
+        let mut buf = [0; 10];
+        assert_eq!(format_percent(0.0, &mut buf), "0");
+        assert_eq!(format_percent(10.0, &mut buf), "10");
+        assert_eq!(format_percent(12.5, &mut buf), "12.5");
+        assert_eq!(format_percent(0.1, &mut buf), "0.1");
+        assert_eq!(format_percent(0.0001, &mut buf), "0.0001");
+        assert_eq!(format_percent(33.33333, &mut buf), "33.3333");
+        assert_eq!(format_percent(99.99999, &mut buf), "100");
+        assert_eq!(format_percent(100.0, &mut buf), "100");
+    }
+
+    #[test]
+    fn percent_out_of_range() {
+        let mut buf = [0; 10];
+        assert_eq!(format_percent(-1.0, &mut buf), "0");
+        assert_eq!(format_percent(250.0, &mut buf), "100");
+        assert_eq!(format_percent(f32::INFINITY, &mut buf), "100");
+        assert_eq!(format_percent(f32::NAN, &mut buf), "0");
+    }
 }
@@ -1,14 +1,45 @@
 #![doc = include_str!("./docs.md")]
 #![no_std]
+#![cfg_attr(
+    not(test),
+    deny(clippy::indexing_slicing, clippy::arithmetic_side_effects)
+)]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 mod base;
+mod error;
+mod execute;
 mod format_num;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod placeholder;
+#[cfg(feature = "postgres")]
+mod postgres;
+mod script;
+#[cfg(feature = "sqlx-postgres")]
+mod sqlx_pg;
 
+pub mod arguments;
+pub mod bulk;
 pub mod expr;
+pub mod scan;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 pub use crate::base::*;
+pub use crate::error::*;
+pub use crate::execute::*;
+#[cfg(feature = "metrics")]
+pub use crate::metrics::*;
+pub use crate::placeholder::*;
+#[cfg(feature = "postgres")]
+pub use crate::postgres::*;
+pub use crate::script::*;
+#[cfg(feature = "sqlx-postgres")]
+pub use crate::sqlx_pg::*;
 
 #[cfg(test)]
 mod test;
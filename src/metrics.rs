@@ -0,0 +1,424 @@
+use crate::expr::{fnv1a, select, FNV1A_OFFSET};
+use crate::scan::{tokens, SpanKind};
+use crate::{AppendArguments, SqlCommand};
+
+/// Clauses flagged in the shape of a command, in bit order, as their first
+/// keyword and their second keyword, if any.
+const CLAUSES: [(&str, Option<&str>); 16] = [
+    ("SELECT", None),
+    ("INSERT", None),
+    ("UPDATE", None),
+    ("DELETE", None),
+    ("WITH", None),
+    ("JOIN", None),
+    ("WHERE", None),
+    ("GROUP", Some("BY")),
+    ("HAVING", None),
+    ("ORDER", Some("BY")),
+    ("LIMIT", None),
+    ("OFFSET", None),
+    ("RETURNING", None),
+    ("ON", Some("CONFLICT")),
+    ("UNION", None),
+    ("FOR", Some("UPDATE")),
+];
+
+/// Metrics of a finished command, reported to the [MetricsSink] registered
+/// with [SqlCommand::with_metrics].
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CommandMetrics {
+    /// Length of the command text, in bytes.
+    pub text_len: usize,
+    /// Number of arguments bound by the command.
+    pub argument_count: u32,
+    /// Coarse hash of the shape of the command, combining the clauses
+    /// written and the tables referenced.
+    ///
+    /// Commands differing only in the bound values or in the length of
+    /// their lists share the same shape.
+    pub shape_hash: u32,
+}
+
+impl CommandMetrics {
+    /// Measures the command.
+    pub fn of<Arg>(sql: &SqlCommand<Arg>) -> Self
+    where
+        Arg: AppendArguments,
+    {
+        let command = sql.as_command();
+
+        let flags = fnv1a(FNV1A_OFFSET, &clause_flags(command).to_le_bytes());
+        let shape_hash = sql.tables().iter().fold(flags, |hash, table| {
+            fnv1a(fnv1a(hash, table.as_bytes()), &[0])
+        });
+
+        Self {
+            text_len: command.len(),
+            argument_count: sql.arguments.argument_count(),
+            shape_hash,
+        }
+    }
+}
+
+/// Receiver of the [CommandMetrics] of finished commands, used to size
+/// prepared statement caches and to find the shapes of commands built.
+///
+/// The sink is registered for the whole life of the commands, usually as a
+/// `static`, and shared between threads, so implementations record the
+/// metrics with interior mutability (e.g. atomics or a lock).
+pub trait MetricsSink {
+    fn record(&self, metrics: CommandMetrics);
+}
+
+/// Sink registered into a command, if any, with the measure of its argument
+/// buffer.
+pub(crate) struct MetricsHook<Arg>(Option<Hook<Arg>>);
+
+struct Hook<Arg> {
+    sink: &'static (dyn MetricsSink + Sync),
+    measure: fn(&SqlCommand<Arg>) -> CommandMetrics,
+}
+
+impl<Arg> MetricsHook<Arg> {
+    /// Hook of a command without a sink.
+    pub(crate) const NONE: Self = Self(None);
+
+    /// Takes the sink out of the hook, so the command is reported once.
+    pub(crate) fn take(&mut self) -> Self {
+        Self(self.0.take())
+    }
+
+    pub(crate) fn report(self, sql: &SqlCommand<Arg>) {
+        if let Some(hook) = self.0 {
+            hook.sink.record((hook.measure)(sql));
+        }
+    }
+}
+
+/// A clone of a command is not reported, the sink stays with the command it
+/// was registered into.
+impl<Arg> Clone for MetricsHook<Arg> {
+    fn clone(&self) -> Self {
+        Self::NONE
+    }
+}
+
+impl<Arg> Default for MetricsHook<Arg> {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// The sink is not part of the command, every hook is equal.
+impl<Arg> PartialEq for MetricsHook<Arg> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<Arg> Eq for MetricsHook<Arg> {}
+
+impl<Arg> core::fmt::Debug for MetricsHook<Arg> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("MetricsHook")
+    }
+}
+
+impl<Arg> SqlCommand<Arg>
+where
+    Arg: AppendArguments,
+{
+    /// Registers `sink` to receive the [CommandMetrics] of the command when
+    /// it is finished.
+    ///
+    /// The metrics are reported exactly once, by the first terminal method
+    /// called: the `end` and `returning` methods of the builders, such as
+    /// [UpdateBuilder::end](crate::expr::UpdateBuilder), or
+    /// [into_parts](Self::into_parts),
+    /// [into_parts_with_options](Self::into_parts_with_options) and
+    /// [into_command](Self::into_command) for a command built with the
+    /// expression functions. A command merged into another, as a subquery or
+    /// a combined query, is reported with the command containing it. A clone
+    /// of the command is not registered to the sink, only the original
+    /// command is reported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, Void, CommandMetrics, MetricsSink};
+    /// # use sqlstr::expr::{column, from_table, filter_where, lhs_binary_rhs, Cmp};
+    /// # use sqlstr::{select_with_metrics, sqlexpr, sqlvalue};
+    /// # use core::{convert::Infallible, sync::atomic::{AtomicUsize, Ordering}};
+    /// # fn main() -> Result<(), SqlError<Infallible>> {
+    /// struct LongestCommand(AtomicUsize);
+    ///
+    /// impl MetricsSink for LongestCommand {
+    ///     fn record(&self, metrics: CommandMetrics) {
+    ///         self.0.fetch_max(metrics.text_len, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// static SINK: LongestCommand = LongestCommand(AtomicUsize::new(0));
+    ///
+    /// let mut sql = select_with_metrics(Void::new(), &SINK);
+    /// column(&mut sql, "id");
+    /// from_table(&mut sql, "user");
+    /// filter_where(&mut sql);
+    /// lhs_binary_rhs(&mut sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(7))?;
+    /// let (command, _) = sql.into_parts()?;
+    ///
+    /// assert_eq!(SINK.0.load(Ordering::Relaxed), command.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_metrics(mut self, sink: &'static (dyn MetricsSink + Sync)) -> Self {
+        self.metrics = MetricsHook(Some(Hook {
+            sink,
+            measure: CommandMetrics::of,
+        }));
+        self
+    }
+}
+
+/// Starts a `SELECT` command binding into `arguments`, reporting its
+/// [CommandMetrics] to `sink` when finished, see
+/// [with_metrics](SqlCommand::with_metrics).
+pub fn select_with_metrics<Arg>(
+    arguments: Arg,
+    sink: &'static (dyn MetricsSink + Sync),
+) -> SqlCommand<Arg>
+where
+    Arg: AppendArguments,
+{
+    let mut sql = SqlCommand::new(alloc::string::String::new(), arguments).with_metrics(sink);
+    select(&mut sql);
+    sql
+}
+
+/// Flags of the clauses written in `command`, matching the keywords outside
+/// quoted text and comments, ignoring case.
+fn clause_flags(command: &str) -> u16 {
+    let is_word = |ch: char| ch.is_alphanumeric() || ch == '_' || ch == '$';
+
+    let mut flags = 0u16;
+    let mut previous: Option<&str> = None;
+    for span in tokens(command) {
+        let Ok(span) = span else { break };
+        if span.kind != SpanKind::Text {
+            previous = None;
+            continue;
+        }
+        for word in span
+            .text
+            .split(|ch| !is_word(ch))
+            .filter(|word| !word.is_empty())
+        {
+            for (bit, (first, second)) in CLAUSES.iter().enumerate() {
+                let matched = match second {
+                    None => word.eq_ignore_ascii_case(first),
+                    Some(second) => {
+                        word.eq_ignore_ascii_case(second)
+                            && previous.is_some_and(|previous| previous.eq_ignore_ascii_case(first))
+                    }
+                };
+                if matched {
+                    flags |= 1u16.checked_shl(bit as u32).unwrap_or(0);
+                }
+            }
+            previous = Some(word);
+        }
+    }
+    flags
+}
+
+#[cfg(test)]
+mod test {
+    use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+    use super::{clause_flags, select_with_metrics, CommandMetrics, MetricsSink};
+    use crate::{
+        expr::{
+            columns_iter, delete_from, filter_where, from_table, insert_columns_iter, insert_into,
+            lhs_binary_rhs, limit, select, values, values_rows, Cmp, DeleteBuilder, UpdateBuilder,
+            ValuesBuilder,
+        },
+        sqlexpr, sqlvalue, SqlCommand, Void,
+    };
+
+    /// Sink keeping the number of events and the last one.
+    struct RecordingSink {
+        events: AtomicUsize,
+        text_len: AtomicUsize,
+        argument_count: AtomicU32,
+        shape_hash: AtomicU32,
+    }
+
+    impl RecordingSink {
+        const fn new() -> Self {
+            Self {
+                events: AtomicUsize::new(0),
+                text_len: AtomicUsize::new(0),
+                argument_count: AtomicU32::new(0),
+                shape_hash: AtomicU32::new(0),
+            }
+        }
+
+        fn events(&self) -> usize {
+            self.events.load(Ordering::SeqCst)
+        }
+
+        fn last(&self) -> CommandMetrics {
+            CommandMetrics {
+                text_len: self.text_len.load(Ordering::SeqCst),
+                argument_count: self.argument_count.load(Ordering::SeqCst),
+                shape_hash: self.shape_hash.load(Ordering::SeqCst),
+            }
+        }
+    }
+
+    impl MetricsSink for RecordingSink {
+        fn record(&self, metrics: CommandMetrics) {
+            self.text_len.store(metrics.text_len, Ordering::SeqCst);
+            self.argument_count
+                .store(metrics.argument_count, Ordering::SeqCst);
+            self.shape_hash.store(metrics.shape_hash, Ordering::SeqCst);
+            self.events.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn insert(rows: u32) -> SqlCommand<Void> {
+        let mut sql: SqlCommand<Void> = SqlCommand::default();
+        insert_into(&mut sql, "point");
+        insert_columns_iter(&mut sql, ["x", "y"]).unwrap();
+        values(&mut sql);
+        values_rows::<_, _, _, _, u32>(&mut sql, 2, (0..rows).map(|i| [i, i])).unwrap();
+        sql
+    }
+
+    #[test]
+    fn one_event_per_command() {
+        static SINK: RecordingSink = RecordingSink::new();
+
+        let small = insert(1).with_metrics(&SINK);
+        let small_len = small.as_command().len();
+        assert_eq!(SINK.events(), 0);
+        small.into_parts().unwrap();
+        assert_eq!(SINK.events(), 1);
+        let small = SINK.last();
+        assert_eq!(small.text_len, small_len);
+        assert_eq!(small.argument_count, 2);
+
+        insert(3).with_metrics(&SINK).into_command();
+        let large = SINK.last();
+        assert_eq!(large.argument_count, 6);
+        assert_eq!(small.shape_hash, large.shape_hash);
+
+        let mut sql = select_with_metrics(Void::new(), &SINK);
+        columns_iter(&mut sql, ["x", "y"]);
+        from_table(&mut sql, "point");
+        filter_where(&mut sql);
+        sql.push_cmd(" x > y");
+        limit(&mut sql, 10).unwrap();
+        let select_len = sql.as_command().len();
+        sql.into_parts().unwrap();
+        let select = SINK.last();
+        assert_eq!(SINK.events(), 3);
+        assert_eq!(select.text_len, select_len);
+        assert_eq!(select.argument_count, 1);
+        assert_ne!(small.shape_hash, select.shape_hash);
+
+        insert(1).into_parts().unwrap();
+        assert_eq!(SINK.events(), 3);
+    }
+
+    #[test]
+    fn builders_report_once() {
+        static SINK: RecordingSink = RecordingSink::new();
+
+        let sql = UpdateBuilder::new(Void::new(), "user")
+            .with_metrics(&SINK)
+            .set("name", "ana")
+            .unwrap()
+            .filter_where(|sql| lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(1)))
+            .unwrap()
+            .end();
+        assert_eq!(SINK.events(), 1);
+        assert_eq!(SINK.last().argument_count, 2);
+        sql.into_parts().unwrap();
+        assert_eq!(SINK.events(), 1);
+
+        DeleteBuilder::new(Void::new(), "session")
+            .with_metrics(&SINK)
+            .filter_where(|sql| lhs_binary_rhs(sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(1)))
+            .unwrap()
+            .returning_all();
+        assert_eq!(SINK.events(), 2);
+
+        ValuesBuilder::new(Void::new(), 2)
+            .with_metrics(&SINK)
+            .rows([[1, 2]])
+            .unwrap()
+            .end()
+            .unwrap();
+        assert_eq!(SINK.events(), 3);
+        assert_eq!(SINK.last().argument_count, 2);
+
+        let mut sql: SqlCommand<Void> = SqlCommand::default().with_metrics(&SINK);
+        delete_from(&mut sql, "session");
+        sql.push_cmd("\0");
+        assert!(sql.into_parts().is_err());
+        assert_eq!(SINK.events(), 3);
+    }
+
+    #[test]
+    fn clones_not_reported() {
+        static SINK: RecordingSink = RecordingSink::new();
+
+        let sql = insert(1).with_metrics(&SINK);
+        let clone = sql.clone();
+        assert_eq!(clone, sql);
+        clone.into_parts().unwrap();
+        assert_eq!(SINK.events(), 0);
+        sql.into_parts().unwrap();
+        assert_eq!(SINK.events(), 1);
+    }
+
+    #[test]
+    fn tables_in_shape() {
+        let shape = |table| {
+            let mut sql: SqlCommand<Void> = SqlCommand::default();
+            select(&mut sql);
+            columns_iter(&mut sql, ["id"]);
+            from_table(&mut sql, table);
+            CommandMetrics::of(&sql).shape_hash
+        };
+
+        assert_eq!(shape("a"), shape("a"));
+        assert_ne!(shape("a"), shape("b"));
+    }
+
+    #[test]
+    fn clause_keywords() {
+        let bit = |index: u32| 1u16 << index;
+        let select = bit(0);
+        let where_ = bit(6);
+        let order_by = bit(9);
+
+        assert_eq!(clause_flags("SELECT * FROM t WHERE a"), select | where_);
+        assert_eq!(clause_flags("select * from t where a"), select | where_);
+        assert_eq!(clause_flags("SELECT * FROM (SELECT 1)"), select);
+        assert_eq!(clause_flags("SELECT nowhere FROM t"), select);
+        assert_eq!(clause_flags("SELECT limit_x FROM t"), select);
+        assert_eq!(
+            clause_flags("SELECT * FROM t WHERE note = 'ORDER BY'"),
+            select | where_
+        );
+        assert_eq!(
+            clause_flags("SELECT * FROM t -- ORDER BY\nORDER\n  by a"),
+            select | order_by
+        );
+        assert_eq!(clause_flags("SELECT \"order\" BY FROM t"), select);
+        assert_eq!(clause_flags("SELECT order_by FROM t"), select);
+    }
+}
@@ -0,0 +1,485 @@
+use alloc::{string::String, vec::Vec};
+use core::convert::Infallible;
+
+use crate::expr::separator_optional;
+use crate::format_num::format_u32_base10;
+use crate::scan::{tokens, SpanKind};
+use crate::{ArgumentBuffer, SqlError, WriteSql};
+
+/// Rewrites a sql command shifting every `$n` placeholder by `offset`,
+/// writing the result into `out`.
+///
+/// On error, `out` is truncated back to its length before the call.
+///
+/// Placeholders inside string literals (`'...'`, `E'...'`), quoted
+/// identifiers (`"..."`), dollar-quoted strings (`$$...$$`, `$tag$...$tag$`)
+/// and comments (`-- ...`, `/* ... */`) are left untouched. Returns the
+/// greatest placeholder written, or `0` if the command has no placeholders.
+///
+/// A `$0` placeholder or a shifted placeholder that overflows `u32` returns an
+/// [InvalidPlaceholder](SqlError::InvalidPlaceholder) error. When `strict` is
+/// set, the placeholders of the command must also be contiguous, from `$1` to
+/// the greatest one, otherwise an error is returned for the first gap.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{renumber_placeholders, SqlError};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut out = String::new();
+/// let max = renumber_placeholders("id = $1 AND tag = '$1' AND n = $2::int", 3, true, &mut out)?;
+///
+/// assert_eq!(out, "id = $4 AND tag = '$1' AND n = $5::int");
+/// assert_eq!(max, 5);
+///
+/// out.clear();
+/// renumber_placeholders("SELECT $$ $1 $$, $1 -- $2", 1, true, &mut out)?;
+///
+/// assert_eq!(out, "SELECT $$ $1 $$, $2 -- $2");
+/// # Ok(())
+/// # }
+/// ```
+pub fn renumber_placeholders(
+    sql: &str,
+    offset: u32,
+    strict: bool,
+    out: &mut String,
+) -> Result<u32, SqlError<Infallible>> {
+    let len = out.len();
+    let renumbered = shift_placeholders(sql, offset, strict, out);
+    if renumbered.is_err() {
+        out.truncate(len);
+    }
+    renumbered
+}
+
+fn shift_placeholders(
+    sql: &str,
+    offset: u32,
+    strict: bool,
+    out: &mut String,
+) -> Result<u32, SqlError<Infallible>> {
+    let mut seen = Vec::new();
+    let mut max = 0;
+
+    out.reserve(sql.len());
+    for_each_segment::<Infallible, _>(sql, |segment| {
+        match segment {
+            Segment::Text(text) => out.push_str(text),
+            Segment::Placeholder { number, position } => {
+                let Some(shifted) = number.checked_add(offset) else {
+                    return Err(SqlError::InvalidPlaceholder { position });
+                };
+
+                if strict {
+                    seen.push((number, position));
+                }
+                max = max.max(shifted);
+
+                let mut buf = [0; 10];
+                out.push('$');
+                out.push_str(format_u32_base10(shifted, &mut buf));
+            }
+        }
+        Ok(())
+    })?;
+
+    if strict {
+        check_contiguous::<Infallible>(seen)?;
+    }
+
+    Ok(max)
+}
+
+/// Writes a static fragment containing `$n` placeholders, such as a
+/// [static_condition](crate::static_condition) or
+/// [static_join](crate::static_join) with placeholder operands, binding
+/// `values[n - 1]` for each `$n`.
+///
+/// The placeholders of the fragment are numbered from `$1`, independently of
+/// the arguments already written. Each placeholder is rewritten to continue
+/// the numbering of the command, so the fragment can be written after other
+/// values. Each value is bound once, in the order its placeholder first
+/// appears, and a repeated placeholder reuses the number written by
+/// [push_value](WriteSql::push_value) for its first occurrence. A writer not
+/// ending the command with a `$n` placeholder on
+/// [push_value](WriteSql::push_value) returns an
+/// [InvalidPlaceholder](SqlError::InvalidPlaceholder) error for the
+/// placeholder.
+///
+/// The placeholders must be contiguous, from `$1` to the number of `values`,
+/// otherwise an [InvalidPlaceholder](SqlError::InvalidPlaceholder) or an
+/// [ArgumentCountMismatch](SqlError::ArgumentCountMismatch) error is returned
+/// without writing anything.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void, static_join, push_static_values};
+/// # use sqlstr::expr::{select, column_expr_value, from_table_as};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut sql);
+/// column_expr_value(&mut sql, "", "label", "")?;
+/// from_table_as(&mut sql, "user", "u");
+/// push_static_values(
+///     &mut sql,
+///     static_join!(INNER "tenant" ON "tenant.id" = "$1" AND "u.tenant_id" = "$1"),
+///     &[7],
+/// )?;
+///
+/// assert_eq!(
+///     sql.as_command(),
+///     "SELECT $1 FROM user AS u INNER JOIN tenant ON tenant.id = $2 AND u.tenant_id = $2"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn push_static_values<Sql, Arg, T>(
+    sql: &mut Sql,
+    fragment: &str,
+    values: &[T],
+) -> Result<(), SqlError<Arg::Error>>
+where
+    Sql: WriteSql<Arg>,
+    Arg: ArgumentBuffer<T>,
+    T: Clone,
+{
+    let max = placeholder_count(fragment).map_err(SqlError::widen)?;
+    if max as usize != values.len() {
+        return Err(SqlError::ArgumentCountMismatch {
+            expected: max as usize,
+            found: values.len(),
+        });
+    }
+
+    // command placeholder bound to each value, once written
+    let mut bound = Vec::new();
+    bound.resize(values.len(), None);
+
+    separator_optional(sql);
+    for_each_segment(fragment, |segment| {
+        match segment {
+            Segment::Text(text) => sql.push_cmd(text),
+            Segment::Placeholder { number, position } => {
+                let index = (number as usize)
+                    .checked_sub(1)
+                    .ok_or(SqlError::InvalidPlaceholder { position })?;
+                let (Some(slot), Some(value)) = (bound.get_mut(index), values.get(index)) else {
+                    return Err(SqlError::InvalidPlaceholder { position });
+                };
+                match slot {
+                    Some(placeholder) => {
+                        let mut buf = [0; 10];
+                        sql.push_cmd("$");
+                        sql.push_cmd(format_u32_base10(*placeholder, &mut buf));
+                    }
+                    None => {
+                        sql.push_value(value.clone())?;
+                        let placeholder = last_placeholder(sql.as_command())
+                            .ok_or(SqlError::InvalidPlaceholder { position })?;
+                        *slot = Some(placeholder);
+                    }
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Number of the `$n` placeholder ending a command, as written by
+/// [push_value](WriteSql::push_value).
+fn last_placeholder(sql: &str) -> Option<u32> {
+    let digits = sql.bytes().rev().take_while(u8::is_ascii_digit).count();
+    let start = sql.len().checked_sub(digits)?;
+    sql.get(..start)?.strip_suffix('$')?;
+    sql.get(start..)?.parse().ok()
+}
+
+/// Returns the number of arguments bound by the placeholders of `sql`,
+/// checking that they are contiguous from `$1`.
+pub(crate) fn placeholder_count(sql: &str) -> Result<u32, SqlError<Infallible>> {
+    let mut seen = Vec::new();
+    for_each_segment::<Infallible, _>(sql, |segment| {
+        if let Segment::Placeholder { number, position } = segment {
+            seen.push((number, position));
+        }
+        Ok(())
+    })?;
+    check_contiguous(seen)
+}
+
+/// Part of a sql command.
+pub(crate) enum Segment<'s> {
+    Text(&'s str),
+    /// A `$n` placeholder starting at the byte `position`.
+    Placeholder {
+        number: u32,
+        position: usize,
+    },
+}
+
+/// Splits a sql command into text and `$n` placeholders, skipping the
+/// placeholders inside string literals, quoted identifiers, dollar-quoted
+/// strings and comments, see [tokens].
+///
+/// A `$0` placeholder or a placeholder that overflows `u32` returns an
+/// [InvalidPlaceholder](SqlError::InvalidPlaceholder) error, and a quoted
+/// text never closed an [InvalidText](SqlError::InvalidText) error.
+pub(crate) fn for_each_segment<'s, E, F>(sql: &'s str, mut segment: F) -> Result<(), SqlError<E>>
+where
+    F: FnMut(Segment<'s>) -> Result<(), SqlError<E>>,
+{
+    let mut text_start = 0;
+
+    for span in tokens(sql) {
+        let span = span.map_err(|err| SqlError::InvalidText {
+            position: err.position,
+        })?;
+        if span.kind != SpanKind::Placeholder {
+            continue;
+        }
+
+        let position = span.position;
+        let number = span
+            .text
+            .get(1..)
+            .and_then(|digits| digits.parse::<u32>().ok())
+            .filter(|&number| number != 0)
+            .ok_or(SqlError::InvalidPlaceholder { position })?;
+
+        let text = sql
+            .get(text_start..position)
+            .ok_or(SqlError::InvalidText { position })?;
+        segment(Segment::Text(text))?;
+        segment(Segment::Placeholder { number, position })?;
+        text_start = position.saturating_add(span.text.len());
+    }
+
+    let text = sql.get(text_start..).ok_or(SqlError::InvalidText {
+        position: text_start,
+    })?;
+    segment(Segment::Text(text))
+}
+
+/// Checks that the placeholders are contiguous, from `$1` to the greatest one,
+/// returning the greatest placeholder.
+fn check_contiguous<E>(mut seen: Vec<(u32, usize)>) -> Result<u32, SqlError<E>> {
+    seen.sort_unstable();
+    seen.dedup_by_key(|(number, _)| *number);
+
+    let mut max = 0;
+    for ((number, position), expected) in seen.into_iter().zip(1..) {
+        if number != expected {
+            return Err(SqlError::InvalidPlaceholder { position });
+        }
+        max = number;
+    }
+    Ok(max)
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::String;
+
+    use super::*;
+
+    type Error = SqlError<Infallible>;
+
+    fn renumber(sql: &str, offset: u32, strict: bool) -> Result<(String, u32), Error> {
+        let mut out = String::new();
+        let max = renumber_placeholders(sql, offset, strict, &mut out)?;
+        Ok((out, max))
+    }
+
+    #[test]
+    fn single_and_multi_digit() {
+        assert_eq!(
+            renumber("$1, $10, $2", 5, false).unwrap(),
+            ("$6, $15, $7".into(), 15)
+        );
+        assert_eq!(renumber("$9", 1, false).unwrap(), ("$10".into(), 10));
+    }
+
+    #[test]
+    fn error_truncates_the_output() {
+        let mut out = String::from("SELECT ");
+        assert_eq!(
+            renumber_placeholders("$1, $3", 0, true, &mut out),
+            Err(SqlError::InvalidPlaceholder { position: 4 })
+        );
+        assert_eq!(out, "SELECT ");
+
+        assert_eq!(
+            renumber_placeholders("$1, $4294967295", 1, false, &mut out),
+            Err(SqlError::InvalidPlaceholder { position: 4 })
+        );
+        assert_eq!(out, "SELECT ");
+    }
+
+    #[test]
+    fn zero_offset_and_no_placeholders() {
+        assert_eq!(
+            renumber("SELECT $1, $2", 0, true).unwrap(),
+            ("SELECT $1, $2".into(), 2)
+        );
+        assert_eq!(
+            renumber("SELECT 1 AS a$", 4, true).unwrap(),
+            ("SELECT 1 AS a$".into(), 0)
+        );
+    }
+
+    #[test]
+    fn adjacent_to_casts_and_end_of_string() {
+        assert_eq!(
+            renumber("$1::int + $2", 2, true).unwrap(),
+            ("$3::int + $4".into(), 4)
+        );
+        assert_eq!(renumber("x = $1)", 1, true).unwrap(), ("x = $2)".into(), 2));
+    }
+
+    #[test]
+    fn inside_quotes() {
+        assert_eq!(
+            renumber("a = '$1' AND \"$2\" = $1 AND b = 'it''s $3'", 1, true).unwrap(),
+            ("a = '$1' AND \"$2\" = $2 AND b = 'it''s $3'".into(), 2)
+        );
+    }
+
+    #[test]
+    fn invalid_placeholders() {
+        assert_eq!(
+            renumber("a = $0", 1, false),
+            Err(SqlError::InvalidPlaceholder { position: 4 })
+        );
+        assert_eq!(
+            renumber("a = $1 AND b = $3", 1, true),
+            Err(SqlError::InvalidPlaceholder { position: 15 })
+        );
+        assert_eq!(
+            renumber("a = $1 AND b = $3", 1, false).unwrap(),
+            ("a = $2 AND b = $4".into(), 4)
+        );
+        assert_eq!(
+            renumber("a = $4294967295", 1, false),
+            Err(SqlError::InvalidPlaceholder { position: 4 })
+        );
+        assert_eq!(
+            renumber("a = $99999999999", 0, false),
+            Err(SqlError::InvalidPlaceholder { position: 4 })
+        );
+    }
+
+    #[test]
+    fn static_values_after_dynamic_value() {
+        use crate::expr::{column_expr_value, filter_where, select};
+        use crate::{static_condition, test::TestArgs, SqlCommand};
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column_expr_value(&mut sql, "", "x", "").unwrap();
+        filter_where(&mut sql);
+        push_static_values(
+            &mut sql,
+            static_condition!("a" = "$1" AND "b" > "$2" OR "c" = "'$1'"),
+            &[10, 20],
+        )
+        .unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT $1 WHERE a = $2 AND b > $3 OR c = '$1'"
+        );
+        assert_eq!(sql.arguments.as_str(), "x;10;20;");
+    }
+
+    #[test]
+    fn static_values_repeated_placeholder() {
+        use crate::expr::{column_expr_value, filter_where, select};
+        use crate::{test::TestArgs, SqlCommand};
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        select(&mut sql);
+        column_expr_value(&mut sql, "", "x", "").unwrap();
+        filter_where(&mut sql);
+        push_static_values(&mut sql, "t.a = $1 OR t.b = $1 OR t.c = $2", &[10, 20]).unwrap();
+        push_static_values(&mut sql, "AND t.d IN ($2, $1, $2)", &[30, 40]).unwrap();
+
+        assert_eq!(
+            sql.as_command(),
+            "SELECT $1 WHERE t.a = $2 OR t.b = $2 OR t.c = $3 AND t.d IN ($4, $5, $4)"
+        );
+        assert_eq!(sql.arguments.as_str(), "x;10;20;40;30;");
+    }
+
+    #[test]
+    fn static_values_repeated_without_arguments_hook() {
+        use crate::{test::TestArgs, ArgumentBuffer, SqlCommand, SqlExpr};
+
+        /// Writer relying on the default [WriteSql::arguments].
+        struct Writer(SqlCommand<TestArgs>);
+
+        impl WriteSql<TestArgs> for Writer {
+            fn push_expr<T>(
+                &mut self,
+                operand: SqlExpr<'_, T>,
+            ) -> Result<(), <TestArgs as ArgumentBuffer<T>>::Error>
+            where
+                TestArgs: ArgumentBuffer<T>,
+            {
+                self.0.push_expr(operand)
+            }
+
+            fn push_value<T>(
+                &mut self,
+                value: T,
+            ) -> Result<(), <TestArgs as ArgumentBuffer<T>>::Error>
+            where
+                TestArgs: ArgumentBuffer<T>,
+            {
+                self.0.push_value(value)
+            }
+
+            fn push_cmd(&mut self, expr: &str) {
+                self.0.push_cmd(expr)
+            }
+
+            fn as_command(&self) -> &str {
+                self.0.as_command()
+            }
+        }
+
+        let mut sql = Writer(SqlCommand::default());
+        sql.push_cmd("SELECT ");
+        sql.push_value(1).unwrap();
+        push_static_values(&mut sql, "WHERE a = $1 OR b = $1", &[10]).unwrap();
+
+        assert_eq!(sql.as_command(), "SELECT $1 WHERE a = $2 OR b = $2");
+        assert_eq!(sql.0.arguments.as_str(), "1;10;");
+    }
+
+    #[test]
+    fn static_values_mismatch() {
+        use crate::{test::TestArgs, SqlCommand};
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+
+        assert_eq!(
+            push_static_values(&mut sql, "a = $1 AND b = $3", &[1, 2, 3]),
+            Err(SqlError::InvalidPlaceholder { position: 15 })
+        );
+        assert_eq!(
+            push_static_values(&mut sql, "a = $1 AND b = $2", &[1]),
+            Err(SqlError::ArgumentCountMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(sql.as_command(), "");
+        assert_eq!(sql.arguments.as_str(), "");
+    }
+}
@@ -0,0 +1,94 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+
+use postgres_types::ToSql;
+
+use crate::{AppendArguments, ArgumentBuffer, ArgumentError};
+
+/// Argument buffer holding [ToSql] values, to bind them with the
+/// `postgres` and `tokio-postgres` clients.
+///
+/// Available with the `postgres` feature.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{PgToSqlArguments, SqlCommand, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), Infallible> {
+/// let mut sql: SqlCommand<PgToSqlArguments> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, "name");
+/// from_table(&mut sql, "user");
+/// filter_where(&mut sql);
+/// lhs_binary_rhs(&mut sql, sqlexpr::<i32>("id"), Cmp::Eq, sqlvalue(7))?;
+///
+/// let params = sql.arguments.as_params();
+/// // client.query(sql.as_command(), &params)
+/// assert_eq!(sql.as_command(), "SELECT name FROM user WHERE id = $1");
+/// assert_eq!(params.len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Default)]
+pub struct PgToSqlArguments(Vec<Box<dyn ToSql + Sync + Send>>);
+
+impl PgToSqlArguments {
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity(capacity))
+    }
+
+    /// Returns the parameters of the command, in placeholder order, as taken
+    /// by the `query` and `execute` methods of the clients.
+    pub fn as_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.0
+            .iter()
+            .map(|value| value.as_ref() as &(dyn ToSql + Sync))
+            .collect()
+    }
+
+    /// Returns the values pushed.
+    pub fn into_inner(self) -> Vec<Box<dyn ToSql + Sync + Send>> {
+        self.0
+    }
+}
+
+impl ArgumentError for PgToSqlArguments {
+    type Error = Infallible;
+}
+
+impl AppendArguments for PgToSqlArguments {
+    type Error = Infallible;
+
+    fn argument_count(&self) -> u32 {
+        u32::try_from(self.0.len()).unwrap_or(u32::MAX)
+    }
+
+    fn append(&mut self, mut other: Self) -> Result<(), Self::Error> {
+        self.0.append(&mut other.0);
+        Ok(())
+    }
+}
+
+impl<T> ArgumentBuffer<T> for PgToSqlArguments
+where
+    T: ToSql + Sync + Send + 'static,
+{
+    type Error = Infallible;
+
+    fn push(&mut self, value: T) -> Result<(), Self::Error> {
+        self.0.push(Box::new(value));
+        Ok(())
+    }
+
+    fn count(&self) -> u32 {
+        u32::try_from(self.0.len()).unwrap_or(u32::MAX)
+    }
+}
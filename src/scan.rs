@@ -0,0 +1,497 @@
+//! Lexer of sql command text.
+//!
+//! Every feature reading the text of a command, such as the placeholder
+//! renumbering or the script validation, splits it with [tokens], so a `$`
+//! inside a string literal, a quoted identifier, a comment or a dollar-quoted
+//! string (`$$...$$`, `$tag$...$tag$`) is never read as a `$n` placeholder.
+
+use core::iter::FusedIterator;
+
+/// Kind of a [Span] of a command.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    /// Text outside quotes and comments, such as keywords and identifiers.
+    Text,
+    /// A `$n` placeholder.
+    Placeholder,
+    /// A string literal (`'...'`), an escape string literal (`E'...'`) or a
+    /// quoted identifier (`"..."`).
+    Quoted,
+    /// A dollar-quoted string, `$$...$$` or `$tag$...$tag$`.
+    DollarQuoted,
+    /// A line comment (`-- ...`), with its line break, or a block comment
+    /// (`/* ... */`), which may nest.
+    Comment,
+}
+
+/// Part of a command returned by [tokens].
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Span<'s> {
+    pub kind: SpanKind,
+    /// Byte position of the span in the command.
+    pub position: usize,
+    /// Text of the span, including its quotes.
+    pub text: &'s str,
+}
+
+/// A quoted text, dollar-quoted string or block comment starting at the byte
+/// `position` is never closed.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ScanError {
+    pub position: usize,
+}
+
+#[cfg(feature = "fmt")]
+impl core::fmt::Display for ScanError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "quoted text never closed at byte {}", self.position)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ScanError {}
+
+/// Iterator over the spans of a command, see [tokens].
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone)]
+pub struct Tokens<'s> {
+    sql: &'s str,
+    position: usize,
+}
+
+/// Splits a command into [Text](SpanKind::Text),
+/// [Placeholder](SpanKind::Placeholder), [Quoted](SpanKind::Quoted),
+/// [DollarQuoted](SpanKind::DollarQuoted) and [Comment](SpanKind::Comment)
+/// spans.
+///
+/// A `$` inside an identifier, as in `price$1`, neither starts a placeholder
+/// nor a dollar-quoted string. An escape string literal, as in `E'it\'s'`, is
+/// closed by the first quote not escaped by a backslash. A dollar-quoted string is closed by the first
+/// occurrence of its own tag only, so `$a$ $b$ $a$` is a single string.
+///
+/// A quoted text, dollar-quoted string or block comment never closed yields a
+/// [ScanError] at its opening, ending the iteration. A line comment may end
+/// with the command.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::scan::{tokens, SpanKind};
+/// let kinds: Vec<_> = tokens("SELECT $1, $$ $2 $$")
+///     .map(|span| span.map(|span| (span.kind, span.text)))
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(
+///     kinds,
+///     [
+///         (SpanKind::Text, "SELECT "),
+///         (SpanKind::Placeholder, "$1"),
+///         (SpanKind::Text, ", "),
+///         (SpanKind::DollarQuoted, "$$ $2 $$"),
+///     ]
+/// );
+/// ```
+pub const fn tokens(sql: &str) -> Tokens<'_> {
+    Tokens { sql, position: 0 }
+}
+
+/// How a command ends, outside quotes and comments, see [command_end].
+#[derive(Clone, Copy, Default)]
+pub(crate) struct CommandEnd {
+    /// Byte position of the `;` terminating the command, followed only by
+    /// whitespace and comments.
+    pub(crate) terminator: Option<usize>,
+    /// Whether the command ends inside a line comment, before the line break
+    /// closing it.
+    pub(crate) line_comment: bool,
+}
+
+/// Finds the `;` terminating a command and whether the command ends inside a
+/// line comment, where a terminator appended to the command would be
+/// commented out.
+///
+/// A command with a quoted text never closed ends inside the quotes, with
+/// neither a terminator nor a line comment.
+pub(crate) fn command_end(sql: &str) -> CommandEnd {
+    let mut end = CommandEnd::default();
+    for span in tokens(sql) {
+        let Ok(span) = span else {
+            return CommandEnd::default();
+        };
+        end.line_comment = span.kind == SpanKind::Comment
+            && span.text.starts_with("--")
+            && !span.text.ends_with('\n');
+        match span.kind {
+            SpanKind::Comment => {}
+            SpanKind::Text => {
+                let text = span.text.trim_end();
+                if !text.is_empty() {
+                    end.terminator = text
+                        .ends_with(';')
+                        .then(|| span.position.saturating_add(text.len()).saturating_sub(1));
+                }
+            }
+            _ => end.terminator = None,
+        }
+    }
+    end
+}
+
+/// Delimiter opening a span other than text.
+struct Opening<'s> {
+    kind: SpanKind,
+    /// Length of the opening delimiter.
+    len: usize,
+    closing: &'s str,
+    /// Whether a backslash escapes the next character of the body.
+    escapes: bool,
+}
+
+impl<'s> Tokens<'s> {
+    /// Returns the span opened at `position`, if any, `ident` being set
+    /// inside an identifier.
+    fn opening(&self, position: usize, ident: bool) -> Option<Opening<'s>> {
+        let bytes = self.sql.as_bytes();
+        let delimited = |kind, len, closing| {
+            Some(Opening {
+                kind,
+                len,
+                closing,
+                escapes: false,
+            })
+        };
+
+        match (bytes.get(position)?, bytes.get(position.saturating_add(1))) {
+            (b'\'', _) => delimited(SpanKind::Quoted, 1, "'"),
+            (b'E' | b'e', Some(b'\'')) if !ident => Some(Opening {
+                kind: SpanKind::Quoted,
+                len: 2,
+                closing: "'",
+                escapes: true,
+            }),
+            (b'"', _) => delimited(SpanKind::Quoted, 1, "\""),
+            (b'-', Some(b'-')) => delimited(SpanKind::Comment, 2, "\n"),
+            (b'/', Some(b'*')) => delimited(SpanKind::Comment, 2, "*/"),
+            (b'$', _) if ident => None,
+            (b'$', Some(digit)) if digit.is_ascii_digit() => {
+                let digits = bytes
+                    .get(position.saturating_add(1)..)?
+                    .iter()
+                    .take_while(|byte| byte.is_ascii_digit())
+                    .count();
+                // a placeholder is delimited by its digits
+                delimited(SpanKind::Placeholder, digits.saturating_add(1), "")
+            }
+            (b'$', _) => {
+                let tag = dollar_tag(self.sql, position)?;
+                delimited(SpanKind::DollarQuoted, tag.len(), tag)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'s> Iterator for Tokens<'s> {
+    type Item = Result<Span<'s>, ScanError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.position;
+        let bytes = self.sql.as_bytes();
+        bytes.get(start)?;
+
+        let mut position = start;
+        let mut ident = false;
+        let opening = loop {
+            let Some(&byte) = bytes.get(position) else {
+                break None;
+            };
+            if let Some(opening) = self.opening(position, ident) {
+                break Some(opening);
+            }
+            ident = if ident {
+                is_ident_continue(byte)
+            } else {
+                is_ident_start(byte)
+            };
+            position = position.saturating_add(1);
+        };
+
+        // every delimiter is ASCII, so the positions are char boundaries
+        let opening = match opening {
+            Some(opening) if position == start => opening,
+            _ => {
+                self.position = position;
+                return Some(Ok(Span {
+                    kind: SpanKind::Text,
+                    position: start,
+                    text: self.sql.get(start..position).unwrap_or_default(),
+                }));
+            }
+        };
+
+        let body = start.saturating_add(opening.len);
+        let end = if opening.kind == SpanKind::Placeholder {
+            body
+        } else {
+            let closing = if opening.escapes {
+                escaped_quote(bytes, body)
+            } else if opening.closing == "*/" {
+                block_comment_end(bytes, body)
+            } else {
+                self.sql
+                    .get(body..)
+                    .and_then(|rest| rest.find(opening.closing))
+            };
+            match closing {
+                Some(end) => body
+                    .saturating_add(end)
+                    .saturating_add(opening.closing.len()),
+                // a line comment ends with the command
+                None if opening.closing == "\n" => bytes.len(),
+                None => {
+                    self.position = bytes.len();
+                    return Some(Err(ScanError { position: start }));
+                }
+            }
+        };
+
+        self.position = end;
+        Some(Ok(Span {
+            kind: opening.kind,
+            position: start,
+            text: self.sql.get(start..end).unwrap_or_default(),
+        }))
+    }
+}
+
+impl FusedIterator for Tokens<'_> {}
+
+/// Returns the offset from `body` of the quote closing an escape string
+/// literal, skipping the characters escaped by a backslash and the doubled
+/// quotes.
+fn escaped_quote(bytes: &[u8], body: usize) -> Option<usize> {
+    let rest = bytes.get(body..)?;
+    let mut offset = 0;
+    while let Some(&byte) = rest.get(offset) {
+        match (byte, rest.get(offset.saturating_add(1))) {
+            (b'\\', Some(_)) | (b'\'', Some(b'\'')) => offset = offset.saturating_add(2),
+            (b'\'', _) => return Some(offset),
+            _ => offset = offset.saturating_add(1),
+        }
+    }
+    None
+}
+
+/// Returns the offset from `body` of the `*/` closing a block comment,
+/// skipping the nested block comments.
+fn block_comment_end(bytes: &[u8], body: usize) -> Option<usize> {
+    let rest = bytes.get(body..)?;
+    let mut depth = 0_usize;
+    let mut offset = 0;
+    while let Some(&byte) = rest.get(offset) {
+        match (byte, rest.get(offset.saturating_add(1))) {
+            (b'/', Some(b'*')) => {
+                depth = depth.saturating_add(1);
+                offset = offset.saturating_add(2);
+            }
+            (b'*', Some(b'/')) => match depth.checked_sub(1) {
+                Some(outer) => {
+                    depth = outer;
+                    offset = offset.saturating_add(2);
+                }
+                None => return Some(offset),
+            },
+            _ => offset = offset.saturating_add(1),
+        }
+    }
+    None
+}
+
+/// Returns the `$<tag>$` opening a dollar-quoted string at `position`.
+///
+/// The tag is empty or an identifier without `$`, so a `$n` placeholder is
+/// not a tag.
+fn dollar_tag(sql: &str, position: usize) -> Option<&str> {
+    let rest = sql.get(position.saturating_add(1)..)?;
+    let len = rest
+        .bytes()
+        .take_while(|&byte| is_ident_continue(byte) && byte != b'$')
+        .count();
+    if rest
+        .bytes()
+        .next()
+        .is_some_and(|byte| byte.is_ascii_digit())
+        || rest.as_bytes().get(len) != Some(&b'$')
+    {
+        return None;
+    }
+    sql.get(position..position.saturating_add(len).saturating_add(2))
+}
+
+const fn is_ident_start(byte: u8) -> bool {
+    byte.is_ascii_alphabetic() || byte == b'_' || !byte.is_ascii()
+}
+
+const fn is_ident_continue(byte: u8) -> bool {
+    is_ident_start(byte) || byte.is_ascii_digit() || byte == b'$'
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    fn spans(sql: &str) -> Result<Vec<(SpanKind, &str)>, ScanError> {
+        tokens(sql)
+            .map(|span| span.map(|span| (span.kind, span.text)))
+            .collect()
+    }
+
+    #[test]
+    fn placeholders_adjacent_to_dollar_quotes() {
+        assert_eq!(
+            spans("$1$$a$$$2").unwrap(),
+            [
+                (SpanKind::Placeholder, "$1"),
+                (SpanKind::DollarQuoted, "$$a$$"),
+                (SpanKind::Placeholder, "$2"),
+            ]
+        );
+        assert_eq!(
+            spans("f($1,$tag$ $3 $tag$)::int").unwrap(),
+            [
+                (SpanKind::Text, "f("),
+                (SpanKind::Placeholder, "$1"),
+                (SpanKind::Text, ","),
+                (SpanKind::DollarQuoted, "$tag$ $3 $tag$"),
+                (SpanKind::Text, ")::int"),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_looking_tags() {
+        assert_eq!(
+            spans("$a$ $b$ $1 $b$ $a$ = $2").unwrap(),
+            [
+                (SpanKind::DollarQuoted, "$a$ $b$ $1 $b$ $a$"),
+                (SpanKind::Text, " = "),
+                (SpanKind::Placeholder, "$2"),
+            ]
+        );
+        assert_eq!(
+            spans("$ab$ $a$ $abc$ $ab$").unwrap(),
+            [(SpanKind::DollarQuoted, "$ab$ $a$ $abc$ $ab$")]
+        );
+    }
+
+    #[test]
+    fn dollar_inside_identifiers() {
+        assert_eq!(
+            spans("price$1 + a$b$ + $1").unwrap(),
+            [
+                (SpanKind::Text, "price$1 + a$b$ + "),
+                (SpanKind::Placeholder, "$1"),
+            ]
+        );
+        assert_eq!(
+            spans("$1a $ $").unwrap(),
+            [(SpanKind::Placeholder, "$1"), (SpanKind::Text, "a $ $")]
+        );
+    }
+
+    #[test]
+    fn quotes_and_comments() {
+        assert_eq!(
+            spans("'it''s $1' \"$2\" -- $3\n/* $4 */").unwrap(),
+            [
+                (SpanKind::Quoted, "'it'"),
+                (SpanKind::Quoted, "'s $1'"),
+                (SpanKind::Text, " "),
+                (SpanKind::Quoted, "\"$2\""),
+                (SpanKind::Text, " "),
+                (SpanKind::Comment, "-- $3\n"),
+                (SpanKind::Comment, "/* $4 */"),
+            ]
+        );
+        assert_eq!(
+            spans("x -- $1").unwrap(),
+            [(SpanKind::Text, "x "), (SpanKind::Comment, "-- $1")]
+        );
+    }
+
+    #[test]
+    fn escape_string_literals() {
+        assert_eq!(
+            spans("E'it\\'s; $1' || e'a''b\\\\' = $2").unwrap(),
+            [
+                (SpanKind::Quoted, "E'it\\'s; $1'"),
+                (SpanKind::Text, " || "),
+                (SpanKind::Quoted, "e'a''b\\\\'"),
+                (SpanKind::Text, " = "),
+                (SpanKind::Placeholder, "$2"),
+            ]
+        );
+        assert_eq!(
+            spans("nodE'a\\' $1").unwrap(),
+            [
+                (SpanKind::Text, "nodE"),
+                (SpanKind::Quoted, "'a\\'"),
+                (SpanKind::Text, " "),
+                (SpanKind::Placeholder, "$1"),
+            ]
+        );
+        assert_eq!(spans("E'a\\'"), Err(ScanError { position: 0 }));
+    }
+
+    #[test]
+    fn nested_block_comments() {
+        assert_eq!(
+            spans("SELECT /* a /* b */ ; $1 */ $1; /**/").unwrap(),
+            [
+                (SpanKind::Text, "SELECT "),
+                (SpanKind::Comment, "/* a /* b */ ; $1 */"),
+                (SpanKind::Text, " "),
+                (SpanKind::Placeholder, "$1"),
+                (SpanKind::Text, "; "),
+                (SpanKind::Comment, "/**/"),
+            ]
+        );
+        assert_eq!(spans("/* a /* b */"), Err(ScanError { position: 0 }));
+    }
+
+    #[test]
+    fn command_ends() {
+        let end = |sql| {
+            let end = command_end(sql);
+            (end.terminator, end.line_comment)
+        };
+
+        assert_eq!(end("SELECT 1"), (None, false));
+        assert_eq!(end("SELECT 1; "), (Some(8), false));
+        assert_eq!(end("SELECT 1; -- done"), (Some(8), true));
+        assert_eq!(end("SELECT 1 -- done;"), (None, true));
+        assert_eq!(end("SELECT 1 -- done;\n"), (None, false));
+        assert_eq!(end("SELECT 1 /* done; */"), (None, false));
+        assert_eq!(end("SELECT ';'"), (None, false));
+        assert_eq!(end("SELECT 'a;"), (None, false));
+    }
+
+    #[test]
+    fn unterminated_quotes() {
+        assert_eq!(spans("a = 'b"), Err(ScanError { position: 4 }));
+        assert_eq!(spans("\"b"), Err(ScanError { position: 0 }));
+        assert_eq!(spans("$1 $x$ $1 $y$"), Err(ScanError { position: 3 }));
+        assert_eq!(spans("SELECT /* a"), Err(ScanError { position: 7 }));
+
+        let mut iter = tokens("a 'b");
+        assert!(iter.next().is_some_and(|span| span.is_ok()));
+        assert_eq!(iter.next(), Some(Err(ScanError { position: 2 })));
+        assert_eq!(iter.next(), None);
+    }
+}
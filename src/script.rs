@@ -0,0 +1,1078 @@
+use alloc::{format, string::String, vec::Vec};
+use core::convert::Infallible;
+
+use crate::expr::{check_plain_ident, Token};
+use crate::placeholder::{for_each_segment, Segment};
+use crate::scan::{command_end, tokens, CommandEnd, SpanKind};
+use crate::{renumber_placeholders, AppendArguments, ArgumentError, SqlCommand, SqlError};
+
+/// Transaction isolation level of a `BEGIN` statement.
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Isolation {
+    /// `BEGIN`, using the default isolation level of the database.
+    #[default]
+    Default,
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl Isolation {
+    /// `BEGIN` statement starting a transaction with this isolation level.
+    pub const fn begin_statement(&self) -> &'static str {
+        match self {
+            Self::Default => "BEGIN",
+            Self::ReadUncommitted => "BEGIN ISOLATION LEVEL READ UNCOMMITTED",
+            Self::ReadCommitted => "BEGIN ISOLATION LEVEL READ COMMITTED",
+            Self::RepeatableRead => "BEGIN ISOLATION LEVEL REPEATABLE READ",
+            Self::Serializable => "BEGIN ISOLATION LEVEL SERIALIZABLE",
+        }
+    }
+}
+
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+enum Statement<Arg> {
+    Control(String),
+    /// Savepoint statement, `<keyword> <name>`.
+    Savepoint(&'static str, String),
+    Command(SqlCommand<Arg>),
+}
+
+/// Ordered list of commands executed inside a transaction.
+///
+/// The script starts with a `BEGIN` statement and is finished with
+/// [commit](Self::commit). Sections of the script can be scoped into a
+/// savepoint with [savepoint](Self::savepoint).
+///
+/// The commands can be taken one by one with
+/// [into_commands](Self::into_commands), for drivers without multi-statement
+/// support, or joined into a single command with
+/// [into_batch](Self::into_batch).
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void, Isolation, TransactionScript};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut script: TransactionScript<Void> = TransactionScript::begin(Isolation::Serializable);
+/// script.push(SqlCommand::new("DELETE FROM session WHERE expired".into(), Void::new()));
+/// let commands = script.commit().into_commands();
+///
+/// let commands: Vec<&str> = commands.iter().map(SqlCommand::as_command).collect();
+/// assert_eq!(
+///     commands,
+///     [
+///         "BEGIN ISOLATION LEVEL SERIALIZABLE",
+///         "DELETE FROM session WHERE expired",
+///         "COMMIT"
+///     ]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+pub struct TransactionScript<Arg> {
+    statements: Vec<Statement<Arg>>,
+}
+
+impl<Arg> TransactionScript<Arg> {
+    /// Starts a script with a `BEGIN` statement.
+    pub fn begin(isolation: Isolation) -> Self {
+        Self {
+            statements: Vec::from([Statement::Control(isolation.begin_statement().into())]),
+        }
+    }
+
+    /// Pushes a command into the script.
+    pub fn push(&mut self, command: SqlCommand<Arg>) {
+        self.statements.push(Statement::Command(command));
+    }
+
+    /// Scopes the commands pushed by `build` into a savepoint.
+    ///
+    /// The commands are written between `SAVEPOINT <name>` and
+    /// `RELEASE SAVEPOINT <name>`. When `build` returns an error, the scope is
+    /// closed with `ROLLBACK TO SAVEPOINT <name>` instead, undoing the effects
+    /// of the commands already pushed, and the error is returned. The script
+    /// remains usable after the error.
+    ///
+    /// A `name` that is not a plain identifier returns an
+    /// [InvalidText](SqlError::InvalidText) error at the byte position of the
+    /// `SAVEPOINT <name>` statement, and a name longer than
+    /// [POSTGRES_IDENT_MAX_LEN](crate::expr::POSTGRES_IDENT_MAX_LEN) an
+    /// [IdentifierTooLong](SqlError::IdentifierTooLong) error, converted into
+    /// the error of `build`. Nothing is pushed into the script and `build` is
+    /// not called.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, Void, Isolation, TransactionScript};
+    /// # use core::convert::Infallible;
+    /// let mut script: TransactionScript<Void> = TransactionScript::begin(Isolation::Default);
+    /// let result = script.savepoint("import", |script| {
+    ///     script.push(SqlCommand::new("INSERT INTO staging SELECT * FROM raw".into(), Void::new()));
+    ///     Err(SqlError::<Infallible>::EmptyList)
+    /// });
+    /// let commands = script.commit().into_commands();
+    ///
+    /// assert_eq!(result, Err(SqlError::EmptyList));
+    /// let commands: Vec<&str> = commands.iter().map(SqlCommand::as_command).collect();
+    /// assert_eq!(
+    ///     commands,
+    ///     [
+    ///         "BEGIN",
+    ///         "SAVEPOINT import",
+    ///         "INSERT INTO staging SELECT * FROM raw",
+    ///         "ROLLBACK TO SAVEPOINT import",
+    ///         "COMMIT"
+    ///     ]
+    /// );
+    /// ```
+    pub fn savepoint<F, E>(&mut self, name: &str, build: F) -> Result<(), E>
+    where
+        F: FnOnce(&mut Self) -> Result<(), E>,
+        E: From<SqlError<Arg::Error>>,
+        Arg: ArgumentError,
+    {
+        check_plain_ident(name, "SAVEPOINT ".len()).map_err(SqlError::widen)?;
+
+        self.statements
+            .push(Statement::Savepoint("SAVEPOINT", name.into()));
+
+        let result = build(self);
+        let close = match result {
+            Ok(()) => "RELEASE SAVEPOINT",
+            Err(_) => "ROLLBACK TO SAVEPOINT",
+        };
+        self.statements
+            .push(Statement::Savepoint(close, name.into()));
+
+        result
+    }
+
+    /// Finishes the script with a `COMMIT` statement.
+    pub fn commit(mut self) -> Self {
+        self.statements.push(Statement::Control("COMMIT".into()));
+        self
+    }
+
+    /// Ordered list of the commands of the script.
+    ///
+    /// The transaction statements are created with the default argument
+    /// buffer.
+    pub fn into_commands(self) -> Vec<SqlCommand<Arg>>
+    where
+        Arg: Default,
+    {
+        self.statements
+            .into_iter()
+            .map(|statement| match statement {
+                Statement::Control(command) => SqlCommand::new(command, Arg::default()),
+                Statement::Savepoint(keyword, name) => {
+                    SqlCommand::new(format!("{keyword} {name}"), Arg::default())
+                }
+                Statement::Command(command) => command,
+            })
+            .collect()
+    }
+
+    /// Joins the script into a single command, separating the statements
+    /// with `; `, written on a new line after a command ending inside a `--`
+    /// line comment.
+    ///
+    /// The placeholders of each command are shifted to continue the
+    /// numbering of the previous commands, and the argument buffers are kept
+    /// in the order of the commands, so they can be bound one after another.
+    ///
+    /// The placeholders of every command must be contiguous, from `$1` to the
+    /// greatest one, otherwise an
+    /// [InvalidPlaceholder](SqlError::InvalidPlaceholder) error is returned.
+    /// A command with rejected text, see
+    /// [check_text](SqlCommand::check_text), returns an
+    /// [InvalidText](SqlError::InvalidText) error at the byte position of the
+    /// batch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sqlstr::{SqlCommand, SqlError, Void, Isolation, TransactionScript};
+    /// # use core::convert::Infallible;
+    /// # fn main() -> Result<(), SqlError<Infallible>> {
+    /// let mut script = TransactionScript::begin(Isolation::Default);
+    /// script.push(SqlCommand::new("UPDATE account SET balance = balance - $1 WHERE id = $2".into(), Void::with_count(2)));
+    /// script.push(SqlCommand::new("UPDATE account SET balance = balance + $1 WHERE id = $2".into(), Void::with_count(2)));
+    /// let batch = script.commit().into_batch()?;
+    ///
+    /// assert_eq!(
+    ///     batch.as_command(),
+    ///     "BEGIN; \
+    ///      UPDATE account SET balance = balance - $1 WHERE id = $2; \
+    ///      UPDATE account SET balance = balance + $3 WHERE id = $4; \
+    ///      COMMIT"
+    /// );
+    /// assert_eq!(batch.arguments.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_batch(self) -> Result<SqlCommand<Vec<Arg>>, SqlError<Infallible>> {
+        let mut command = String::new();
+        let mut arguments = Vec::new();
+        let mut offset = 0;
+        // byte position of the last statement in the command
+        let mut last = None;
+
+        for statement in self.statements {
+            if let Some(last) = last {
+                let end = command_end(command.get(last..).unwrap_or_default());
+                command.push_str(statement_terminator(end));
+                command.push(' ');
+            }
+            last = Some(command.len());
+            match statement {
+                Statement::Control(control) => command.push_str(&control),
+                Statement::Savepoint(keyword, name) => {
+                    command.push_str(keyword);
+                    command.push(' ');
+                    command.push_str(&name);
+                }
+                Statement::Command(cmd) => {
+                    cmd.check_text().map_err(|err| match err {
+                        SqlError::InvalidText { position } => SqlError::InvalidText {
+                            position: position.saturating_add(command.len()),
+                        },
+                        err => err,
+                    })?;
+                    let max = renumber_placeholders(&cmd.command, offset, true, &mut command)?;
+                    offset = offset.max(max);
+                    arguments.push(cmd.arguments);
+                }
+            }
+        }
+
+        Ok(SqlCommand::new(command, arguments))
+    }
+}
+
+/// Writes commands as a script for `psql` or a migration file.
+///
+/// Each command is terminated by `;`, see
+/// [write_terminated](SqlCommand::write_terminated), and separated from the
+/// next by a blank line.
+///
+/// Scripts can not bind arguments, a command with a `$n` placeholder returns
+/// an [InvalidPlaceholder](SqlError::InvalidPlaceholder) error at the byte
+/// position of the placeholder, without writing the command. Neither is a
+/// command with rejected text written, see
+/// [check_text](SqlCommand::check_text). An error of the output is returned
+/// as [Argument](SqlError::Argument).
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void, ScriptWriter};
+/// # fn main() -> Result<(), SqlError<core::fmt::Error>> {
+/// let commands: [SqlCommand<Void>; 2] = [
+///     SqlCommand::new("CREATE TABLE tag (name text)".into(), Void::new()),
+///     SqlCommand::new("CREATE INDEX tag_name ON tag (name)".into(), Void::new()),
+/// ];
+/// let mut script = ScriptWriter::new(String::new());
+/// script.write_all(&commands)?;
+///
+/// assert_eq!(
+///     script.into_inner(),
+///     "CREATE TABLE tag (name text);\n\nCREATE INDEX tag_name ON tag (name);\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+pub struct ScriptWriter<W> {
+    out: W,
+    written: bool,
+}
+
+impl<W> ScriptWriter<W>
+where
+    W: core::fmt::Write,
+{
+    /// Creates a script writing into `out`.
+    pub const fn new(out: W) -> Self {
+        Self {
+            out,
+            written: false,
+        }
+    }
+
+    /// Writes a terminated command into the script.
+    pub fn write<Arg>(
+        &mut self,
+        command: &SqlCommand<Arg>,
+    ) -> Result<(), SqlError<core::fmt::Error>> {
+        command.check_text().map_err(SqlError::widen)?;
+        for_each_segment(command.as_command(), |segment| match segment {
+            Segment::Text(_) => Ok(()),
+            Segment::Placeholder { position, .. } => Err(SqlError::InvalidPlaceholder { position }),
+        })?;
+
+        if self.written {
+            self.out.write_char('\n')?;
+        }
+        command.write_terminated(&mut self.out)?;
+        self.out.write_char('\n')?;
+        self.written = true;
+        Ok(())
+    }
+
+    /// Writes every command of `commands` into the script, stopping at the
+    /// first error.
+    pub fn write_all<'cmd, Arg, I>(&mut self, commands: I) -> Result<(), SqlError<core::fmt::Error>>
+    where
+        Arg: 'cmd,
+        I: IntoIterator<Item = &'cmd SqlCommand<Arg>>,
+    {
+        commands
+            .into_iter()
+            .try_for_each(|command| self.write(command))
+    }
+
+    /// Returns the output of the script.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// Several commands joined into a single multi-statement command, separated
+/// by `; `, for drivers running a simple query with many statements.
+///
+/// The placeholders of each command are shifted to continue the numbering of
+/// the previous commands, and the arguments are appended in the same order,
+/// as in [union](SqlCommand::union).
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{Batch, SqlCommand, SqlError, Void, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+/// # use core::convert::Infallible;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let touch = SqlCommand::new("UPDATE user SET seen = now() WHERE id = $1".into(), Void::with_count(1));
+///
+/// let mut find: SqlCommand<Void> = SqlCommand::default();
+/// select(&mut find);
+/// column(&mut find, "name");
+/// from_table(&mut find, "user");
+/// filter_where(&mut find);
+/// lhs_binary_rhs(&mut find, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(7))?;
+///
+/// let batch = Batch::new(touch).then(find)?;
+///
+/// assert_eq!(batch.statement_count(), 2);
+/// assert_eq!(
+///     batch.into_command().as_command(),
+///     "UPDATE user SET seen = now() WHERE id = $1; SELECT name FROM user WHERE id = $2"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Batch<Arg> {
+    sql: SqlCommand<Arg>,
+    statements: usize,
+    /// Byte position of the last statement in the command.
+    last: usize,
+}
+
+impl<Arg> Batch<Arg>
+where
+    Arg: AppendArguments,
+{
+    /// Starts a batch with its first command.
+    pub fn new(first: SqlCommand<Arg>) -> Self {
+        Self {
+            sql: first,
+            statements: 1,
+            last: 0,
+        }
+    }
+
+    /// Appends a command to the batch, `<batch>; <command>`.
+    ///
+    /// When the last command of the batch ends inside a `--` line comment,
+    /// the `;` is written on a new line, so the comment does not swallow the
+    /// appended command.
+    ///
+    /// A command already terminated by `;`, outside quotes and comments, is
+    /// refused with a [TerminatedCommand](SqlError::TerminatedCommand) error
+    /// at the byte position of its terminator, as is a batch whose first
+    /// command is terminated. A
+    /// `$0` placeholder or a placeholder that overflows `u32` in `command`
+    /// returns an [InvalidPlaceholder](SqlError::InvalidPlaceholder) error.
+    pub fn then(self, command: SqlCommand<Arg>) -> Result<Self, SqlError<Arg::Error>> {
+        let last = self.sql.as_command().get(self.last..).unwrap_or_default();
+        let end = command_end(last);
+        let terminator = end
+            .terminator
+            .map(|position| position.saturating_add(self.last))
+            .or_else(|| command_end(command.as_command()).terminator);
+        if let Some(position) = terminator {
+            return Err(SqlError::TerminatedCommand { position });
+        }
+
+        let separator = statement_terminator(end);
+        let last = self.sql.as_command().len().saturating_add(separator.len());
+        Ok(Self {
+            sql: self.sql.combine(Token::Raw(separator), command)?,
+            statements: self.statements.saturating_add(1),
+            last,
+        })
+    }
+
+    /// Number of statements of the batch.
+    pub const fn statement_count(&self) -> usize {
+        self.statements
+    }
+
+    /// Returns the multi-statement command.
+    pub fn into_command(self) -> SqlCommand<Arg> {
+        self.sql
+    }
+}
+
+/// `;` separating a statement from the next one of a multi-statement
+/// command, written on a new line after a statement ending inside a line
+/// comment.
+fn statement_terminator(end: CommandEnd) -> &'static str {
+    if end.line_comment {
+        "\n;"
+    } else {
+        ";"
+    }
+}
+
+/// Scans a script, calling `visit` with the byte position and byte of every
+/// character outside string literals, quoted identifiers, dollar-quoted
+/// strings and comments.
+///
+/// Returns the byte position of the opening quote of a quoted text or comment
+/// never closed.
+fn scan_script<F>(script: &str, mut visit: F) -> Result<(), usize>
+where
+    F: FnMut(usize, u8),
+{
+    for span in tokens(script) {
+        let span = span.map_err(|err| err.position)?;
+        if matches!(span.kind, SpanKind::Text | SpanKind::Placeholder) {
+            for (offset, byte) in span.text.bytes().enumerate() {
+                visit(span.position.saturating_add(offset), byte);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits a script into its statements, at each `;` outside string literals
+/// (including the `E'...'` escape strings), quoted identifiers, dollar-quoted strings (`$$...$$`, `$tag$...$tag$`)
+/// and comments.
+///
+/// The statements are trimmed and returned without the `;`, skipping the
+/// empty ones. A quoted text never closed extends to the end of the script,
+/// see [validate_script] to reject it.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::split_statements;
+/// let script = "CREATE FUNCTION one() RETURNS int AS $$ SELECT 1; $$ LANGUAGE sql;\n\n\
+///               SELECT 'a;b';\n";
+///
+/// assert_eq!(
+///     split_statements(script),
+///     ["CREATE FUNCTION one() RETURNS int AS $$ SELECT 1; $$ LANGUAGE sql", "SELECT 'a;b'"]
+/// );
+/// ```
+pub fn split_statements(script: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut push = |end: usize, start: usize| {
+        let statement = script.get(start..end).unwrap_or_default().trim();
+        if !statement.is_empty() {
+            statements.push(statement);
+        }
+    };
+
+    let _ = scan_script(script, |position, byte| {
+        if byte == b';' {
+            push(position, start);
+            start = position.saturating_add(1);
+        }
+    });
+    push(script.len(), start);
+
+    statements
+}
+
+/// Checks the structure of every statement of a script, as written by
+/// [ScriptWriter].
+///
+/// Returns an [InvalidText](SqlError::InvalidText) error at the byte position
+/// of a quoted text or comment never closed, of a parenthesis not matched
+/// within its statement, or of a NUL character. A `$n` placeholder, which a
+/// script can not bind, returns an
+/// [InvalidPlaceholder](SqlError::InvalidPlaceholder) error.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{validate_script, SqlError};
+/// assert_eq!(validate_script("SELECT 1; SELECT (2);"), Ok(()));
+/// assert_eq!(
+///     validate_script("SELECT 1; SELECT (2;"),
+///     Err(SqlError::InvalidText { position: 17 })
+/// );
+/// assert_eq!(
+///     validate_script("DELETE FROM t WHERE id = $1;"),
+///     Err(SqlError::InvalidPlaceholder { position: 25 })
+/// );
+/// ```
+pub fn validate_script(script: &str) -> Result<(), SqlError<Infallible>> {
+    let bytes = script.as_bytes();
+    let mut open = Vec::new();
+    let mut error = None;
+
+    scan_script(script, |position, byte| {
+        if error.is_some() {
+            return;
+        }
+        match byte {
+            b'(' => open.push(position),
+            b')' if open.pop().is_none() => error = Some(SqlError::InvalidText { position }),
+            b';' => {
+                if let Some(&position) = open.first() {
+                    error = Some(SqlError::InvalidText { position });
+                }
+            }
+            b'$' if bytes
+                .get(position.saturating_add(1))
+                .is_some_and(u8::is_ascii_digit) =>
+            {
+                error = Some(SqlError::InvalidPlaceholder { position });
+            }
+            b'\0' => error = Some(SqlError::InvalidText { position }),
+            _ => {}
+        }
+    })
+    .map_err(|position| SqlError::<Infallible>::InvalidText { position })?;
+
+    match (error, open.first()) {
+        (Some(err), _) => Err(err),
+        (None, Some(&position)) => Err(SqlError::InvalidText { position }),
+        (None, None) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{format, string::String, vec::Vec};
+
+    use super::{
+        split_statements, validate_script, Batch, Isolation, ScriptWriter, TransactionScript,
+    };
+    use crate::{
+        expr::{
+            delete_from, filter_where, insert_into, lhs_binary_rhs, update_table, values,
+            values_rows, Cmp,
+        },
+        sqlexpr, sqlvalue,
+        test::TestArgs,
+        SqlCommand, SqlError,
+    };
+
+    type Error = SqlError<core::fmt::Error>;
+
+    fn script() -> Result<TransactionScript<TestArgs>, Error> {
+        let mut script = TransactionScript::begin(Isolation::RepeatableRead);
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        update_table(&mut sql, "account");
+        sql.push_cmd(" SET frozen = true");
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(7))?;
+        script.push(sql);
+
+        script.savepoint("audit", |script| {
+            let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+            insert_into(&mut sql, "audit");
+            values(&mut sql);
+            values_rows(&mut sql, 2, [[7, 1]])?;
+            script.push(sql);
+            Ok::<_, Error>(())
+        })?;
+
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        delete_from(&mut sql, "session");
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("account_id"), Cmp::Eq, sqlvalue(7))?;
+        script.push(sql);
+
+        Ok(script.commit())
+    }
+
+    #[test]
+    fn script_commands() {
+        let commands = script().unwrap().into_commands();
+
+        let text: Vec<&str> = commands.iter().map(SqlCommand::as_command).collect();
+        assert_eq!(
+            text,
+            [
+                "BEGIN ISOLATION LEVEL REPEATABLE READ",
+                "UPDATE account SET frozen = true WHERE id = $1",
+                "SAVEPOINT audit",
+                "INSERT INTO audit VALUES ($1, $2)",
+                "RELEASE SAVEPOINT audit",
+                "DELETE FROM session WHERE account_id = $1",
+                "COMMIT",
+            ]
+        );
+        let arguments: Vec<&str> = commands.iter().map(|cmd| cmd.arguments.as_str()).collect();
+        assert_eq!(arguments, ["", "7;", "", "7;1;", "", "7;", ""]);
+    }
+
+    #[test]
+    fn script_batch() {
+        let batch = script().unwrap().into_batch().unwrap();
+
+        assert_eq!(
+            batch.as_command(),
+            "BEGIN ISOLATION LEVEL REPEATABLE READ; \
+             UPDATE account SET frozen = true WHERE id = $1; \
+             SAVEPOINT audit; \
+             INSERT INTO audit VALUES ($2, $3); \
+             RELEASE SAVEPOINT audit; \
+             DELETE FROM session WHERE account_id = $4; \
+             COMMIT"
+        );
+        let arguments: Vec<&str> = batch.arguments.iter().map(TestArgs::as_str).collect();
+        assert_eq!(arguments, ["7;", "7;1;", "7;"]);
+    }
+
+    #[test]
+    fn savepoint_rollback_on_error() {
+        let mut script: TransactionScript<TestArgs> = TransactionScript::begin(Isolation::Default);
+
+        let result = script.savepoint("import", |script| {
+            script.push(SqlCommand::new("TRUNCATE staging".into(), TestArgs::new()));
+            Err(SqlError::<core::fmt::Error>::EmptyList)
+        });
+        script.push(SqlCommand::new("SELECT 1".into(), TestArgs::new()));
+        let batch = script.commit().into_batch().unwrap();
+
+        assert_eq!(result, Err(SqlError::EmptyList));
+        assert_eq!(
+            batch.as_command(),
+            "BEGIN; SAVEPOINT import; TRUNCATE staging; ROLLBACK TO SAVEPOINT import; SELECT 1; COMMIT"
+        );
+    }
+
+    #[test]
+    fn savepoint_name_checked() {
+        let mut script: TransactionScript<TestArgs> = TransactionScript::begin(Isolation::Default);
+        let mut called = false;
+        let result = script.savepoint("a; DROP TABLE user", |_| {
+            called = true;
+            Ok::<_, Error>(())
+        });
+        assert_eq!(result, Err(SqlError::InvalidText { position: 11 }));
+        assert!(!called);
+        assert_eq!(
+            script.savepoint("1st", |_| Ok::<_, Error>(())).unwrap_err(),
+            SqlError::InvalidText { position: 10 }
+        );
+        assert_eq!(
+            script
+                .savepoint(&"s".repeat(64), |_| Ok::<_, Error>(()))
+                .unwrap_err(),
+            SqlError::IdentifierTooLong { len: 64, max: 63 }
+        );
+
+        let commands = script.clone().commit().into_commands();
+        let text: Vec<&str> = commands.iter().map(SqlCommand::as_command).collect();
+        assert_eq!(text, ["BEGIN", "COMMIT"]);
+
+        script.savepoint("_step$2", |_| Ok::<_, Error>(())).unwrap();
+        let commands = script.clone().commit().into_commands();
+        let text: Vec<&str> = commands.iter().map(SqlCommand::as_command).collect();
+        assert_eq!(
+            text,
+            [
+                "BEGIN",
+                "SAVEPOINT _step$2",
+                "RELEASE SAVEPOINT _step$2",
+                "COMMIT"
+            ]
+        );
+        assert_eq!(
+            script.commit().into_batch().unwrap().as_command(),
+            "BEGIN; SAVEPOINT _step$2; RELEASE SAVEPOINT _step$2; COMMIT"
+        );
+    }
+
+    #[test]
+    fn script_batch_after_line_comment() {
+        let mut script: TransactionScript<TestArgs> = TransactionScript::begin(Isolation::Default);
+        script
+            .savepoint("step", |script| {
+                script.push(SqlCommand::new(
+                    "DELETE FROM session -- expired".into(),
+                    TestArgs::new(),
+                ));
+                Ok::<_, Error>(())
+            })
+            .unwrap();
+        script.push(SqlCommand::new(
+            "SELECT 1 -- done;\n".into(),
+            TestArgs::new(),
+        ));
+        let batch = script.commit().into_batch().unwrap();
+
+        assert_eq!(
+            batch.as_command(),
+            "BEGIN; SAVEPOINT step; DELETE FROM session -- expired\n; \
+             RELEASE SAVEPOINT step; SELECT 1 -- done;\n; COMMIT"
+        );
+        assert_eq!(
+            split_statements(batch.as_command()),
+            [
+                "BEGIN",
+                "SAVEPOINT step",
+                "DELETE FROM session -- expired",
+                "RELEASE SAVEPOINT step",
+                "SELECT 1 -- done;",
+                "COMMIT"
+            ]
+        );
+    }
+
+    #[test]
+    fn batch_checks_command_text() {
+        let mut script: TransactionScript<TestArgs> = TransactionScript::begin(Isolation::Default);
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        update_table(&mut sql, "account");
+        sql.push_cmd(" SET na\0me = 'x'");
+        script.push(sql);
+
+        assert_eq!(
+            script.commit().into_batch(),
+            Err(SqlError::InvalidText { position: 28 })
+        );
+    }
+
+    #[test]
+    fn terminated_commands() {
+        let sql = SqlCommand::new("SELECT 1".into(), TestArgs::new());
+        let mut out = String::new();
+        sql.write_terminated(&mut out).unwrap();
+        assert_eq!(out, "SELECT 1;");
+
+        let sql = SqlCommand::new("SELECT 1; ".into(), TestArgs::new());
+        let mut out = String::new();
+        sql.write_terminated(&mut out).unwrap();
+        assert_eq!(out, "SELECT 1; ");
+
+        let sql = SqlCommand::new("SELECT 1 -- done;".into(), TestArgs::new());
+        let mut out = String::new();
+        sql.write_terminated(&mut out).unwrap();
+        assert_eq!(out, "SELECT 1 -- done;\n;");
+
+        let sql = SqlCommand::new("SELECT 1; -- done".into(), TestArgs::new());
+        let mut out = String::new();
+        sql.write_terminated(&mut out).unwrap();
+        assert_eq!(out, "SELECT 1; -- done");
+
+        let sql = SqlCommand::new("SELECT ';'".into(), TestArgs::new());
+        let mut out = String::new();
+        sql.write_terminated(&mut out).unwrap();
+        assert_eq!(out, "SELECT ';';");
+
+        #[cfg(feature = "fmt")]
+        {
+            use alloc::string::ToString;
+            let sql = SqlCommand::new("SELECT 1".into(), TestArgs::new());
+            assert_eq!(sql.terminated().to_string(), "SELECT 1;");
+            assert_eq!(sql.as_command(), "SELECT 1");
+        }
+    }
+
+    #[test]
+    fn script_writer_output() {
+        let commands: [SqlCommand<TestArgs>; 3] = [
+            SqlCommand::new(
+                "CREATE TABLE account (id int, note text DEFAULT '$1')".into(),
+                TestArgs::new(),
+            ),
+            SqlCommand::new(
+                "INSERT INTO account (id) VALUES (1)".into(),
+                TestArgs::new(),
+            ),
+            SqlCommand::new("ANALYZE account;".into(), TestArgs::new()),
+        ];
+        let mut script = ScriptWriter::new(String::new());
+        script.write_all(&commands).unwrap();
+
+        assert_eq!(
+            script.into_inner(),
+            "CREATE TABLE account (id int, note text DEFAULT '$1');\n\
+             \n\
+             INSERT INTO account (id) VALUES (1);\n\
+             \n\
+             ANALYZE account;\n"
+        );
+    }
+
+    #[test]
+    fn script_writer_refuses_placeholders() {
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        delete_from(&mut sql, "session");
+        filter_where(&mut sql);
+        lhs_binary_rhs(&mut sql, sqlexpr::<u32>("account_id"), Cmp::Eq, sqlvalue(7)).unwrap();
+
+        let mut script = ScriptWriter::new(String::new());
+        script
+            .write(&SqlCommand::new("SELECT 1".into(), TestArgs::new()))
+            .unwrap();
+        assert_eq!(
+            script.write(&sql),
+            Err(SqlError::InvalidPlaceholder { position: 39 })
+        );
+        assert_eq!(script.into_inner(), "SELECT 1;\n");
+    }
+
+    const FUNCTION: &str = "CREATE FUNCTION touch() RETURNS trigger AS $body$\n\
+         BEGIN\n\
+           NEW.note := 'it''s; done';\n\
+           RAISE NOTICE $$quoted \"$$;\n\
+           RETURN NEW;\n\
+         END;\n\
+         $body$ LANGUAGE plpgsql";
+
+    #[test]
+    fn split_dollar_quoted_bodies() {
+        let script = format!(
+            "{FUNCTION};\n\n\
+             -- comment; with a semicolon\n\
+             SELECT $$a;b$$, 'c;d', \"e;f\" /* g; h */;\n\
+             ;\n\
+             SELECT 1"
+        );
+
+        assert_eq!(
+            split_statements(&script),
+            [
+                FUNCTION,
+                "-- comment; with a semicolon\nSELECT $$a;b$$, 'c;d', \"e;f\" /* g; h */",
+                "SELECT 1",
+            ]
+        );
+        assert_eq!(validate_script(&script), Ok(()));
+    }
+
+    #[test]
+    fn split_nested_block_comments() {
+        let script = "SELECT /* a /* b */ ; $1 */ 1; SELECT 2";
+
+        assert_eq!(
+            split_statements(script),
+            ["SELECT /* a /* b */ ; $1 */ 1", "SELECT 2"]
+        );
+        assert_eq!(validate_script(script), Ok(()));
+        assert_eq!(
+            validate_script("SELECT /* a /* b */ 1; SELECT 2"),
+            Err(SqlError::InvalidText { position: 7 })
+        );
+    }
+
+    #[test]
+    fn split_escape_strings() {
+        let script = "INSERT INTO note VALUES (E'it\\'s; done');\nSELECT e'\\\\';";
+
+        assert_eq!(
+            split_statements(script),
+            [
+                "INSERT INTO note VALUES (E'it\\'s; done')",
+                "SELECT e'\\\\'"
+            ]
+        );
+        assert_eq!(validate_script(script), Ok(()));
+        assert_eq!(
+            validate_script("SELECT E'open\\'; SELECT 1"),
+            Err(SqlError::InvalidText { position: 7 })
+        );
+    }
+
+    #[test]
+    fn split_script_writer_output() {
+        let commands = [
+            SqlCommand::<TestArgs>::new(
+                String::from("CREATE TABLE tag (name text)"),
+                TestArgs::new(),
+            ),
+            SqlCommand::new(String::from(FUNCTION), TestArgs::new()),
+            SqlCommand::new(
+                String::from("INSERT INTO tag VALUES ('a;b');"),
+                TestArgs::new(),
+            ),
+        ];
+        let mut script = ScriptWriter::new(String::new());
+        script.write_all(&commands).unwrap();
+        let script = script.into_inner();
+
+        let statements = split_statements(&script);
+        assert_eq!(
+            statements,
+            [
+                "CREATE TABLE tag (name text)",
+                FUNCTION,
+                "INSERT INTO tag VALUES ('a;b')",
+            ]
+        );
+        assert_eq!(validate_script(&script), Ok(()));
+    }
+
+    #[test]
+    fn split_script_writer_trailing_comments() {
+        let command = SqlCommand::<TestArgs>::new("SELECT 1 -- trailing".into(), TestArgs::new());
+        let mut script = ScriptWriter::new(String::new());
+        script.write_all([&command, &command]).unwrap();
+        let script = script.into_inner();
+
+        assert_eq!(
+            script,
+            "SELECT 1 -- trailing\n;\n\nSELECT 1 -- trailing\n;\n"
+        );
+        assert_eq!(
+            split_statements(&script),
+            ["SELECT 1 -- trailing", "SELECT 1 -- trailing"]
+        );
+        assert_eq!(validate_script(&script), Ok(()));
+    }
+
+    #[test]
+    fn validate_script_errors() {
+        assert_eq!(
+            validate_script("SELECT 'open;"),
+            Err(SqlError::InvalidText { position: 7 })
+        );
+        assert_eq!(
+            validate_script("SELECT $$ body; $tag$;"),
+            Err(SqlError::InvalidText { position: 7 })
+        );
+        assert_eq!(
+            validate_script("SELECT 1 /* open"),
+            Err(SqlError::InvalidText { position: 9 })
+        );
+        assert_eq!(
+            validate_script("SELECT 1); SELECT 2"),
+            Err(SqlError::InvalidText { position: 8 })
+        );
+        assert_eq!(
+            validate_script("SELECT count(*;"),
+            Err(SqlError::InvalidText { position: 12 })
+        );
+        assert_eq!(
+            validate_script("SELECT '$1', $2"),
+            Err(SqlError::InvalidPlaceholder { position: 13 })
+        );
+        assert_eq!(validate_script("SELECT 1 -- open comment"), Ok(()));
+    }
+
+    #[test]
+    fn batch_renumbering() {
+        let mut update: SqlCommand<TestArgs> = SqlCommand::default();
+        update_table(&mut update, "account");
+        update.push_cmd(" SET balance = balance - ");
+        update.push_value(50).unwrap();
+        filter_where(&mut update);
+        lhs_binary_rhs(&mut update, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(1)).unwrap();
+
+        let mut select: SqlCommand<TestArgs> = SqlCommand::default();
+        select.push_cmd("SELECT balance FROM account");
+        filter_where(&mut select);
+        lhs_binary_rhs(&mut select, sqlexpr::<u32>("id"), Cmp::Eq, sqlvalue(1)).unwrap();
+
+        let mut audit: SqlCommand<TestArgs> = SqlCommand::default();
+        audit.push_cmd("SELECT count(*) FROM audit");
+
+        let batch = Batch::new(update)
+            .then(select)
+            .unwrap()
+            .then(audit)
+            .unwrap();
+        assert_eq!(batch.statement_count(), 3);
+
+        let sql = batch.into_command();
+        assert_eq!(
+            sql.as_command(),
+            "UPDATE account SET balance = balance - $1 WHERE id = $2; \
+             SELECT balance FROM account WHERE id = $3; \
+             SELECT count(*) FROM audit"
+        );
+        assert_eq!(sql.arguments.as_str(), "50;1;1;");
+    }
+
+    #[test]
+    fn batch_after_line_comment() {
+        let command = |text: &str| SqlCommand::new(String::from(text), TestArgs::new());
+
+        let mut delete = command("DELETE FROM t WHERE id = ");
+        delete.push_value(7).unwrap();
+        let batch = Batch::new(command("SELECT 1 -- note"))
+            .then(delete)
+            .unwrap()
+            .then(command("SELECT 2 -- done;"))
+            .unwrap()
+            .then(command("SELECT 3"))
+            .unwrap();
+        assert_eq!(batch.statement_count(), 4);
+
+        let sql = batch.into_command();
+        assert_eq!(
+            sql.as_command(),
+            "SELECT 1 -- note\n; DELETE FROM t WHERE id = $1; SELECT 2 -- done;\n; SELECT 3"
+        );
+        assert_eq!(
+            split_statements(sql.as_command()),
+            [
+                "SELECT 1 -- note",
+                "DELETE FROM t WHERE id = $1",
+                "SELECT 2 -- done;",
+                "SELECT 3"
+            ]
+        );
+    }
+
+    #[test]
+    fn batch_refuses_terminated_commands() {
+        let command = |text: &str| SqlCommand::new(String::from(text), TestArgs::new());
+
+        assert_eq!(
+            Batch::new(command("SELECT 1;")).then(command("SELECT 2")),
+            Err(SqlError::TerminatedCommand { position: 8 })
+        );
+        assert_eq!(
+            Batch::new(command("SELECT 1")).then(command("SELECT 22; ")),
+            Err(SqlError::TerminatedCommand { position: 9 })
+        );
+        assert_eq!(
+            Batch::new(command("SELECT 1")).then(command("SELECT 2; -- done")),
+            Err(SqlError::TerminatedCommand { position: 8 })
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use alloc::string::String;
+use core::convert::Infallible;
+
+use sqlx::postgres::PgArguments;
+use sqlx::{Arguments, Encode, Postgres, Type};
+
+use crate::{ArgumentBuffer, ArgumentError, SqlCommand, SqlError};
+
+/// Argument buffer binding into the [PgArguments] of `sqlx`.
+///
+/// `PgArguments` does not expose the number of values added, so it is
+/// counted alongside.
+///
+/// Available with the `sqlx-postgres` feature.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlxPgArguments, SqlCommand, SqlError, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+/// # use core::convert::Infallible;
+/// # use sqlx::Execute;
+/// # fn main() -> Result<(), SqlError<Infallible>> {
+/// let mut sql: SqlCommand<SqlxPgArguments> = SqlCommand::default();
+/// select(&mut sql);
+/// column(&mut sql, "name");
+/// from_table(&mut sql, "user");
+/// filter_where(&mut sql);
+/// lhs_binary_rhs(&mut sql, sqlexpr::<i32>("id"), Cmp::Eq, sqlvalue(7))?;
+///
+/// let (command, arguments) = sql.into_sqlx_query()?;
+/// let query = sqlx::query_with(&command, arguments);
+/// // query.fetch_one(&pool).await?
+/// assert_eq!(query.sql(), "SELECT name FROM user WHERE id = $1");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct SqlxPgArguments {
+    arguments: PgArguments,
+    count: u32,
+}
+
+impl SqlxPgArguments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the `sqlx` arguments, to bind with [sqlx::query_with].
+    pub fn into_inner(self) -> PgArguments {
+        self.arguments
+    }
+}
+
+impl ArgumentError for SqlxPgArguments {
+    type Error = Infallible;
+}
+
+impl<T> ArgumentBuffer<T> for SqlxPgArguments
+where
+    T: for<'q> Encode<'q, Postgres> + Type<Postgres> + Send,
+{
+    type Error = Infallible;
+
+    fn push(&mut self, value: T) -> Result<(), Self::Error> {
+        self.arguments.add(value);
+        self.count = self.count.saturating_add(1);
+        Ok(())
+    }
+
+    fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+impl SqlCommand<SqlxPgArguments> {
+    /// Splits the command into its text and the `sqlx` arguments, to be
+    /// executed with `sqlx::query_with(&command, arguments)`.
+    ///
+    /// The query of `sqlx` borrows its text, so the text is returned for the
+    /// caller to own while the query runs. A command with rejected text
+    /// returns an error, as in [into_parts](SqlCommand::into_parts).
+    pub fn into_sqlx_query(self) -> Result<(String, PgArguments), SqlError<Infallible>> {
+        let (command, arguments) = self.into_parts()?;
+        Ok((command, arguments.into_inner()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        expr::{
+            column, continue_condition, filter_where, from_table, lhs_binary_rhs, select, Cmp,
+            LogicBi,
+        },
+        sqlexpr, sqlvalue,
+    };
+
+    #[test]
+    fn sqlx_query_with_arguments() {
+        let mut sql: SqlCommand<SqlxPgArguments> = SqlCommand::default();
+        select(&mut sql);
+        column(&mut sql, "id");
+        from_table(&mut sql, "user");
+        filter_where(&mut sql);
+        lhs_binary_rhs(
+            &mut sql,
+            sqlexpr::<i64>("team_id"),
+            Cmp::Eq,
+            sqlvalue(3_i64),
+        )
+        .unwrap();
+        continue_condition(&mut sql, LogicBi::And);
+        lhs_binary_rhs(
+            &mut sql,
+            sqlexpr::<&str>("role"),
+            Cmp::Neq,
+            sqlvalue("admin"),
+        )
+        .unwrap();
+        assert_eq!(ArgumentBuffer::<i64>::count(&sql.arguments), 2);
+
+        let (command, arguments) = sql.into_sqlx_query().unwrap();
+        let query = sqlx::query_with(&command, arguments);
+        assert_eq!(
+            sqlx::Execute::sql(&query),
+            "SELECT id FROM user WHERE team_id = $1 AND role <> $2"
+        );
+    }
+}
@@ -2,7 +2,7 @@ use alloc::string::String;
 use core::fmt::{self, Display, Write};
 use core::ops::Deref;
 
-use crate::ArgumentBuffer;
+use crate::{AppendArguments, ArgumentBuffer, ArgumentError};
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct TestArgs(String, u32);
@@ -25,6 +25,10 @@ impl Deref for TestArgs {
     }
 }
 
+impl ArgumentError for TestArgs {
+    type Error = fmt::Error;
+}
+
 impl<T> ArgumentBuffer<T> for TestArgs
 where
     T: Display,
@@ -42,6 +46,53 @@ where
     }
 }
 
+impl AppendArguments for TestArgs {
+    type Error = fmt::Error;
+
+    fn argument_count(&self) -> u32 {
+        self.1
+    }
+
+    fn append(&mut self, other: Self) -> Result<(), Self::Error> {
+        self.0.push_str(&other.0);
+        self.1 += other.1;
+        Ok(())
+    }
+}
+
+/// Buffer counting its arguments, failing every append.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FailingAppend(u32);
+
+impl ArgumentError for FailingAppend {
+    type Error = fmt::Error;
+}
+
+impl<T> ArgumentBuffer<T> for FailingAppend {
+    type Error = fmt::Error;
+
+    fn push(&mut self, _: T) -> Result<(), Self::Error> {
+        self.0 += 1;
+        Ok(())
+    }
+
+    fn count(&self) -> u32 {
+        self.0
+    }
+}
+
+impl AppendArguments for FailingAppend {
+    type Error = fmt::Error;
+
+    fn argument_count(&self) -> u32 {
+        self.0
+    }
+
+    fn append(&mut self, _: Self) -> Result<(), Self::Error> {
+        Err(fmt::Error)
+    }
+}
+
 pub fn display_iter<'a, I, T>(iter: I) -> Result<String, fmt::Error>
 where
     I: IntoIterator<Item = &'a T>,
@@ -0,0 +1,314 @@
+//! Test doubles of the command execution.
+//!
+//! Available with the `testing` feature.
+
+use alloc::{collections::VecDeque, string::String, vec::Vec};
+use core::convert::Infallible;
+
+use crate::scan::{tokens, SpanKind};
+use crate::{AppendArguments, ExecOptions, Execute, SqlCommand, SqlError};
+
+/// Normalizes the whitespace of a sql command, collapsing every run of
+/// whitespace into a single space and trimming the ends.
+///
+/// The text inside string literals (`'...'`), quoted identifiers (`"..."`),
+/// dollar-quoted strings and comments is kept as is, as is the rest of the
+/// command after a quoted text never closed.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::testing::normalize_sql;
+/// assert_eq!(
+///     normalize_sql("  SELECT id\n  FROM user\n  WHERE name = 'a  b' OR bio = $$ c  d $$ "),
+///     "SELECT id FROM user WHERE name = 'a  b' OR bio = $$ c  d $$"
+/// );
+/// ```
+pub fn normalize_sql(sql: &str) -> String {
+    let sql = sql.trim();
+    let mut normalized = String::with_capacity(sql.len());
+    let mut space = false;
+
+    for span in tokens(sql) {
+        let span = match span {
+            Ok(span) => span,
+            Err(err) => {
+                push_space(&mut normalized, &mut space);
+                normalized.push_str(sql.get(err.position..).unwrap_or_default());
+                break;
+            }
+        };
+        if span.kind != SpanKind::Text {
+            push_space(&mut normalized, &mut space);
+            normalized.push_str(span.text);
+            continue;
+        }
+        for ch in span.text.chars() {
+            if ch.is_whitespace() {
+                space = true;
+            } else {
+                push_space(&mut normalized, &mut space);
+                normalized.push(ch);
+            }
+        }
+    }
+
+    normalized
+}
+
+/// Pushes the space collapsing a run of whitespace, if any.
+fn push_space(normalized: &mut String, space: &mut bool) {
+    if core::mem::take(space) {
+        normalized.push(' ');
+    }
+}
+
+/// Command executed by a [RecordingExecutor].
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Executed {
+    pub command: String,
+    pub argument_count: u32,
+    pub options: ExecOptions,
+}
+
+/// Error of a [RecordingExecutor].
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+pub enum RecordingError {
+    /// No rows were queued for the command executed at `index`.
+    QueueExhausted { index: usize },
+    /// The command was refused before its execution, such as a command with
+    /// rejected text, see [into_parts](SqlCommand::into_parts), and was not
+    /// recorded.
+    Command(SqlError<Infallible>),
+}
+
+#[cfg(feature = "fmt")]
+impl core::fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::QueueExhausted { index } => {
+                write!(f, "no rows queued for the command {index}")
+            }
+            Self::Command(err) => write!(f, "command refused: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RecordingError {}
+
+/// [Execute] implementation recording every command executed, returning the
+/// rows queued with [push_rows](Self::push_rows) in order.
+///
+/// A command executed without queued rows is still recorded, and returns a
+/// [QueueExhausted](RecordingError::QueueExhausted) error.
+///
+/// # Example
+///
+/// ```
+/// # use sqlstr::{SqlCommand, SqlError, Void, Execute, sqlexpr, sqlvalue};
+/// # use sqlstr::expr::{select, column, from_table, filter_where, lhs_binary_rhs, Cmp};
+/// # use sqlstr::testing::RecordingExecutor;
+/// fn find_name<E: Execute<Void, Row = String>>(db: &mut E, id: u64) -> Option<String> {
+///     let mut sql = SqlCommand::default();
+///     select(&mut sql);
+///     column(&mut sql, "name");
+///     from_table(&mut sql, "user");
+///     filter_where(&mut sql);
+///     lhs_binary_rhs(&mut sql, sqlexpr::<u64>("id"), Cmp::Eq, sqlvalue(id)).ok()?;
+///     db.execute(sql).ok()?.into_iter().next()
+/// }
+///
+/// let mut db = RecordingExecutor::new();
+/// db.push_rows(vec!["Rusty".to_string()]);
+///
+/// assert_eq!(find_name(&mut db, 7), Some("Rusty".to_string()));
+/// db.assert_executed_sql(0, "SELECT name FROM user WHERE id = $1");
+/// assert_eq!(db.executed()[0].argument_count, 1);
+/// ```
+#[cfg_attr(any(feature = "fmt", test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+pub struct RecordingExecutor<Row> {
+    executed: Vec<Executed>,
+    rows: VecDeque<Vec<Row>>,
+}
+
+impl<Row> Default for RecordingExecutor<Row> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Row> RecordingExecutor<Row> {
+    pub const fn new() -> Self {
+        Self {
+            executed: Vec::new(),
+            rows: VecDeque::new(),
+        }
+    }
+
+    /// Queues the rows returned by the next command executed without rows.
+    pub fn push_rows(&mut self, rows: Vec<Row>) -> &mut Self {
+        self.rows.push_back(rows);
+        self
+    }
+
+    /// Commands executed, in execution order.
+    pub fn executed(&self) -> &[Executed] {
+        &self.executed
+    }
+
+    /// Takes the commands executed, leaving the executor without records. The
+    /// queued rows are kept.
+    pub fn drain(&mut self) -> Vec<Executed> {
+        core::mem::take(&mut self.executed)
+    }
+
+    /// Asserts that the command executed at `index` is `expected`, comparing
+    /// the commands with [normalize_sql].
+    ///
+    /// # Panics
+    ///
+    /// Panics if no command was executed at `index` or the commands differ.
+    #[track_caller]
+    pub fn assert_executed_sql(&self, index: usize, expected: &str) {
+        let Some(executed) = self.executed.get(index) else {
+            panic!(
+                "no command executed at index {index}, {} commands executed",
+                self.executed.len()
+            );
+        };
+        assert_eq!(
+            normalize_sql(&executed.command),
+            normalize_sql(expected),
+            "command executed at index {index}"
+        );
+    }
+}
+
+impl<Arg, Row> Execute<Arg> for RecordingExecutor<Row>
+where
+    Arg: AppendArguments,
+{
+    type Row = Row;
+    type Error = RecordingError;
+
+    fn execute(&mut self, command: SqlCommand<Arg>) -> Result<Vec<Row>, RecordingError> {
+        let index = self.executed.len();
+        let argument_count = command.arguments.argument_count();
+        let (command, _, options) = command
+            .into_parts_with_options()
+            .map_err(RecordingError::Command)?;
+        self.executed.push(Executed {
+            command,
+            argument_count,
+            options,
+        });
+
+        self.rows
+            .pop_front()
+            .ok_or(RecordingError::QueueExhausted { index })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::ToString, vec};
+    use core::time::Duration;
+
+    use super::*;
+    use crate::{test::TestArgs, Void};
+
+    fn command(text: &str, count: u32) -> SqlCommand<Void> {
+        SqlCommand::new(text.to_string(), Void::with_count(count))
+    }
+
+    #[test]
+    fn records_in_order() {
+        let mut db = RecordingExecutor::new();
+        db.push_rows(vec![1, 2]).push_rows(vec![]);
+
+        assert_eq!(db.execute(command("SELECT $1", 1)), Ok(vec![1, 2]));
+        let timed = command("DELETE FROM job", 0).with_statement_timeout(Duration::from_secs(5));
+        assert_eq!(db.execute(timed), Ok(vec![]));
+
+        let executed = db.drain();
+        assert_eq!(
+            executed,
+            [
+                Executed {
+                    command: "SELECT $1".to_string(),
+                    argument_count: 1,
+                    options: ExecOptions::default(),
+                },
+                Executed {
+                    command: "DELETE FROM job".to_string(),
+                    argument_count: 0,
+                    options: ExecOptions {
+                        statement_timeout: Some(Duration::from_secs(5)),
+                        ..ExecOptions::default()
+                    },
+                },
+            ]
+        );
+        assert!(db.executed().is_empty());
+    }
+
+    #[test]
+    fn queue_exhausted() {
+        let mut db: RecordingExecutor<u32> = RecordingExecutor::new();
+        db.push_rows(vec![1]);
+
+        assert_eq!(db.execute(command("SELECT 1", 0)), Ok(vec![1]));
+        assert_eq!(
+            db.execute(command("SELECT 2", 0)),
+            Err(RecordingError::QueueExhausted { index: 1 })
+        );
+        assert_eq!(db.executed().len(), 2);
+        db.assert_executed_sql(1, "SELECT 2");
+    }
+
+    #[test]
+    fn refuses_invalid_text() {
+        let mut db: RecordingExecutor<u32> = RecordingExecutor::new();
+        db.push_rows(vec![1]);
+
+        let mut sql = SqlCommand::new(String::new(), Void::new());
+        sql.push_cmd("SELECT '\0'");
+        assert_eq!(
+            db.execute(sql),
+            Err(RecordingError::Command(SqlError::InvalidText {
+                position: 8
+            }))
+        );
+        assert!(db.executed().is_empty());
+    }
+
+    #[test]
+    fn normalized_assertion() {
+        let mut db: RecordingExecutor<()> = RecordingExecutor::new();
+        let mut sql: SqlCommand<TestArgs> = SqlCommand::default();
+        sql.push_cmd("SELECT  id\n\tFROM user WHERE name = 'a  b'");
+        db.execute(sql).unwrap_err();
+
+        db.assert_executed_sql(0, "SELECT id FROM user\n WHERE name = 'a  b'");
+    }
+
+    #[test]
+    #[should_panic(expected = "command executed at index 0")]
+    fn normalized_assertion_mismatch() {
+        let mut db: RecordingExecutor<()> = RecordingExecutor::new();
+        db.execute(command("SELECT name = 'a  b'", 0)).unwrap_err();
+
+        db.assert_executed_sql(0, "SELECT name = 'a b'");
+    }
+
+    #[test]
+    #[should_panic(expected = "no command executed at index 2")]
+    fn assertion_out_of_range() {
+        let db: RecordingExecutor<()> = RecordingExecutor::new();
+        db.assert_executed_sql(2, "SELECT 1");
+    }
+}
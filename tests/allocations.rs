@@ -0,0 +1,179 @@
+//! Allocation count regression tests.
+//!
+//! The bounds have headroom over the current counts, to catch order of
+//! magnitude regressions without failing on small changes.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt::Write;
+
+use sqlstr::{ArgumentBuffer, SqlExpr, Void, WriteSql};
+
+#[path = "../benches/scenarios/mod.rs"]
+mod scenarios;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static LIVE_BYTES: Cell<usize> = const { Cell::new(0) };
+    static PEAK_BYTES: Cell<usize> = const { Cell::new(0) };
+}
+
+fn track_bytes(freed: usize, allocated: usize) {
+    let live = LIVE_BYTES.with(|live| {
+        live.set(live.get().saturating_sub(freed) + allocated);
+        live.get()
+    });
+    PEAK_BYTES.with(|peak| peak.set(peak.get().max(live)));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        track_bytes(0, layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        track_bytes(layout.size(), 0);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        track_bytes(layout.size(), new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Number of allocations (and reallocations) made by `f` in the current thread.
+fn count_allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    let value = f();
+    let after = ALLOCATIONS.with(Cell::get);
+    drop(value);
+    after - before
+}
+
+/// Peak of the bytes allocated by `f` in the current thread, above the bytes
+/// already allocated before.
+fn peak_bytes<T>(f: impl FnOnce() -> T) -> usize {
+    let before = LIVE_BYTES.with(Cell::get);
+    PEAK_BYTES.with(|peak| peak.set(before));
+    let value = f();
+    let peak = PEAK_BYTES.with(Cell::get);
+    drop(value);
+    peak - before
+}
+
+/// Writer growing its command with the amortized strategy of [String],
+/// regardless of the `reserve-exact` feature.
+#[derive(Default)]
+struct AmortizedWriter {
+    command: String,
+    arguments: Void,
+}
+
+impl WriteSql<Void> for AmortizedWriter {
+    fn push_expr<T>(
+        &mut self,
+        operand: SqlExpr<'_, T>,
+    ) -> Result<(), <Void as ArgumentBuffer<T>>::Error> {
+        match operand {
+            SqlExpr::Value(value) => self.push_value(value),
+            SqlExpr::Expr(expr) => {
+                self.push_cmd(expr);
+                Ok(())
+            }
+        }
+    }
+
+    fn push_value<T>(&mut self, value: T) -> Result<(), <Void as ArgumentBuffer<T>>::Error> {
+        self.arguments.push(value)?;
+        let count = ArgumentBuffer::<T>::count(&self.arguments);
+        write!(self.command, "${count}").expect("write into a String");
+        Ok(())
+    }
+
+    fn push_cmd(&mut self, expr: &str) {
+        self.command.push_str(expr);
+    }
+
+    fn as_command(&self) -> &str {
+        &self.command
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.command.reserve(additional);
+    }
+
+    fn arguments(&self) -> Option<&Void> {
+        Some(&self.arguments)
+    }
+}
+
+/// Allocation bound of a scenario, `exact` with the `reserve-exact` feature,
+/// which reallocates more often while the command is small.
+const fn bound(amortized: usize, exact: usize) -> usize {
+    if cfg!(feature = "reserve-exact") {
+        exact
+    } else {
+        amortized
+    }
+}
+
+#[test]
+fn select_joined_allocations() {
+    let allocations = count_allocations(scenarios::select_joined);
+    assert!(allocations <= bound(24, 32), "{allocations} allocations");
+}
+
+#[test]
+fn insert_values_1000_allocations() {
+    let allocations = count_allocations(scenarios::insert_values_1000);
+    assert!(allocations <= bound(48, 64), "{allocations} allocations");
+}
+
+#[test]
+fn static_query_allocations() {
+    let allocations = count_allocations(scenarios::static_query);
+    assert!(allocations <= bound(16, 24), "{allocations} allocations");
+}
+
+#[test]
+fn select_in_list_owned_allocations() {
+    let borrowed =
+        count_allocations(|| scenarios::select_in_list_borrowed(scenarios::in_list_fragment()));
+    let owned =
+        count_allocations(|| scenarios::select_in_list_owned(scenarios::in_list_fragment()));
+    assert!(
+        owned < borrowed,
+        "{owned} owned, {borrowed} borrowed allocations"
+    );
+
+    let borrowed = scenarios::select_in_list_borrowed(scenarios::in_list_fragment());
+    let owned = scenarios::select_in_list_owned(scenarios::in_list_fragment());
+    assert_eq!(owned.as_command(), borrowed.as_command());
+}
+
+#[test]
+fn insert_values_1000_peak_capacity() {
+    let amortized = peak_bytes(|| {
+        let mut sql = AmortizedWriter::default();
+        scenarios::write_insert_values_1000(&mut sql).unwrap();
+        sql
+    });
+    let command = peak_bytes(scenarios::insert_values_1000);
+
+    #[cfg(feature = "reserve-exact")]
+    assert!(
+        command <= amortized,
+        "{command} exact, {amortized} amortized bytes"
+    );
+    #[cfg(not(feature = "reserve-exact"))]
+    assert_eq!(command, amortized);
+}
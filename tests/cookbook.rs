@@ -0,0 +1,237 @@
+//! One realistic query per capability, built with the public API.
+//!
+//! The [Void] buffer only counts the arguments, so the tests assert the exact
+//! command and the number of arguments bound.
+
+use core::convert::Infallible;
+
+use sqlstr::expr::comparison::is_null;
+use sqlstr::expr::{
+    column, columns_iter, continue_condition, filter_where, from_table, from_table_as, group_by,
+    insert_columns_iter, insert_into, join, join_on, lhs_binary_rhs, limit, offset, on_conflict,
+    order_by, select, set_column, set_update, values, values_rows, Cmp, ConflictAction,
+    ConflictTarget, JoinType, LogicBi, OrderByNulls, OrderByOrd, With,
+};
+use sqlstr::{sqlexpr, sqlvalue, SqlCommand, SqlError, Void};
+
+type Error = SqlError<Infallible>;
+
+#[test]
+fn joined_paginated_listing() -> Result<(), Error> {
+    let (page, page_size) = (3, 20);
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+
+    select(&mut sql);
+    columns_iter(&mut sql, ["p.id", "p.title", "u.name AS author"]);
+    from_table_as(&mut sql, "post", "p");
+    join(&mut sql, JoinType::Inner, "user", Some("u"));
+    join_on(&mut sql);
+    sql.push_cmd(" u.id = p.author_id");
+    filter_where(&mut sql);
+    lhs_binary_rhs(
+        &mut sql,
+        sqlexpr::<bool>("p.published"),
+        Cmp::Eq,
+        sqlvalue(true),
+    )?;
+    order_by(
+        &mut sql,
+        [("p.created_at", OrderByOrd::Desc, OrderByNulls::Last)],
+    );
+    limit(&mut sql, page_size)?;
+    offset(&mut sql, page * page_size)?;
+
+    assert_eq!(
+        sql.as_command(),
+        "SELECT p.id, p.title, u.name AS author FROM post AS p \
+         INNER JOIN user AS u ON u.id = p.author_id \
+         WHERE p.published = $1 ORDER BY p.created_at DESC NULLS LAST LIMIT $2 OFFSET $3"
+    );
+    assert_eq!(sql.arguments.count(), 3);
+    Ok(())
+}
+
+#[test]
+fn upsert() -> Result<(), Error> {
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+
+    insert_into(&mut sql, "user");
+    insert_columns_iter(&mut sql, ["email", "name"]).unwrap();
+    values(&mut sql);
+    values_rows(&mut sql, 2, [["ana@example.com", "Ana"]])?;
+    on_conflict(
+        &mut sql,
+        Some(ConflictTarget::IndexColumn("(email)")),
+        ConflictAction::Update,
+    );
+    set_update(&mut sql);
+    set_column(&mut sql, "name");
+    sql.push_cmd(" EXCLUDED.name");
+
+    assert_eq!(
+        sql.as_command(),
+        "INSERT INTO user (email, name) VALUES ($1, $2) \
+         ON CONFLICT (email) DO UPDATE SET name = EXCLUDED.name"
+    );
+    assert_eq!(sql.arguments.count(), 2);
+    Ok(())
+}
+
+#[test]
+fn bulk_insert_chunks() -> Result<(), Error> {
+    const CHUNK_ROWS: usize = 2;
+    let readings = [(1, 20), (1, 21), (2, 19), (2, 18), (3, 25)];
+
+    let commands = readings
+        .chunks(CHUNK_ROWS)
+        .map(|chunk| {
+            let mut sql: SqlCommand<Void> = SqlCommand::default();
+            insert_into(&mut sql, "reading");
+            insert_columns_iter(&mut sql, ["sensor_id", "value"]).unwrap();
+            values(&mut sql);
+            values_rows(&mut sql, 2, chunk.iter().map(|&(id, value)| [id, value]))?;
+            Ok(sql)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let text: Vec<&str> = commands.iter().map(SqlCommand::as_command).collect();
+    assert_eq!(
+        text,
+        [
+            "INSERT INTO reading (sensor_id, value) VALUES ($1, $2), ($3, $4)",
+            "INSERT INTO reading (sensor_id, value) VALUES ($1, $2), ($3, $4)",
+            "INSERT INTO reading (sensor_id, value) VALUES ($1, $2)",
+        ]
+    );
+    assert_eq!(commands[2].arguments.count(), 2);
+    Ok(())
+}
+
+#[test]
+fn cte_union_report() -> Result<(), Error> {
+    let mut current: SqlCommand<Void> = SqlCommand::default();
+
+    let mut with = With::open(&mut current);
+    with.cte("recent", |sql, _| {
+        select(sql);
+        columns_iter(sql, ["customer_id", "total"]);
+        from_table(sql, "order");
+        filter_where(sql);
+        lhs_binary_rhs(
+            sql,
+            sqlexpr::<&str>("created_at"),
+            Cmp::Gte,
+            sqlvalue("2024-01-01"),
+        )?;
+        Ok::<_, Error>(())
+    })?;
+    let ctes = with.close();
+
+    select(&mut current);
+    columns_iter(&mut current, ["customer_id", "sum(total)"]);
+    from_table(&mut current, ctes.cte_ref("recent")?);
+    group_by(&mut current, ["customer_id"]);
+
+    let mut archived: SqlCommand<Void> = SqlCommand::default();
+    select(&mut archived);
+    columns_iter(&mut archived, ["customer_id", "sum(total)"]);
+    from_table(&mut archived, "order_archive");
+    filter_where(&mut archived);
+    lhs_binary_rhs(
+        &mut archived,
+        sqlexpr::<u32>("year"),
+        Cmp::Eq,
+        sqlvalue(2023),
+    )?;
+    group_by(&mut archived, ["customer_id"]);
+
+    let sql = current.union_all(archived)?;
+
+    assert_eq!(
+        sql.as_command(),
+        "WITH recent AS (SELECT customer_id, total FROM order WHERE created_at >= $1) \
+         SELECT customer_id, sum(total) FROM recent GROUP BY customer_id \
+         UNION ALL \
+         SELECT customer_id, sum(total) FROM order_archive WHERE year = $2 GROUP BY customer_id"
+    );
+    assert_eq!(sql.arguments.count(), 2);
+    Ok(())
+}
+
+/// Search options, usually deserialized from a request.
+#[derive(Default)]
+struct UserFilter<'a> {
+    name: Option<&'a str>,
+    min_age: Option<u32>,
+    deleted: bool,
+}
+
+/// Writes a `WHERE` clause with one condition per option set, or nothing if
+/// no option is set.
+fn user_filter(sql: &mut SqlCommand<Void>, filter: &UserFilter<'_>) -> Result<(), Error> {
+    let mut conditions = 0;
+    let mut next_condition = |sql: &mut SqlCommand<Void>| {
+        if conditions == 0 {
+            filter_where(sql);
+        } else {
+            continue_condition(sql, LogicBi::And);
+        }
+        conditions += 1;
+    };
+
+    if let Some(name) = filter.name {
+        next_condition(sql);
+        lhs_binary_rhs(sql, sqlexpr::<&str>("name"), Cmp::Eq, sqlvalue(name))?;
+    }
+    if let Some(min_age) = filter.min_age {
+        next_condition(sql);
+        lhs_binary_rhs(sql, sqlexpr::<u32>("age"), Cmp::Gte, sqlvalue(min_age))?;
+    }
+    if !filter.deleted {
+        next_condition(sql);
+        column(sql, "deleted_at");
+        is_null(sql);
+    }
+    Ok(())
+}
+
+fn search_users(filter: &UserFilter<'_>) -> Result<SqlCommand<Void>, Error> {
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+    select(&mut sql);
+    columns_iter(&mut sql, ["id", "name"]);
+    from_table(&mut sql, "user");
+    user_filter(&mut sql, filter)?;
+    Ok(sql)
+}
+
+#[test]
+fn dynamic_filter_from_options() -> Result<(), Error> {
+    let sql = search_users(&UserFilter {
+        name: Some("ana"),
+        min_age: Some(18),
+        deleted: false,
+    })?;
+    assert_eq!(
+        sql.as_command(),
+        "SELECT id, name FROM user WHERE name = $1 AND age >= $2 AND deleted_at IS NULL"
+    );
+    assert_eq!(sql.arguments.count(), 2);
+
+    let sql = search_users(&UserFilter {
+        min_age: Some(65),
+        deleted: true,
+        ..UserFilter::default()
+    })?;
+    assert_eq!(
+        sql.as_command(),
+        "SELECT id, name FROM user WHERE age >= $1"
+    );
+
+    let sql = search_users(&UserFilter {
+        deleted: true,
+        ..UserFilter::default()
+    })?;
+    assert_eq!(sql.as_command(), "SELECT id, name FROM user");
+    assert_eq!(sql.arguments.count(), 0);
+    Ok(())
+}
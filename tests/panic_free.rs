@@ -0,0 +1,146 @@
+//! Adversarial sizes and counts must return errors, never panic.
+
+use core::convert::Infallible;
+
+use sqlstr::arguments::{CheckedArguments, CheckedError};
+use sqlstr::expr::{
+    from_values, limit, table_lock, table_lock_checked, truncate_ident_hash, TableLock,
+    TableLockWait,
+};
+use sqlstr::{renumber_placeholders, AppendArguments, ArgumentBuffer, SqlCommand, SqlError, Void};
+
+/// Buffer reporting a count it does not hold.
+#[derive(Default)]
+struct LyingArgs(u32);
+
+impl<T> ArgumentBuffer<T> for LyingArgs {
+    type Error = Infallible;
+
+    fn push(&mut self, _: T) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn count(&self) -> u32 {
+        self.0
+    }
+}
+
+impl AppendArguments for LyingArgs {
+    type Error = Infallible;
+
+    fn argument_count(&self) -> u32 {
+        self.0
+    }
+
+    fn append(&mut self, _: Self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Buffer counting its arguments, refusing to grow past `u32::MAX`.
+struct BoundedArgs(u32);
+
+#[derive(Debug, PartialEq)]
+struct Overflow;
+
+impl BoundedArgs {
+    fn add(&mut self, count: u32) -> Result<(), Overflow> {
+        self.0 = self.0.checked_add(count).ok_or(Overflow)?;
+        Ok(())
+    }
+}
+
+impl<T> ArgumentBuffer<T> for BoundedArgs {
+    type Error = Overflow;
+
+    fn push(&mut self, _: T) -> Result<(), Self::Error> {
+        self.add(1)
+    }
+
+    fn count(&self) -> u32 {
+        self.0
+    }
+}
+
+impl AppendArguments for BoundedArgs {
+    type Error = Overflow;
+
+    fn argument_count(&self) -> u32 {
+        self.0
+    }
+
+    fn append(&mut self, other: Self) -> Result<(), Self::Error> {
+        self.add(other.0)
+    }
+}
+
+#[test]
+fn max_count_placeholder() {
+    let args = CheckedArguments::new(LyingArgs(u32::MAX - 1));
+    let mut sql = SqlCommand::new(String::new(), args);
+
+    assert_eq!(
+        limit(&mut sql, 10),
+        Err(CheckedError::CountMismatch {
+            expected: u32::MAX,
+            found: u32::MAX - 1,
+        })
+    );
+}
+
+#[test]
+fn max_count_union_overflow() {
+    let left = SqlCommand::new("SELECT 1".into(), LyingArgs(u32::MAX));
+    let right = SqlCommand::new("SELECT $1".into(), LyingArgs(1));
+
+    assert_eq!(
+        left.union(right).map(|sql| sql.command),
+        Err(SqlError::InvalidPlaceholder { position: 7 })
+    );
+}
+
+#[test]
+fn max_count_overflow() {
+    let mut sql = SqlCommand::new(String::new(), BoundedArgs(u32::MAX));
+
+    assert_eq!(limit(&mut sql, 1), Err(Overflow));
+    assert_eq!(sql.arguments.argument_count(), u32::MAX);
+
+    let mut args = BoundedArgs(u32::MAX);
+    assert_eq!(args.append(BoundedArgs(1)), Err(Overflow));
+    assert_eq!(args.argument_count(), u32::MAX);
+}
+
+#[test]
+fn overflowing_placeholder_number() {
+    let mut out = String::new();
+
+    let result = renumber_placeholders("id = $99999999999", 0, false, &mut out);
+    assert_eq!(result, Err(SqlError::InvalidPlaceholder { position: 5 }));
+
+    let result = renumber_placeholders("id = $4294967295", 1, false, &mut out);
+    assert_eq!(result, Err(SqlError::InvalidPlaceholder { position: 5 }));
+}
+
+#[test]
+fn empty_and_huge_slices() {
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+    assert_eq!(
+        table_lock_checked(&mut sql, TableLock::Tables(&[]), None, TableLockWait::None),
+        Err(SqlError::EmptyList)
+    );
+    table_lock(&mut sql, TableLock::Tables(&[]), None, TableLockWait::None);
+    assert_eq!(sql.as_command(), "");
+
+    let mut sql: SqlCommand<Void> = SqlCommand::default();
+    let rows: [[u8; 0]; 0] = [];
+    assert_eq!(
+        from_values(&mut sql, "t", &[], rows),
+        Err(SqlError::EmptyList)
+    );
+
+    let huge = "é".repeat(1 << 20);
+    let short = truncate_ident_hash(&huge);
+    assert!(short.len() <= 63);
+    assert!(short.starts_with('é'));
+}
@@ -0,0 +1,73 @@
+//! Binding with the `postgres-types` buffer, as done before a
+//! `client.query(&sql.command, &sql.arguments.as_params())` call.
+
+#![cfg(feature = "postgres")]
+
+use core::convert::Infallible;
+
+use sqlstr::expr::{
+    column, continue_condition, filter_where, from_table, lhs_binary_rhs, select, Cmp, LogicBi,
+};
+use sqlstr::{sqlexpr, sqlvalue, PgToSqlArguments, SqlCommand, SqlError};
+
+type Error = SqlError<Infallible>;
+
+#[test]
+fn select_with_two_params() -> Result<(), Error> {
+    let mut sql: SqlCommand<PgToSqlArguments> = SqlCommand::default();
+
+    select(&mut sql);
+    column(&mut sql, "id, name");
+    from_table(&mut sql, "user");
+    filter_where(&mut sql);
+    lhs_binary_rhs(
+        &mut sql,
+        sqlexpr::<i64>("team_id"),
+        Cmp::Eq,
+        sqlvalue(3_i64),
+    )?;
+    continue_condition(&mut sql, LogicBi::And);
+    lhs_binary_rhs(
+        &mut sql,
+        sqlexpr::<String>("name"),
+        Cmp::Neq,
+        sqlvalue(String::from("admin")),
+    )?;
+
+    assert_eq!(
+        sql.as_command(),
+        "SELECT id, name FROM user WHERE team_id = $1 AND name <> $2"
+    );
+    assert_eq!(sql.arguments.as_params().len(), 2);
+    Ok(())
+}
+
+#[test]
+fn union_keeps_params_order() -> Result<(), Error> {
+    let mut first: SqlCommand<PgToSqlArguments> = SqlCommand::default();
+    select(&mut first);
+    column(&mut first, "id");
+    from_table(&mut first, "user");
+    filter_where(&mut first);
+    lhs_binary_rhs(&mut first, sqlexpr::<i32>("age"), Cmp::Gt, sqlvalue(18))?;
+
+    let mut second: SqlCommand<PgToSqlArguments> = SqlCommand::default();
+    select(&mut second);
+    column(&mut second, "id");
+    from_table(&mut second, "admin");
+    filter_where(&mut second);
+    lhs_binary_rhs(
+        &mut second,
+        sqlexpr::<bool>("active"),
+        Cmp::Eq,
+        sqlvalue(true),
+    )?;
+
+    let sql = first.union(second)?;
+    assert_eq!(
+        sql.as_command(),
+        "SELECT id FROM user WHERE age > $1 UNION SELECT id FROM admin WHERE active = $2"
+    );
+    assert_eq!(format!("{:?}", sql.arguments.as_params()), "[18, true]");
+    Ok(())
+}